@@ -2,12 +2,14 @@ use proc_macro::TokenStream;
 use quote::{format_ident, quote};
 use syn::parse::{Parse, ParseStream, Result};
 use syn::punctuated::Punctuated;
-use syn::{braced, parse_macro_input, Ident, LitStr, Token, Type};
+use syn::{braced, parse_macro_input, Ident, LitBool, LitStr, Token, Type};
 
 struct Command {
     name: Ident,
     description: LitStr,
     args: Punctuated<Arg, Token![,]>,
+    opens_prompt: bool,
+    moves_cursor: bool,
     handler: syn::Expr,
 }
 
@@ -39,6 +41,8 @@ impl Parse for Command {
 
         let mut description: Option<LitStr> = None;
         let mut args: Option<Punctuated<Arg, Token![,]>> = None;
+        let mut opens_prompt = false;
+        let mut moves_cursor = false;
         let mut handler: Option<syn::Expr> = None;
 
         while !content.is_empty() {
@@ -55,6 +59,12 @@ impl Parse for Command {
                     let args_list = inner.parse_terminated(Arg::parse, Token![,])?;
                     args = Some(args_list);
                 }
+                "opens_prompt" => {
+                    opens_prompt = content.parse::<LitBool>()?.value;
+                }
+                "moves_cursor" => {
+                    moves_cursor = content.parse::<LitBool>()?.value;
+                }
                 "handler" => {
                     handler = Some(content.parse()?);
                 }
@@ -69,11 +79,48 @@ impl Parse for Command {
             description: description
                 .ok_or_else(|| syn::Error::new(name.span(), "Missing description"))?,
             args: args.unwrap_or_default(),
+            opens_prompt,
+            moves_cursor,
             handler: handler.ok_or_else(|| syn::Error::new(name.span(), "Missing handler"))?,
         })
     }
 }
 
+/// Returns the inner type if the type is `Option<T>`.
+fn option_inner(ty: &Type) -> Option<&Type> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) => match args.args.first()? {
+            syn::GenericArgument::Type(inner) => Some(inner),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Returns the usage of a command, e.g. `open <path: String>`. Optional arguments are shown in
+/// square brackets.
+fn usage(name: &Ident, args: &Punctuated<Arg, Token![,]>) -> String {
+    let mut usage = name.to_string().to_lowercase();
+    for arg in args {
+        let name = &arg.name;
+        match option_inner(&arg.ty) {
+            Some(inner) => usage.push_str(&format!(" [{name}: {}]", quote!(#inner))),
+            None => {
+                let ty = &arg.ty;
+                usage.push_str(&format!(" <{name}: {}>", quote!(#ty)));
+            }
+        }
+    }
+    usage.replace(" < ", "<").replace(" >", ">")
+}
+
 // Parsing multiple commands.
 impl Parse for Commands {
     fn parse(input: ParseStream) -> Result<Self> {
@@ -94,6 +141,10 @@ pub fn define_commands(input: TokenStream) -> TokenStream {
         let cmd_name = &cmd.name;
         let cmd_spec = format_ident!("{}Spec", cmd_name);
         let description = &cmd.description;
+        let opens_prompt = cmd.opens_prompt;
+        let moves_cursor = cmd.moves_cursor;
+        let command = cmd_name.to_string().to_lowercase();
+        let usage = usage(cmd_name, &cmd.args);
 
         let arg_names: Vec<_> = cmd.args.iter().map(|a| &a.name).collect();
         let arg_types: Vec<_> = cmd.args.iter().map(|a| &a.ty).collect();
@@ -112,22 +163,12 @@ pub fn define_commands(input: TokenStream) -> TokenStream {
             quote! {}
         } else {
             let mut parse_tokens = Vec::new();
-            for arg in &cmd.args {
+            for (position, arg) in cmd.args.iter().enumerate() {
                 let name = &arg.name;
                 let ty = &arg.ty;
+                let position = position + 1;
 
-                // Check if type is Option<T>.
-                let is_option = match ty {
-                    syn::Type::Path(type_path) => type_path
-                        .path
-                        .segments
-                        .last()
-                        .map(|s| s.ident == "Option")
-                        .unwrap_or(false),
-                    _ => false,
-                };
-
-                if is_option {
+                if option_inner(ty).is_some() {
                     parse_tokens.push(quote! {
                         let #name: #ty = iter.next().map(|v| v.parse()).transpose().unwrap_or(None);
                     });
@@ -135,11 +176,18 @@ pub fn define_commands(input: TokenStream) -> TokenStream {
                     parse_tokens.push(quote! {
                         let #name: #ty = iter
                             .next()
-                            .ok_or_else(|| crate::editor::command::Error::MissingArgument(stringify!(#name).to_string()))?
+                            .ok_or_else(|| crate::editor::command::Error::MissingArgument {
+                                command: #command.to_string(),
+                                name: stringify!(#name).to_string(),
+                                usage: #usage.to_string(),
+                            })?
                             .parse::<#ty>()
                             .map_err(|e| crate::editor::command::Error::InvalidArgument {
+                                command: #command.to_string(),
                                 name: stringify!(#name).to_string(),
+                                position: #position,
                                 error: e.to_string(),
+                                usage: #usage.to_string(),
                             })?;
                     });
                 }
@@ -163,9 +211,17 @@ pub fn define_commands(input: TokenStream) -> TokenStream {
             }
 
             impl crate::editor::command::Command for #cmd_name {
+                fn name(&self) -> &'static str {
+                    stringify!(#cmd_name)
+                }
+
                 fn execute(&self, editor: &mut crate::editor::Editor) -> Result<(), crate::editor::command::Error> {
                     self.cmd_handler(editor)
                 }
+
+                fn moves_cursor(&self) -> bool {
+                    #moves_cursor
+                }
             }
         });
 
@@ -183,6 +239,14 @@ pub fn define_commands(input: TokenStream) -> TokenStream {
                     #description
                 }
 
+                fn opens_prompt(&self) -> bool {
+                    #opens_prompt
+                }
+
+                fn usage(&self) -> &'static str {
+                    #usage
+                }
+
                 fn parse(&self, raw_args: &str) -> Result<Box<dyn crate::editor::command::Command>, crate::editor::command::Error> {
                     let mut iter = raw_args.split_whitespace();
                     #arg_parse
@@ -1,28 +1,63 @@
 use std::{
-    fmt,
+    collections::{HashMap, HashSet, VecDeque},
+    fmt, fs, mem,
     path::{Path, PathBuf},
+    rc::Rc,
     sync::Arc,
+    time::{Duration, Instant},
 };
 
-use crossterm::event::{Event, KeyCode, MouseButton, MouseEvent, MouseEventKind};
+use crossterm::event::{
+    Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
 use thiserror::Error;
 
 use crate::editor::{
-    backend::EditorBackend,
-    buffer::{BufferEntry, manager::BufferManager},
-    command::{CommandRegistry, register_commands},
+    backend::{EditorBackend, EventBackend, RenderBackend, RenderingBackend},
+    buffer::{
+        BufferEntry,
+        manager::BufferManager,
+        modification::{BufferAction, BufferModification},
+        row::Row,
+    },
+    command::{AllPanesSpec, Command, CommandRegistry, register_commands},
     command_palette::CommandPalette,
+    completion::{CompletionMenu, WordIndex},
     config::Config,
-    keymap::Keymap,
-    pane::{cursor::CursorMovement, manager::PaneManager},
+    explorer::Explorer,
+    git::GitBranchCache,
+    hex::HexFile,
+    keymap::{Keymap, describe_key, key_chord},
+    locations::{LocationList, parse_errorformat},
+    lsp::{LspEvent, LspManager, Progress},
+    marks::{Mark, Marks},
+    message_log::MessageLog,
+    pane::{NumberError, cursor::CursorMovement, jump_list::Jump, manager::PaneManager},
     prompt::{
         PromptAction, PromptManager, PromptResponse, PromptStatus, PromptType,
+        choice::ChoicePrompt,
         confirm::ConfirmPrompt,
+        input::InputPrompt,
+        matches::{MatchesPrompt, SearchMatch},
+    },
+    registers::{Register, RegisterKind, RegisterName, Registers},
+    renderer::{
+        Renderer,
+        compositor::{
+            Compositor,
+            layer::{LayerArea, LayerId, Z_DEBUG},
+        },
+        stats::{FrameStats, RenderStats},
     },
-    renderer::{Renderer, compositor::Compositor},
+    search_history::SearchHistory,
+    spell::SpellChecker,
+    state::EditorState,
+    swap::SwapFiles,
+    tasks::{TaskId, Tasks},
     ui::{
         component::{
-            RenderingContext,
+            ClickAction, RenderingContext,
+            debug_overlay::DebugOverlay,
             status_bar::{Message, MessageType},
         },
         geometry::{point::Point, rect::Rect},
@@ -35,21 +70,44 @@ use crate::editor::{
             registry::ThemeRegistry,
         },
     },
+    watcher::FileWatcher,
 };
 
 pub mod backend;
 mod buffer;
 pub mod command;
 mod command_palette;
+mod completion;
 pub mod config;
+mod diff;
+mod explorer;
+mod filetype;
+mod git;
+mod hex;
 mod keymap;
+mod locations;
+mod lsp;
+mod marks;
+mod message_log;
 mod pane;
 mod prompt;
+mod registers;
 mod renderer;
+mod search_history;
+mod snippet;
+mod spell;
+mod state;
+mod swap;
+mod tasks;
 pub mod ui;
+mod watcher;
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// An action run on the editor once the active buffer has been saved. It is shared by the prompts
+/// that may be shown before the buffer is saved.
+type AfterSave = Rc<dyn Fn(&mut Editor) -> Result<()>>;
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error(transparent)]
@@ -69,6 +127,16 @@ pub enum Mode {
     Command,
 }
 
+/// The part of the editor that receives key input in insert mode.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Focus {
+    /// The active pane.
+    #[default]
+    Panes,
+    /// The file explorer.
+    Explorer,
+}
+
 impl From<Mode> for &HighlightGroup {
     fn from(value: Mode) -> Self {
         match value {
@@ -94,7 +162,7 @@ pub struct Editor {
     /// The pane manager.
     pane_manager: PaneManager,
     /// The editor backend.
-    backend: EditorBackend,
+    backend: Box<dyn EventBackend>,
     /// The renderer.
     renderer: Renderer,
     /// The compositor.
@@ -109,43 +177,121 @@ pub struct Editor {
     prompt_manager: PromptManager,
     /// The theme registry for loading and managing themes.
     theme_registry: ThemeRegistry,
-    /// The current theme.
-    theme: Arc<Theme>,
-    // TODO: Make this into new editor state struct.
-    /// The editor configuration.
-    pub config: Config,
-    /// The current mode.
-    pub mode: Mode,
-    /// An optional message to display in the status bar.
-    pub status_message: Option<Message>,
+    /// The cached git branch of the active buffer.
+    git_branch: GitBranchCache,
+    /// The marks set in buffers.
+    marks: Marks,
+    /// The registers holding yanked and deleted text.
+    registers: Registers,
+    /// The language servers and the diagnostics they reported.
+    lsp: LspManager,
+    /// The crash-recovery copies of dirty buffers.
+    swap_files: SwapFiles,
+    /// The running background tasks shown in the status bar.
+    tasks: Tasks,
+    /// The tasks showing the progress of language server operations and their titles, keyed by
+    /// filetype and progress token.
+    lsp_tasks: HashMap<(String, String), (TaskId, String)>,
+    /// The previous search queries, recalled in the search prompt and used when searching all
+    /// buffers.
+    pub search_history: SearchHistory,
+    /// The list of locations walked through with `NextLocation` and `PrevLocation`, or `None` if
+    /// it is closed.
+    location_list: Option<LocationList>,
+    /// The words of the buffers, used for completion.
+    word_index: WordIndex,
+    /// The open completion menu, if any.
+    completion: Option<CompletionMenu>,
+    /// The file explorer, or `None` if it is hidden.
+    explorer: Option<Explorer>,
+    /// The file shown as a hex dump over the panes, or `None` if the hex view is closed.
+    hex_file: Option<HexFile>,
+    /// The spell checker, or `None` if spell checking is disabled.
+    spell: Option<SpellChecker>,
+    /// The id of the empty buffer the editor started with, or `None` if files were opened at
+    /// startup. The welcome screen is shown while it is untouched.
+    welcome_buffer: Option<usize>,
+    /// The state read by components while rendering.
+    pub state: EditorState,
+    /// The configuration file given at startup, or `None` if the default path is used.
+    config_path: Option<PathBuf>,
+    /// The watcher of the configuration file, used if `editor.watch_config` is set.
+    config_watcher: FileWatcher,
+    /// The watchers of the files of the open buffers, keyed by buffer id, used if
+    /// `editor.auto_reload` is set.
+    file_watchers: HashMap<usize, FileWatcher>,
+    /// Whether the next key typed in insert mode skips the expansion of abbreviations, set by
+    /// `NoExpandAbbrev`.
+    skip_abbreviation: bool,
+    /// Whether the current status message has been rendered.
+    status_message_rendered: bool,
+    /// Messages waiting to be displayed after the current status message.
+    message_queue: VecDeque<Message>,
+    /// A log of all messages shown in the status bar.
+    message_log: MessageLog,
     /// Whether the editor should quit.
     pub should_quit: bool,
+    /// Whether the editor state changed since the last render.
+    needs_redraw: bool,
+    /// The rendering statistics and the layer of the debug overlay, or `None` if the overlay is
+    /// hidden.
+    render_stats: Option<RenderStats>,
+    debug_overlay: Option<LayerId>,
 }
 
 impl Editor {
-    /// Returns a new editor.
+    /// Returns a new editor running in the terminal.
     pub fn new<P: AsRef<Path>>(
         files: Option<Vec<P>>,
         config_path: Option<PathBuf>,
     ) -> Result<Self> {
-        let renderer = Renderer::initialize()?;
-        let backend = EditorBackend;
+        Self::with_backends(
+            files,
+            config_path,
+            Box::new(EditorBackend),
+            Box::new(RenderingBackend::new()),
+        )
+    }
+
+    /// Returns a new editor that reads events from `backend` and renders to `rendering_backend`.
+    pub fn with_backends<P: AsRef<Path>>(
+        files: Option<Vec<P>>,
+        config_path: Option<PathBuf>,
+        backend: Box<dyn EventBackend>,
+        rendering_backend: Box<dyn RenderBackend>,
+    ) -> Result<Self> {
+        let mut renderer = Renderer::initialize(rendering_backend)?;
 
-        let mut status_message = None;
+        // Messages to show once the editor is initialized.
+        let mut messages = Vec::new();
 
         // Try to load the configuration.
-        let config = Config::load(config_path).unwrap_or_else(|e| {
-            let err_message = Message::new(&format!(
-                "Failed to load configuration, using default configuration: {e}"
-            ))
-            .with_type(MessageType::Error);
-            status_message = Some(err_message);
-            Config::default()
-        });
+        let config = match Config::load(config_path.clone()) {
+            Ok((config, warnings)) => {
+                messages.extend(warnings.iter().map(|warning| {
+                    tracing::warn!(warning, "invalid configuration");
+                    Message::new(&format!("Invalid configuration: {warning}"))
+                        .with_type(MessageType::Warning)
+                }));
+                config
+            }
+            Err(e) => {
+                tracing::warn!(%e, "failed to load configuration");
+                let warn_message = Message::new(&format!(
+                    "Failed to load configuration, using default configuration: {e}"
+                ))
+                .with_type(MessageType::Warning);
+                messages.push(warn_message);
+                Config::default()
+            }
+        };
+        renderer.set_mouse_capture(config.editor.mouse())?;
+        // Swap files and other state kept between sessions live next to the configuration file.
+        let config_dir = Config::get_config_dir(config_path.as_deref());
 
         // Open a buffer via the buffer manager.
         let mut buffer_manager = BufferManager::default();
-        let buffers = if let Some(paths) = files {
+        let buffers: Vec<BufferEntry> = if let Some(paths) = files {
             paths
                 .into_iter()
                 .map(|path| -> Result<BufferEntry> {
@@ -163,12 +309,28 @@ impl Editor {
         // Initialize commands and keybindings.
         let mut command_registry = CommandRegistry::new();
         register_commands(&mut command_registry);
+        command_registry.register(Rc::new(AllPanesSpec));
+
+        let keymap = Keymap::default();
+        for conflict in keymap.conflicts() {
+            messages.push(Message::new(&conflict.message()).with_type(MessageType::Warning));
+        }
 
         let command_palette = CommandPalette::new(&command_registry);
         let prompt_manager = PromptManager::default();
 
-        // Create a new pane and add it to the pane manager.
-        let mut pane_manager = PaneManager::new();
+        // Create a pane for each buffer, falling back to an empty buffer if no files were given.
+        let mut buffers = buffers.into_iter();
+        let first_buffer = buffers
+            .next()
+            .unwrap_or_else(|| buffer_manager.open_empty_file());
+        let welcome_buffer = first_buffer
+            .read_buf()
+            .filepath()
+            .is_none()
+            .then_some(first_buffer.id);
+        let mut pane_manager = PaneManager::new(first_buffer);
+        pane_manager.set_virtual_edit(config.editor.virtual_edit);
         for buffer in buffers {
             pane_manager.open_pane(buffer);
         }
@@ -177,22 +339,10 @@ impl Editor {
         let mut theme_registry = ThemeRegistry::default();
         theme_registry.load_builtin_themes()?;
 
-        let theme = if let Some(ref name) = config.editor.theme {
-            match theme_registry.themes.get(name) {
-                Some(theme) => theme.clone(),
-                None => {
-                    status_message = Some(
-                        Message::new(&format!("Theme not found: {name}"))
-                            .with_type(MessageType::Error),
-                    );
-                    theme_registry.get_default_theme()
-                }
-            }
-        } else {
-            theme_registry.get_default_theme()
-        };
+        let (theme, theme_message) = Self::configured_theme(&theme_registry, &config);
+        messages.extend(theme_message);
 
-        Ok(Self {
+        let mut editor = Self {
             buffer_manager,
             pane_manager,
             backend,
@@ -200,82 +350,933 @@ impl Editor {
             compositor,
             command_registry,
             command_palette,
-            keymap: Keymap::default(),
+            keymap,
             prompt_manager,
             theme_registry,
-            theme,
-            mode,
-            status_message,
+            git_branch: GitBranchCache::default(),
+            marks: Marks::default(),
+            registers: Registers::default(),
+            lsp: LspManager::new(config.lsp.clone()),
+            swap_files: SwapFiles::new(config_dir.as_deref()),
+            tasks: Tasks::default(),
+            lsp_tasks: HashMap::new(),
+            search_history: SearchHistory::load(config_dir.as_deref()),
+            location_list: None,
+            word_index: WordIndex::default(),
+            completion: None,
+            explorer: None,
+            hex_file: None,
+            spell: Self::spell_checker(&config, config_dir.as_deref()),
+            welcome_buffer,
+            skip_abbreviation: false,
+            status_message_rendered: false,
+            message_queue: VecDeque::new(),
+            message_log: MessageLog::default(),
             should_quit: false,
-            config,
-        })
+            needs_redraw: true,
+            render_stats: None,
+            debug_overlay: None,
+            state: EditorState::new(mode, config, theme),
+            config_path,
+            config_watcher: FileWatcher::default(),
+            file_watchers: HashMap::new(),
+        };
+        for message in messages {
+            editor.push_message(message);
+        }
+        let buffers: Vec<BufferEntry> = editor.buffer_manager.iter().cloned().collect();
+        for buffer in &buffers {
+            editor.lsp_did_open(buffer);
+            editor.check_swap_file(buffer);
+        }
+
+        Ok(editor)
+    }
+
+    /// Returns a spell checker using the configured user dictionary, or the one in the given
+    /// configuration directory, or `None` if spell checking is disabled.
+    fn spell_checker(config: &Config, config_dir: Option<&Path>) -> Option<SpellChecker> {
+        config
+            .editor
+            .spell
+            .then(|| SpellChecker::load(config.editor.spell_dictionary.as_deref(), config_dir))
+    }
+
+    /// Returns the theme named in the configuration, or the default theme along with a warning if
+    /// it does not exist. The style overrides of the configuration are applied on top.
+    fn configured_theme(
+        theme_registry: &ThemeRegistry,
+        config: &Config,
+    ) -> (Arc<Theme>, Option<Message>) {
+        let (theme, message) = match &config.editor.theme {
+            None => (theme_registry.get_default_theme(), None),
+            Some(name) => match theme_registry.themes.get(name) {
+                Some(theme) => (theme.clone(), None),
+                None => {
+                    tracing::warn!(theme = name, "theme not found");
+                    let message = Message::new(&format!("Theme not found: {name}"))
+                        .with_type(MessageType::Warning);
+                    (theme_registry.get_default_theme(), Some(message))
+                }
+            },
+        };
+        (
+            Theme::with_overrides(&theme, &config.theme.overrides),
+            message,
+        )
+    }
+
+    /// Loads the configuration again from the path used at startup and applies it. The current
+    /// configuration is kept if the file fails to load.
+    pub fn reload_config(&mut self) -> Result<()> {
+        let config = match Config::load(self.config_path.clone()) {
+            Ok((config, warnings)) => {
+                for warning in warnings {
+                    self.show_warn_message(&format!("Invalid configuration: {warning}"));
+                }
+                config
+            }
+            Err(err) => {
+                tracing::warn!(%err, "failed to reload configuration");
+                self.show_err_message(&format!("Failed to reload configuration: {err}"));
+                return Ok(());
+            }
+        };
+
+        let (theme, theme_message) = Self::configured_theme(&self.theme_registry, &config);
+        self.state.theme = theme;
+        self.compositor.invalidate();
+        self.renderer.set_mouse_capture(config.editor.mouse())?;
+        self.lsp.set_servers(config.lsp.clone());
+        self.pane_manager
+            .set_virtual_edit(config.editor.virtual_edit);
+        let config_dir = Config::get_config_dir(self.config_path.as_deref());
+        self.spell = Self::spell_checker(&config, config_dir.as_deref());
+        self.state.config = config;
+
+        match theme_message {
+            Some(message) => self.push_message(message),
+            None => self.show_message("Configuration reloaded"),
+        }
+        self.request_redraw();
+        Ok(())
+    }
+
+    /// Changes the current theme to the loaded theme with the given name, keeping the overrides
+    /// of the configuration.
+    pub fn set_theme(&mut self, name: &str) {
+        if let Some(theme) = self.theme_registry.themes.get(name) {
+            self.state.theme = Theme::with_overrides(theme, &self.state.config.theme.overrides);
+            self.compositor.invalidate();
+            self.show_message(&format!("Loaded theme: {name}"));
+        } else {
+            self.show_warn_message(&format!("No such theme: {name}"));
+        }
     }
 
-    /// Opens a new file and loads its contents into the buffer manager and the pane manager.
+    /// Opens a file, focusing a pane that already shows it or opening a new pane otherwise.
     pub fn open_file<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
+        if let Some(buffer) = self.buffer_manager.get_buffer_by_path(&path)
+            && let Some(index) = self.pane_manager.find_buffer(buffer.id)
+        {
+            let _ = self.pane_manager.set_active(index);
+            return Ok(());
+        }
+        self.open_file_in_new_pane(path)
+    }
+
+    /// Opens a file in a new pane, even if another pane already shows it, loading its contents
+    /// into the buffer manager if it is not open yet.
+    pub fn open_file_in_new_pane<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
+        let opened = self.buffer_manager.get_buffer_by_path(&path).is_none();
         let buffer = self.buffer_manager.open_new_or_existing_file(path)?;
+        if opened {
+            self.lsp_did_open(&buffer);
+            self.check_swap_file(&buffer);
+        }
         self.pane_manager.open_pane(buffer);
         Ok(())
     }
 
+    /// Opens each of the given files, focusing panes that already show them. Paths that are not
+    /// files are reported in a warning instead of being opened.
+    pub fn open_files(&mut self, paths: &[PathBuf]) {
+        let (files, missing): (Vec<&PathBuf>, Vec<&PathBuf>) =
+            paths.iter().partition(|path| path.is_file());
+        let mut opened = 0;
+        for path in files {
+            match self.open_file(path) {
+                Ok(()) => opened += 1,
+                Err(err) => {
+                    self.show_err_message(&format!("Failed to open {}: {err}", path.display()))
+                }
+            }
+        }
+
+        let plural = if opened == 1 { "" } else { "s" };
+        if missing.is_empty() {
+            self.show_message(&format!("Opened {opened} file{plural}"));
+        } else {
+            let missing: Vec<String> = missing
+                .iter()
+                .map(|path| path.display().to_string())
+                .collect();
+            self.show_warn_message(&format!(
+                "Opened {opened} file{plural}, not found: {}",
+                missing.join(", ")
+            ));
+        }
+    }
+
+    /// Queues the buffer to be offered for recovery if its file has a newer swap file.
+    fn check_swap_file(&mut self, buffer: &BufferEntry) {
+        let Some(path) = buffer.read_buf().filepath().map(Path::to_path_buf) else {
+            return;
+        };
+        self.swap_files.check(buffer.id, &path);
+    }
+
+    /// Asks whether to recover the contents of the swap file of the next queued buffer. Recovered
+    /// contents replace the buffer and leave it dirty, while declining removes the swap file.
+    fn offer_swap_recovery(&mut self) {
+        let Some(id) = self.swap_files.next_recoverable() else {
+            return;
+        };
+        let Some(buffer) = self.buffer_manager.get(id) else {
+            return;
+        };
+        let Some((name, path)) = ({
+            let b = buffer.read_buf();
+            b.filepath().map(|path| (b.file_name(), path.to_path_buf()))
+        }) else {
+            return;
+        };
+        let Some(swap) = self.swap_files.recoverable(&path) else {
+            return;
+        };
+
+        self.prompt_manager.show_prompt(
+            PromptType::Confirm(ConfirmPrompt::new(&format!(
+                "Found unsaved changes to {name} from a previous session, recover them?"
+            ))),
+            move |editor, response| {
+                match response {
+                    PromptResponse::Yes => {
+                        let text = match fs::read_to_string(&swap) {
+                            Ok(text) => text,
+                            Err(err) => {
+                                editor.show_err_message(&format!(
+                                    "Failed to read swap file {}: {err}",
+                                    swap.display()
+                                ));
+                                return Ok(());
+                            }
+                        };
+                        let action = buffer.write_buf().set_text(&text);
+                        editor.handle_buffer_modification(&BufferModification::new(id, action));
+                        editor.show_message(&format!("Recovered unsaved changes to {name}"));
+                    }
+                    PromptResponse::No => editor.swap_files.remove(&path),
+                    _ => {}
+                }
+                Ok(())
+            },
+        );
+    }
+
+    /// Returns the filetype, absolute path and contents of the buffer, or `None` if the buffer
+    /// has no path.
+    fn lsp_document(buffer: &BufferEntry) -> Option<(String, PathBuf, String)> {
+        let buffer = buffer.read_buf();
+        let path = std::path::absolute(buffer.filepath()?).ok()?;
+        Some((buffer.filetype(), path, buffer.text()))
+    }
+
+    /// Notifies the language server of the buffer's filetype that the buffer was opened. A server
+    /// that fails to start is reported as a warning.
+    fn lsp_did_open(&mut self, buffer: &BufferEntry) {
+        let Some((filetype, path, text)) = Self::lsp_document(buffer) else {
+            return;
+        };
+        if let Err(err) = self.lsp.did_open(&filetype, &path, &text) {
+            self.show_warn_message(&err.to_string());
+        }
+    }
+
+    /// Shows the progress of a language server operation as a background task, registering the
+    /// task when the operation begins and removing it once it ends.
+    fn lsp_progress(&mut self, filetype: String, progress: Progress) {
+        let key = (filetype, progress.token);
+        if progress.done {
+            if let Some((id, _)) = self.lsp_tasks.remove(&key) {
+                self.tasks.unregister(id);
+            }
+            return;
+        }
+
+        let (id, title) = match self.lsp_tasks.get(&key) {
+            Some(task) => task.clone(),
+            None => {
+                let title = match progress.title {
+                    Some(title) => format!("{}: {title}", key.0),
+                    None => key.0.clone(),
+                };
+                let id = self.tasks.register(&title);
+                self.lsp_tasks.insert(key, (id, title.clone()));
+                (id, title)
+            }
+        };
+        if let Some(task) = self.tasks.get_mut(id) {
+            task.label = match progress.message {
+                Some(message) => format!("{title} {message}"),
+                None => title,
+            };
+        }
+        if let Some(percentage) = progress.percentage {
+            self.tasks.set_progress(id, Some(percentage as f32 / 100.0));
+        }
+    }
+
+    /// Returns the absolute path of the active buffer, or `None` if it has no path.
+    fn active_absolute_path(&self) -> Option<PathBuf> {
+        let path = self.pane_manager.active().file_path()?;
+        std::path::absolute(path).ok()
+    }
+
+    /// The default interval at which the editor updates while idle.
+    const DEFAULT_TICK_RATE: Duration = Duration::from_millis(250);
+
     /// Runs the editor main loop.
     pub fn run(&mut self) -> Result<()> {
+        let tick_rate = self
+            .state
+            .config
+            .editor
+            .tick_rate_ms
+            .map(Duration::from_millis)
+            .unwrap_or(Self::DEFAULT_TICK_RATE);
+
+        // An event read ahead while coalescing repeated cursor movements, and whether it repeats
+        // the previous event.
+        let mut queued: Option<Event> = None;
+        let mut repeated = false;
         while !self.should_quit {
-            self.update()?;
-            self.render()?;
+            // Repeats are handled without rendering in between, as the frame would be replaced
+            // right away.
+            if !repeated {
+                self.update()?;
+                // Pause rendering while the terminal is unfocused.
+                if self.state.focused {
+                    self.render()?;
+                }
+            }
+
+            let event = match queued.take() {
+                Some(event) => event,
+                None => {
+                    // Wait for the next event, running another update on timeout. Update more
+                    // often while tasks are running to animate the spinner.
+                    let timeout = if self.tasks.is_empty() {
+                        tick_rate
+                    } else {
+                        tick_rate.min(Tasks::SPINNER_INTERVAL)
+                    };
+                    if !self.backend.poll_event(timeout)? {
+                        continue;
+                    }
+                    self.backend.read_event()?
+                }
+            };
+            repeated = false;
+            if let Some(stats) = &mut self.render_stats {
+                stats.last_event = Some(event_kind(&event));
+                self.request_redraw();
+            }
 
-            let event = self.backend.read_event()?;
+            match event {
+                // Key releases are only reported on some platforms and never trigger any action.
+                Event::Key(KeyEvent {
+                    kind: KeyEventKind::Release,
+                    ..
+                }) => continue,
+                // The renderer redraws everything when the frame size changes.
+                Event::Resize(..) => {
+                    self.compositor.invalidate();
+                    self.request_redraw();
+                    continue;
+                }
+                Event::FocusLost => {
+                    // Render a final frame to show the unfocused state.
+                    self.state.focused = false;
+                    self.request_redraw();
+                    self.render()?;
+                    continue;
+                }
+                Event::FocusGained => {
+                    // Other programs may have drawn over the screen, so redraw everything.
+                    self.state.focused = true;
+                    self.renderer.invalidate();
+                    self.compositor.invalidate();
+                    self.request_redraw();
+                    continue;
+                }
+                _ => {}
+            }
 
             // Handle prompt input first.
-            if self.prompt_manager.active_prompt.is_some() {
+            if self.prompt_manager.is_active() {
                 self.handle_prompt_input(event);
+                // Drop the prompts below the answered one if its callback quit the editor.
+                if self.should_quit {
+                    self.prompt_manager.clear();
+                }
                 continue;
             }
 
-            match self.mode {
-                Mode::Insert => self.handle_insert_mode_input(event),
-                Mode::Command => self.handle_command_mode_input(event),
+            let movement = self.is_cursor_movement(&event);
+            match (self.state.mode, self.state.focus) {
+                (Mode::Insert, Focus::Panes) if self.hex_file.is_some() => {
+                    self.handle_hex_view_input(event.clone())
+                }
+                (Mode::Insert, Focus::Panes) => self.handle_insert_mode_input(event.clone()),
+                (Mode::Insert, Focus::Explorer) => self.handle_explorer_input(event.clone()),
+                (Mode::Command, _) => self.handle_command_mode_input(event.clone()),
             };
 
             // Only quit if there is no active prompt.
-            if self.should_quit && self.prompt_manager.active_prompt.is_none() {
+            if self.should_quit && !self.prompt_manager.is_active() {
                 break;
             }
+
+            // Read ahead when holding a movement key, so that all repeats already waiting are
+            // handled before the next frame. Other events are handled as usual after rendering.
+            if movement && self.backend.poll_event(Duration::ZERO)? {
+                let next = self.backend.read_event()?;
+                repeated = is_same_key(&event, &next);
+                queued = Some(next);
+            }
         }
         self.exit()
     }
 
+    /// Returns `true` if the event is a key bound to a command that only moves the cursor of the
+    /// active pane.
+    fn is_cursor_movement(&self, event: &Event) -> bool {
+        let Event::Key(key) = event else {
+            return false;
+        };
+        self.state.mode == Mode::Insert
+            && self.state.focus == Focus::Panes
+            && self
+                .keymap
+                .get(key)
+                .is_some_and(|command| command.moves_cursor())
+    }
+
     /// Handles event input in insert mode.
     pub fn handle_insert_mode_input(&mut self, event: Event) {
+        // The completion menu and snippets take priority over other keys.
+        if let Event::Key(key) = &event
+            && (self.handle_completion_key(key)
+                || self.handle_snippet_key(key)
+                || self.handle_cursors_key(key))
+        {
+            self.request_redraw();
+            return;
+        }
+
         match event {
             Event::Key(event) => {
-                if let Some(command) = self.keymap.get(&event).cloned() {
-                    if let Err(err) = command.execute(self) {
-                        self.show_err_message(&err.to_string());
-                    }
+                if !mem::take(&mut self.skip_abbreviation) && self.ends_word(&event) {
+                    self.expand_abbreviations();
+                }
+                if self.execute_bound_command(&event) {
+                    self.request_redraw();
                 } else if let KeyCode::Char(c) = event.code {
                     // TODO: Replace by a command.
-                    self.pane_manager.active_mut().insert_char(c);
+                    let modification = self
+                        .pane_manager
+                        .active_mut()
+                        .edit_at_cursors(|pane| pane.insert_char(c));
+                    self.handle_buffer_modification(&modification);
+                    self.request_redraw();
                 }
             }
+            Event::Paste(text) => {
+                let register = Register::new(text.replace("\r\n", "\n"), RegisterKind::Charwise);
+                let modification = self.pane_manager.active_mut().paste(&register);
+                self.handle_buffer_modification(&modification);
+                self.request_redraw();
+            }
             Event::Mouse(MouseEvent {
                 kind: MouseEventKind::Down(MouseButton::Left),
-                column: _column,
-                row: _row,
+                column,
+                row,
                 ..
             }) => {
-                // TODO: Implement a UI layer to map screen coordinates to components, so we can
-                // redirect mouse events to the correct component.
-                // self.pane_manager
-                //     .active_mut()
-                //     .click(column as usize, row as usize);
+                // Focus the clicked pane or the explorer.
+                // TODO: Move the cursor to the clicked position.
+                let point = Point::new(column as usize, row as usize);
+                if let Some(action) = self.compositor.handle_click(point) {
+                    self.handle_click_action(action);
+                } else if self.compositor.topmost_at(point) == Some(LayerId::EXPLORER) {
+                    self.state.focus = Focus::Explorer;
+                    self.request_redraw();
+                } else if let Some(index) = self
+                    .compositor
+                    .pane_at(point, self.pane_manager.num_panes())
+                    && self.pane_manager.set_active(index).is_ok()
+                {
+                    self.request_redraw();
+                }
             }
             _ => {}
         }
+
+        if self.completion.is_some() {
+            self.refresh_completion();
+        }
+    }
+
+    /// The commands whose keys are listed on the welcome screen, along with their descriptions.
+    const WELCOME_COMMANDS: [(&str, &str); 3] = [
+        ("EnterCommandMode", "Open the command palette"),
+        ("OpenFilesPicker", "Open a file"),
+        ("Quit", "Quit"),
+    ];
+
+    /// Returns the keys and descriptions listed on the welcome screen, or `None` if it is hidden.
+    /// The welcome screen is shown while the only pane shows the empty buffer the editor started
+    /// with, and the buffer has not been modified.
+    fn welcome_hints(&self) -> Option<Vec<(String, &'static str)>> {
+        let pane = self.pane_manager.active();
+        if self.welcome_buffer != Some(pane.buffer_id())
+            || self.pane_manager.num_panes() > 1
+            || pane.is_dirty()
+        {
+            return None;
+        }
+        let hints = Self::WELCOME_COMMANDS
+            .iter()
+            .filter_map(|(command, description)| {
+                let key = self.keymap.key_for(command)?;
+                Some((describe_key(&key), *description))
+            })
+            .collect();
+        Some(hints)
+    }
+
+    /// Performs the action resulting from a click on a component.
+    fn handle_click_action(&mut self, action: ClickAction) {
+        match action {
+            ClickAction::GotoRow { pane, row } => {
+                if self.pane_manager.set_active(pane).is_err() {
+                    return;
+                }
+                let pane = self.pane_manager.active_mut();
+                let row = row.min(pane.buffer_lines().saturating_sub(1));
+                pane.move_cursor(CursorMovement::Position(0, row));
+            }
+            ClickAction::ShowBuffer { buffer_id } => {
+                let Some(buffer) = self.buffer_manager.get(buffer_id) else {
+                    return;
+                };
+                if self.pane_manager.active().buffer_id() == buffer_id {
+                    return;
+                }
+                // Start at the cursor of another pane showing the buffer, if any.
+                let position = self
+                    .pane_manager
+                    .iter()
+                    .find(|pane| pane.buffer_id() == buffer_id)
+                    .map_or(Point::new(0, 0), |pane| pane.cursor_position().into());
+                let pane = self.pane_manager.active_mut();
+                let from = pane.current_jump();
+                pane.jump_list.push(from);
+                pane.restore_jump(Jump::new(buffer_id, position), buffer);
+            }
+        }
+        self.request_redraw();
+    }
+
+    /// Executes the command bound to the key, if any. Returns `true` if a command was bound.
+    fn execute_bound_command(&mut self, key: &KeyEvent) -> bool {
+        let Some(command) = self.keymap.get(key).cloned() else {
+            return false;
+        };
+        tracing::debug!(command = command.name(), ?key, "executing command");
+        if let Err(err) = command.execute(self) {
+            tracing::warn!(command = command.name(), %err, "command failed");
+            self.show_err_message(&err.to_string());
+        }
+        true
+    }
+
+    /// Handles event input while the file explorer is focused. Keys not used by the explorer run
+    /// their bound commands, but never insert text. Mouse events return the focus to the panes.
+    pub fn handle_explorer_input(&mut self, event: Event) {
+        let Some(explorer) = &mut self.explorer else {
+            self.state.focus = Focus::Panes;
+            return;
+        };
+        let key = match event {
+            Event::Key(key) => key,
+            Event::Mouse(_) => {
+                self.state.focus = Focus::Panes;
+                self.handle_insert_mode_input(event);
+                return;
+            }
+            _ => return,
+        };
+
+        match (key.code, key.modifiers) {
+            (KeyCode::Down | KeyCode::Char('j'), KeyModifiers::NONE) => explorer.select_next(),
+            (KeyCode::Up | KeyCode::Char('k'), KeyModifiers::NONE) => explorer.select_prev(),
+            (KeyCode::Right | KeyCode::Char('l'), KeyModifiers::NONE) => explorer.expand(),
+            (KeyCode::Left | KeyCode::Char('h'), KeyModifiers::NONE) => explorer.collapse(),
+            (KeyCode::Enter, KeyModifiers::NONE) => self.open_explorer_selection(false),
+            (KeyCode::Char('t'), KeyModifiers::NONE) => self.open_explorer_selection(true),
+            (KeyCode::Esc, KeyModifiers::NONE) => self.state.focus = Focus::Panes,
+            _ => {
+                self.execute_bound_command(&key);
+            }
+        }
+        self.request_redraw();
+    }
+
+    /// Opens the file selected in the explorer in the active pane, or in a new pane if
+    /// `new_pane` is set, and moves the focus to the panes. A selected directory is expanded or
+    /// collapsed instead.
+    fn open_explorer_selection(&mut self, new_pane: bool) {
+        let Some(explorer) = &mut self.explorer else {
+            return;
+        };
+        let Some(entry) = explorer.selected() else {
+            return;
+        };
+        if entry.is_dir {
+            explorer.toggle();
+            return;
+        }
+
+        let path = entry.path.clone();
+        let result = if new_pane {
+            self.open_file_in_new_pane(&path)
+        } else {
+            self.show_file(&path, Point::new(0, 0))
+        };
+        match result {
+            Ok(()) => self.state.focus = Focus::Panes,
+            Err(err) => self.show_err_message(&err.to_string()),
+        }
+    }
+
+    /// Shows the file explorer of the working directory and focuses it. If the explorer is
+    /// already focused, it is hidden instead.
+    pub fn toggle_explorer(&mut self) {
+        match (&self.explorer, self.state.focus) {
+            (Some(_), Focus::Explorer) => {
+                self.explorer = None;
+                self.state.focus = Focus::Panes;
+            }
+            (Some(_), Focus::Panes) => self.state.focus = Focus::Explorer,
+            (None, _) => {
+                self.explorer = Some(Explorer::new("."));
+                self.state.focus = Focus::Explorer;
+            }
+        }
+    }
+
+    /// The commands that can be run by their keys while the hex view is shown. Other keys are
+    /// ignored, so that the file cannot be edited.
+    const HEX_VIEW_COMMANDS: [&str; 9] = [
+        "Quit",
+        "ForceQuit",
+        "EnterCommandMode",
+        "OpenFilesPicker",
+        "ToggleExplorer",
+        "HexView",
+        "ToggleHexView",
+        "DismissMessage",
+        "ToggleDebugOverlay",
+    ];
+
+    /// Handles event input while the hex view is shown. The selected line is moved by lines or
+    /// pages, and only the keys of [`Self::HEX_VIEW_COMMANDS`] run their commands.
+    pub fn handle_hex_view_input(&mut self, event: Event) {
+        let Some(file) = &mut self.hex_file else {
+            return;
+        };
+        let Event::Key(key) = event else {
+            return;
+        };
+        // The title bar and the status bar are not part of a page.
+        let page = self
+            .backend
+            .size()
+            .map_or(1, |(_, height)| height.saturating_sub(3).max(1)) as isize;
+
+        match (key.code, key.modifiers) {
+            (KeyCode::Down | KeyCode::Char('j'), KeyModifiers::NONE) => file.move_by(1),
+            (KeyCode::Up | KeyCode::Char('k'), KeyModifiers::NONE) => file.move_by(-1),
+            (KeyCode::PageDown, KeyModifiers::NONE) => file.move_by(page),
+            (KeyCode::PageUp, KeyModifiers::NONE) => file.move_by(-page),
+            (KeyCode::Home | KeyCode::Char('g'), KeyModifiers::NONE) => file.move_to(0),
+            (KeyCode::End, KeyModifiers::NONE)
+            | (KeyCode::Char('G'), KeyModifiers::NONE | KeyModifiers::SHIFT) => {
+                file.move_to(usize::MAX)
+            }
+            (KeyCode::Esc, KeyModifiers::NONE) => self.toggle_hex_view(),
+            _ => {
+                if self
+                    .keymap
+                    .get(&key)
+                    .is_some_and(|command| Self::HEX_VIEW_COMMANDS.contains(&command.name()))
+                {
+                    self.execute_bound_command(&key);
+                }
+            }
+        }
+        self.request_redraw();
+    }
+
+    /// Shows the file of the active pane as a read-only hex dump over the panes.
+    pub fn open_hex_view(&mut self) {
+        let Some(path) = self.pane_manager.active().file_path() else {
+            self.show_warn_message("The buffer has no file to show as hex");
+            return;
+        };
+        match HexFile::open(&path) {
+            Ok(file) => {
+                self.hex_file = Some(file);
+                self.state.focus = Focus::Panes;
+            }
+            Err(err) => self.show_err_message(&format!("{}: {err}", path.display())),
+        }
+    }
+
+    /// Opens the hex view of the file of the active pane, or closes it if it is open. The hex
+    /// view is only closed if the file is valid UTF-8, as the text buffer of other files does not
+    /// show their contents.
+    pub fn toggle_hex_view(&mut self) {
+        let Some(file) = &self.hex_file else {
+            self.open_hex_view();
+            return;
+        };
+        match hex::is_utf8(file.path()) {
+            Ok(true) => self.hex_file = None,
+            Ok(false) => {
+                self.show_warn_message("The file is not valid UTF-8, staying in the hex view")
+            }
+            Err(err) => self.show_err_message(&format!("{}: {err}", file.path().display())),
+        }
+    }
+
+    /// Handles Esc collapsing the secondary cursors or the block selection of the active pane.
+    /// Returns `true` if there were any.
+    fn handle_cursors_key(&mut self, key: &KeyEvent) -> bool {
+        if (key.code, key.modifiers) != (KeyCode::Esc, KeyModifiers::NONE) {
+            return false;
+        }
+        let pane = self.pane_manager.active_mut();
+        if pane.block {
+            pane.clear_selection();
+            return true;
+        }
+        pane.collapse_cursors()
+    }
+
+    /// Handles a key moving through or expanding snippets. Tab moves to the next placeholder and
+    /// Esc ends the snippet session of the active pane. Otherwise, the configured expand key
+    /// expands the snippet triggered by the word before the cursor. Returns `true` if the key was
+    /// used.
+    fn handle_snippet_key(&mut self, key: &KeyEvent) -> bool {
+        let pane = self.pane_manager.active_mut();
+        if pane.snippet.is_some() {
+            match (key.code, key.modifiers) {
+                (KeyCode::Tab, KeyModifiers::NONE) => return pane.next_snippet_stop(),
+                (KeyCode::Esc, KeyModifiers::NONE) => {
+                    pane.snippet = None;
+                    return true;
+                }
+                _ => {}
+            }
+        }
+
+        if key_chord(key) != self.state.config.editor.snippet_expand_key() {
+            return false;
+        }
+        let (start, trigger) = pane.word_prefix();
+        let Some(body) = self
+            .state
+            .config
+            .snippets
+            .get(&pane.filetype())
+            .and_then(|snippets| snippets.get(&trigger))
+            .cloned()
+        else {
+            return false;
+        };
+
+        let (modification, session) = pane.expand_snippet(start, &body);
+        self.handle_buffer_modification(&modification);
+        self.pane_manager.active_mut().snippet = session;
+        true
+    }
+
+    /// Returns `true` if the key ends the word before the cursor, i.e. it types a character that
+    /// is not part of a word or inserts a new line.
+    fn ends_word(&self, key: &KeyEvent) -> bool {
+        match key.code {
+            KeyCode::Char(c) => {
+                !key.modifiers
+                    .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+                    && !c.is_alphanumeric()
+                    && c != '_'
+            }
+            _ => self
+                .keymap
+                .get(key)
+                .is_some_and(|command| command.name() == "InsertNewline"),
+        }
+    }
+
+    /// Replaces the word before each cursor of the active pane with its expansion if it is an
+    /// abbreviation. Words are matched case-sensitively.
+    fn expand_abbreviations(&mut self) {
+        let abbreviations = &self.state.config.abbreviations;
+        if abbreviations.is_empty() {
+            return;
+        }
+        let modification = self.pane_manager.active_mut().edit_at_cursors(|pane| {
+            let (start, word) = pane.word_prefix();
+            match abbreviations.get(&word) {
+                Some(expansion) => pane.complete(start, expansion),
+                None => BufferModification::new(pane.buffer_id(), BufferAction::None),
+            }
+        });
+        self.handle_buffer_modification(&modification);
+    }
+
+    /// Makes the next key typed in insert mode leave the abbreviation before the cursor as it
+    /// is.
+    pub fn skip_next_abbreviation(&mut self) {
+        self.skip_abbreviation = true;
+    }
+
+    /// Returns the completion menu for the word before the cursor of the active pane, or `None`
+    /// if there is no word or no word completes it.
+    fn completion_menu(&mut self) -> Option<CompletionMenu> {
+        let pane = self.pane_manager.active();
+        let (start, prefix) = pane.word_prefix();
+        if prefix.is_empty() {
+            return None;
+        }
+        let candidates =
+            self.word_index
+                .candidates(&prefix, pane.buffer_id(), &self.buffer_manager);
+        (!candidates.is_empty()).then(|| CompletionMenu::new(start, candidates))
+    }
+
+    /// Opens a menu completing the word before the cursor with the words of the open buffers.
+    pub fn open_completion(&mut self) {
+        self.completion = self.completion_menu();
+        if self.completion.is_none() {
+            self.show_message("No completions");
+        }
+    }
+
+    /// Updates the candidates of the completion menu after the word before the cursor changed,
+    /// closing the menu if the cursor left the word or nothing completes it anymore.
+    fn refresh_completion(&mut self) {
+        let start = self.completion.as_ref().map(|menu| menu.start);
+        self.completion = self
+            .completion_menu()
+            .filter(|menu| Some(menu.start) == start);
+        self.request_redraw();
+    }
+
+    /// Handles a key while the completion menu is open. Returns `true` if the key was used by
+    /// the menu.
+    fn handle_completion_key(&mut self, key: &KeyEvent) -> bool {
+        let Some(menu) = &mut self.completion else {
+            return false;
+        };
+        match (key.code, key.modifiers) {
+            (KeyCode::Down, _) | (KeyCode::Char('n'), KeyModifiers::CONTROL) => menu.select_next(),
+            (KeyCode::Up, _) | (KeyCode::Char('p'), KeyModifiers::CONTROL) => menu.select_prev(),
+            (KeyCode::Enter | KeyCode::Tab, _) => {
+                let Some(menu) = self.completion.take() else {
+                    return false;
+                };
+                if let Some(word) = menu.selected() {
+                    let modification = self.pane_manager.active_mut().complete(menu.start, word);
+                    self.handle_buffer_modification(&modification);
+                }
+            }
+            (KeyCode::Esc, _) => self.completion = None,
+            _ => return false,
+        }
+        true
+    }
+
+    /// Executes the command once in every pane, making each pane active in turn, and restores
+    /// the active pane afterwards. Errors do not stop the remaining panes, but are summarized once
+    /// all panes ran. If the command opens a prompt, the remaining panes are skipped and the pane
+    /// stays active so that the prompt applies to it.
+    pub fn execute_in_all_panes(&mut self, command: &dyn Command) {
+        let active = self.pane_manager.active_pane();
+        let num_panes = self.pane_manager.num_panes();
+        let mut errors = Vec::new();
+        let mut ran = 0;
+        for index in 0..num_panes {
+            // The command may have closed panes.
+            if self.pane_manager.set_active(index).is_err() {
+                break;
+            }
+            ran += 1;
+            if let Err(err) = command.execute(self) {
+                tracing::warn!(command = command.name(), pane = index, %err, "command failed");
+                errors.push(format!("pane {}: {err}", index + 1));
+            }
+            if self.prompt_manager.is_active() {
+                break;
+            }
+        }
+
+        if self.prompt_manager.is_active() {
+            self.show_warn_message(&format!(
+                "{} opened a prompt in pane {ran}, skipped the remaining panes",
+                command.name()
+            ));
+        } else {
+            let last = self.pane_manager.num_panes() - 1;
+            let _ = self.pane_manager.set_active(active.min(last));
+        }
+
+        if !errors.is_empty() {
+            self.show_err_message(&format!(
+                "{} failed in {} of {ran} panes\n{}",
+                command.name(),
+                errors.len(),
+                errors.join("\n")
+            ));
+        } else if !self.prompt_manager.is_active() {
+            self.show_message(&format!("Ran {} in {ran} panes", command.name()));
+        }
     }
 
     /// Handles event input in command mode.
     pub fn handle_command_mode_input(&mut self, event: Event) {
+        // Pasted text is inserted into the query, without line breaks.
+        if let Event::Paste(text) = &event {
+            for c in text.chars().filter(|c| !c.is_control()) {
+                self.command_palette.insert_char(c);
+            }
+            self.request_redraw();
+            return;
+        }
+
         if let Event::Key(event) = event {
             // TODO: Implement command mode keybindings as commands with context (mode).
             match event.code {
@@ -284,7 +1285,13 @@ impl Editor {
                     let command_name = self.command_palette.command_query();
                     match self.command_palette.parse_query(&self.command_registry) {
                         Some(Ok(command)) => {
+                            tracing::debug!(
+                                command = command.name(),
+                                query = self.command_palette.query(),
+                                "executing command"
+                            );
                             if let Err(err) = command.execute(self) {
+                                tracing::warn!(command = command.name(), %err, "command failed");
                                 self.show_err_message(&err.to_string());
                             }
                         }
@@ -300,16 +1307,25 @@ impl Editor {
                 KeyCode::Down | KeyCode::BackTab => self.command_palette.select_prev_command(),
                 KeyCode::Up => self.command_palette.select_next_command(),
                 KeyCode::Backspace => self.command_palette.delete_char(),
-                _ => {}
+                KeyCode::Delete => self.command_palette.delete_char_forward(),
+                KeyCode::Left => self.command_palette.move_cursor_left(),
+                KeyCode::Right => self.command_palette.move_cursor_right(),
+                KeyCode::Home => self.command_palette.move_cursor_to_start(),
+                KeyCode::End => self.command_palette.move_cursor_to_end(),
+                _ => return,
             }
+            self.request_redraw();
         }
     }
 
     fn handle_prompt_input(&mut self, event: Event) {
-        if let Event::Key(key) = event
-            && let Some(active) = self.prompt_manager.active_prompt.as_mut()
-        {
-            let status = active.prompt.process_key(&key);
+        if let Some(active) = self.prompt_manager.active_mut() {
+            let status = match &event {
+                Event::Key(key) => active.prompt.process_key(key),
+                Event::Paste(text) => active.prompt.process_paste(text),
+                _ => return,
+            };
+            self.needs_redraw = true;
             match status {
                 PromptStatus::Pending => {}
                 PromptStatus::Changed => {
@@ -321,7 +1337,9 @@ impl Editor {
                     }
                 }
                 PromptStatus::Done(response) => {
-                    let active = self.prompt_manager.active_prompt.take().unwrap();
+                    // Pop the prompt before running its callback, which may show another prompt.
+                    let active = self.prompt_manager.pop().unwrap();
+                    tracing::debug!(prompt = active.prompt.name(), ?response, "prompt closed");
                     if let Err(err) = (active.callback)(self, response) {
                         self.show_err_message(&err.to_string());
                     }
@@ -333,68 +1351,862 @@ impl Editor {
     /// Shows a message in the status bar.
     pub fn show_message(&mut self, s: &str) {
         let message = Message::new(s);
-        self.status_message = Some(message);
+        self.push_message(message);
+    }
+
+    /// Shows a warning message in the status bar.
+    pub fn show_warn_message(&mut self, s: &str) {
+        let message = Message::new(s).with_type(MessageType::Warning);
+        self.push_message(message);
     }
 
     /// Shows an error message in the status bar.
     pub fn show_err_message(&mut self, s: &str) {
         let message = Message::new(s).with_type(MessageType::Error);
-        self.status_message = Some(message);
+        self.push_message(message);
+    }
+
+    /// Logs a message and shows it in the status bar. If the current message has not been
+    /// rendered yet, or other messages are already waiting, the message is queued instead.
+    fn push_message(&mut self, mut message: Message) {
+        self.message_log.push(&message);
+
+        // Apply the configured timeout for the message type, falling back to the global timeout.
+        let timeouts = &self.state.config.editor.message_timeout;
+        let timeout = match message.message_type() {
+            MessageType::Info => timeouts.info,
+            MessageType::Warning => timeouts.warning,
+            MessageType::Error => timeouts.error,
+        };
+        let timeout = timeout.map(Duration::from_secs).or(self
+            .state
+            .config
+            .editor
+            .message_timeout_ms
+            .map(Duration::from_millis));
+        match timeout {
+            Some(Duration::ZERO) => message = message.sticky(),
+            Some(duration) => message = message.with_duration(duration),
+            None => {}
+        }
+
+        let replace_current = self.state.status_message.is_none()
+            || (self.status_message_rendered && self.message_queue.is_empty());
+        if replace_current {
+            self.state.status_message = Some(message);
+            self.status_message_rendered = false;
+            self.request_redraw();
+        } else {
+            self.message_queue.push_back(message);
+        }
+    }
+
+    /// Dismisses the current status message, showing the next queued message if there is one.
+    pub fn dismiss_message(&mut self) {
+        if self.state.status_message.is_some() {
+            self.next_message();
+        }
+    }
+
+    /// Replaces the current status message with the next queued message, or clears it.
+    fn next_message(&mut self) {
+        self.state.status_message = self.message_queue.pop_front().map(|mut message| {
+            message.restart();
+            message
+        });
+        self.status_message_rendered = false;
+        self.request_redraw();
+    }
+
+    /// Opens the log file in a read-only scratch buffer.
+    pub fn open_log(&mut self) {
+        let Some(path) = crate::logging::path() else {
+            self.show_warn_message("Logging is disabled, start the editor with --log <path>");
+            return;
+        };
+        crate::logging::flush();
+        match fs::read_to_string(path) {
+            Ok(text) => {
+                let buffer = self.buffer_manager.open_scratch("Log", &text);
+                self.pane_manager.open_pane(buffer);
+            }
+            Err(err) => self.show_err_message(&format!("Failed to read {}: {err}", path.display())),
+        }
+    }
+
+    /// Shows the message log in a read-only output buffer with the cursor on the newest message.
+    /// The buffer is reused and refreshed if it is already open.
+    pub fn open_message_log(&mut self) {
+        let text = self.message_log.text();
+        self.clear_output("Messages");
+        self.append_to_output("Messages", &text);
     }
 
     /// Exits command mode and cleans up the stored query.
     pub fn exit_command_mode(&mut self) {
         self.command_palette.clear_query();
-        self.mode = Mode::Insert;
+        self.state.mode = Mode::Insert;
     }
 
     /// Saves the active buffer.
     pub fn save_active_buffer<P: AsRef<Path>>(&mut self, path: Option<P>) -> Result<()> {
+        self.save_active_buffer_then(path, Rc::new(|_| Ok(())))
+    }
+
+    /// Saves the active buffer and runs `then` once it has been saved. If the user is asked
+    /// whether to overwrite an existing file or where to save instead, `then` runs after the
+    /// answer, and not at all if the buffer ends up unsaved.
+    fn save_active_buffer_then<P: AsRef<Path>>(
+        &mut self,
+        path: Option<P>,
+        then: AfterSave,
+    ) -> Result<()> {
         let path = path.map(|p| p.as_ref().to_path_buf());
 
         // It a path was given, attempt to save the buffer to that path, prompting to overwrite if
         // the file already exists. Otherwise, save the buffer to the current path.
-        if let Some(path) = path {
-            // TODO: Use eyre to handle errors instead of long matches.
-            if let Err(buffer::Error::SaveError(buffer::SaveError::FileAlreadyExists(_))) =
-                self.pane_manager.active_mut().save_as(&path, false)
-            {
+        let result = match &path {
+            Some(path) => self.pane_manager.active_mut().save_as(path, false),
+            None => self.pane_manager.active_mut().save(),
+        };
+        // TODO: Use eyre to handle errors instead of long matches.
+        match (result, path) {
+            (
+                Err(buffer::Error::SaveError(buffer::SaveError::FileAlreadyExists(_))),
+                Some(path),
+            ) => self.prompt_manager.show_prompt(
+                PromptType::Confirm(ConfirmPrompt::new(
+                    "File already exists, do you want to overwrite it?",
+                )),
+                move |editor, response| {
+                    if response == PromptResponse::Yes {
+                        let result = editor.pane_manager.active_mut().save_as(&path, true);
+                        editor.handle_save_result(result, Rc::clone(&then))?;
+                    }
+                    Ok(())
+                },
+            ),
+            (Err(buffer::Error::SaveError(buffer::SaveError::Lossy)), path) => {
                 self.prompt_manager.show_prompt(
                     PromptType::Confirm(ConfirmPrompt::new(
-                        "File already exists, do you want to overwrite it?",
+                        "File was not valid UTF-8, save it with the invalid bytes replaced?",
                     )),
                     move |editor, response| {
                         if response == PromptResponse::Yes {
-                            editor.pane_manager.active_mut().save_as(&path, true)?;
+                            editor.pane_manager.active_mut().discard_invalid_bytes();
+                            editor.save_active_buffer_then(path.as_ref(), Rc::clone(&then))?;
                         }
                         Ok(())
                     },
                 )
             }
-        } else {
-            self.pane_manager.active_mut().save()?;
+            (result, _) => self.handle_save_result(result, then)?,
+        }
+        Ok(())
+    }
+
+    /// Writes a copy of the active buffer to the given path, prompting to overwrite if the file
+    /// already exists. The buffer keeps its path and unsaved changes.
+    pub fn write_copy<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
+        let path = path.as_ref().to_path_buf();
+        match self.pane_manager.active().write_to(&path, false) {
+            Err(buffer::Error::SaveError(buffer::SaveError::FileAlreadyExists(_))) => {
+                self.prompt_manager.show_prompt(
+                    PromptType::Confirm(ConfirmPrompt::new(
+                        "File already exists, do you want to overwrite it?",
+                    )),
+                    move |editor, response| {
+                        if response == PromptResponse::Yes {
+                            editor.pane_manager.active().write_to(&path, true)?;
+                            editor.show_message(&format!("Wrote copy to {}", path.display()));
+                        }
+                        Ok(())
+                    },
+                );
+            }
+            result => {
+                result?;
+                self.show_message(&format!("Wrote copy to {}", path.display()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Finishes a save of the active buffer, running `then` if it succeeded. A save that failed
+    /// because of missing permissions is reported and the user is asked for another path to save
+    /// the buffer to. Other errors are returned.
+    fn handle_save_result(
+        &mut self,
+        result: std::result::Result<(), buffer::Error>,
+        then: AfterSave,
+    ) -> Result<()> {
+        match result {
+            Ok(()) => {
+                let pane = self.pane_manager.active();
+                if let Some(path) = self.active_absolute_path() {
+                    self.lsp.did_save(&pane.filetype(), &path);
+                }
+
+                // The file may have been saved into a different repository.
+                self.git_branch.invalidate();
+                then(self)
+            }
+            Err(buffer::Error::SaveError(err @ buffer::SaveError::PermissionDenied(_))) => {
+                self.show_err_message(&format!("{err} — try saving elsewhere"));
+                let path = self
+                    .pane_manager
+                    .active()
+                    .file_path()
+                    .map(|path| path.display().to_string())
+                    .unwrap_or_default();
+                self.prompt_manager.show_prompt(
+                    PromptType::Input(InputPrompt::new("save as", &path)),
+                    move |editor, response| {
+                        if let PromptResponse::Text(path) = response
+                            && !path.is_empty()
+                        {
+                            editor.save_active_buffer_then(Some(path), Rc::clone(&then))?;
+                        }
+                        Ok(())
+                    },
+                );
+                Ok(())
+            }
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Moves the cursor of the active pane to the previous position in its jump list. Jumps to
+    /// buffers that have since been closed are skipped.
+    pub fn jump_back(&mut self) {
+        let pane = self.pane_manager.active_mut();
+        let current = pane.current_jump();
+        while let Some(jump) = pane.jump_list.back(current) {
+            if let Some(buffer) = self.buffer_manager.get(jump.buffer_id) {
+                pane.restore_jump(jump, buffer);
+                return;
+            }
+        }
+    }
+
+    /// Moves the cursor of the active pane to the next position in its jump list. Jumps to
+    /// buffers that have since been closed are skipped.
+    pub fn jump_forward(&mut self) {
+        let pane = self.pane_manager.active_mut();
+        while let Some(jump) = pane.jump_list.forward() {
+            if let Some(buffer) = self.buffer_manager.get(jump.buffer_id) {
+                pane.restore_jump(jump, buffer);
+                return;
+            }
+        }
+    }
+
+    /// Handles a buffer modification and keeps the panes and marks showing the buffer anchored
+    /// to their rows.
+    pub fn handle_buffer_modification(&mut self, modification: &BufferModification) {
+        self.pane_manager.handle_buffer_modification(modification);
+        self.marks.handle_buffer_modification(modification);
+        if modification.action != BufferAction::None {
+            self.swap_files.mark_changed(modification.buffer_id);
+            self.word_index.invalidate(modification.buffer_id);
+        }
+
+        if modification.action != BufferAction::None
+            && let Some(entry) = self.buffer_manager.get(modification.buffer_id)
+        {
+            let buffer = entry.read_buf();
+            if let Some(path) = buffer
+                .filepath()
+                .and_then(|path| std::path::absolute(path).ok())
+            {
+                let filetype = buffer.filetype();
+                self.lsp
+                    .did_change(&filetype, &path, &modification.action, &buffer);
+            }
+        }
+    }
+
+    /// Sets the mark with the given name to the cursor position of the active pane.
+    pub fn set_mark(&mut self, name: char) {
+        let pane = self.pane_manager.active();
+        let mark = Mark {
+            jump: pane.current_jump(),
+            path: pane.file_path(),
+        };
+        self.marks.set(name, mark);
+        self.show_message(&format!("Set mark: {name}"));
+    }
+
+    /// Moves the cursor of the active pane to the mark with the given name. The pane switches to
+    /// the buffer of the mark, reopening it if it has been closed.
+    pub fn goto_mark(&mut self, name: char) -> Result<()> {
+        let Some(mark) = self.marks.get(name).cloned() else {
+            self.show_warn_message(&format!("No such mark: {name}"));
+            return Ok(());
+        };
+
+        let buffer = match (self.buffer_manager.get(mark.jump.buffer_id), &mark.path) {
+            (Some(buffer), _) => buffer,
+            (None, Some(path)) => {
+                let buffer = self.buffer_manager.open_new_or_existing_file(path)?;
+                self.marks.reassign_buffer(mark.jump.buffer_id, buffer.id);
+                buffer
+            }
+            (None, None) => {
+                self.show_warn_message(&format!("The buffer of mark {name} has been closed"));
+                return Ok(());
+            }
+        };
+
+        let pane = self.pane_manager.active_mut();
+        let from = pane.current_jump();
+        pane.jump_list.push(from);
+        pane.restore_jump(Jump::new(buffer.id, mark.jump.position), buffer);
+        Ok(())
+    }
+
+    /// Searches every open buffer for the query and lists the matches in a prompt. Selecting a
+    /// match shows its buffer in the active pane with the cursor on the match. Dirty buffers are
+    /// searched as they are in memory rather than as saved.
+    pub fn search_all_buffers(&mut self, query: &str) {
+        let mut matches = Vec::new();
+        for entry in self.buffer_manager.iter() {
+            let buffer = entry.read_buf();
+            let name = buffer.file_name();
+            for point in buffer.find_all(query) {
+                matches.push(SearchMatch {
+                    jump: Jump::new(entry.id, point),
+                    location: format!("{name}:{}:{}", point.row + 1, point.col + 1),
+                    preview: buffer
+                        .row(point.row)
+                        .map(Row::text)
+                        .unwrap_or_default()
+                        .to_string(),
+                });
+            }
+        }
+        self.search_history.push(query);
+
+        if matches.is_empty() {
+            self.show_message(&format!("No matches for {query}"));
+            return;
+        }
+        let count = matches.len();
+        let buffers = matches
+            .iter()
+            .map(|m| m.jump.buffer_id)
+            .collect::<HashSet<_>>()
+            .len();
+        self.show_message(&format!("{count} matches for {query} in {buffers} buffers"));
+
+        self.prompt_manager.show_prompt(
+            PromptType::Matches(MatchesPrompt::new(query, matches)),
+            |editor, response| {
+                if let PromptResponse::Jump(jump) = response
+                    && let Some(buffer) = editor.buffer_manager.get(jump.buffer_id)
+                {
+                    let pane = editor.pane_manager.active_mut();
+                    let from = pane.current_jump();
+                    pane.jump_list.push(from);
+                    pane.restore_jump(jump, buffer);
+                }
+                Ok(())
+            },
+        );
+    }
+
+    /// Parses the locations in compiler output, such as that of `cargo build`, and opens them in
+    /// the location list. The output is read from the file at the given path, or from the active
+    /// buffer if no path is given.
+    pub fn load_errorformat(&mut self, path: Option<&str>) {
+        let text = match path {
+            Some(path) => match fs::read_to_string(path) {
+                Ok(text) => text,
+                Err(err) => {
+                    self.show_err_message(&format!("Failed to read {path}: {err}"));
+                    return;
+                }
+            },
+            None => self.pane_manager.active().buffer.read_buf().text(),
+        };
+
+        let locations = parse_errorformat(&text);
+        if locations.is_empty() {
+            self.show_message("No locations found");
+            self.location_list = None;
+            return;
         }
+        self.show_message(&format!("Loaded {} locations", locations.len()));
+        self.location_list = Some(LocationList::new(locations));
+    }
+
+    /// Closes the location list.
+    pub fn close_location_list(&mut self) {
+        self.location_list = None;
+    }
+
+    /// Opens the next location in the location list, or the previous one if `forward` is `false`,
+    /// in the active pane. The list wraps around at either end.
+    pub fn goto_location(&mut self, forward: bool) -> Result<()> {
+        let Some(list) = &mut self.location_list else {
+            self.show_warn_message("The location list is empty");
+            return Ok(());
+        };
+        let next = if forward { list.next() } else { list.prev() };
+        let Some((location, wrapped)) = next else {
+            self.show_warn_message("The location list is empty");
+            return Ok(());
+        };
+        let location = location.clone();
+        let index = list.current().unwrap_or_default() + 1;
+        let len = list.len();
+        self.show_file(&location.path, location.position)?;
 
+        let message = format!("({index} of {len}) {}", location.message);
+        match (wrapped, forward) {
+            (true, true) => self.show_message(&format!("Wrapped to the first location {message}")),
+            (true, false) => self.show_message(&format!("Wrapped to the last location {message}")),
+            (false, _) => self.show_message(&message),
+        }
+        Ok(())
+    }
+
+    /// Shows the file in the active pane with the cursor at the given position, opening it if it
+    /// is not open yet. The previous position of the pane is pushed to its jump list.
+    fn show_file(&mut self, path: &Path, position: Point) -> Result<()> {
+        let opened = self.buffer_manager.get_buffer_by_path(path).is_none();
+        let buffer = self.buffer_manager.open_new_or_existing_file(path)?;
+        if opened {
+            self.lsp_did_open(&buffer);
+            self.check_swap_file(&buffer);
+        }
+        let pane = self.pane_manager.active_mut();
+        let from = pane.current_jump();
+        pane.jump_list.push(from);
+        pane.restore_jump(Jump::new(buffer.id, position), buffer);
         Ok(())
     }
 
+    /// Shows all marks in the status bar.
+    pub fn list_marks(&mut self) {
+        let marks: Vec<String> = self
+            .marks
+            .iter()
+            .map(|(name, mark)| {
+                let file_name = self
+                    .buffer_manager
+                    .get(mark.jump.buffer_id)
+                    .and_then(|entry| entry.buffer.try_read().map(|b| b.file_name()).ok())
+                    .or_else(|| {
+                        mark.path
+                            .as_ref()
+                            .map(|path| path.to_string_lossy().to_string())
+                    })
+                    .unwrap_or("[closed]".to_string());
+                let Point { col, row } = mark.jump.position;
+                format!("{name}:{file_name}:{}:{}", row + 1, col + 1)
+            })
+            .collect();
+
+        if marks.is_empty() {
+            self.show_message("No marks set");
+        } else {
+            self.show_message(&marks.join(" "));
+        }
+    }
+
+    /// Yanks the selection, or the current row if nothing is selected, into the registers.
+    pub fn yank(&mut self, name: Option<RegisterName>) {
+        let register = self.pane_manager.active_mut().yank();
+        self.registers.store(name, register);
+    }
+
+    /// Deletes the selection, or the current row if nothing is selected, storing the deleted text
+    /// in the registers.
+    pub fn delete(&mut self, name: Option<RegisterName>) {
+        let (register, modification) = self.pane_manager.active_mut().delete();
+        if modification.action != BufferAction::None {
+            self.registers.store(name, register);
+            self.handle_buffer_modification(&modification);
+        }
+    }
+
+    /// Pastes the contents of the given register, or of the unnamed register if none is given.
+    pub fn paste(&mut self, name: Option<RegisterName>) {
+        let Some(register) = self.registers.get(name).cloned() else {
+            self.show_warn_message("Register is empty");
+            return;
+        };
+        let modification = self.pane_manager.active_mut().paste(&register);
+        self.handle_buffer_modification(&modification);
+    }
+
+    /// Files larger than this many bytes require confirmation before being read into a buffer.
+    const LARGE_FILE_BYTES: u64 = 1024 * 1024;
+
+    /// Inserts the contents of the file at the given path at the cursor of the active pane.
+    /// Files that cannot be read are reported in the status bar, and large files are only
+    /// inserted after confirmation.
+    pub fn read_file(&mut self, path: &str) {
+        let metadata = match fs::metadata(path) {
+            Ok(metadata) => metadata,
+            Err(err) => {
+                self.show_err_message(&format!("Failed to read {path}: {err}"));
+                return;
+            }
+        };
+        if metadata.is_dir() {
+            self.show_err_message(&format!("{path} is a directory"));
+            return;
+        }
+
+        if metadata.len() <= Self::LARGE_FILE_BYTES {
+            self.insert_file(path);
+            return;
+        }
+        let path = path.to_string();
+        self.prompt_manager.show_prompt(
+            PromptType::Confirm(ConfirmPrompt::new(&format!(
+                "{path} is {} KiB, do you want to insert it?",
+                metadata.len() / 1024
+            ))),
+            move |editor, response| {
+                if response == PromptResponse::Yes {
+                    editor.insert_file(&path);
+                }
+                Ok(())
+            },
+        );
+    }
+
+    /// Reads the file at the given path and inserts it at the cursor of the active pane, leaving
+    /// the cursor after the inserted text.
+    fn insert_file(&mut self, path: &str) {
+        let text = match fs::read(path) {
+            Ok(bytes) => String::from_utf8_lossy(&bytes).replace("\r\n", "\n"),
+            Err(err) => {
+                self.show_err_message(&format!("Failed to read {path}: {err}"));
+                return;
+            }
+        };
+        if text.is_empty() {
+            return;
+        }
+
+        let register = Register::new(text, RegisterKind::Charwise);
+        let modification = self.pane_manager.active_mut().paste(&register);
+        self.handle_buffer_modification(&modification);
+    }
+
+    /// Opens a preview of the first line of each register in a read-only buffer.
+    pub fn list_registers(&mut self) {
+        let text = self
+            .registers
+            .iter()
+            .map(|(name, register)| {
+                let preview = register.text.lines().next().unwrap_or_default();
+                format!("{name:>2} {:<4} {preview}", register.kind)
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        if text.is_empty() {
+            self.show_message("All registers are empty");
+            return;
+        }
+
+        let buffer = self.buffer_manager.open_scratch("Registers", &text);
+        self.pane_manager.open_pane(buffer);
+    }
+
+    /// Adds `delta` to the integer under or after each cursor of the active pane.
+    pub fn add_to_number(&mut self, delta: i64) {
+        let mut changed = false;
+        let mut overflowed = false;
+        let modification = self.pane_manager.active_mut().edit_at_cursors(|pane| {
+            match pane.add_to_number(delta) {
+                Ok(modification) => {
+                    changed = true;
+                    modification
+                }
+                Err(err) => {
+                    overflowed |= err == NumberError::Overflow;
+                    BufferModification::new(pane.buffer_id(), BufferAction::None)
+                }
+            }
+        });
+        if changed {
+            self.handle_buffer_modification(&modification);
+        }
+        if overflowed {
+            self.show_err_message("The result does not fit in the number");
+        } else if !changed {
+            self.show_message("No number under or after the cursor");
+        }
+    }
+
+    /// Toggles whether the active pane scrolls along with the other scroll-bound panes showing
+    /// the same buffer. Unbound panes keep their current viewport and scroll on their own again.
+    pub fn toggle_scroll_bind(&mut self) {
+        let pane = self.pane_manager.active_mut();
+        pane.scroll_bound = !pane.scroll_bound;
+        if pane.scroll_bound {
+            self.show_message("Scroll binding enabled");
+        } else {
+            self.show_message("Scroll binding disabled");
+        }
+    }
+
+    /// Toggles line comments on the selected rows or the cursor row of the active pane.
+    pub fn toggle_comment(&mut self) {
+        let filetype = self.pane_manager.active().filetype();
+        let Some(token) = self.state.config.comment_token(&filetype) else {
+            self.show_warn_message(&format!("No comment token for filetype: {filetype}"));
+            return;
+        };
+
+        let modification = self.pane_manager.active_mut().toggle_comment(token);
+        self.handle_buffer_modification(&modification);
+    }
+
+    /// Lists the suggested corrections of the word under the cursor of the active pane, replacing
+    /// the word with the selected one.
+    pub fn spell_suggest(&mut self) {
+        let Some(spell) = &self.spell else {
+            self.show_warn_message("Spell checking is disabled");
+            return;
+        };
+        let Some((start, word)) = self.pane_manager.active().word_under_cursor() else {
+            self.show_message("No word under the cursor");
+            return;
+        };
+        if spell.is_correct(&word) {
+            self.show_message(&format!("{word} is spelled correctly"));
+            return;
+        }
+        let suggestions = spell.suggestions(&word);
+        if suggestions.is_empty() {
+            self.show_message(&format!("No suggestions for {word}"));
+            return;
+        }
+
+        let end = Point::new(start.col + word.chars().count(), start.row);
+        let prompt = ChoicePrompt::new(&format!("Replace {word} with"), suggestions.clone());
+        self.prompt_manager
+            .show_prompt(PromptType::Choice(prompt), move |editor, response| {
+                if let PromptResponse::Choice(index) = response
+                    && let Some(suggestion) = suggestions.get(index)
+                {
+                    let modification = editor
+                        .pane_manager
+                        .active_mut()
+                        .replace_range(start, end, suggestion);
+                    editor.handle_buffer_modification(&modification);
+                }
+                Ok(())
+            });
+    }
+
+    /// Adds the given word, or the word under the cursor of the active pane, to the user
+    /// dictionary.
+    pub fn add_to_dictionary(&mut self, word: Option<&str>) {
+        let word = match word {
+            Some(word) => word.to_string(),
+            None => match self.pane_manager.active().word_under_cursor() {
+                Some((_, word)) => word,
+                None => {
+                    self.show_message("No word under the cursor");
+                    return;
+                }
+            },
+        };
+        let Some(spell) = &mut self.spell else {
+            self.show_warn_message("Spell checking is disabled");
+            return;
+        };
+        match spell.add_word(&word) {
+            Ok(()) => {
+                self.compositor.invalidate();
+                self.show_message(&format!("Added {word} to the dictionary"));
+            }
+            Err(err) => {
+                self.show_err_message(&format!("Failed to add {word} to the dictionary: {err}"))
+            }
+        }
+    }
+
+    /// Opens the diagnostics of all documents in a read-only buffer.
+    pub fn show_diagnostics(&mut self) {
+        let mut text = String::new();
+        for (path, diagnostics) in self.lsp.diagnostics().iter() {
+            for diagnostic in diagnostics {
+                let Point { col, row } = diagnostic.start;
+                text.push_str(&format!(
+                    "{}:{}:{}: {}: {}\n",
+                    path.display(),
+                    row + 1,
+                    col + 1,
+                    diagnostic.severity,
+                    diagnostic.message
+                ));
+            }
+        }
+        if text.is_empty() {
+            self.show_message("No diagnostics");
+            return;
+        }
+
+        self.clear_output("Diagnostics");
+        self.append_to_output("Diagnostics", &text);
+    }
+
+    /// Appends text to the read-only output buffer with the given name, creating it on first
+    /// use. The buffer is opened in a new pane if no pane shows it, and the panes showing it are
+    /// scrolled to the end of the output.
+    pub fn append_to_output(&mut self, name: &str, text: &str) {
+        let buffer = match self.buffer_manager.get_scratch(name) {
+            Some(buffer) => buffer,
+            None => self.buffer_manager.open_scratch(name, ""),
+        };
+        let action = buffer.write_buf().append_output(text);
+        self.handle_buffer_modification(&BufferModification::new(buffer.id, action));
+
+        if !self.pane_manager.iter().any(|p| p.buffer_id() == buffer.id) {
+            self.pane_manager.open_pane(buffer.clone());
+        }
+        for pane in self
+            .pane_manager
+            .iter_mut()
+            .filter(|p| p.buffer_id() == buffer.id)
+        {
+            pane.move_cursor(CursorMovement::EndOfBuffer);
+        }
+    }
+
+    /// Removes the text of the output buffer with the given name, if it exists.
+    pub fn clear_output(&mut self, name: &str) {
+        let Some(buffer) = self.buffer_manager.get_scratch(name) else {
+            return;
+        };
+        let action = buffer.write_buf().clear_output();
+        self.handle_buffer_modification(&BufferModification::new(buffer.id, action));
+    }
+
+    /// Opens a unified diff of the unsaved changes of the active buffer against its file in a
+    /// read-only buffer, replacing the previous diff. Buffers without a file on disk are compared
+    /// against an empty file.
+    pub fn diff_unsaved(&mut self) {
+        let (name, text, path) = {
+            let buffer = self.pane_manager.active().buffer.read_buf();
+            (
+                buffer.file_name(),
+                buffer.text(),
+                buffer.filepath().map(Path::to_path_buf),
+            )
+        };
+        let saved = path
+            .and_then(|path| fs::read(path).ok())
+            .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+            .unwrap_or_default();
+        let Some(diff) = diff::unified_diff(
+            &diff::lines(&saved),
+            &diff::lines(&text),
+            &format!("{name} (saved)"),
+            &format!("{name} (unsaved)"),
+        ) else {
+            self.show_message("No unsaved changes");
+            return;
+        };
+
+        self.clear_output("Diff");
+        self.append_to_output("Diff", diff.strip_suffix('\n').unwrap_or(&diff));
+        let Some(buffer) = self.buffer_manager.get_scratch("Diff") else {
+            return;
+        };
+        buffer.write_buf().set_filetype("diff");
+        for pane in self
+            .pane_manager
+            .iter_mut()
+            .filter(|p| p.buffer_id() == buffer.id)
+        {
+            pane.move_cursor(CursorMovement::StartOfBuffer);
+        }
+    }
+
+    /// Moves the cursor of the active pane to the next diagnostic in its buffer, wrapping around
+    /// to the first one after the last.
+    pub fn goto_next_diagnostic(&mut self) {
+        let Some(path) = self.active_absolute_path() else {
+            self.show_message("No diagnostics");
+            return;
+        };
+        let diagnostics = self.lsp.diagnostics().get(&path);
+        let (col, row) = self.pane_manager.active().cursor_position();
+        let next = diagnostics
+            .iter()
+            .find(|d| (d.start.row, d.start.col) > (row, col))
+            .or(diagnostics.first());
+
+        match next {
+            Some(diagnostic) => {
+                let Point { col, row } = diagnostic.start;
+                let message = format!("{}: {}", diagnostic.severity, diagnostic.message);
+                self.pane_manager
+                    .active_mut()
+                    .move_cursor(CursorMovement::Position(col, row));
+                self.show_message(&message);
+            }
+            None => self.show_message("No diagnostics"),
+        }
+    }
+
     /// Closes the active pane. If the pane is dirty, prompts the user to save the pane
     /// before closing it.
     pub fn close_active_pane(&mut self) -> Result<()> {
         // If there are multiple panes with the same buffer id, only close the active pane.
         let active_buffer_id = self.pane_manager.active().buffer_id();
         if !self.pane_manager.is_unique(active_buffer_id) {
-            self.pane_manager.close_active();
+            self.pane_manager.close_active(&mut self.buffer_manager);
             return Ok(());
         }
 
         self.close_buffer(active_buffer_id)
     }
 
+    /// Saves the active buffer and closes its pane, quitting the editor if it was the last pane.
+    /// A buffer without a path is saved to a path asked for in a prompt. The pane is kept open if
+    /// the buffer could not be saved.
+    pub fn write_quit<P: AsRef<Path>>(&mut self, path: Option<P>) -> Result<()> {
+        if path.is_none() && self.pane_manager.active().file_path().is_none() {
+            self.prompt_manager.show_prompt(
+                PromptType::Input(InputPrompt::new("save as", "")),
+                |editor, response| {
+                    if let PromptResponse::Text(path) = response
+                        && !path.is_empty()
+                    {
+                        editor.write_quit(Some(path))?;
+                    }
+                    Ok(())
+                },
+            );
+            return Ok(());
+        }
+        self.save_active_buffer_then(path, Rc::new(Self::close_saved_pane))
+    }
+
+    /// Closes the active pane once `WriteQuit` saved it, quitting the editor if it was the last
+    /// pane.
+    fn close_saved_pane(&mut self) -> Result<()> {
+        if self.pane_manager.num_panes() == 1 {
+            self.should_quit = true;
+            return Ok(());
+        }
+        self.close_active_pane()
+    }
+
     /// Closes the buffer with the given id prompting the user to save the buffer if it is dirty.
     pub fn close_buffer(&mut self, id: usize) -> Result<()> {
         if !self.pane_manager.active().is_dirty() {
-            self.pane_manager.close_active();
+            self.pane_manager.close_active(&mut self.buffer_manager);
             self.buffer_manager.close(id);
             return Ok(());
         }
@@ -405,13 +2217,18 @@ impl Editor {
             )),
             move |editor, response| {
                 match response {
-                    PromptResponse::Yes => {
-                        editor.save_active_buffer(None::<&str>)?;
-                        editor.pane_manager.close_active();
-                        editor.buffer_manager.close(id);
-                    }
+                    // The buffer is closed only once it has been saved, which may take further
+                    // prompts.
+                    PromptResponse::Yes => editor.save_active_buffer_then(
+                        None::<&str>,
+                        Rc::new(move |editor| {
+                            editor.pane_manager.close_active(&mut editor.buffer_manager);
+                            editor.buffer_manager.close(id);
+                            Ok(())
+                        }),
+                    )?,
                     PromptResponse::No => {
-                        editor.pane_manager.close_active();
+                        editor.pane_manager.close_active(&mut editor.buffer_manager);
                         editor.buffer_manager.close(id);
                     }
                     _ => return Ok(()),
@@ -422,35 +2239,832 @@ impl Editor {
         Ok(())
     }
 
+    /// Suspends the editor to the shell. The terminal is restored before the process is stopped
+    /// and reinitialized once it is resumed.
+    #[cfg(unix)]
+    pub fn suspend(&mut self) -> Result<()> {
+        self.renderer.deinitialize()?;
+        // SAFETY: `kill` has no memory safety requirements. Sending `SIGTSTP` to the process
+        // group stops the process until it receives `SIGCONT`, at which point execution
+        // continues here.
+        unsafe {
+            libc::kill(0, libc::SIGTSTP);
+        }
+        self.renderer.reinitialize()?;
+        Ok(())
+    }
+
+    /// Suspends the editor to the shell. Suspending is not supported on this platform.
+    #[cfg(not(unix))]
+    pub fn suspend(&mut self) -> Result<()> {
+        self.show_warn_message("Suspending is not supported on this platform");
+        Ok(())
+    }
+
+    /// Enables or disables capturing of mouse events, handing the mouse back to the terminal while
+    /// disabled.
+    pub fn toggle_mouse(&mut self) -> Result<()> {
+        let enabled = !self.renderer.mouse_capture();
+        self.renderer.set_mouse_capture(enabled)?;
+        if enabled {
+            self.show_message("Mouse capture enabled");
+        } else {
+            self.show_message("Mouse capture disabled");
+        }
+        Ok(())
+    }
+
+    /// Shows or hides the debug overlay with the rendering statistics of recent frames.
+    pub fn toggle_debug_overlay(&mut self) {
+        match self.debug_overlay.take() {
+            Some(id) => {
+                self.compositor.remove_layer(id);
+                self.render_stats = None;
+            }
+            None => {
+                let id = self
+                    .compositor
+                    .push_layer(Z_DEBUG, LayerArea::Editor, DebugOverlay);
+                self.debug_overlay = Some(id);
+                self.render_stats = Some(RenderStats::default());
+            }
+        }
+        self.request_redraw();
+    }
+
     /// Exits the editor.
     pub fn exit(&mut self) -> Result<()> {
+        self.swap_files.flush(&self.buffer_manager);
         self.renderer.deinitialize()?;
         Ok(())
     }
 
+    /// Marks the editor as changed so that it is rendered on the next iteration of the main loop.
+    pub fn request_redraw(&mut self) {
+        self.needs_redraw = true;
+    }
+
+    /// Returns the description of the command selected in the command palette while in command
+    /// mode, or `None` otherwise.
+    fn palette_hint(&self) -> Option<String> {
+        (self.state.mode == Mode::Command)
+            .then(|| self.command_palette.selection_hint())
+            .flatten()
+    }
+
     /// Updates the state of the editor.
     pub fn update(&mut self) -> Result<()> {
-        // Check if the message has timed out. If so, clear it.
-        if let Some(message) = &self.status_message
+        // Check if the message has timed out. If so, show the next queued message or clear it.
+        // Messages hidden by the palette hint are kept until the palette is closed.
+        if self.palette_hint().is_some() {
+            if let Some(message) = &mut self.state.status_message {
+                message.restart();
+            }
+        } else if let Some(message) = &self.state.status_message
             && message.timed_out()
         {
-            self.status_message = None;
+            self.next_message();
+        }
+
+        // Reload the configuration once its file has changed.
+        if self.state.config.editor.watch_config {
+            let path = self
+                .config_path
+                .clone()
+                .or_else(|| Config::get_config_path().ok());
+            if let Some(path) = path
+                && self.config_watcher.poll(&path)
+            {
+                self.reload_config()?;
+            }
+        }
+
+        if self.state.config.editor.auto_reload {
+            self.reload_changed_files();
+        }
+
+        // Write the swap files of dirty buffers and offer to recover those found on opening.
+        self.swap_files.update(&self.buffer_manager);
+        if !self.prompt_manager.is_active() {
+            self.offer_swap_recovery();
+        }
+
+        // Refresh the git branch of the active buffer if needed.
+        let file_path = self.pane_manager.active().file_path();
+        if self.git_branch.refresh(file_path.as_deref()) {
+            self.request_redraw();
+        }
+
+        // Apply the diagnostics and progress reported by language servers.
+        for event in self.lsp.poll() {
+            match event {
+                LspEvent::Progress { filetype, progress } => self.lsp_progress(filetype, progress),
+                LspEvent::Exited { filetype } => {
+                    self.lsp_tasks.retain(|(task_filetype, _), (id, _)| {
+                        let keep = *task_filetype != filetype;
+                        if !keep {
+                            self.tasks.unregister(*id);
+                        }
+                        keep
+                    });
+                    self.show_warn_message(&format!("The {filetype} language server exited"));
+                }
+                // The gutter shows the diagnostics of the panes.
+                LspEvent::Diagnostics { .. } => self.compositor.invalidate(),
+            }
+            self.request_redraw();
+        }
+
+        // Clear the highlights of yanked and pasted ranges once they expire.
+        let mut flash_expired = false;
+        for pane in self.pane_manager.iter_mut() {
+            flash_expired |= pane.clear_expired_flash();
+        }
+        if flash_expired {
+            self.request_redraw();
+        }
+
+        // Advance the spinner of the running tasks.
+        if !self.tasks.is_empty() {
+            self.request_redraw();
         }
 
         Ok(())
     }
 
-    /// Creates a new rendering context from the editor and calls the renderer.
+    /// Reloads the buffers without unsaved changes whose files changed on disk. Buffers with
+    /// unsaved changes are left as they are with a warning.
+    fn reload_changed_files(&mut self) {
+        let entries: Vec<BufferEntry> = self.buffer_manager.iter().cloned().collect();
+        self.file_watchers
+            .retain(|id, _| entries.iter().any(|entry| entry.id == *id));
+
+        for entry in entries {
+            let Some(path) = entry.read_buf().filepath().map(Path::to_path_buf) else {
+                continue;
+            };
+            if !self.file_watchers.entry(entry.id).or_default().poll(&path) {
+                continue;
+            }
+
+            let mut buffer = entry.write_buf();
+            let name = buffer.file_name();
+            if buffer.is_dirty() {
+                drop(buffer);
+                self.show_warn_message(&format!("{name} changed on disk and has unsaved changes"));
+                continue;
+            }
+            let result = buffer.reload();
+            drop(buffer);
+            match result {
+                // The file was written with the same contents, e.g. by saving the buffer.
+                Ok(BufferAction::None) => {}
+                Ok(action) => {
+                    let modification = BufferModification::new(entry.id, action);
+                    self.handle_buffer_modification(&modification);
+                    for pane in self.pane_manager.iter_mut() {
+                        if pane.buffer_id() == entry.id {
+                            pane.clamp_cursor();
+                        }
+                    }
+                    tracing::info!(path = %path.display(), "reloaded file");
+                    self.show_message(&format!("Reloaded {name}"));
+                }
+                Err(err) => self.show_err_message(&format!("Failed to reload {name}: {err}")),
+            }
+        }
+    }
+
+    /// Returns the terminal title for the active pane, e.g. `tedit — src/main.rs [+]`.
+    fn title(&self) -> String {
+        let active_pane = self.pane_manager.active();
+        let dirty_marker = if active_pane.is_dirty() { " [+]" } else { "" };
+        format!("tedit — {}{dirty_marker}", active_pane.file_name())
+    }
+
+    /// Creates a new rendering context from the editor and calls the renderer. Does nothing if the
+    /// editor has not changed since the last render.
     pub fn render(&mut self) -> Result<()> {
+        // Nothing changed since the last frame.
+        if !self.needs_redraw {
+            return Ok(());
+        }
+
         let (width, height) = self.backend.size()?;
         let editor_view = Rect::new(0, 0, width, height);
-        let rendering_context = RenderingContext::new(&*self, editor_view);
+        if self.state.config.editor.set_title {
+            self.renderer.set_title(&self.title())?;
+        }
+
+        let start = Instant::now();
+        let palette_hint = self.palette_hint();
+        let hides_message = palette_hint.is_some();
+        let rendering_context = RenderingContext {
+            state: &self.state,
+            pane_manager: &self.pane_manager,
+            buffer_manager: &self.buffer_manager,
+            git_branch: self.git_branch.branch(),
+            diagnostics: self.lsp.diagnostics(),
+            render_stats: self.render_stats.as_ref(),
+            tasks: &self.tasks,
+            location_list: self.location_list.as_ref(),
+            completion: self.completion.as_ref(),
+            explorer: self.explorer.as_ref(),
+            hex_file: self.hex_file.as_ref(),
+            spell: self.spell.as_ref(),
+            palette_hint,
+            welcome: self.welcome_hints(),
+            editor_view,
+        };
         let frame = self.compositor.compose_frame(
             &rendering_context,
             &mut self.prompt_manager,
             &mut self.command_palette,
         );
+        let composed = Instant::now();
         self.renderer.render(frame)?;
+        tracing::trace!(elapsed = ?start.elapsed(), "rendered frame");
+
+        if let Some(stats) = &mut self.render_stats {
+            stats.record(FrameStats {
+                compose: composed - start,
+                render: composed.elapsed(),
+                changed_cells: self.renderer.changed_cells(),
+                size: (width, height),
+            });
+        }
+        // A message hidden by the palette hint has not been seen yet.
+        if !hides_message {
+            self.status_message_rendered = self.state.status_message.is_some();
+        }
+        self.needs_redraw = false;
         Ok(())
     }
 }
+
+/// Returns `true` if both events are presses or repeats of the same key with the same modifiers.
+fn is_same_key(a: &Event, b: &Event) -> bool {
+    match (a, b) {
+        (Event::Key(a), Event::Key(b)) => {
+            a.code == b.code && a.modifiers == b.modifiers && b.kind != KeyEventKind::Release
+        }
+        _ => false,
+    }
+}
+
+/// Returns the name of the kind of the event, as shown in the debug overlay.
+fn event_kind(event: &Event) -> &'static str {
+    match event {
+        Event::Key(_) => "key",
+        Event::Mouse(_) => "mouse",
+        Event::Paste(_) => "paste",
+        Event::Resize(..) => "resize",
+        Event::FocusGained => "focus gained",
+        Event::FocusLost => "focus lost",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, io};
+
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    use super::*;
+    use crate::editor::backend::test_backend::{TestBackend, TestCell};
+
+    /// A directory in the system temporary directory that is removed when dropped.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let path =
+                std::env::temp_dir().join(format!("tedit-editor-{name}-{}", std::process::id()));
+            fs::create_dir_all(&path).unwrap();
+            Self(path)
+        }
+
+        /// Writes a file into the directory and returns its path.
+        fn write(&self, name: &str, contents: &str) -> PathBuf {
+            let path = self.0.join(name);
+            fs::write(&path, contents).unwrap();
+            path
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    /// Returns a key press without modifiers.
+    fn key(code: KeyCode) -> Event {
+        Event::Key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    /// Returns a key press with the Control modifier.
+    fn ctrl(c: char) -> Event {
+        Event::Key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL))
+    }
+
+    /// Returns the key presses that type the text.
+    fn keys(text: &str) -> Vec<Event> {
+        text.chars()
+            .map(|c| match c {
+                '\n' => key(KeyCode::Enter),
+                c => key(KeyCode::Char(c)),
+            })
+            .collect()
+    }
+
+    /// Starts an editor with the configuration and files on a test backend of the given size.
+    fn editor(
+        dir: &TempDir,
+        config: &str,
+        files: &[PathBuf],
+        size: (usize, usize),
+    ) -> (Editor, TestBackend) {
+        let config = dir.write("config.toml", config);
+        let files = (!files.is_empty()).then(|| files.to_vec());
+        let backend = TestBackend::new(size.0, size.1);
+        let editor = Editor::with_backends(
+            files,
+            Some(config),
+            Box::new(backend.clone()),
+            Box::new(backend.clone()),
+        )
+        .unwrap();
+        (editor, backend)
+    }
+
+    /// Returns the cells of the backend, row by row.
+    fn cells(backend: &TestBackend) -> Vec<TestCell> {
+        let (width, height) = backend.size().unwrap();
+        (0..height)
+            .flat_map(|row| (0..width).map(move |col| backend.cell(col, row).unwrap()))
+            .collect()
+    }
+
+    /// Handles the events, rendering after each of them, until they run out.
+    fn run(editor: &mut Editor, backend: &TestBackend, events: impl IntoIterator<Item = Event>) {
+        backend.push_events(events);
+        // The test backend fails to read once the events run out, after the last frame has been
+        // rendered.
+        match editor.run() {
+            Err(Error::BackendError(err)) if err.kind() == io::ErrorKind::UnexpectedEof => {}
+            result => panic!("editor stopped before the events ran out: {result:?}"),
+        }
+    }
+
+    #[test]
+    fn typed_text_is_rendered_with_the_cursor_after_it() {
+        let dir = TempDir::new("typing");
+        let (mut editor, backend) = editor(&dir, "", &[], (40, 8));
+        run(&mut editor, &backend, keys("hello\nwörld"));
+
+        let rows = backend.rows();
+        assert_eq!(rows[0].trim_end(), "   1 hello");
+        assert_eq!(rows[1].trim_end(), "   2 wörld");
+        assert!(rows[2].trim().is_empty());
+        assert_eq!(backend.cursor(), Some(Point::new(10, 1)));
+        assert_eq!(backend.cell(6, 1).unwrap().symbol, "ö");
+    }
+
+    #[test]
+    fn cursor_movements_follow_the_buffer() {
+        let dir = TempDir::new("movement");
+        let file = dir.write("a.txt", "first\nsecond row\nthird");
+        let (mut editor, backend) = editor(&dir, "", &[file], (40, 8));
+        run(
+            &mut editor,
+            &backend,
+            [key(KeyCode::Down), key(KeyCode::End), key(KeyCode::Down)],
+        );
+
+        // The cursor is clamped to the end of the shorter row below.
+        assert_eq!(editor.pane_manager.active().cursor_position(), (5, 2));
+        assert_eq!(backend.cursor(), Some(Point::new(10, 2)));
+        assert_eq!(backend.rows()[2].trim_end(), "   3 third");
+    }
+
+    #[test]
+    fn status_bar_and_title_show_the_buffer_state() {
+        let dir = TempDir::new("status");
+        let (mut editor, backend) = editor(&dir, "[editor]\nset_title = true\n", &[], (40, 8));
+        run(&mut editor, &backend, keys("a"));
+
+        assert_eq!(backend.title(), "tedit — [No Filename] [+]");
+        let status = &backend.rows()[7];
+        assert!(status.starts_with(" INS "), "{status:?}");
+        assert!(status.trim_end().ends_with("All  1 lines"), "{status:?}");
+        assert_ne!(
+            backend.cell(0, 7).unwrap().style,
+            backend.cell(5, 0).unwrap().style
+        );
+    }
+
+    #[test]
+    fn quit_stops_the_editor() {
+        let dir = TempDir::new("quit");
+        let (mut editor, backend) = editor(&dir, "", &[], (40, 8));
+        backend.push_events([ctrl('q')]);
+        editor.run().unwrap();
+        assert!(editor.should_quit);
+    }
+
+    #[test]
+    fn message_log_reuses_its_buffer() {
+        let dir = TempDir::new("messages");
+        let (mut editor, _backend) = editor(&dir, "", &[], (40, 8));
+        editor.show_message("first");
+        editor.open_message_log();
+        editor.show_message("second");
+        editor.open_message_log();
+
+        let messages: Vec<_> = editor
+            .buffer_manager
+            .iter()
+            .filter(|entry| entry.read_buf().file_name() == "[Messages]")
+            .collect();
+        assert_eq!(messages.len(), 1);
+        let text = messages[0].read_buf().text();
+        assert!(
+            text.contains("first") && text.contains("second"),
+            "{text:?}"
+        );
+        assert_eq!(editor.pane_manager.num_panes(), 2);
+    }
+
+    #[test]
+    fn repeated_cursor_movements_render_once() {
+        let dir = TempDir::new("burst");
+        let text = (1..=100)
+            .map(|i| i.to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let file = dir.write("a.txt", &text);
+        let (mut editor, backend) = editor(&dir, "", &[file], (40, 8));
+        run(&mut editor, &backend, vec![key(KeyCode::Down); 50]);
+
+        assert_eq!(editor.pane_manager.active().cursor_position(), (0, 50));
+        // The first frame is rendered before the burst, and the second once it was handled.
+        assert_eq!(backend.frames(), 2);
+        assert_eq!(backend.rows()[6].trim_end(), "   51  51");
+    }
+
+    #[test]
+    fn frames_render_the_buffer_status_bar_and_prompt() {
+        let dir = TempDir::new("frames");
+        let (mut editor, backend) = editor(&dir, "", &[], (40, 6));
+        run(&mut editor, &backend, keys("fn main() {}\n"));
+        assert_eq!(
+            backend.rows(),
+            [
+                "   1 fn main() {}                       ",
+                "   2                                    ",
+                "                                        ",
+                "                                        ",
+                "                                        ",
+                " INS  [No File              All  2 lines",
+            ]
+        );
+
+        run(&mut editor, &backend, [ctrl('f')]);
+        run(&mut editor, &backend, keys("main"));
+        assert_eq!(
+            backend.rows(),
+            [
+                "   1 fn main() {}                       ",
+                "   2                                    ",
+                "                                        ",
+                "                                        ",
+                "search: main                            ",
+                " INS  [No File              All  2 lines",
+            ]
+        );
+        assert_eq!(backend.cursor(), Some(Point::new(5, 1)));
+    }
+
+    #[test]
+    fn incremental_frames_match_full_redraws() {
+        let dir = TempDir::new("redraws");
+        let file = dir.write("a.txt", "fn main() {}\n");
+        let (mut editor, backend) = editor(&dir, "", &[file], (50, 10));
+        let steps = [
+            vec![ctrl('b')],
+            vec![key(KeyCode::Esc)],
+            vec![ctrl('p')],
+            keys("sav"),
+            vec![key(KeyCode::Esc), ctrl('f')],
+            keys("main"),
+        ];
+        for (i, events) in steps.into_iter().enumerate() {
+            run(&mut editor, &backend, events);
+            let incremental = cells(&backend);
+            // Regaining focus redraws every cell.
+            run(&mut editor, &backend, [Event::FocusGained]);
+            assert!(
+                cells(&backend) == incremental,
+                "frames differ after step {i}"
+            );
+        }
+    }
+
+    #[test]
+    fn components_larger_than_the_terminal_are_clipped() {
+        let dir = TempDir::new("tiny");
+        let (mut editor, backend) = editor(&dir, "[editor]\nshow_tabline = true\n", &[], (5, 3));
+        run(&mut editor, &backend, keys("hello\nworld"));
+        assert_eq!(backend.rows(), [" [No ", "   2 ", " I  s"]);
+
+        // The explorer, command palette, search prompt and file picker are all cut off, and the
+        // cursor is hidden when it would be outside the terminal.
+        let steps = [
+            vec![ctrl('b')],
+            vec![key(KeyCode::Esc), ctrl('p')],
+            keys("save"),
+            vec![key(KeyCode::Esc), ctrl('f')],
+            vec![key(KeyCode::Esc), ctrl('t')],
+        ];
+        for events in steps {
+            run(&mut editor, &backend, events);
+            assert_eq!(backend.rows().len(), 3);
+            assert!(
+                backend
+                    .cursor()
+                    .is_none_or(|cursor| cursor.col < 5 && cursor.row < 3)
+            );
+        }
+    }
+
+    #[test]
+    fn confirm_prompts_shown_by_callbacks_are_answered_in_turn() {
+        let dir = TempDir::new("prompts");
+        let (mut editor, backend) = editor(&dir, "", &[], (60, 12));
+        let responses = Rc::new(RefCell::new(Vec::new()));
+
+        let outer = Rc::clone(&responses);
+        editor.prompt_manager.show_prompt(
+            PromptType::Confirm(ConfirmPrompt::new("First question?")),
+            move |editor, response| {
+                outer.borrow_mut().push(("first", response));
+                let inner = Rc::clone(&outer);
+                editor.prompt_manager.show_prompt(
+                    PromptType::Confirm(ConfirmPrompt::new("Second question?")),
+                    move |_, response| {
+                        inner.borrow_mut().push(("second", response));
+                        Ok(())
+                    },
+                );
+                Ok(())
+            },
+        );
+
+        run(&mut editor, &backend, keys("y"));
+        assert_eq!(*responses.borrow(), [("first", PromptResponse::Yes)]);
+        assert!(editor.prompt_manager.is_active());
+        let rows = backend.rows();
+        assert!(rows.iter().any(|row| row.contains("Second question?")));
+        assert!(!rows.iter().any(|row| row.contains("First question?")));
+
+        run(&mut editor, &backend, keys("n"));
+        assert_eq!(
+            *responses.borrow(),
+            [
+                ("first", PromptResponse::Yes),
+                ("second", PromptResponse::No)
+            ]
+        );
+        assert!(!editor.prompt_manager.is_active());
+        assert!(!backend.rows().iter().any(|row| row.contains("question?")));
+        // The answers were not typed into the buffer.
+        assert_eq!(editor.pane_manager.active().cursor_position(), (0, 0));
+    }
+
+    #[test]
+    fn gutter_width_follows_the_buffer_of_each_pane() {
+        let dir = TempDir::new("gutter-panes");
+        let large = dir.write("large.txt", &vec!["x"; 1000].join("\n"));
+        let small = dir.write("small.txt", &vec!["y"; 50].join("\n"));
+        let (mut editor, backend) = editor(&dir, "", &[large, small], (40, 8));
+        run(&mut editor, &backend, []);
+
+        // The panes split the width in two, below their title bars.
+        assert_eq!(backend.cell(8, 1).unwrap().symbol, "x");
+        assert_eq!(backend.cell(26, 1).unwrap().symbol, "y");
+        assert_eq!(backend.cursor(), Some(Point::new(26, 1)));
+
+        let mut events = vec![ctrl('p')];
+        events.extend(keys("closepane\n"));
+        run(&mut editor, &backend, events);
+        assert_eq!(backend.rows()[0].trim_end(), "     1  x");
+        assert_eq!(backend.cursor(), Some(Point::new(8, 0)));
+    }
+
+    #[test]
+    fn gutter_width_changes_when_typing_crosses_a_digit_boundary() {
+        let dir = TempDir::new("gutter-digits");
+        let file = dir.write("rows.txt", &vec!["row"; 99].join("\n"));
+        let (mut editor, backend) = editor(&dir, "", &[file], (40, 8));
+        run(&mut editor, &backend, [ctrl('e')]);
+        assert_eq!(backend.rows()[6].trim_end(), "  99  row");
+        assert_eq!(backend.cursor(), Some(Point::new(6, 6)));
+
+        // The new row widens the gutter of every visible row, and the cursor moves along.
+        run(&mut editor, &backend, [key(KeyCode::Enter)]);
+        let rows = backend.rows();
+        assert_eq!(rows[0].trim_end(), "   94  row");
+        assert_eq!(rows[5].trim_end(), "   99");
+        assert_eq!(rows[6].trim_end(), "  100  row");
+        assert_eq!(backend.cursor(), Some(Point::new(7, 6)));
+
+        // Removing it narrows the gutter again.
+        run(&mut editor, &backend, [key(KeyCode::Backspace)]);
+        let rows = backend.rows();
+        assert_eq!(rows[0].trim_end(), "  94  row");
+        assert_eq!(rows[5].trim_end(), "  99  row");
+        assert_eq!(backend.cursor(), Some(Point::new(6, 5)));
+
+        // Saving removes the swap file written for the edits.
+        run(&mut editor, &backend, [ctrl('s')]);
+        assert!(!editor.pane_manager.active().is_dirty());
+    }
+
+    #[test]
+    fn inserted_wide_and_combining_characters_are_rendered_in_place() {
+        let dir = TempDir::new("insert-char");
+        let (mut editor, backend) = editor(&dir, "", &[], (40, 8));
+        let mut events = keys("a");
+        for arg in ["U+754C", "101", "u+0301", "nbsp", "U+D800"] {
+            events.push(ctrl('p'));
+            events.extend(keys(&format!("insertchar {arg}\n")));
+        }
+        run(&mut editor, &backend, events);
+
+        let pane = editor.pane_manager.active();
+        assert_eq!(pane.buffer.read_buf().text(), "a界e\u{301}\u{a0}");
+        assert!(pane.is_dirty());
+        // The cursor is after the five characters. They take five columns, since the wide
+        // character takes two and the accent is combined with the `e` before it.
+        assert_eq!(pane.cursor_position(), (5, 0));
+        assert_eq!(backend.cursor(), Some(Point::new(10, 0)));
+
+        let symbols: Vec<_> = (5..11)
+            .map(|col| backend.cell(col, 0).unwrap().symbol)
+            .collect();
+        assert_eq!(symbols, ["a", "界", "", "e\u{301}", "\u{a0}", " "]);
+        assert!(
+            editor
+                .message_log
+                .text()
+                .contains("U+D800 is not a valid character")
+        );
+    }
+
+    /// Returns the events that close the active pane from the command palette.
+    fn close_pane() -> Vec<Event> {
+        let mut events = vec![ctrl('p')];
+        events.extend(keys("closepane\n"));
+        events
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn closing_a_buffer_that_cannot_be_saved_keeps_it() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = TempDir::new("close-read-only");
+        let read_only = dir.0.join("read-only");
+        fs::create_dir_all(&read_only).unwrap();
+        let file = read_only.join("a.txt");
+        fs::write(&file, "text").unwrap();
+        let other = dir.write("b.txt", "other");
+        // The file is read-only as well, since root may write into read-only directories.
+        fs::set_permissions(&file, fs::Permissions::from_mode(0o444)).unwrap();
+        fs::set_permissions(&read_only, fs::Permissions::from_mode(0o555)).unwrap();
+
+        let (mut editor, backend) = editor(&dir, "", &[file.clone(), other], (60, 10));
+        editor.pane_manager.set_active(0).unwrap();
+        let mut events = keys("x");
+        events.extend(close_pane());
+        events.extend(keys("y"));
+        run(&mut editor, &backend, events);
+
+        // The save was refused, so the buffer stays open while asking where to save it.
+        assert_eq!(editor.pane_manager.num_panes(), 2);
+        let pane = editor.pane_manager.active();
+        assert_eq!(pane.file_path(), Some(file.clone()));
+        assert!(pane.is_dirty());
+        assert!(editor.prompt_manager.is_active());
+
+        run(&mut editor, &backend, [key(KeyCode::Esc)]);
+        assert_eq!(editor.pane_manager.num_panes(), 2);
+        assert_eq!(
+            editor.pane_manager.active().buffer.read_buf().text(),
+            "xtext"
+        );
+        assert_eq!(fs::read_to_string(&file).unwrap(), "text");
+
+        fs::set_permissions(&read_only, fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    #[test]
+    fn closing_a_lossy_buffer_waits_for_the_lossy_save_to_be_confirmed() {
+        let dir = TempDir::new("close-lossy");
+        let file = dir.0.join("latin1.txt");
+        fs::write(&file, b"caf\xe9").unwrap();
+        let other = dir.write("b.txt", "other");
+        let (mut editor, backend) = editor(&dir, "", &[file.clone(), other], (60, 10));
+        editor.pane_manager.set_active(0).unwrap();
+        let mut events = keys("x");
+        events.extend(close_pane());
+        events.extend(keys("y"));
+        run(&mut editor, &backend, events);
+
+        // The buffer stays open while asking whether to replace the invalid bytes.
+        assert_eq!(editor.pane_manager.num_panes(), 2);
+        assert_eq!(editor.pane_manager.active().file_path(), Some(file.clone()));
+        assert!(editor.pane_manager.active().is_dirty());
+        assert!(
+            backend
+                .rows()
+                .iter()
+                .any(|row| row.contains("not valid UTF-8"))
+        );
+        assert_eq!(fs::read(&file).unwrap(), b"caf\xe9");
+
+        run(&mut editor, &backend, keys("y"));
+        assert_eq!(editor.pane_manager.num_panes(), 1);
+        assert!(!editor.prompt_manager.is_active());
+        assert_eq!(fs::read_to_string(&file).unwrap(), "xcaf\u{fffd}");
+    }
+
+    #[test]
+    fn swap_files_are_written_next_to_the_configuration() {
+        let dir = TempDir::new("swap-dir");
+        let file = dir.write("a.txt", "text");
+        // The swap file is only recoverable if it is newer than the file.
+        let past = std::time::SystemTime::now() - Duration::from_secs(60);
+        fs::File::options()
+            .write(true)
+            .open(&file)
+            .and_then(|f| f.set_modified(past))
+            .unwrap();
+        let (mut editor, backend) = editor(&dir, "", std::slice::from_ref(&file), (40, 8));
+        run(&mut editor, &backend, keys("x"));
+
+        let swap = editor.swap_files.swap_path(&file).unwrap();
+        assert_eq!(swap.parent(), Some(dir.0.join("swap").as_path()));
+        assert_eq!(fs::read_to_string(&swap).unwrap(), "xtext");
+        assert_eq!(editor.swap_files.recoverable(&file), Some(swap));
+    }
+
+    /// Returns the average time spent composing a frame after typing a character in the active
+    /// pane, optionally invalidating every pane before each frame.
+    fn average_compose_time(editor: &mut Editor, frames: u32, invalidate: bool) -> Duration {
+        let mut total = Duration::ZERO;
+        for _ in 0..frames {
+            let modification = editor.pane_manager.active_mut().insert_char('x');
+            editor.handle_buffer_modification(&modification);
+            if invalidate {
+                editor.compositor.invalidate();
+            }
+            editor.request_redraw();
+            editor.render().unwrap();
+            total += editor
+                .render_stats
+                .as_ref()
+                .unwrap()
+                .last()
+                .unwrap()
+                .compose;
+        }
+        total / frames
+    }
+
+    /// Compares composing frames while typing in one of four panes on large files with composing
+    /// them in full. Run with `cargo test --release compose_time -- --ignored --nocapture`.
+    #[test]
+    #[ignore = "benchmark"]
+    fn compose_time_of_edits_in_one_of_four_panes() {
+        const FRAMES: u32 = 500;
+        let dir = TempDir::new("compose-time");
+        let text: String = (0..20_000)
+            .map(|i| format!("    let value_{i} = compute({i}, \"some text\") + {i};\n"))
+            .collect();
+        let edited = dir.write("edited.rs", &text);
+        let other = dir.write("other.rs", &text);
+        let files = [edited, other.clone(), other.clone(), other];
+        let (mut editor, _backend) = editor(&dir, "", &files, (200, 60));
+        assert_eq!(editor.pane_manager.num_panes(), 4);
+        editor.pane_manager.set_active(0).unwrap();
+        editor.render_stats = Some(RenderStats::default());
+
+        average_compose_time(&mut editor, 10, false);
+        let incremental = average_compose_time(&mut editor, FRAMES, false);
+        let full = average_compose_time(&mut editor, FRAMES, true);
+        println!("compose time per frame: {incremental:?} reusing clean panes, {full:?} in full");
+        assert!(incremental < full);
+    }
+}
@@ -1,4 +1,8 @@
-use std::io::{self, Stdout, Write};
+use std::{
+    fmt,
+    io::{self, Stdout, Write},
+    time::Duration,
+};
 
 use crossterm::{
     cursor,
@@ -10,24 +14,80 @@ use crossterm::{
 
 use crate::editor::ui::style::{Color, FontIntensity, ResolvedStyle};
 
+#[cfg(test)]
+pub mod test_backend;
+
 pub type Error = io::Error;
 
 pub type Result<T> = std::result::Result<T, Error>;
 
-// TODO: Convert into implementor of trait.
+/// A backend that provides input events and the size of the terminal.
+pub trait EventBackend: fmt::Debug {
+    /// Returns the size of the terminal viewport.
+    fn size(&self) -> Result<(usize, usize)>;
+
+    /// Waits up to `timeout` for an event and returns `true` if one is ready to be read.
+    fn poll_event(&self, timeout: Duration) -> Result<bool>;
+
+    /// Reads and returns an event from the backend.
+    fn read_event(&mut self) -> Result<Event>;
+}
+
+/// A backend that output is rendered to.
+pub trait RenderBackend: fmt::Debug {
+    /// Initializes the backend.
+    fn initialize(&mut self) -> Result<()>;
+
+    /// Deinitializes the backend.
+    fn deinitialize(&mut self) -> Result<()>;
+
+    /// Writes text at the cursor position.
+    fn write(&mut self, s: &str) -> Result<()>;
+
+    /// Sets the style for all following writes.
+    fn set_style(&mut self, style: ResolvedStyle) -> Result<()>;
+
+    /// Flushes the output.
+    fn flush(&mut self) -> Result<()>;
+
+    /// Updates the cursor position on screen.
+    fn move_cursor(&mut self, col: usize, row: usize) -> Result<()>;
+
+    /// Hides the cursor.
+    fn hide_cursor(&mut self) -> Result<()>;
+
+    /// Shows the cursor.
+    fn show_cursor(&mut self) -> Result<()>;
+
+    /// Sets the title of the terminal window.
+    fn set_title(&mut self, title: &str) -> Result<()>;
+
+    /// Enables or disables capturing of mouse events. While disabled, the terminal handles the
+    /// mouse itself, e.g. for selecting text.
+    fn set_mouse_capture(&mut self, enabled: bool) -> Result<()>;
+
+    /// Begins a synchronized update. The terminal will not redraw until the update ends.
+    fn begin_synchronized_update(&mut self) -> Result<()>;
+
+    /// Ends a synchronized update and lets the terminal draw all changes at once.
+    fn end_synchronized_update(&mut self) -> Result<()>;
+}
+
 /// The backend for handling input and terminal size.
 #[derive(Debug)]
 pub struct EditorBackend;
 
-impl EditorBackend {
-    /// Returns the size of the terminal viewport.
-    pub fn size(&self) -> Result<(usize, usize)> {
+impl EventBackend for EditorBackend {
+    fn size(&self) -> Result<(usize, usize)> {
         let (cols, rows) = terminal::size()?;
         Ok((cols as usize, rows as usize))
     }
 
-    /// Reads and returns an event from the backend.
-    pub fn read_event(&self) -> Result<Event> {
+    fn poll_event(&self, timeout: Duration) -> Result<bool> {
+        event::poll(timeout)
+    }
+
+    fn read_event(&mut self) -> Result<Event> {
         event::read()
     }
 }
@@ -39,79 +99,102 @@ pub struct RenderingBackend {
 }
 
 impl RenderingBackend {
-    /// Initializes the terminal backend.
-    pub fn initialize() -> Result<Self> {
+    /// Returns a new uninitialized terminal backend.
+    pub fn new() -> Self {
+        Self {
+            stdout: io::stdout(),
+        }
+    }
+}
+
+impl RenderBackend for RenderingBackend {
+    fn initialize(&mut self) -> Result<()> {
         terminal::enable_raw_mode()?;
-        let mut stdout = io::stdout();
         queue!(
-            stdout,
+            self.stdout,
             terminal::EnterAlternateScreen,
-            event::EnableMouseCapture,
+            event::EnableFocusChange,
+            event::EnableBracketedPaste,
             cursor::MoveTo(0, 0),
         )?;
-        Ok(Self { stdout })
+        Ok(())
     }
 
-    /// Deinitializes the terminal backend.
-    pub fn deinitialize(&mut self) -> Result<()> {
+    fn deinitialize(&mut self) -> Result<()> {
         queue!(
             self.stdout,
             terminal::LeaveAlternateScreen,
-            event::DisableMouseCapture
+            event::DisableMouseCapture,
+            event::DisableFocusChange,
+            event::DisableBracketedPaste,
         )?;
+        self.stdout.flush()?;
         terminal::disable_raw_mode()?;
         Ok(())
     }
 
-    /// Writes a character to the terminal.
-    pub fn write_char(&mut self, c: char) -> Result<()> {
-        write!(self.stdout, "{c}")?;
-        Ok(())
-    }
-
-    /// Writes text to the terminal.
-    pub fn write(&mut self, s: &str) -> Result<()> {
+    fn write(&mut self, s: &str) -> Result<()> {
         write!(self.stdout, "{s}")?;
         Ok(())
     }
 
-    /// Sets the bold style.
-    pub fn set_style(&mut self, style: ResolvedStyle) -> Result<()> {
+    fn set_style(&mut self, style: ResolvedStyle) -> Result<()> {
+        // The attributes start with a reset, so they have to be written before the colors.
+        self.write(&style.to_string())?;
         queue!(
             self.stdout,
             style::SetForegroundColor(style.fg.into()),
             style::SetBackgroundColor(style.bg.into()),
         )?;
-
-        self.write(&style.to_string())?;
         Ok(())
     }
 
-    /// Flushes the terminal output.
-    pub fn flush(&mut self) -> Result<()> {
+    fn flush(&mut self) -> Result<()> {
         self.stdout.flush()?;
         Ok(())
     }
 
-    /// Updates the cursor position on screen.
-    pub fn move_cursor(&mut self, col: usize, row: usize) -> Result<()> {
+    fn move_cursor(&mut self, col: usize, row: usize) -> Result<()> {
         let col = col.min(u16::MAX as usize) as u16;
         let row = row.min(u16::MAX as usize) as u16;
         queue!(self.stdout, cursor::MoveTo(col, row))?;
         Ok(())
     }
 
-    /// Hides the cursor.
-    pub fn hide_cursor(&mut self) -> Result<()> {
+    fn hide_cursor(&mut self) -> Result<()> {
         queue!(self.stdout, cursor::Hide)?;
         Ok(())
     }
 
-    /// Shows the cursor.
-    pub fn show_cursor(&mut self) -> Result<()> {
+    fn show_cursor(&mut self) -> Result<()> {
         queue!(self.stdout, cursor::Show)?;
         Ok(())
     }
+
+    fn set_title(&mut self, title: &str) -> Result<()> {
+        queue!(self.stdout, terminal::SetTitle(title))?;
+        Ok(())
+    }
+
+    fn set_mouse_capture(&mut self, enabled: bool) -> Result<()> {
+        if enabled {
+            queue!(self.stdout, event::EnableMouseCapture)?;
+        } else {
+            queue!(self.stdout, event::DisableMouseCapture)?;
+        }
+        self.stdout.flush()?;
+        Ok(())
+    }
+
+    fn begin_synchronized_update(&mut self) -> Result<()> {
+        queue!(self.stdout, terminal::BeginSynchronizedUpdate)?;
+        Ok(())
+    }
+
+    fn end_synchronized_update(&mut self) -> Result<()> {
+        queue!(self.stdout, terminal::EndSynchronizedUpdate)?;
+        Ok(())
+    }
 }
 
 impl From<Color> for crossterm::style::Color {
@@ -154,7 +237,8 @@ impl From<FontIntensity> for Attribute {
 #[allow(clippy::to_string_trait_impl)]
 impl ToString for ResolvedStyle {
     fn to_string(&self) -> String {
-        let mut s = String::new();
+        // Reset all attributes first so that no stale attributes are left behind.
+        let mut s = Attribute::Reset.to_string();
 
         let intensity: Attribute = self.intensity.into();
         s.push_str(&intensity.to_string());
@@ -164,6 +248,12 @@ impl ToString for ResolvedStyle {
         } else {
             s.push_str(&Attribute::NoUnderline.to_string());
         }
+
+        if self.reverse {
+            s.push_str(&Attribute::Reverse.to_string());
+        } else {
+            s.push_str(&Attribute::NoReverse.to_string());
+        }
         s
     }
 }
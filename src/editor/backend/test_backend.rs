@@ -0,0 +1,208 @@
+use std::{cell::RefCell, collections::VecDeque, io, rc::Rc, time::Duration};
+
+use crossterm::event::Event;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::editor::{
+    backend::{EventBackend, RenderBackend, Result},
+    ui::{geometry::point::Point, style::ResolvedStyle, unicode::grapheme_width},
+};
+
+/// A single cell written to the test backend.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestCell {
+    /// The grapheme cluster in the cell, or an empty string for the second column of a wide
+    /// cluster.
+    pub symbol: String,
+    pub style: Option<ResolvedStyle>,
+}
+
+impl Default for TestCell {
+    fn default() -> Self {
+        Self {
+            symbol: " ".to_string(),
+            style: None,
+        }
+    }
+}
+
+/// The shared state of a [`TestBackend`].
+#[derive(Debug)]
+struct TestState {
+    width: usize,
+    height: usize,
+    /// The written cells in row-major order.
+    cells: Vec<TestCell>,
+    /// The position that the next write will start at.
+    cursor: Point,
+    cursor_visible: bool,
+    style: Option<ResolvedStyle>,
+    title: String,
+    /// The scripted events to return from `read_event`.
+    events: VecDeque<Event>,
+    /// The number of synchronized updates that were ended, one for each rendered frame.
+    frames: usize,
+}
+
+/// An in-memory backend that records output into a grid of cells and feeds scripted events.
+/// Clones of the backend share the same state, so one clone can be given to the editor while
+/// another is used to inspect the output.
+#[derive(Debug, Clone)]
+pub struct TestBackend {
+    state: Rc<RefCell<TestState>>,
+}
+
+impl TestBackend {
+    /// Returns a new test backend with a grid of the given size.
+    pub fn new(width: usize, height: usize) -> Self {
+        let state = TestState {
+            width,
+            height,
+            cells: vec![TestCell::default(); width * height],
+            cursor: Point::new(0, 0),
+            cursor_visible: true,
+            style: None,
+            title: String::new(),
+            events: VecDeque::new(),
+            frames: 0,
+        };
+        Self {
+            state: Rc::new(RefCell::new(state)),
+        }
+    }
+
+    /// Queues events to be returned by `read_event`.
+    pub fn push_events(&self, events: impl IntoIterator<Item = Event>) {
+        self.state.borrow_mut().events.extend(events);
+    }
+
+    /// Returns the cell at the given position, or `None` if it is out of bounds.
+    pub fn cell(&self, col: usize, row: usize) -> Option<TestCell> {
+        let state = self.state.borrow();
+        if col >= state.width || row >= state.height {
+            return None;
+        }
+        state.cells.get(row * state.width + col).cloned()
+    }
+
+    /// Returns the text of each row of the grid.
+    pub fn rows(&self) -> Vec<String> {
+        let state = self.state.borrow();
+        state
+            .cells
+            .chunks_exact(state.width.max(1))
+            .map(|row| row.iter().map(|cell| cell.symbol.as_str()).collect())
+            .collect()
+    }
+
+    /// Returns the cursor position if the cursor is visible.
+    pub fn cursor(&self) -> Option<Point> {
+        let state = self.state.borrow();
+        state.cursor_visible.then_some(state.cursor)
+    }
+
+    /// Returns the title set by the editor.
+    pub fn title(&self) -> String {
+        self.state.borrow().title.clone()
+    }
+
+    /// Returns the number of frames rendered to the backend.
+    pub fn frames(&self) -> usize {
+        self.state.borrow().frames
+    }
+}
+
+impl EventBackend for TestBackend {
+    fn size(&self) -> Result<(usize, usize)> {
+        let state = self.state.borrow();
+        Ok((state.width, state.height))
+    }
+
+    fn poll_event(&self, timeout: Duration) -> Result<bool> {
+        // Always report an event when waiting so that `read_event` fails once the scripted events
+        // run out. Checking without waiting only reports events that are already queued.
+        Ok(!timeout.is_zero() || !self.state.borrow().events.is_empty())
+    }
+
+    fn read_event(&mut self) -> Result<Event> {
+        self.state
+            .borrow_mut()
+            .events
+            .pop_front()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::UnexpectedEof, "no more test events"))
+    }
+}
+
+impl RenderBackend for TestBackend {
+    fn initialize(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    fn deinitialize(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    fn write(&mut self, s: &str) -> Result<()> {
+        let mut state = self.state.borrow_mut();
+        for grapheme in s.graphemes(true) {
+            let Point { col, row } = state.cursor;
+            let width = grapheme_width(grapheme);
+            let style = state.style;
+            for offset in 0..width {
+                if col + offset < state.width && row < state.height {
+                    let index = row * state.width + col + offset;
+                    // Only the first column holds the grapheme, like in a terminal.
+                    let symbol = if offset == 0 { grapheme } else { "" };
+                    state.cells[index] = TestCell {
+                        symbol: symbol.to_string(),
+                        style,
+                    };
+                }
+            }
+            state.cursor.col += width;
+        }
+        Ok(())
+    }
+
+    fn set_style(&mut self, style: ResolvedStyle) -> Result<()> {
+        self.state.borrow_mut().style = Some(style);
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    fn move_cursor(&mut self, col: usize, row: usize) -> Result<()> {
+        self.state.borrow_mut().cursor = Point::new(col, row);
+        Ok(())
+    }
+
+    fn hide_cursor(&mut self) -> Result<()> {
+        self.state.borrow_mut().cursor_visible = false;
+        Ok(())
+    }
+
+    fn show_cursor(&mut self) -> Result<()> {
+        self.state.borrow_mut().cursor_visible = true;
+        Ok(())
+    }
+
+    fn set_title(&mut self, title: &str) -> Result<()> {
+        self.state.borrow_mut().title = title.to_string();
+        Ok(())
+    }
+
+    fn set_mouse_capture(&mut self, _enabled: bool) -> Result<()> {
+        Ok(())
+    }
+
+    fn begin_synchronized_update(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    fn end_synchronized_update(&mut self) -> Result<()> {
+        self.state.borrow_mut().frames += 1;
+        Ok(())
+    }
+}
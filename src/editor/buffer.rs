@@ -1,21 +1,29 @@
 use std::{
     fs, io, mem,
-    ops::{Deref, DerefMut},
+    ops::{Deref, DerefMut, Range},
     path::{Path, PathBuf},
-    sync::{Arc, RwLock},
+    sync::{
+        Arc, RwLock, RwLockReadGuard, RwLockWriteGuard,
+        atomic::{AtomicBool, Ordering},
+    },
 };
 
 use thiserror::Error;
 
 use crate::editor::{
     buffer::{
+        indent::Indent,
+        line_ending::LineEnding,
         modification::{ActionRange, BufferAction},
         row::Row,
     },
+    filetype,
     pane::cursor::Cursor,
     ui::geometry::point::Point,
 };
 
+pub mod indent;
+pub mod line_ending;
 pub mod manager;
 pub mod modification;
 pub mod row;
@@ -34,6 +42,10 @@ pub enum SaveError {
     MissingPath,
     #[error("file already exists: {0}")]
     FileAlreadyExists(PathBuf),
+    #[error("permission denied: {0}")]
+    PermissionDenied(PathBuf),
+    #[error("the file was not valid UTF-8, and saving would replace its invalid bytes")]
+    Lossy,
     #[error(transparent)]
     IoError(#[from] io::Error),
 }
@@ -44,10 +56,38 @@ pub struct BufferEntry {
     pub buffer: Arc<RwLock<Buffer>>,
 }
 
+/// Whether a poisoned buffer lock has been logged, so that it is only logged once.
+static POISON_LOGGED: AtomicBool = AtomicBool::new(false);
+
+/// Logs a warning the first time a poisoned buffer lock is recovered.
+fn log_poisoned(id: usize) {
+    if !POISON_LOGGED.swap(true, Ordering::Relaxed) {
+        tracing::warn!(buffer = id, "recovered buffer lock poisoned by a panic");
+    }
+}
+
 impl BufferEntry {
     pub fn new(id: usize, buffer: Arc<RwLock<Buffer>>) -> Self {
         BufferEntry { id, buffer }
     }
+
+    /// Locks the buffer for reading. A lock poisoned by a panic in another holder is recovered,
+    /// since every edit leaves the buffer structurally valid.
+    pub fn read_buf(&self) -> RwLockReadGuard<'_, Buffer> {
+        self.buffer.read().unwrap_or_else(|err| {
+            log_poisoned(self.id);
+            err.into_inner()
+        })
+    }
+
+    /// Locks the buffer for writing, recovering a lock poisoned by a panic like
+    /// [`BufferEntry::read_buf`].
+    pub fn write_buf(&self) -> RwLockWriteGuard<'_, Buffer> {
+        self.buffer.write().unwrap_or_else(|err| {
+            log_poisoned(self.id);
+            err.into_inner()
+        })
+    }
 }
 
 impl Deref for BufferEntry {
@@ -64,6 +104,17 @@ impl DerefMut for BufferEntry {
     }
 }
 
+/// The contents of a buffer at a position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CharAt {
+    /// A character.
+    Char(char),
+    /// The end of a row.
+    EndOfLine,
+    /// A position past the last row.
+    EndOfFile,
+}
+
 #[derive(Debug, Clone)]
 pub struct Buffer {
     /// The rows of the buffer.
@@ -72,6 +123,18 @@ pub struct Buffer {
     filepath: Option<PathBuf>,
     /// Whether the buffer has been modified.
     dirty: bool,
+    /// The filetype overriding the detected filetype, if any.
+    filetype: Option<String>,
+    /// The line ending used when writing the buffer.
+    line_ending: LineEnding,
+    /// The indentation style of the buffer, or `None` to use the configured style.
+    indent: Option<Indent>,
+    /// Whether the file contained invalid UTF-8 and was decoded lossily.
+    lossy: bool,
+    /// The display name of a scratch buffer that is not backed by a file.
+    scratch_name: Option<String>,
+    /// Whether the buffer can be modified.
+    read_only: bool,
 }
 
 impl Buffer {
@@ -90,29 +153,105 @@ impl Buffer {
             rows: vec![Row::default()],
             filepath: Some(path.as_ref().to_path_buf()),
             dirty: false,
+            filetype: None,
+            line_ending: LineEnding::default(),
+            indent: None,
+            lossy: false,
+            scratch_name: None,
+            read_only: false,
         }
     }
 
     /// Open a new file and read its contents.
     pub fn open_file<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
-        let contents = fs::read_to_string(&path)?;
+        let (rows, line_ending, lossy) = read_rows(path.as_ref())?;
+        let indent = Indent::detect(rows.iter().map(Row::text));
+        tracing::info!(path = %path.as_ref().display(), lines = rows.len(), lossy, "opened file");
 
         Ok(Self {
-            rows: contents.split("\n").map(Row::new).collect(),
+            rows,
             filepath: Some(path.as_ref().to_path_buf()),
             dirty: false,
+            filetype: None,
+            line_ending,
+            indent,
+            lossy,
+            scratch_name: None,
+            read_only: false,
         })
     }
 
+    /// Replaces the rows of the buffer with the current contents of its file and marks it as
+    /// unmodified. Returns [`BufferAction::None`] if the contents did not change.
+    pub fn reload(&mut self) -> Result<BufferAction, Error> {
+        let path = self.filepath.as_ref().ok_or(SaveError::MissingPath)?;
+        let (rows, line_ending, lossy) = read_rows(path)?;
+        self.line_ending = line_ending;
+        self.lossy = lossy;
+        if rows
+            .iter()
+            .map(Row::text)
+            .eq(self.rows.iter().map(Row::text))
+        {
+            return Ok(BufferAction::None);
+        }
+        let action = self.replace_rows(0..self.rows.len(), rows);
+        self.dirty = false;
+        Ok(action)
+    }
+
+    /// Returns a new read-only scratch buffer with the given name and contents.
+    pub fn scratch(name: &str, text: &str) -> Self {
+        Self {
+            rows: text.split('\n').map(Row::new).collect(),
+            scratch_name: Some(name.to_string()),
+            read_only: true,
+            ..Default::default()
+        }
+    }
+
     /// Inserts a character at the given cursor position.
     pub fn insert_char(&mut self, c: char, cursor: &Cursor) -> BufferAction {
+        if self.read_only {
+            return BufferAction::None;
+        }
+
+        let Some(row) = self.rows.get_mut(cursor.row()) else {
+            return BufferAction::None;
+        };
+        // A cursor past the end of the row pads it with spaces, which are reported as inserted
+        // along with the character.
+        let (col, row_index) = cursor.position();
+        let start = col.min(row.len());
+        let mut text = " ".repeat(col - start);
+        row.pad_to(col);
+        if row.insert_char(col, c) {
+            self.dirty = true;
+            text.push(c);
+            return BufferAction::Insert {
+                start: Point::new(start, row_index),
+                text,
+            };
+        }
+        BufferAction::None
+    }
+
+    /// Inserts a string without line breaks at the given cursor position.
+    pub fn insert_str(&mut self, s: &str, cursor: &Cursor) -> BufferAction {
+        if self.read_only || s.is_empty() {
+            return BufferAction::None;
+        }
+
         if let Some(row) = self.rows.get_mut(cursor.row())
-            && row.insert_char(cursor.col(), c)
+            && cursor.col() <= row.len()
         {
+            for (i, c) in s.chars().enumerate() {
+                row.insert_char(cursor.col() + i, c);
+            }
             self.dirty = true;
             return BufferAction::Insert {
                 start: cursor.position().into(),
-                text: c.to_string(),
+                text: s.to_string(),
             };
         }
         BufferAction::None
@@ -120,6 +259,10 @@ impl Buffer {
 
     /// Inserts a newline at the given cursor position.
     pub fn insert_newline(&mut self, cursor: &Cursor) -> BufferAction {
+        if self.read_only {
+            return BufferAction::None;
+        }
+
         if let Some(row) = self.rows.get_mut(cursor.row()) {
             let (left, right) = row.split_at(cursor.col());
             let _ = mem::replace(row, left);
@@ -137,9 +280,32 @@ impl Buffer {
         BufferAction::None
     }
 
+    /// Inserts a row before the row at index `at`. The insertion is reported as a newline
+    /// inserted at the end of the previous row, so that panes anchor to it the same way.
+    pub fn insert_row(&mut self, at: usize, row: Row) -> BufferAction {
+        if self.read_only || at > self.rows.len() {
+            return BufferAction::None;
+        }
+
+        let start = match at.checked_sub(1) {
+            Some(prev) => Point::new(self.rows[prev].len(), prev),
+            None => Point::new(0, 0),
+        };
+        self.rows.insert(at, row);
+        self.dirty = true;
+        BufferAction::Insert {
+            start,
+            text: "\n".into(),
+        }
+    }
+
     /// Deletes a character at the given cursor position. If the cursor is at the end of the row,
     /// joins the row with the next row.
     pub fn delete_char(&mut self, cursor: &Cursor) -> BufferAction {
+        if self.read_only {
+            return BufferAction::None;
+        }
+
         let current_row_len = self
             .rows
             .get(cursor.row())
@@ -155,6 +321,10 @@ impl Buffer {
             let next_row = cursor.row().saturating_add(1);
             return self.append_line_to_line(cursor.row(), next_row);
         } else if let Some(row) = self.rows.get_mut(cursor.row())
+            && let Some(c) = row
+                .text()
+                .get(cursor.col()..)
+                .and_then(|s| s.chars().next())
             && row.delete_char(cursor.col())
         {
             self.dirty = true;
@@ -163,7 +333,10 @@ impl Buffer {
                 from: cursor.position().into(),
                 to: cursor.position().into(),
             };
-            return BufferAction::Delete(delete_range);
+            return BufferAction::Delete {
+                range: delete_range,
+                text: c.to_string(),
+            };
         }
 
         BufferAction::None
@@ -171,6 +344,10 @@ impl Buffer {
 
     /// Appends the row at index `right` to the row at index `left`.
     pub fn append_line_to_line(&mut self, from: usize, to: usize) -> BufferAction {
+        if self.read_only {
+            return BufferAction::None;
+        }
+
         let right_row = self.rows.remove(from);
         if let Some(row) = self.rows.get_mut(to) {
             row.append_row(&right_row);
@@ -178,27 +355,208 @@ impl Buffer {
 
             // FIXME: This is a hack to make sure that the buffer viewport maintains its position
             // when another pane deletes a line.
-            return BufferAction::Delete(ActionRange::Line(from));
+            return BufferAction::Delete {
+                range: ActionRange::Line(from),
+                text: "\n".to_string(),
+            };
         }
         BufferAction::None
     }
 
+    /// Replaces the contents of the buffer with the given text, split into rows at the line
+    /// ending of the buffer.
+    pub fn set_text(&mut self, text: &str) -> BufferAction {
+        let rows = text
+            .split('\n')
+            .map(|line| match self.line_ending {
+                LineEnding::Crlf => Row::new(line.strip_suffix('\r').unwrap_or(line)),
+                LineEnding::Lf => Row::new(line),
+            })
+            .collect();
+        self.replace_rows(0..self.rows.len(), rows)
+    }
+
+    /// Appends text to the end of the buffer without marking it as modified. Unlike other edits,
+    /// this also works on read-only buffers, so that the editor can write to output buffers.
+    pub fn append_output(&mut self, text: &str) -> BufferAction {
+        self.edit_output(|buffer| {
+            let row = buffer.rows.len() - 1;
+            let end = Point::new(buffer.rows[row].len(), row);
+            buffer.insert_text(end, text)
+        })
+    }
+
+    /// Removes all text from the buffer without marking it as modified, also if it is read-only.
+    pub fn clear_output(&mut self) -> BufferAction {
+        self.edit_output(|buffer| buffer.set_text(""))
+    }
+
+    /// Applies an edit made by the editor rather than the user, keeping the read-only and
+    /// modified state of the buffer.
+    fn edit_output(&mut self, edit: impl FnOnce(&mut Self) -> BufferAction) -> BufferAction {
+        let (read_only, dirty) = (self.read_only, self.dirty);
+        self.read_only = false;
+        let action = edit(self);
+        self.read_only = read_only;
+        self.dirty = dirty;
+        action
+    }
+
+    /// Replaces the rows in the given range with new rows. An empty range inserts the rows
+    /// before `range.start`. The replacement is reported as a single composite action deleting
+    /// the old rows and inserting the new ones. The buffer always keeps at least one row.
+    pub fn replace_rows(&mut self, range: Range<usize>, rows: Vec<Row>) -> BufferAction {
+        if self.read_only
+            || range.start > range.end
+            || range.end > self.rows.len()
+            || (range.is_empty() && rows.is_empty())
+        {
+            return BufferAction::None;
+        }
+
+        let start = Point::new(0, range.start);
+        let text = rows.iter().map(Row::text).collect::<Vec<_>>().join("\n");
+        let removed: Vec<Row> = self.rows.splice(range.clone(), rows).collect();
+        if self.rows.is_empty() {
+            self.rows.push(Row::default());
+        }
+        self.dirty = true;
+
+        let mut actions = Vec::new();
+        if let Some(last) = removed.last() {
+            let end = Point::new(last.len(), range.end - 1);
+            actions.push(BufferAction::Delete {
+                range: ActionRange::PointToPoint {
+                    from: start,
+                    to: end,
+                },
+                text: removed.iter().map(Row::text).collect::<Vec<_>>().join("\n"),
+            });
+        }
+        if !text.is_empty() {
+            actions.push(BufferAction::Insert { start, text });
+        }
+        BufferAction::Composite(actions)
+    }
+
+    /// Deletes the characters in the given column range of a row, without joining rows. Returns
+    /// a delete action carrying the removed text, or `None` if nothing was removed.
+    pub fn delete_in_row(&mut self, row: usize, cols: Range<usize>) -> BufferAction {
+        if self.read_only || cols.is_empty() {
+            return BufferAction::None;
+        }
+        let Some(r) = self.rows.get_mut(row) else {
+            return BufferAction::None;
+        };
+
+        let text = r.remove_range(cols.start, cols.end);
+        if text.is_empty() {
+            return BufferAction::None;
+        }
+        self.dirty = true;
+
+        let end = cols.start + text.chars().count();
+        BufferAction::Delete {
+            range: ActionRange::PointToPoint {
+                from: Point::new(cols.start, row),
+                to: Point::new(end, row),
+            },
+            text,
+        }
+    }
+
+    /// Returns the text between two positions, with rows separated by `\n`. The end position is
+    /// exclusive.
+    pub fn text_in_range(&self, start: Point, end: Point) -> String {
+        (start.row..=end.row)
+            .filter_map(|i| {
+                let row = self.row(i)?;
+                let from = if i == start.row { start.col } else { 0 };
+                let to = if i == end.row { end.col } else { usize::MAX };
+                Some(row.chars_in_range(from, to))
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Deletes the text between two positions. The end position is exclusive.
+    pub fn delete_range(&mut self, start: Point, end: Point) -> BufferAction {
+        if (start.row, start.col) >= (end.row, end.col) {
+            return BufferAction::None;
+        }
+        self.replace_range(start, end, "")
+    }
+
+    /// Inserts text that may contain line breaks at the given position.
+    pub fn insert_text(&mut self, point: Point, text: &str) -> BufferAction {
+        self.replace_range(point, point, text)
+    }
+
+    /// Replaces the text between two positions with text that may contain line breaks. The end
+    /// position is exclusive.
+    pub fn replace_range(&mut self, start: Point, end: Point, text: &str) -> BufferAction {
+        let (Some(first), Some(last)) = (self.row(start.row), self.row(end.row)) else {
+            return BufferAction::None;
+        };
+
+        let prefix = first.chars_in_range(0, start.col);
+        let suffix = last.chars_in_range(end.col, usize::MAX);
+        let mut lines: Vec<String> = text.split('\n').map(str::to_string).collect();
+        if let Some(first) = lines.first_mut() {
+            first.insert_str(0, &prefix);
+        }
+        if let Some(last) = lines.last_mut() {
+            last.push_str(&suffix);
+        }
+        self.replace_rows(
+            start.row..end.row + 1,
+            lines.into_iter().map(Row::new).collect(),
+        )
+    }
+
     /// Finds the next occurrence of the given string in the buffer and returns its position or
     /// `None` if not found.
     pub fn find_next(&self, s: &str, cursor: &Cursor) -> Option<Point> {
+        let last_row = self.rows.len().saturating_sub(1);
+        self.find_next_in(
+            s,
+            Point::new(cursor.col().saturating_add(1), cursor.row()),
+            Point::new(usize::MAX, last_row),
+        )
+    }
+
+    /// Finds the next occurrence of the given string that starts at or after `start` and ends at
+    /// or before `end`, and returns its position or `None` if not found. Rows after `end` are not
+    /// searched.
+    pub fn find_next_in(&self, s: &str, start: Point, end: Point) -> Option<Point> {
+        let len = s.chars().count();
         self.rows
             .iter()
             .enumerate()
-            .skip(cursor.row())
+            .take(end.row.saturating_add(1))
+            .skip(start.row)
             .find_map(|(i, row)| {
-                // Ensure that the first row is searched from the cursor column.
-                let offset = if i == cursor.row() {
-                    cursor.col().saturating_add(1)
-                } else {
-                    0
-                };
+                // Ensure that the first row is searched from the start column.
+                let offset = if i == start.row { start.col } else { 0 };
                 row.find_next(s, offset).map(|col| Point::new(col, i))
             })
+            .filter(|point| point.row < end.row || point.col.saturating_add(len) <= end.col)
+    }
+
+    /// Returns the positions of all occurrences of the string in the buffer, in order.
+    pub fn find_all(&self, s: &str) -> Vec<Point> {
+        if s.is_empty() {
+            return Vec::new();
+        }
+        self.rows
+            .iter()
+            .enumerate()
+            .flat_map(|(i, row)| {
+                let text = row.text();
+                text.match_indices(s)
+                    .map(move |(byte, _)| Point::new(text[..byte].chars().count(), i))
+            })
+            .collect()
     }
 
     /// Returns the row at the given index or `None` if the index is out of bounds.
@@ -206,48 +564,139 @@ impl Buffer {
         self.rows.get(row)
     }
 
+    /// Returns the contents of the buffer at the given column and row.
+    pub fn char_at(&self, point: Point) -> CharAt {
+        match self.row(point.row) {
+            Some(row) => row
+                .char_at(point.col)
+                .map_or(CharAt::EndOfLine, CharAt::Char),
+            None => CharAt::EndOfFile,
+        }
+    }
+
     /// Returns the full text of the buffer as a [`String`].
     pub fn text(&self) -> String {
         self.rows
             .iter()
             .map(|r| r.text().to_string())
             .collect::<Vec<String>>()
-            .join("\n")
+            .join(self.line_ending.as_str())
     }
 
-    /// Saves the buffer to the path stored in the buffer.
+    /// Saves the buffer to the path stored in the buffer. Lossily decoded buffers are only saved
+    /// once their invalid bytes have been discarded with [`Buffer::discard_invalid_bytes`].
     pub fn save(&mut self) -> Result<(), Error> {
         let path = self.filepath.as_ref().ok_or(SaveError::MissingPath)?;
-        fs::write(path, self.text()).map_err(SaveError::IoError)?;
+        if self.lossy {
+            return Err(SaveError::Lossy.into());
+        }
+        write_file(path, &self.text())?;
+        tracing::info!(path = %path.display(), "saved file");
         self.dirty = false;
         Ok(())
     }
 
     /// Saves the buffer to the given path. If the file already exists at the given path and
     /// `force` is `false`, the buffer will not be saved and the function will return
-    /// an error. If `force` is `true`, the file will instead be overwritten.
+    /// an error. If `force` is `true`, the file will instead be overwritten. Lossily decoded
+    /// buffers are refused as with [`Buffer::save`].
     pub fn save_as<P: AsRef<Path>>(&mut self, path: P, force: bool) -> Result<(), Error> {
+        if self.lossy {
+            return Err(SaveError::Lossy.into());
+        }
+        self.write_to(&path, force)?;
+        tracing::info!(path = %path.as_ref().display(), "saved file");
+        self.filepath = Some(path.as_ref().to_path_buf());
+        self.dirty = false;
+        Ok(())
+    }
+
+    /// Writes the text of the buffer to the given path without changing the path of the buffer
+    /// or marking it as saved. Existing files are only overwritten if `force` is `true`, as with
+    /// [`Buffer::save_as`].
+    pub fn write_to<P: AsRef<Path>>(&self, path: P, force: bool) -> Result<(), Error> {
         if fs::exists(&path)? && !force {
             return Err(SaveError::FileAlreadyExists(path.as_ref().to_path_buf()).into());
         }
 
-        fs::write(&path, self.text()).map_err(SaveError::IoError)?;
-        self.filepath = Some(path.as_ref().to_path_buf());
-        self.dirty = false;
+        write_file(path.as_ref(), &self.text())?;
         Ok(())
     }
 
-    /// Returns the path of the file this buffer represents, or `[No Filename]` if none.
+    /// Returns the path of the file this buffer represents, the name of the scratch buffer, or
+    /// `[No Filename]` if none.
     pub fn file_name(&self) -> String {
         /// The file name to use for an empty buffer.
         const NO_FILENAME: &str = "[No Filename]";
 
+        if let Some(name) = &self.scratch_name {
+            return format!("[{name}]");
+        }
+
         self.filepath
             .as_ref()
             .map(|f| f.to_string_lossy().trim_start_matches("./").to_string())
             .unwrap_or(NO_FILENAME.into())
     }
 
+    /// Returns true if the buffer can not be modified.
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// Returns the path of the file this buffer represents, or `None` if the buffer has no path.
+    pub fn filepath(&self) -> Option<&Path> {
+        self.filepath.as_deref()
+    }
+
+    /// Returns the filetype of the buffer. Unless overridden, the filetype is detected from the
+    /// path and the first line of the buffer.
+    pub fn filetype(&self) -> String {
+        self.filetype.clone().unwrap_or_else(|| {
+            filetype::detect(self.filepath(), self.row(0).map(|r| r.text())).to_string()
+        })
+    }
+
+    /// Overrides the detected filetype of the buffer.
+    pub fn set_filetype(&mut self, filetype: &str) {
+        self.filetype = Some(filetype.to_string());
+    }
+
+    /// Returns the line ending used when writing the buffer.
+    pub fn line_ending(&self) -> LineEnding {
+        self.line_ending
+    }
+
+    /// Converts the buffer to the given line ending, marking it as modified if it changed.
+    pub fn set_line_ending(&mut self, line_ending: LineEnding) {
+        if self.line_ending != line_ending {
+            self.line_ending = line_ending;
+            self.dirty = true;
+        }
+    }
+
+    /// Returns the indentation style of the buffer, or `None` if it has none and the configured
+    /// style should be used.
+    pub fn indent(&self) -> Option<Indent> {
+        self.indent
+    }
+
+    /// Overrides the indentation style of the buffer.
+    pub fn set_indent(&mut self, indent: Indent) {
+        self.indent = Some(indent);
+    }
+
+    /// Returns true if the file contained invalid UTF-8 and was decoded lossily.
+    pub fn is_lossy(&self) -> bool {
+        self.lossy
+    }
+
+    /// Accepts that the bytes that were not valid UTF-8 are replaced when the buffer is saved,
+    /// which allows saving a lossily decoded buffer.
+    pub fn discard_invalid_bytes(&mut self) {
+        self.lossy = false;
+    }
+
     /// Returns true if the buffer has been modified.
     pub fn is_dirty(&self) -> bool {
         self.dirty
@@ -265,6 +714,158 @@ impl Default for Buffer {
             rows: vec![Row::default()],
             filepath: Default::default(),
             dirty: Default::default(),
+            filetype: Default::default(),
+            line_ending: Default::default(),
+            indent: Default::default(),
+            lossy: Default::default(),
+            scratch_name: Default::default(),
+            read_only: Default::default(),
+        }
+    }
+}
+
+/// Reads the rows of the file at the given path, along with its line ending and whether it had to
+/// be decoded lossily because it is not valid UTF-8.
+fn read_rows(path: &Path) -> Result<(Vec<Row>, LineEnding, bool), Error> {
+    // Fall back to a lossy conversion if the file is not valid UTF-8.
+    let (contents, lossy) = match String::from_utf8(fs::read(path)?) {
+        Ok(contents) => (contents, false),
+        Err(err) => (String::from_utf8_lossy(err.as_bytes()).into_owned(), true),
+    };
+
+    let line_ending = LineEnding::detect(&contents);
+    let rows = contents
+        .split('\n')
+        .map(|line| match line_ending {
+            LineEnding::Crlf => Row::new(line.strip_suffix('\r').unwrap_or(line)),
+            LineEnding::Lf => Row::new(line),
+        })
+        .collect();
+    Ok((rows, line_ending, lossy))
+}
+
+/// Writes the text to the file at the given path through a temporary file in the same directory
+/// that is renamed over it, so that a failed write never leaves a truncated file. An existing
+/// file keeps its permissions, and symbolic links are followed so that their target is replaced.
+fn write_file(path: &Path, text: &str) -> Result<(), SaveError> {
+    let permission_denied = || SaveError::PermissionDenied(path.to_path_buf());
+
+    let target = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let permissions = match fs::metadata(&target) {
+        Ok(metadata) if metadata.permissions().readonly() => return Err(permission_denied()),
+        Ok(metadata) => Some(metadata.permissions()),
+        Err(_) => None,
+    };
+
+    let file_name = target.file_name().unwrap_or_default().to_string_lossy();
+    let tmp = target.with_file_name(format!(".{file_name}.tedit-tmp"));
+    let result = fs::write(&tmp, text)
+        .and_then(|()| permissions.map_or(Ok(()), |p| fs::set_permissions(&tmp, p)))
+        .and_then(|()| fs::rename(&tmp, &target));
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+
+    result.map_err(|err| match err.kind() {
+        io::ErrorKind::PermissionDenied => permission_denied(),
+        _ => SaveError::IoError(err),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a path in the temporary directory that is unique to the test.
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("tedit-buffer-{name}-{}", std::process::id()))
+    }
+
+    #[test]
+    fn reload_replaces_rows_of_changed_file() {
+        let path = temp_path("reload");
+        fs::write(&path, "one\ntwo").unwrap();
+        let mut buffer = Buffer::open_file(&path).unwrap();
+
+        assert_eq!(buffer.reload().unwrap(), BufferAction::None);
+
+        fs::write(&path, "one\r\nthree\r\n").unwrap();
+        let action = buffer.reload().unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_ne!(action, BufferAction::None);
+        assert_eq!(buffer.text(), "one\r\nthree\r\n");
+        assert_eq!(buffer.line_ending(), LineEnding::Crlf);
+        assert!(!buffer.is_dirty());
+    }
+
+    #[test]
+    fn lossy_buffer_is_saved_only_after_discarding_invalid_bytes() {
+        let path = temp_path("lossy");
+        fs::write(&path, b"a\xffb").unwrap();
+        let mut buffer = Buffer::open_file(&path).unwrap();
+        assert!(buffer.is_lossy());
+
+        assert!(matches!(
+            buffer.save(),
+            Err(Error::SaveError(SaveError::Lossy))
+        ));
+        assert_eq!(fs::read(&path).unwrap(), b"a\xffb");
+
+        buffer.discard_invalid_bytes();
+        buffer.save().unwrap();
+        let contents = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(contents, "a\u{fffd}b");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn saving_keeps_permissions_and_reports_denied_writes() {
+        use std::os::unix::fs::{PermissionsExt, symlink};
+
+        let dir = temp_path("permissions");
+        fs::create_dir_all(&dir).unwrap();
+        let script = dir.join("script.sh");
+        fs::write(&script, "echo").unwrap();
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+        let link = dir.join("link.sh");
+        symlink(&script, &link).unwrap();
+
+        // Saving through the link replaces the target and keeps its mode.
+        let mut buffer = Buffer::open_file(&link).unwrap();
+        buffer.save().unwrap();
+        assert!(
+            fs::symlink_metadata(&link)
+                .unwrap()
+                .file_type()
+                .is_symlink()
+        );
+        let mode = fs::metadata(&script).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o755);
+
+        // Read-only files are refused, and so are files in read-only directories. The directory
+        // check is skipped for root, which may write anywhere.
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o444)).unwrap();
+        let result = buffer.save();
+        assert!(matches!(
+            result,
+            Err(Error::SaveError(SaveError::PermissionDenied(_)))
+        ));
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o644)).unwrap();
+
+        fs::set_permissions(&dir, fs::Permissions::from_mode(0o555)).unwrap();
+        let result = buffer.save();
+        fs::set_permissions(&dir, fs::Permissions::from_mode(0o755)).unwrap();
+        // SAFETY: `geteuid` has no preconditions and cannot fail.
+        if unsafe { libc::geteuid() } != 0 {
+            assert!(matches!(
+                result,
+                Err(Error::SaveError(SaveError::PermissionDenied(_)))
+            ));
+            assert_eq!(fs::read_to_string(&script).unwrap(), "echo");
+            assert_eq!(fs::read_dir(&dir).unwrap().count(), 2);
         }
+        fs::remove_dir_all(&dir).unwrap();
     }
 }
@@ -0,0 +1,97 @@
+use std::{fmt, str::FromStr};
+
+/// The indentation style of a buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Indent {
+    /// Indentation with a single tab per level.
+    Tabs,
+    /// Indentation with the given number of spaces per level.
+    Spaces(usize),
+}
+
+impl Indent {
+    /// The number of lines sampled when detecting the indentation of a file.
+    const SAMPLE_LINES: usize = 500;
+    /// The widest space indentation that is detected.
+    const MAX_SPACES: usize = 8;
+
+    /// Detects the indentation style of the given lines. Space indentation is detected from the
+    /// most common increase in indentation between consecutive indented lines. Returns `None` if
+    /// the lines are not indented.
+    pub fn detect<'a>(lines: impl Iterator<Item = &'a str>) -> Option<Self> {
+        let mut tab_lines = 0;
+        let mut space_lines = 0;
+        let mut increases = [0usize; Self::MAX_SPACES + 1];
+        let mut previous_width = 0;
+
+        for line in lines.take(Self::SAMPLE_LINES) {
+            // Blank lines say nothing about the indentation.
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            if line.starts_with('\t') {
+                tab_lines += 1;
+                continue;
+            }
+            let width = line.len() - line.trim_start_matches(' ').len();
+            if width > 0 {
+                space_lines += 1;
+            }
+            if let Some(count) = width
+                .checked_sub(previous_width)
+                .and_then(|increase| increases.get_mut(increase))
+            {
+                *count += 1;
+            }
+            previous_width = width;
+        }
+
+        if tab_lines == 0 && space_lines == 0 {
+            return None;
+        }
+        if tab_lines > space_lines {
+            return Some(Self::Tabs);
+        }
+
+        // Prefer the narrower width on ties, since it divides the wider one.
+        (1..=Self::MAX_SPACES)
+            .filter(|&width| increases[width] > 0)
+            .max_by_key(|&width| (increases[width], std::cmp::Reverse(width)))
+            .map(Self::Spaces)
+    }
+
+    /// Returns the text inserted for a single level of indentation.
+    pub fn unit(&self) -> String {
+        match self {
+            Self::Tabs => "\t".to_string(),
+            Self::Spaces(width) => " ".repeat(*width),
+        }
+    }
+}
+
+impl fmt::Display for Indent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tabs => write!(f, "tabs"),
+            Self::Spaces(width) => write!(f, "spaces:{width}"),
+        }
+    }
+}
+
+impl FromStr for Indent {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.to_lowercase();
+        if s == "tabs" {
+            return Ok(Self::Tabs);
+        }
+
+        let width = s.strip_prefix("spaces:").unwrap_or(&s);
+        match width.parse::<usize>() {
+            Ok(width) if width > 0 => Ok(Self::Spaces(width)),
+            _ => Err(format!("expected `tabs` or `spaces:<width>`, got `{s}`")),
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use std::{fmt, str::FromStr};
+
+/// The line ending used when writing a buffer.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum LineEnding {
+    /// A line feed (`\n`).
+    #[default]
+    Lf,
+    /// A carriage return followed by a line feed (`\r\n`).
+    Crlf,
+}
+
+impl LineEnding {
+    /// Detects the line ending of the given text based on its first line break. Defaults to
+    /// [`LineEnding::Lf`] if the text contains no line breaks.
+    pub fn detect(text: &str) -> Self {
+        match text.find('\n') {
+            Some(index) if text[..index].ends_with('\r') => Self::Crlf,
+            _ => Self::Lf,
+        }
+    }
+
+    /// Returns the line ending as a string.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Lf => "\n",
+            Self::Crlf => "\r\n",
+        }
+    }
+}
+
+impl fmt::Display for LineEnding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Self::Lf => "LF",
+            Self::Crlf => "CRLF",
+        };
+        write!(f, "{s}")
+    }
+}
+
+impl FromStr for LineEnding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "lf" => Ok(Self::Lf),
+            "crlf" => Ok(Self::Crlf),
+            _ => Err(format!("expected `lf` or `crlf`, got `{s}`")),
+        }
+    }
+}
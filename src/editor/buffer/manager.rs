@@ -1,5 +1,5 @@
 use std::{
-    path::Path,
+    path::{Path, PathBuf},
     sync::{Arc, RwLock},
 };
 
@@ -21,6 +21,13 @@ impl BufferManager {
         self.add(buffer)
     }
 
+    /// Opens a read-only scratch buffer with the given name and contents and returns a reference
+    /// to the buffer.
+    pub fn open_scratch(&mut self, name: &str, text: &str) -> BufferEntry {
+        let buffer = Buffer::scratch(name, text);
+        self.add(buffer)
+    }
+
     /// Opens a new or existing file and returns a reference to the buffer.
     pub fn open_new_or_existing_file<P: AsRef<Path>>(
         &mut self,
@@ -43,12 +50,14 @@ impl BufferManager {
         entry
     }
 
-    /// Gets a buffer by path. Returns `None` if the buffer doesn't exist.
+    /// Gets a buffer by path. Paths are canonicalized before comparing them, so different paths to
+    /// the same file find the same buffer. Returns `None` if the buffer doesn't exist.
     pub fn get_buffer_by_path<P: AsRef<Path>>(&self, path: P) -> Option<BufferEntry> {
+        let path = canonicalize(path.as_ref());
         for entry in &self.buffers {
-            let buffer = entry.buffer.read().ok()?;
+            let buffer = entry.read_buf();
             if let Some(file_path) = &buffer.filepath
-                && file_path == path.as_ref()
+                && canonicalize(file_path) == path
             {
                 return Some(entry.clone());
             }
@@ -56,11 +65,24 @@ impl BufferManager {
         None
     }
 
+    /// Gets a scratch buffer by name. Returns `None` if the buffer doesn't exist.
+    pub fn get_scratch(&self, name: &str) -> Option<BufferEntry> {
+        self.buffers
+            .iter()
+            .find(|entry| entry.read_buf().scratch_name.as_deref() == Some(name))
+            .cloned()
+    }
+
     /// Returns the index of the buffer with the given id.
     fn buffer_index(&self, id: usize) -> Option<usize> {
         self.buffers.iter().position(|entry| entry.id == id)
     }
 
+    /// Gets a buffer by id. Returns `None` if the buffer doesn't exist.
+    pub fn get(&self, id: usize) -> Option<BufferEntry> {
+        self.buffers.iter().find(|entry| entry.id == id).cloned()
+    }
+
     /// Closes the buffer with the given id.
     pub fn close(&mut self, id: usize) -> Option<BufferEntry> {
         let index = self.buffer_index(id)?;
@@ -70,7 +92,7 @@ impl BufferManager {
     /// Saves all open buffers.
     pub fn save_all_buffers(&self) -> Result<(), buffer::Error> {
         for entry in &self.buffers {
-            entry.buffer.write().unwrap().save()?;
+            entry.write_buf().save()?;
         }
         Ok(())
     }
@@ -80,3 +102,11 @@ impl BufferManager {
         self.buffers.iter()
     }
 }
+
+/// Returns the canonical form of the path. Paths to files that do not exist yet are only made
+/// absolute.
+fn canonicalize(path: &Path) -> PathBuf {
+    path.canonicalize()
+        .or_else(|_| std::path::absolute(path))
+        .unwrap_or_else(|_| path.to_path_buf())
+}
@@ -1,3 +1,5 @@
+use std::cmp::Ordering;
+
 use crate::editor::ui::geometry::point::Point;
 
 /// Represents a change to a buffer with a specific buffer id.
@@ -18,8 +20,10 @@ impl BufferModification {
 pub enum BufferAction {
     /// An insert operation containing the insertet text and the position it was inserted at.
     Insert { start: Point, text: String },
-    /// A delete operation containing the range of text that was deleted.
-    Delete(ActionRange),
+    /// A delete operation containing the range of text that was deleted and the deleted text.
+    Delete { range: ActionRange, text: String },
+    /// Multiple actions that were applied as a single operation, in order.
+    Composite(Vec<BufferAction>),
     /// Represents that the buffer was not been modified.
     None,
 }
@@ -29,6 +33,66 @@ impl BufferAction {
     pub fn is_insert_newline(&self) -> bool {
         matches!(self, BufferAction::Insert { text, .. } if text == "\n")
     }
+
+    /// Returns the number of rows that rows after the affected row are shifted by, along with
+    /// the affected row, or `None` if the action does not add or remove rows.
+    pub fn row_shift(&self) -> Option<(isize, usize)> {
+        match self {
+            BufferAction::Insert { start, .. } if self.is_insert_newline() => Some((1, start.row)),
+            BufferAction::Delete {
+                range: ActionRange::Line(row),
+                ..
+            } => Some((-1, *row)),
+            _ => None,
+        }
+    }
+
+    /// Returns the position of the point after the action, or `None` if the text at the point was
+    /// removed.
+    pub fn shift_point(&self, point: Point) -> Option<Point> {
+        let before = |a: Point, b: Point| (a.row, a.col) < (b.row, b.col);
+        match self {
+            BufferAction::None => Some(point),
+            BufferAction::Composite(actions) => actions
+                .iter()
+                .try_fold(point, |point, action| action.shift_point(point)),
+            BufferAction::Insert { start, text } => {
+                if before(point, *start) {
+                    return Some(point);
+                }
+                let end = end_of(*start, text);
+                Some(if point.row == start.row {
+                    Point::new(end.col + point.col - start.col, end.row)
+                } else {
+                    Point::new(point.col, point.row + end.row - start.row)
+                })
+            }
+            // A line joined onto the previous one. The column it ends up at is unknown.
+            BufferAction::Delete {
+                range: ActionRange::Line(row),
+                ..
+            } => match point.row.cmp(row) {
+                Ordering::Less => Some(point),
+                Ordering::Equal => None,
+                Ordering::Greater => Some(Point::new(point.col, point.row - 1)),
+            },
+            BufferAction::Delete {
+                range: ActionRange::PointToPoint { from, .. },
+                text,
+            } => {
+                let end = end_of(*from, text);
+                if !before(*from, point) {
+                    Some(point)
+                } else if before(point, end) {
+                    None
+                } else if point.row == end.row {
+                    Some(Point::new(from.col + point.col - end.col, from.row))
+                } else {
+                    Some(Point::new(point.col, point.row - (end.row - from.row)))
+                }
+            }
+        }
+    }
 }
 
 /// Descrives the range of text that was affected by a buffer action.
@@ -39,3 +103,14 @@ pub enum ActionRange {
     /// A range of characters identified by the start and end point.
     PointToPoint { from: Point, to: Point },
 }
+
+/// Returns the end of the given text when it starts at `start`.
+fn end_of(start: Point, text: &str) -> Point {
+    match text.rsplit_once('\n') {
+        Some((before, last)) => Point::new(
+            last.chars().count(),
+            start.row + before.matches('\n').count() + 1,
+        ),
+        None => Point::new(start.col + text.chars().count(), start.row),
+    }
+}
@@ -1,8 +1,10 @@
+use std::ops::Range;
+
 #[derive(Debug, Default, Clone)]
 pub struct Row {
     /// The text of the row.
     text: String,
-    /// The length of the row.
+    /// The length of the row in characters.
     len: usize,
 }
 
@@ -10,7 +12,7 @@ impl Row {
     /// Returns a new row with the given text.
     pub fn new<S: Into<String>>(s: S) -> Self {
         let text = s.into();
-        let len = text.len();
+        let len = text.chars().count();
         Self { text, len }
     }
 
@@ -24,7 +26,7 @@ impl Row {
             return true;
         }
 
-        self.text.insert(index, c);
+        self.text.insert(self.byte_index(index), c);
         self.len += 1;
         true
     }
@@ -35,20 +37,41 @@ impl Row {
         self.len += 1;
     }
 
+    /// Appends spaces to the row until it is `col` characters long. Does nothing if the row is
+    /// already long enough.
+    pub fn pad_to(&mut self, col: usize) {
+        while self.len < col {
+            self.append_char(' ');
+        }
+    }
+
     /// Deletes a character at the given index. Returns `true` if the character was deleted,
     /// `false` otherwise.
     pub fn delete_char(&mut self, index: usize) -> bool {
         if index >= self.len {
             return false;
         }
-        self.text.remove(index);
+        self.text.remove(self.byte_index(index));
         self.len -= 1;
         true
     }
 
+    /// Removes the characters between the given columns and returns them. The end column is
+    /// exclusive and clamped to the end of the row.
+    pub fn remove_range(&mut self, start: usize, end: usize) -> String {
+        let (from, to) = (self.byte_index(start), self.byte_index(end));
+        if from >= to {
+            return String::new();
+        }
+
+        let removed: String = self.text.drain(from..to).collect();
+        self.len -= removed.chars().count();
+        removed
+    }
+
     /// Splits the row at the given index and returns a tuple containing the parts.
     pub fn split_at(&self, index: usize) -> (Self, Self) {
-        let (left, right) = self.text.split_at(index);
+        let (left, right) = self.text.split_at(self.byte_index(index));
         (Row::new(left), Row::new(right))
     }
 
@@ -58,11 +81,16 @@ impl Row {
         self.len += row.len;
     }
 
-    /// Finds the next occurrence of the given string in the row and returns the column or `None`
-    /// if not found..
+    /// Finds the next occurrence of the given string in the row, starting from the given column,
+    /// and returns the column or `None` if not found.
     pub fn find_next(&self, s: &str, offset: usize) -> Option<usize> {
-        let offset_text = self.text.get(offset..).unwrap_or_default();
-        offset_text.find(s).map(|x| x + offset)
+        if offset > self.len {
+            return None;
+        }
+        let offset_text = &self.text[self.byte_index(offset)..];
+        offset_text
+            .find(s)
+            .map(|x| offset + offset_text[..x].chars().count())
     }
 
     /// Returns a `String` of the characters in a specific range.
@@ -73,6 +101,87 @@ impl Row {
             .collect()
     }
 
+    /// Returns the character at the given column, or `None` if the column is at or past the end
+    /// of the row.
+    pub fn char_at(&self, col: usize) -> Option<char> {
+        self.chars().nth(col)
+    }
+
+    /// Returns the column range of the word containing the given column, where a word is a run
+    /// of alphanumeric characters and underscores. Returns `None` if the column is not on a word.
+    pub fn word_at(&self, col: usize) -> Option<Range<usize>> {
+        let chars: Vec<char> = self.chars().collect();
+        if !is_word_char(*chars.get(col)?) {
+            return None;
+        }
+
+        let start = chars[..col]
+            .iter()
+            .rposition(|&c| !is_word_char(c))
+            .map_or(0, |i| i + 1);
+        let end = chars[col..]
+            .iter()
+            .position(|&c| !is_word_char(c))
+            .map_or(chars.len(), |i| col + i);
+        Some(start..end)
+    }
+
+    /// Returns the column range of the integer containing the given column, or of the first one
+    /// after it. Integers are decimal with an optional leading `-`, or hexadecimal with a `0x`
+    /// prefix. Returns `None` if there is no integer at or after the column.
+    pub fn number_at(&self, col: usize) -> Option<Range<usize>> {
+        let chars: Vec<char> = self.chars().collect();
+        let mut i = 0;
+        while i < chars.len() {
+            let hex = chars[i] == '0'
+                && matches!(chars.get(i + 1), Some('x' | 'X'))
+                && chars.get(i + 2).is_some_and(char::is_ascii_hexdigit);
+            let range = if hex {
+                let end = chars[i + 2..]
+                    .iter()
+                    .position(|c| !c.is_ascii_hexdigit())
+                    .map_or(chars.len(), |n| i + 2 + n);
+                i..end
+            } else if chars[i].is_ascii_digit() {
+                let end = chars[i..]
+                    .iter()
+                    .position(|c| !c.is_ascii_digit())
+                    .map_or(chars.len(), |n| i + n);
+                let start = if i > 0 && chars[i - 1] == '-' {
+                    i - 1
+                } else {
+                    i
+                };
+                start..end
+            } else {
+                i += 1;
+                continue;
+            };
+            if range.end > col {
+                return Some(range);
+            }
+            i = range.end;
+        }
+        None
+    }
+
+    /// Returns the column where the word ending at the given column starts. Equal to `col` if
+    /// the character before the column is not part of a word.
+    pub fn word_start_before(&self, col: usize) -> usize {
+        let chars: Vec<char> = self.chars().take(col).collect();
+        chars
+            .iter()
+            .rposition(|&c| !is_word_char(c))
+            .map_or(0, |i| i + 1)
+    }
+
+    /// Returns an iterator over the words of the row.
+    pub fn words(&self) -> impl Iterator<Item = &str> {
+        self.text
+            .split(|c: char| !is_word_char(c))
+            .filter(|word| !word.is_empty())
+    }
+
     /// Returns the length of the row.
     pub fn len(&self) -> usize {
         self.len
@@ -87,4 +196,18 @@ impl Row {
     pub fn text(&self) -> &str {
         &self.text
     }
+
+    /// Returns the byte index of the character at the given column, or the length of the text if
+    /// the column is at or past the end of the row.
+    fn byte_index(&self, col: usize) -> usize {
+        self.text
+            .char_indices()
+            .nth(col)
+            .map_or(self.text.len(), |(i, _)| i)
+    }
+}
+
+/// Returns `true` if the character is part of a word, i.e. alphanumeric or an underscore.
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
 }
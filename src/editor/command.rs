@@ -1,20 +1,48 @@
-use crate::editor::{pane::cursor::CursorMovement, prompt::files::FilesPrompt};
-use std::{collections::HashMap, fmt::Debug, rc::Rc};
+use crate::editor::{
+    buffer::{CharAt, indent::Indent, line_ending::LineEnding},
+    pane::{Pane, cursor::CursorMovement},
+    prompt::files::FilesPrompt,
+};
+use std::{collections::BTreeMap, fmt::Debug, rc::Rc};
 
+use crossterm::event::KeyCode;
 use define_commands_macro::define_commands;
 use thiserror::Error;
 
 use crate::editor::{
     self, Editor,
-    prompt::{PromptResponse, PromptType, confirm::ConfirmPrompt, search::SearchPrompt},
+    keymap::{describe_key, key_chord},
+    prompt::{
+        PromptResponse, PromptType,
+        choice::ChoicePrompt,
+        confirm::{ConfirmChoice, ConfirmPrompt},
+        describe_key::DescribeKeyPrompt,
+        input::InputPrompt,
+        number::NumberPrompt,
+        search::SearchPrompt,
+    },
+    registers::RegisterName,
 };
 
 #[derive(Debug, Error)]
 pub enum Error {
-    #[error("Missing argument: {0}")]
-    MissingArgument(String),
-    #[error("Invalid argument for {name}: {error}")]
-    InvalidArgument { name: String, error: String },
+    #[error("No such command found: {0}")]
+    UnknownCommand(String),
+    #[error("{command}: missing <{name}> — usage: {usage}")]
+    MissingArgument {
+        command: String,
+        name: String,
+        usage: String,
+    },
+    #[error("{command}: invalid <{name}> at argument {position}: {error} — usage: {usage}")]
+    InvalidArgument {
+        command: String,
+        name: String,
+        /// The position of the invalid argument, starting from 1.
+        position: usize,
+        error: String,
+        usage: String,
+    },
     #[allow(clippy::enum_variant_names)]
     #[error(transparent)]
     ExecutionError(#[from] editor::Error),
@@ -28,20 +56,38 @@ pub trait CommandSpec {
     /// Returns a description of the command.
     fn description(&self) -> &'static str;
 
+    /// Returns `true` if the command always opens a prompt.
+    fn opens_prompt(&self) -> bool {
+        false
+    }
+
+    /// Returns the usage of the command, e.g. `open <path: String>`. Optional arguments are shown
+    /// in square brackets.
+    fn usage(&self) -> &'static str;
+
     /// Parses a string of arguments into a runnable command.
     fn parse(&self, raw_args: &str) -> Result<Box<dyn Command>, Error>;
 }
 
 /// A command that encompasses a runnable command and its arguments.
 pub trait Command {
+    /// Returns the name of the command.
+    fn name(&self) -> &'static str;
+
     /// Executes the command.
     fn execute(&self, editor: &mut Editor) -> Result<(), Error>;
+
+    /// Returns `true` if the command only moves the cursor, so that repeats of it can be handled
+    /// without rendering in between.
+    fn moves_cursor(&self) -> bool {
+        false
+    }
 }
 
-/// A registry for all available commands.
+/// A registry for all available commands, keyed and ordered by their lowercase names.
 #[derive(Default)]
 pub struct CommandRegistry {
-    commands: HashMap<String, Rc<dyn CommandSpec>>,
+    commands: BTreeMap<String, Rc<dyn CommandSpec>>,
 }
 
 impl CommandRegistry {
@@ -60,22 +106,94 @@ impl CommandRegistry {
         self.commands.get(name).cloned()
     }
 
-    /// Returns an iterator over all commands.
+    /// Returns an iterator over all commands, sorted by name.
     pub fn get_all_commands(&self) -> impl Iterator<Item = &Rc<dyn CommandSpec>> {
         self.commands.values()
     }
 }
 
+/// Runs a command once in every pane. Unlike other commands, it takes the rest of the query as
+/// its argument, so it is not defined with `define_commands!`.
+pub struct AllPanesSpec;
+
+impl CommandSpec for AllPanesSpec {
+    fn name(&self) -> &'static str {
+        "AllPanes"
+    }
+
+    fn description(&self) -> &'static str {
+        "Run a command in every pane, e.g. `allpanes movecursortostartofbuffer`"
+    }
+
+    fn usage(&self) -> &'static str {
+        "allpanes <command> [args...]"
+    }
+
+    fn parse(&self, raw_args: &str) -> Result<Box<dyn Command>, Error> {
+        let query = raw_args.trim();
+        if query.is_empty() {
+            return Err(Error::MissingArgument {
+                command: "allpanes".to_string(),
+                name: "command".to_string(),
+                usage: self.usage().to_string(),
+            });
+        }
+        Ok(Box::new(AllPanes {
+            query: query.to_string(),
+        }))
+    }
+}
+
+pub struct AllPanes {
+    /// The command to run and its arguments.
+    pub query: String,
+}
+
+impl Command for AllPanes {
+    fn name(&self) -> &'static str {
+        "AllPanes"
+    }
+
+    fn execute(&self, editor: &mut Editor) -> Result<(), Error> {
+        let mut parts = self.query.splitn(2, char::is_whitespace);
+        let name = parts.next().unwrap_or_default().to_lowercase();
+        let raw_args = parts.next().unwrap_or_default().trim_start();
+        let spec = editor
+            .command_registry
+            .get(&name)
+            .ok_or(Error::UnknownCommand(name))?;
+        // A prompt can only be answered for one pane.
+        if spec.opens_prompt() {
+            return Err(Error::InvalidArgument {
+                command: "allpanes".to_string(),
+                name: "command".to_string(),
+                position: 1,
+                error: format!("{} opens a prompt", spec.name()),
+                usage: AllPanesSpec.usage().to_string(),
+            });
+        }
+
+        let command = spec.parse(raw_args)?;
+        editor.execute_in_all_panes(command.as_ref());
+        Ok(())
+    }
+}
+
 define_commands! {
     // Editor actions.
     Quit {
         description: "Quit the editor",
         handler: {
-            if !editor.pane_manager.iter().any(|d| d.is_dirty()) {
-                editor.should_quit = true;
-            } else {
+            if editor.pane_manager.iter().any(|d| d.is_dirty()) {
                 editor.prompt_manager.show_prompt(
-                    PromptType::Confirm(ConfirmPrompt::new("There are open panes with unsaved changes, do you want to save them before quitting?")),
+                    PromptType::Confirm(ConfirmPrompt::with_choices(
+                        "There are open panes with unsaved changes, do you want to save them before quitting?",
+                        vec![
+                            ConfirmChoice::new("save", KeyCode::Char('s'), PromptResponse::Yes),
+                            ConfirmChoice::new("discard", KeyCode::Char('d'), PromptResponse::No),
+                            ConfirmChoice::new("cancel", KeyCode::Esc, PromptResponse::Cancel),
+                        ],
+                    )),
                     |editor, response| {
                         match response {
                             PromptResponse::Yes => {
@@ -88,42 +206,160 @@ define_commands! {
                         Ok(())
                     }
                 );
+            } else if editor.state.config.editor.confirm_quit {
+                editor.prompt_manager.show_prompt(
+                    PromptType::Confirm(ConfirmPrompt::new("Quit tedit?")),
+                    |editor, response| {
+                        if response == PromptResponse::Yes {
+                            editor.should_quit = true;
+                        }
+                        Ok(())
+                    }
+                );
+            } else {
+                editor.should_quit = true;
             }
         }
     },
+    ForceQuit {
+        description: "Quit the editor without saving unsaved changes",
+        handler: { editor.should_quit = true; }
+    },
+    WriteQuit {
+        description: "Save the current pane and close it, quitting the editor if it is the last pane",
+        args: [ path: Option<String> ],
+        handler: { editor.write_quit(self.path.clone())?; }
+    },
+    ReloadConfig {
+        description: "Reload the configuration file",
+        handler: { editor.reload_config()?; }
+    },
+    ToggleDebugOverlay {
+        description: "Toggle an overlay with frame timings and render statistics",
+        handler: { editor.toggle_debug_overlay(); }
+    },
+    OpenLog {
+        description: "Open the log file in a read-only buffer",
+        handler: { editor.open_log(); }
+    },
+    DismissMessage {
+        description: "Dismiss the current status message",
+        handler: { editor.dismiss_message(); }
+    },
+    ToggleMouse {
+        description: "Toggle capturing of mouse events",
+        handler: { editor.toggle_mouse()?; }
+    },
     Save {
         description: "Save the current pane",
         args: [ path: Option<String> ],
         handler: { editor.save_active_buffer(self.path.clone())?; }
     },
+    WriteCopy {
+        description: "Write a copy of the current pane to a file, keeping the pane on its file",
+        args: [ path: String ],
+        handler: { editor.write_copy(&self.path)?; }
+    },
+    InsertChar {
+        description: "Insert a character given as U+XXXX, a decimal codepoint, or a name like emdash",
+        args: [ char: String ],
+        handler: {
+            let c = parse_char(&self.char).map_err(|error| Error::InvalidArgument {
+                command: "insertchar".to_string(),
+                name: "char".to_string(),
+                position: 1,
+                error,
+                usage: InsertCharSpec.usage().to_string(),
+            })?;
+            let modification = editor
+                .pane_manager
+                .active_mut()
+                .edit_at_cursors(|pane| pane.insert_char(c));
+            editor.handle_buffer_modification(&modification);
+        }
+    },
+    ReadFile {
+        description: "Insert the contents of a file at the cursor",
+        args: [ path: String ],
+        handler: { editor.read_file(&self.path); }
+    },
     OpenSearch {
         description: "Open a search prompt",
+        opens_prompt: true,
         handler: {
             editor.prompt_manager.show_prompt(
-                PromptType::Search(SearchPrompt::new(editor.pane_manager.active_mut().clone())),
+                PromptType::Search(Box::new(SearchPrompt::new(
+                    editor.pane_manager.active_mut().clone(),
+                    editor.search_history.entries(),
+                ))),
                 |editor, response| {
                     // TODO: Use text to populate a new search state struct in editor for jumping
                     // between all search results.
                     if let PromptResponse::Text(text) = response {
                         let message = format!("Searched for: {text}");
                         editor.show_message(&message);
+                        editor.search_history.push(&text);
                     }
                     Ok(())
                 }
             );
         }
     },
+    SearchAllBuffers {
+        description: "Search all open buffers for the query, or the last search if none is given",
+        args: [ query: Option<String> ],
+        opens_prompt: true,
+        handler: {
+            let last_search = editor.search_history.last().map(str::to_string);
+            match self.query.clone().or(last_search) {
+                Some(query) => editor.search_all_buffers(&query),
+                None => editor.prompt_manager.show_prompt(
+                    PromptType::Input(InputPrompt::new("search all buffers", "")),
+                    |editor, response| {
+                        if let PromptResponse::Text(query) = response
+                            && !query.is_empty()
+                        {
+                            editor.search_all_buffers(&query);
+                        }
+                        Ok(())
+                    }
+                ),
+            }
+        }
+    },
+    CompleteWord {
+        description: "Complete the word before the cursor with words from the open buffers",
+        handler: { editor.open_completion(); }
+    },
+    NextLocation {
+        description: "Go to the next location in the location list",
+        handler: { editor.goto_location(true)?; }
+    },
+    PrevLocation {
+        description: "Go to the previous location in the location list",
+        handler: { editor.goto_location(false)?; }
+    },
+    CloseLocationList {
+        description: "Close the location list",
+        handler: { editor.close_location_list(); }
+    },
+    LoadErrorformat {
+        description: "Load the path:line:col locations in compiler output from a file, or the active buffer, into the location list",
+        args: [ path: Option<String> ],
+        handler: { editor.load_errorformat(self.path.as_deref()); }
+    },
     EnterInsertMode {
         description: "Enter insert mode",
-        handler: { editor.mode = editor::Mode::Insert; }
+        handler: { editor.state.mode = editor::Mode::Insert; }
     },
     EnterCommandMode {
         description: "Enter command mode",
-        handler: { editor.mode = editor::Mode::Command; }
+        handler: { editor.state.mode = editor::Mode::Command; }
     },
     OpenFilesPicker {
         description: "Open a file picker",
         args: [ dir: Option<String> ],
+        opens_prompt: true,
         handler: {
             let Ok(prompt) = FilesPrompt::new(self.dir.as_deref()) else {
                 editor.show_err_message("Failed to open file picker");
@@ -133,23 +369,63 @@ define_commands! {
             editor.prompt_manager.show_prompt(
                 PromptType::Files(prompt),
                 |editor, response| {
-                    if let PromptResponse::File(ref file) = response {
-                        editor.open_file(file)?;
+                    match response {
+                        PromptResponse::File(file) => editor.open_file(file)?,
+                        PromptResponse::Files(files) => editor.open_files(&files),
+                        _ => {}
                     }
                     Ok(())
                 }
             );
         }
     },
+    ToggleExplorer {
+        description: "Show and focus the file explorer, or hide it if it is focused",
+        handler: { editor.toggle_explorer(); }
+    },
+    NoExpandAbbrev {
+        description: "Type the next word-ending key without expanding the abbreviation before it",
+        handler: { editor.skip_next_abbreviation(); }
+    },
+    SpellSuggest {
+        description: "Pick a correction for the misspelled word under the cursor",
+        opens_prompt: true,
+        handler: { editor.spell_suggest(); }
+    },
+    AddToDictionary {
+        description: "Add a word, or the word under the cursor, to the user dictionary",
+        args: [ word: Option<String> ],
+        handler: { editor.add_to_dictionary(self.word.as_deref()); }
+    },
+    HexView {
+        description: "Show the file of the active buffer as a read-only hex dump",
+        handler: { editor.open_hex_view(); }
+    },
+    ToggleHexView {
+        description: "Show the hex dump of the active file, or return to its text if it is valid UTF-8",
+        handler: { editor.toggle_hex_view(); }
+    },
     Theme {
-        description: "Change the current theme",
-        args: [ theme: String ],
+        description: "Change the current theme, or pick one from a list if none is given",
+        args: [ theme: Option<String> ],
         handler: {
-            if let Some(theme) = editor.theme_registry.themes.get(&self.theme) {
-                editor.theme = theme.clone();
-                editor.show_message(&format!("Loaded theme: {}", self.theme));
-            } else {
-                editor.show_err_message(&format!("No such theme: {}", self.theme));
+            match &self.theme {
+                Some(theme) => editor.set_theme(theme),
+                None => {
+                    let mut themes = editor.theme_registry.list_themes();
+                    themes.sort();
+                    editor.prompt_manager.show_prompt(
+                        PromptType::Choice(ChoicePrompt::new("Pick a theme", themes.clone())),
+                        move |editor, response| {
+                            if let PromptResponse::Choice(index) = response
+                                && let Some(theme) = themes.get(index)
+                            {
+                                editor.set_theme(theme);
+                            }
+                            Ok(())
+                        }
+                    );
+                }
             }
         },
     },
@@ -160,6 +436,110 @@ define_commands! {
            editor.show_message(&themes.join(" "));
         },
     },
+    Suspend {
+        description: "Suspend the editor to the shell",
+        handler: { editor.suspend()?; }
+    },
+    SetFiletype {
+        description: "Override the filetype of the current buffer",
+        args: [ filetype: String ],
+        handler: {
+            editor.pane_manager.active_mut().set_filetype(&self.filetype);
+            editor.show_message(&format!("Set filetype: {}", self.filetype));
+        },
+    },
+    SetLineEnding {
+        description: "Convert the line ending of the current buffer (lf or crlf)",
+        args: [ line_ending: LineEnding ],
+        handler: {
+            editor.pane_manager.active_mut().set_line_ending(self.line_ending);
+            editor.show_message(&format!("Set line ending: {}", self.line_ending));
+        },
+    },
+    SetIndent {
+        description: "Override the indentation of the current buffer (tabs or spaces:<width>)",
+        args: [ indent: Indent ],
+        handler: {
+            editor.pane_manager.active_mut().set_indent(self.indent);
+            editor.show_message(&format!("Set indent: {}", self.indent));
+        },
+    },
+    Messages {
+        description: "Open the message history in a read-only buffer",
+        handler: { editor.open_message_log(); }
+    },
+    DescribeKeyEvent {
+        description: "Show how the terminal reports the next pressed key",
+        opens_prompt: true,
+        handler: {
+            editor.prompt_manager.show_prompt(
+                PromptType::DescribeKey(DescribeKeyPrompt::new(
+                    "Press a key to show its event, or Esc to cancel...",
+                )),
+                |editor, response| {
+                    if let PromptResponse::Key(key) = response
+                        && !(key.code == KeyCode::Esc && key.modifiers.is_empty())
+                    {
+                        editor.show_message(&format!(
+                            "{} (code: {:?}, modifiers: {:?})",
+                            key_chord(&key),
+                            key.code,
+                            key.modifiers
+                        ));
+                    }
+                    Ok(())
+                }
+            );
+        }
+    },
+    DescribeKey {
+        description: "Show the command bound to the next pressed key",
+        opens_prompt: true,
+        handler: {
+            editor.prompt_manager.show_prompt(
+                PromptType::DescribeKey(DescribeKeyPrompt::default()),
+                |editor, response| {
+                    if let PromptResponse::Key(key) = response {
+                        let message = match editor.keymap.get(&key) {
+                            Some(command) => {
+                                format!("{} is bound to {}", describe_key(&key), command.name())
+                            }
+                            None => format!("{} is not bound", describe_key(&key)),
+                        };
+                        editor.show_message(&message);
+                    }
+                    Ok(())
+                }
+            );
+        }
+    },
+    DiffUnsaved {
+        description: "Show the unsaved changes of the buffer as a diff against its file",
+        handler: { editor.diff_unsaved(); }
+    },
+    ShowDiagnostics {
+        description: "Open the diagnostics of all buffers in a read-only buffer",
+        handler: { editor.show_diagnostics(); }
+    },
+    GotoNextDiagnostic {
+        description: "Move the cursor to the next diagnostic in the current buffer",
+        handler: { editor.goto_next_diagnostic(); }
+    },
+    DescribeChar {
+        description: "Show the character under the cursor and its codepoint",
+        handler: {
+            let pane = editor.pane_manager.active();
+            let col = pane.cursor_position().0 + 1;
+            let message = match pane.char_under_cursor() {
+                CharAt::Char(c) => {
+                    format!("{c:?} U+{:04X} ({} bytes, col {col})", c as u32, c.len_utf8())
+                }
+                CharAt::EndOfLine => format!("<EOL> (col {col})"),
+                CharAt::EndOfFile => "<EOF>".to_string(),
+            };
+            editor.show_message(&message);
+        }
+    },
     // // Pane and buffer handling.
     Open {
         description: "Open a file",
@@ -168,12 +548,11 @@ define_commands! {
     },
     DuplicatePane {
         description: "Duplicate the current pane",
-        handler: {
-            let path = editor.pane_manager.active().file_name();
-            if let Err(err) = editor.open_file(path) {
-                return Err(Error::ExecutionError(err));
-            }
-        }
+        handler: { editor.pane_manager.duplicate_active(); }
+    },
+    ToggleScrollBind {
+        description: "Scroll the pane along with other scroll-bound panes showing the same buffer",
+        handler: { editor.toggle_scroll_bind(); }
     },
     ClosePane {
         description: "Close the current pane",
@@ -212,66 +591,352 @@ define_commands! {
     // Cursor movements.
     MoveCursorLeft {
         description: "Move the cursor left",
+        moves_cursor: true,
         handler: { editor.pane_manager.active_mut().move_cursor(CursorMovement::Left); }
     },
     MoveCursorRight {
         description: "Move the cursor right",
+        moves_cursor: true,
         handler: { editor.pane_manager.active_mut().move_cursor(CursorMovement::Right); }
     },
     MoveCursorUp {
         description: "Move the cursor up",
+        moves_cursor: true,
         handler: { editor.pane_manager.active_mut().move_cursor(CursorMovement::Up); }
     },
     MoveCursorDown {
         description: "Move the cursor down",
+        moves_cursor: true,
         handler: { editor.pane_manager.active_mut().move_cursor(CursorMovement::Down); }
     },
     MoveCursorToStartOfRow {
         description: "Move the cursor to the start of the row",
+        moves_cursor: true,
         handler: { editor.pane_manager.active_mut().move_cursor(CursorMovement::StartOfRow); },
     },
+    MoveCursorToSmartHome {
+        description: "Move the cursor to the first non-whitespace character, then the start of the row",
+        moves_cursor: true,
+        handler: { editor.pane_manager.active_mut().move_cursor(CursorMovement::SmartHome); }
+    },
     MoveCursorToEndOfRow {
         description: "Move the cursor to the end of the row",
+        moves_cursor: true,
         handler: { editor.pane_manager.active_mut().move_cursor(CursorMovement::EndOfRow); }
     },
     MoveCursorToStartOfBuffer {
         description: "Move the cursor to the start of the buffer",
+        moves_cursor: true,
         handler: { editor.pane_manager.active_mut().move_cursor(CursorMovement::StartOfBuffer); }
     },
     MoveCursorToEndOfBuffer {
         description: "Move the cursor to the end of the buffer",
+        moves_cursor: true,
         handler: { editor.pane_manager.active_mut().move_cursor(CursorMovement::EndOfBuffer); }
     },
+    MoveCursorParagraphForward {
+        description: "Move the cursor to the next blank line",
+        moves_cursor: true,
+        handler: { editor.pane_manager.active_mut().move_cursor(CursorMovement::ParagraphForward); }
+    },
+    MoveCursorParagraphBackward {
+        description: "Move the cursor to the previous blank line",
+        moves_cursor: true,
+        handler: { editor.pane_manager.active_mut().move_cursor(CursorMovement::ParagraphBackward); }
+    },
     MoveCursorToLine {
-        description: "Move the cursor to the selected line",
-        args: [ line: usize ],
-        handler: { editor.pane_manager.active_mut().move_cursor(CursorMovement::Line(self.line)); }
+        description: "Move the cursor to the given line, or prompt for it if none is given",
+        args: [ line: Option<usize> ],
+        handler: {
+            match self.line {
+                Some(line) => editor.pane_manager.active_mut().move_cursor(CursorMovement::Line(line)),
+                None => {
+                    let lines = editor.pane_manager.active().buffer_lines();
+                    editor.prompt_manager.show_prompt(
+                        PromptType::Number(
+                            NumberPrompt::new("Go to line").with_bounds(Some(1), Some(lines as i64)),
+                        ),
+                        |editor, response| {
+                            if let PromptResponse::Number(line) = response
+                                && let Ok(line) = usize::try_from(line)
+                            {
+                                editor.pane_manager.active_mut().move_cursor(CursorMovement::Line(line));
+                            }
+                            Ok(())
+                        }
+                    );
+                }
+            }
+        }
     },
     MoveCursorToPosition {
         description: "Move the cursor to the given column and row",
         args: [ col: usize, row: usize ],
         handler: { editor.pane_manager.active_mut().move_cursor(CursorMovement::Position(self.col, self.row)); }
     },
+    SelectAll {
+        description: "Select the whole buffer",
+        handler: { editor.pane_manager.active_mut().select_all(); }
+    },
+    AddCursorBelow {
+        description: "Add a cursor one row below the lowest cursor",
+        handler: { editor.pane_manager.active_mut().add_cursor(false); }
+    },
+    AddCursorAbove {
+        description: "Add a cursor one row above the highest cursor",
+        handler: { editor.pane_manager.active_mut().add_cursor(true); }
+    },
+    SelectNextOccurrence {
+        description: "Add a cursor at the next occurrence of the word under the cursor",
+        handler: {
+            if !editor.pane_manager.active_mut().select_next_occurrence() {
+                editor.show_message("No more occurrences");
+            }
+        }
+    },
+    JumpBack {
+        description: "Jump back to the previous cursor position in the jump list",
+        handler: { editor.jump_back(); }
+    },
+    JumpForward {
+        description: "Jump forward to the next cursor position in the jump list",
+        handler: { editor.jump_forward(); }
+    },
+    SetMark {
+        description: "Set a mark with a single character name at the cursor position",
+        args: [ name: char ],
+        handler: { editor.set_mark(self.name); }
+    },
+    GotoMark {
+        description: "Move the cursor to the mark with the given name",
+        args: [ name: char ],
+        handler: { editor.goto_mark(self.name)?; }
+    },
+    ListMarks {
+        description: "Lists all marks",
+        handler: { editor.list_marks(); }
+    },
+    ToggleFold {
+        description: "Fold the block starting at the cursor, or unfold the fold under the cursor",
+        handler: {
+            if !editor.pane_manager.active_mut().toggle_fold() {
+                editor.show_warn_message("Nothing to fold");
+            }
+        }
+    },
+    OpenAllFolds {
+        description: "Unfold all folds in the active pane",
+        handler: { editor.pane_manager.active_mut().open_all_folds(); }
+    },
+    CloseAllFolds {
+        description: "Fold every foldable block in the active pane",
+        handler: { editor.pane_manager.active_mut().close_all_folds(); }
+    },
     // Text manipulation.
+    SortLines {
+        description: "Sort the lines of the buffer, optionally in reverse and without duplicates",
+        args: [ reverse: Option<bool>, unique: Option<bool> ],
+        handler: {
+            let buffer_mod = editor
+                .pane_manager
+                .active_mut()
+                .sort_lines(self.reverse.unwrap_or(false), self.unique.unwrap_or(false));
+            editor.handle_buffer_modification(&buffer_mod);
+        }
+    },
+    ReplaceInLines {
+        description: "Replace all occurrences of a pattern between two lines, both inclusive",
+        args: [ start: usize, end: usize, pattern: String, replacement: String ],
+        handler: {
+            let lines = editor.pane_manager.active().buffer_lines();
+            let invalid = |name: &str, position, min| Error::InvalidArgument {
+                command: "replaceinlines".to_string(),
+                name: name.to_string(),
+                position,
+                error: format!("must be between {min} and {lines}"),
+                usage: ReplaceInLinesSpec.usage().to_string(),
+            };
+            if !(1..=lines).contains(&self.start) {
+                return Err(invalid("start", 1, 1));
+            }
+            if !(self.start..=lines).contains(&self.end) {
+                return Err(invalid("end", 2, self.start));
+            }
+
+            let (buffer_mod, count) = editor.pane_manager.active_mut().replace_in_lines(
+                self.start - 1,
+                self.end - 1,
+                &self.pattern,
+                &self.replacement,
+            );
+            editor.handle_buffer_modification(&buffer_mod);
+            let plural = if count == 1 { "" } else { "s" };
+            editor.show_message(&format!("Replaced {count} occurrence{plural}"));
+        }
+    },
+    ReverseLines {
+        description: "Reverse the order of the lines of the buffer",
+        handler: {
+            let buffer_mod = editor.pane_manager.active_mut().reverse_lines();
+            editor.handle_buffer_modification(&buffer_mod);
+        }
+    },
+    Yank {
+        description: "Copy the selection or the current line, optionally into a named register",
+        args: [ register: Option<RegisterName> ],
+        handler: { editor.yank(self.register); }
+    },
+    Delete {
+        description: "Delete the selection or the current line, optionally into a named register",
+        args: [ register: Option<RegisterName> ],
+        handler: { editor.delete(self.register); }
+    },
+    Paste {
+        description: "Paste the unnamed register, or the given numbered or named register",
+        args: [ register: Option<RegisterName> ],
+        handler: {
+            // Pasting over a selection switches it to a block selection instead.
+            if self.register.is_some() || !editor.pane_manager.active_mut().toggle_block_selection() {
+                editor.paste(self.register);
+            }
+        }
+    },
+    ToggleBlockSelection {
+        description: "Switch the selection between a character-wise and a block selection",
+        handler: {
+            if !editor.pane_manager.active_mut().toggle_block_selection() {
+                editor.show_warn_message("Nothing is selected");
+            }
+        }
+    },
+    ListRegisters {
+        description: "Open the contents of all registers in a read-only buffer",
+        handler: { editor.list_registers(); }
+    },
+    InsertLineBelow {
+        description: "Insert an empty line below the cursor and move onto it",
+        handler: {
+            let auto_indent = editor.state.config.editor.auto_indent;
+            let buffer_mod = editor.pane_manager.active_mut().insert_line_below(auto_indent);
+            editor.handle_buffer_modification(&buffer_mod);
+        }
+    },
+    InsertLineAbove {
+        description: "Insert an empty line above the cursor and move onto it",
+        handler: {
+            let auto_indent = editor.state.config.editor.auto_indent;
+            let buffer_mod = editor.pane_manager.active_mut().insert_line_above(auto_indent);
+            editor.handle_buffer_modification(&buffer_mod);
+        }
+    },
+    ToggleComment {
+        description: "Comment or uncomment the selected lines or the current line",
+        handler: { editor.toggle_comment(); }
+    },
+    InsertIndent {
+        description: "Insert a level of indentation",
+        handler: {
+            let default = editor.state.config.editor.default_indent();
+            let buffer_mod = editor
+                .pane_manager
+                .active_mut()
+                .edit_at_cursors(|pane| pane.insert_indent(default));
+            editor.handle_buffer_modification(&buffer_mod);
+        }
+    },
     InsertNewline {
         description: "Insert a newline",
         handler: {
-            let buffer_mod = editor.pane_manager.active_mut().insert_newline();
-            editor.pane_manager.handle_buffer_modification(&buffer_mod);
+            let buffer_mod = editor.pane_manager.active_mut().edit_at_cursors(Pane::insert_newline);
+            editor.handle_buffer_modification(&buffer_mod);
         }
     },
     DeleteChar {
         description: "Delete the character under the cursor",
         handler: {
-            let buffer_mod = editor.pane_manager.active_mut().delete_char();
-            editor.pane_manager.handle_buffer_modification(&buffer_mod);
+            let buffer_mod = editor.pane_manager.active_mut().edit_at_cursors(Pane::delete_char);
+            editor.handle_buffer_modification(&buffer_mod);
+        }
+    },
+    DeleteToEndOfLine {
+        description: "Delete from the cursor to the end of the line",
+        handler: {
+            let buffer_mod = editor.pane_manager.active_mut().delete_to_end_of_line();
+            editor.handle_buffer_modification(&buffer_mod);
+        }
+    },
+    DeleteToStartOfLine {
+        description: "Delete from the start of the line to the cursor",
+        handler: {
+            let buffer_mod = editor.pane_manager.active_mut().delete_to_start_of_line();
+            editor.handle_buffer_modification(&buffer_mod);
+        }
+    },
+    UppercaseWord {
+        description: "Convert the word under the cursor or the selection to upper case",
+        handler: {
+            let buffer_mod = editor.pane_manager.active_mut().change_word_case(true);
+            editor.handle_buffer_modification(&buffer_mod);
+        }
+    },
+    LowercaseWord {
+        description: "Convert the word under the cursor or the selection to lower case",
+        handler: {
+            let buffer_mod = editor.pane_manager.active_mut().change_word_case(false);
+            editor.handle_buffer_modification(&buffer_mod);
+        }
+    },
+    IncrementNumber {
+        description: "Add the count, or 1, to the number under or after the cursor",
+        args: [ count: Option<usize> ],
+        handler: {
+            match i64::try_from(self.count.unwrap_or(1)) {
+                Ok(count) => editor.add_to_number(count),
+                Err(_) => editor.show_err_message("Count is too large"),
+            }
+        }
+    },
+    DecrementNumber {
+        description: "Subtract the count, or 1, from the number under or after the cursor",
+        args: [ count: Option<usize> ],
+        handler: {
+            match i64::try_from(self.count.unwrap_or(1)) {
+                Ok(count) => editor.add_to_number(-count),
+                Err(_) => editor.show_err_message("Count is too large"),
+            }
+        }
+    },
+    ToggleCaseChar {
+        description: "Toggle the case of the character under the cursor and move right",
+        handler: {
+            let buffer_mod = editor.pane_manager.active_mut().toggle_case_char();
+            editor.handle_buffer_modification(&buffer_mod);
         }
     },
     DeleteCharBefore {
         description: "Delete the character before the cursor",
         handler: {
-            let buffer_mod = editor.pane_manager.active_mut().delete_char_before();
-            editor.pane_manager.handle_buffer_modification(&buffer_mod);
+            let buffer_mod = editor
+                .pane_manager
+                .active_mut()
+                .edit_at_cursors(Pane::delete_char_before);
+            editor.handle_buffer_modification(&buffer_mod);
         }
     },
 }
+
+/// Parses a character given as `U+XXXX`, a decimal codepoint, or one of a few names. Codepoints
+/// that are not Unicode scalar values, such as surrogates, are rejected.
+fn parse_char(s: &str) -> Result<char, String> {
+    let codepoint = match s.to_lowercase().as_str() {
+        "nbsp" => 0xA0,
+        "emdash" => 0x2014,
+        "arrow-right" => 0x2192,
+        lower => match lower.strip_prefix("u+") {
+            Some(hex) => u32::from_str_radix(hex, 16),
+            None => lower.parse(),
+        }
+        .map_err(|_| format!("{s} is not a codepoint or a known name"))?,
+    };
+    char::from_u32(codepoint).ok_or_else(|| format!("U+{codepoint:04X} is not a valid character"))
+}
@@ -2,13 +2,14 @@ use crate::editor::{
     command::{self, Command, CommandRegistry, CommandSpec},
     ui::{
         component::{Component, RenderingContext},
-        geometry::rect::Rect,
-        theme::highlight_group::{HL_UI_COMMAND_PROMPT, HL_UI_COMMAND_PROMPT_SELECTED},
-        viewport::Viewport,
-        widget::{
-            container::{ContainerBuilder},
-            span::Span,
+        geometry::{point::Point, rect::Rect},
+        list_window::ListWindow,
+        theme::highlight_group::{
+            HL_UI_COMMAND_PROMPT, HL_UI_COMMAND_PROMPT_EMPTY, HL_UI_COMMAND_PROMPT_SELECTED,
         },
+        unicode::str_width,
+        viewport::Viewport,
+        widget::{container::ContainerBuilder, span::Span},
     },
 };
 
@@ -18,8 +19,9 @@ pub struct CommandInfo {
     /// The name of the command.
     pub name: &'static str,
     /// A description of the command.
-    #[allow(dead_code)]
     pub description: &'static str,
+    /// The usage of the command, e.g. `open <path: String>`.
+    pub usage: &'static str,
 }
 
 impl From<&dyn CommandSpec> for CommandInfo {
@@ -27,6 +29,7 @@ impl From<&dyn CommandSpec> for CommandInfo {
         CommandInfo {
             name: value.name(),
             description: value.description(),
+            usage: value.usage(),
         }
     }
 }
@@ -36,17 +39,25 @@ pub struct CommandPalette {
     /// The current command query. The query should be a space-seperated list beginning with the
     /// name of the command to execute and ending with the arguments to pass to the command.
     query: String,
+    /// The position of the cursor in the query, in characters.
+    cursor: usize,
     /// The index of the currently selected command.
     selected_index: usize,
     /// A list of filtered commands based on the current query.
     filtered_commands: Vec<CommandInfo>,
     /// All commands that are available.
     commands: Vec<CommandInfo>,
+    /// The part of the filtered commands that is visible.
+    window: ListWindow,
+    /// The rect the palette was last rendered in, used to place the cursor in the query.
+    rect: Rect,
 }
 
 impl CommandPalette {
     /// The prefix to render in the query prompt.
     const QUERY_PREIFX: &str = "> ";
+    /// The text to render when no commands match the query.
+    const NO_MATCHES: &str = "no matching commands";
 
     /// Returns a new command palette populated with all commands registered in the given
     /// [`CommandRegistry`].
@@ -59,9 +70,12 @@ impl CommandPalette {
 
         Self {
             query: String::new(),
+            cursor: 0,
             selected_index: 0,
             filtered_commands,
             commands,
+            window: ListWindow::default(),
+            rect: Rect::default(),
         }
     }
 
@@ -70,6 +84,17 @@ impl CommandPalette {
         self.filtered_commands.get(self.selected_index)
     }
 
+    /// Returns the name, description and usage of the selected command, or `None` if no command
+    /// matches the query.
+    pub fn selection_hint(&self) -> Option<String> {
+        self.get_selected_command().map(|command| {
+            format!(
+                "{} — {}  ·  {}",
+                command.name, command.description, command.usage
+            )
+        })
+    }
+
     pub fn get_filtered_command(&self, index: usize) -> Option<&CommandInfo> {
         self.filtered_commands.get(index)
     }
@@ -83,6 +108,11 @@ impl CommandPalette {
             .to_lowercase()
     }
 
+    /// Returns the current query.
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
     /// Parses and returns the current query as an executable command.
     pub fn parse_query(
         &self,
@@ -93,11 +123,9 @@ impl CommandPalette {
         let command_name = parts.next().unwrap_or_default();
         let raw_args = parts.next().unwrap_or("").trim_start();
 
-        if let Some(command) = registry.get(command_name) {
-            Some(command.parse(raw_args))
-        } else {
-            None
-        }
+        registry
+            .get(command_name)
+            .map(|command| command.parse(raw_args))
     }
 
     /// Updates the list of filtered commands based on the current query. Uses substring matching
@@ -116,30 +144,74 @@ impl CommandPalette {
         self.selected_index = self.selected_index.min(last_command_index);
     }
 
-    /// Inserts a character into the current query.
+    /// Inserts a character into the current query at the cursor.
     pub fn insert_char(&mut self, c: char) {
-        self.query.push(c);
+        self.query.insert(self.byte_index(self.cursor), c);
+        self.cursor += 1;
         self.update_filtered_commands();
     }
 
-    /// Deletes a character from the current query.
+    /// Deletes the character before the cursor from the current query.
     pub fn delete_char(&mut self) {
-        self.query.pop();
+        if self.cursor == 0 {
+            return;
+        }
+        self.cursor -= 1;
+        self.query.remove(self.byte_index(self.cursor));
         self.update_filtered_commands();
     }
 
-    /// Updates the query to the given string without updating the filtered commands.
+    /// Deletes the character at the cursor from the current query.
+    pub fn delete_char_forward(&mut self) {
+        if self.cursor >= self.query.chars().count() {
+            return;
+        }
+        self.query.remove(self.byte_index(self.cursor));
+        self.update_filtered_commands();
+    }
+
+    /// Moves the cursor one character to the left in the query.
+    pub fn move_cursor_left(&mut self) {
+        self.cursor = self.cursor.saturating_sub(1);
+    }
+
+    /// Moves the cursor one character to the right in the query.
+    pub fn move_cursor_right(&mut self) {
+        self.cursor = (self.cursor + 1).min(self.query.chars().count());
+    }
+
+    /// Moves the cursor to the start of the query.
+    pub fn move_cursor_to_start(&mut self) {
+        self.cursor = 0;
+    }
+
+    /// Moves the cursor to the end of the query.
+    pub fn move_cursor_to_end(&mut self) {
+        self.cursor = self.query.chars().count();
+    }
+
+    /// Updates the query to the given string without updating the filtered commands. The cursor
+    /// is moved to the end of the query.
     pub fn set_query(&mut self, query: &str) {
         self.query = query.to_string();
+        self.move_cursor_to_end();
     }
 
     /// Autocompletes the current query to the currently selected command.
     pub fn autocomplete(&mut self) {
         if let Some(command) = self.get_selected_command() {
-            self.query = command.name.to_string();
+            self.set_query(command.name);
         }
     }
 
+    /// Returns the byte index of the character at the given position in the query.
+    fn byte_index(&self, cursor: usize) -> usize {
+        self.query
+            .char_indices()
+            .nth(cursor)
+            .map_or(self.query.len(), |(i, _)| i)
+    }
+
     /// Autocompletes the current query to the currently selected command, or selects the next
     /// index if the query is already autocompleted.
     pub fn autocomplete_or_next(&mut self) {
@@ -155,6 +227,9 @@ impl CommandPalette {
 
     /// Selects the next command in the command palette, wrapping around at the end.
     pub fn select_next_command(&mut self) {
+        if self.filtered_commands.is_empty() {
+            return;
+        }
         let next_index = (self.selected_index + 1) % self.filtered_commands.len();
         if let Some(selected_command) = self.get_filtered_command(next_index).map(|c| c.name) {
             self.set_query(selected_command);
@@ -165,6 +240,9 @@ impl CommandPalette {
     /// Selects the previous command in the command palette, wraoping around at the start. Returns
     /// the name of the selected command.
     pub fn select_prev_command(&mut self) {
+        if self.filtered_commands.is_empty() {
+            return;
+        }
         let prev_index = if self.selected_index == 0 {
             self.filtered_commands.len().saturating_sub(1)
         } else {
@@ -180,8 +258,10 @@ impl CommandPalette {
     /// Clears the current query, then resets the selected index and the filtered commands.
     pub fn clear_query(&mut self) {
         self.query.clear();
+        self.cursor = 0;
         self.update_filtered_commands();
         self.selected_index = 0;
+        self.window.reset();
     }
 }
 
@@ -191,8 +271,10 @@ impl Component for CommandPalette {
     }
 
     fn render(&mut self, ctx: &RenderingContext, mut viewport: Viewport) {
+        self.rect = viewport.rect();
+
         // Render the query prompt.
-        let style = ctx.theme.resolve(&HL_UI_COMMAND_PROMPT);
+        let style = ctx.state.theme.resolve(&HL_UI_COMMAND_PROMPT);
         let text = format!("{}{}", Self::QUERY_PREIFX, self.query);
 
         let span = Span::new(&text);
@@ -203,6 +285,13 @@ impl Component for CommandPalette {
             .build();
         viewport.put_widget(viewport.height().saturating_sub(1), widget);
 
+        if self.filtered_commands.is_empty() {
+            let style = ctx.state.theme.resolve(&HL_UI_COMMAND_PROMPT_EMPTY);
+            let span = Span::new(Self::NO_MATCHES).with_style(style);
+            viewport.put_widget(viewport.height().saturating_sub(2), span);
+            return;
+        }
+
         // Calculate the minimum width needed to render the command list.
         let min_width = self
             .filtered_commands
@@ -211,17 +300,21 @@ impl Component for CommandPalette {
             .max()
             .unwrap_or_default();
 
-        // Render the command list above the query prompt.
-        for i in 0..self.filtered_commands.len() {
+        // Render the visible part of the command list above the query prompt, with the position
+        // indicator above it if not all commands fit.
+        let rows = viewport.height().saturating_sub(1);
+        let len = self.filtered_commands.len();
+        let visible = self.window.scroll(self.selected_index, len, rows);
+        for (offset, i) in visible.clone().enumerate() {
             let command = self.filtered_commands.get(i);
             if let Some(command) = command {
-                let row = viewport.height().saturating_sub(i + 2);
+                let row = viewport.height().saturating_sub(offset + 2);
 
                 // TODO: Show description somwhere, maybe in the status bar.
                 let style = if i == self.selected_index {
-                    ctx.theme.resolve(&HL_UI_COMMAND_PROMPT_SELECTED)
+                    ctx.state.theme.resolve(&HL_UI_COMMAND_PROMPT_SELECTED)
                 } else {
-                    ctx.theme.resolve(&HL_UI_COMMAND_PROMPT)
+                    ctx.state.theme.resolve(&HL_UI_COMMAND_PROMPT)
                 };
 
                 let span = Span::new(command.name);
@@ -233,5 +326,68 @@ impl Component for CommandPalette {
                 viewport.put_widget(row, widget);
             }
         }
+
+        if let Some(indicator) = self.window.indicator(self.selected_index, len, rows) {
+            let row = viewport.height().saturating_sub(visible.len() + 2);
+            let widget = ContainerBuilder::default()
+                .with_width(Some(min_width.max(indicator.chars().count())))
+                .with_child(Span::new(&indicator))
+                .with_style(ctx.state.theme.resolve(&HL_UI_COMMAND_PROMPT))
+                .build();
+            viewport.put_widget(row, widget);
+        }
+    }
+
+    fn cursor_position(&self, _ctx: &RenderingContext) -> Option<Point> {
+        let before_cursor: String = self.query.chars().take(self.cursor).collect();
+        let col = str_width(Self::QUERY_PREIFX) + str_width(&before_cursor);
+        Some(Point::new(
+            self.rect.col + col.min(self.rect.width.saturating_sub(1)),
+            self.rect.row + self.rect.height.saturating_sub(1),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::editor::command::{AllPanesSpec, register_commands};
+
+    /// Returns a palette with every command of the editor.
+    fn palette() -> CommandPalette {
+        let mut registry = CommandRegistry::new();
+        register_commands(&mut registry);
+        registry.register(Rc::new(AllPanesSpec));
+        CommandPalette::new(&registry)
+    }
+
+    /// Returns the names of the commands listed by the palette.
+    fn names(palette: &CommandPalette) -> Vec<&'static str> {
+        palette.filtered_commands.iter().map(|c| c.name).collect()
+    }
+
+    #[test]
+    fn fresh_palettes_list_commands_in_the_same_order() {
+        let (mut first, mut second) = (palette(), palette());
+        assert_eq!(names(&first), names(&second));
+
+        let lowercase: Vec<_> = names(&first)
+            .iter()
+            .map(|name| name.to_lowercase())
+            .collect();
+        assert!(lowercase.is_sorted(), "{lowercase:?}");
+
+        for c in "move".chars() {
+            first.insert_char(c);
+            second.insert_char(c);
+        }
+        assert!(names(&first).len() > 1);
+        assert_eq!(names(&first), names(&second));
+
+        first.autocomplete_or_next();
+        second.autocomplete_or_next();
+        assert_eq!(first.query(), second.query());
     }
 }
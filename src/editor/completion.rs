@@ -0,0 +1,99 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    ops::Bound,
+};
+
+use crate::editor::{
+    buffer::{BufferEntry, manager::BufferManager},
+    ui::geometry::point::Point,
+};
+
+/// The words of each buffer, rebuilt lazily after the buffer is modified.
+#[derive(Debug, Default, Clone)]
+pub struct WordIndex {
+    /// The sorted words of each buffer, keyed by buffer id. Missing entries are rebuilt when
+    /// needed.
+    words: HashMap<usize, BTreeSet<String>>,
+}
+
+impl WordIndex {
+    /// Discards the words of the buffer with the given id, so that they are rebuilt on the next
+    /// completion.
+    pub fn invalidate(&mut self, buffer_id: usize) {
+        self.words.remove(&buffer_id);
+    }
+
+    /// Returns the words of the buffer, rebuilding them if the buffer changed.
+    fn words(&mut self, entry: &BufferEntry) -> &BTreeSet<String> {
+        self.words.entry(entry.id).or_insert_with(|| {
+            let buffer = entry.read_buf();
+            (0..buffer.num_lines())
+                .filter_map(|i| buffer.row(i))
+                .flat_map(|row| row.words().map(str::to_string))
+                .collect()
+        })
+    }
+
+    /// Returns the words starting with the prefix, excluding the prefix itself. Words of the
+    /// buffer with the given id come first, followed by those only found in other buffers.
+    pub fn candidates(
+        &mut self,
+        prefix: &str,
+        buffer_id: usize,
+        buffers: &BufferManager,
+    ) -> Vec<String> {
+        let mut entries: Vec<&BufferEntry> = buffers.iter().collect();
+        entries.sort_by_key(|entry| entry.id != buffer_id);
+
+        let mut candidates: Vec<String> = Vec::new();
+        for entry in entries {
+            let words = self.words(entry);
+            for word in words.range::<str, _>((Bound::Included(prefix), Bound::Unbounded)) {
+                if !word.starts_with(prefix) {
+                    break;
+                }
+                if word != prefix && !candidates.contains(word) {
+                    candidates.push(word.clone());
+                }
+            }
+        }
+        candidates
+    }
+}
+
+/// An open completion menu listing the words that can replace the prefix before the cursor.
+#[derive(Debug, Clone)]
+pub struct CompletionMenu {
+    /// The position where the prefix starts.
+    pub start: Point,
+    /// The words completing the prefix.
+    pub candidates: Vec<String>,
+    /// The index of the selected candidate.
+    pub selected: usize,
+}
+
+impl CompletionMenu {
+    pub fn new(start: Point, candidates: Vec<String>) -> Self {
+        Self {
+            start,
+            candidates,
+            selected: 0,
+        }
+    }
+
+    /// Selects the next candidate, wrapping around to the first.
+    pub fn select_next(&mut self) {
+        self.selected = (self.selected + 1) % self.candidates.len().max(1);
+    }
+
+    /// Selects the previous candidate, wrapping around to the last.
+    pub fn select_prev(&mut self) {
+        let len = self.candidates.len().max(1);
+        self.selected = (self.selected + len - 1) % len;
+    }
+
+    /// Returns the selected candidate.
+    pub fn selected(&self) -> Option<&str> {
+        self.candidates.get(self.selected).map(String::as_str)
+    }
+}
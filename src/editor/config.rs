@@ -1,7 +1,23 @@
-use serde::Deserialize;
-use std::{fs, path::PathBuf};
+use serde::{Deserialize, de::DeserializeOwned};
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+};
 use thiserror::Error;
 
+use crate::editor::{
+    buffer::indent::Indent,
+    filetype,
+    ui::{
+        theme::{
+            ThemeEntry,
+            highlight_group::{HighlightGroup, all_highlight_groups},
+        },
+        widget::border::BorderChars,
+    },
+};
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error(transparent)]
@@ -12,19 +28,154 @@ pub enum Error {
     MissingHomeDirectory,
 }
 
-#[derive(Debug, Default, Deserialize)]
+#[derive(Debug, Default)]
 pub struct Config {
     pub editor: EditorConfig,
+    pub statusbar: StatusBarConfig,
+    /// The prefixes of line comments, keyed by filetype. Overrides the built-in prefixes.
+    pub comments: HashMap<String, String>,
+    /// The command lines of the language servers to start, keyed by filetype.
+    pub lsp: HashMap<String, String>,
+    pub theme: ThemeConfig,
+    /// The bodies of snippets, keyed by filetype and trigger.
+    pub snippets: HashMap<String, HashMap<String, String>>,
+    /// The expansions of words typed in insert mode, keyed by the word. A word is expanded when
+    /// a space, punctuation or a new line is typed right after it.
+    pub abbreviations: HashMap<String, String>,
 }
 
 #[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct EditorConfig {
     pub theme: Option<String>,
+    /// Whether to show a tab line listing all open buffers.
+    pub show_tabline: bool,
+    /// Whether to set the terminal title to the active file.
+    pub set_title: bool,
+    /// The number of spaces per indentation level in buffers without a detected indentation.
+    pub tab_width: Option<usize>,
+    /// Whether new lines inherit the indentation of the line they are created from.
+    pub auto_indent: bool,
+    /// Whether the editor captures mouse events. Defaults to `true`. Disabling it leaves the
+    /// mouse to the terminal, e.g. for selecting and copying text.
+    pub mouse: Option<bool>,
+    /// Whether the configuration is reloaded automatically when its file changes.
+    pub watch_config: bool,
+    /// Whether quitting asks for confirmation even if there are no unsaved changes.
+    pub confirm_quit: bool,
+    /// The interval in milliseconds at which the editor updates while idle.
+    pub tick_rate_ms: Option<u64>,
+    /// The number of milliseconds status messages are displayed for, unless overridden for the
+    /// message type. `0` keeps messages until they are replaced or dismissed.
+    pub message_timeout_ms: Option<u64>,
+    /// Overrides for how long status messages are displayed.
+    pub message_timeout: MessageTimeoutConfig,
+    /// The style of the borders around floating windows.
+    pub border_style: BorderStyle,
+    /// The key that expands the snippet trigger before the cursor, e.g. `ctrl-j`. Defaults to
+    /// `tab`.
+    pub snippet_expand_key: Option<String>,
+    /// Whether the cursor may move past the end of the row. Typing there pads the row with
+    /// spaces up to the cursor.
+    pub virtual_edit: bool,
+    /// Whether misspelled words are highlighted in text files and in the comments of code.
+    pub spell: bool,
+    /// The user dictionary, a file listing additional words one per line. `AddToDictionary`
+    /// appends to it. Defaults to `dictionary` next to the configuration file.
+    pub spell_dictionary: Option<PathBuf>,
+    /// Whether buffers without unsaved changes are reloaded when their files change on disk.
+    pub auto_reload: bool,
+}
+
+impl EditorConfig {
+    /// The default number of spaces per indentation level.
+    const DEFAULT_TAB_WIDTH: usize = 4;
+
+    /// Returns the indentation used in buffers without a detected indentation.
+    pub fn default_indent(&self) -> Indent {
+        Indent::Spaces(self.tab_width.unwrap_or(Self::DEFAULT_TAB_WIDTH).max(1))
+    }
+
+    /// Returns the key that expands snippets.
+    pub fn snippet_expand_key(&self) -> &str {
+        self.snippet_expand_key.as_deref().unwrap_or("tab")
+    }
+
+    /// Returns `true` if the editor should capture mouse events.
+    pub fn mouse(&self) -> bool {
+        self.mouse.unwrap_or(true)
+    }
+}
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ThemeConfig {
+    /// Styles applied on top of the active theme, keyed by highlight group.
+    pub overrides: HashMap<HighlightGroup, ThemeEntry>,
+}
+
+/// The available styles of the borders around floating windows.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BorderStyle {
+    #[default]
+    Rounded,
+    Plain,
+    Double,
+}
+
+impl From<BorderStyle> for BorderChars {
+    fn from(value: BorderStyle) -> Self {
+        match value {
+            BorderStyle::Rounded => BorderChars::ROUNDED,
+            BorderStyle::Plain => BorderChars::PLAIN,
+            BorderStyle::Double => BorderChars::DOUBLE,
+        }
+    }
+}
+
+/// Durations in seconds for which each type of status message is displayed. Unset values use
+/// `message_timeout_ms` or the default duration of the message type, and `0` keeps messages
+/// until they are replaced or dismissed.
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MessageTimeoutConfig {
+    pub info: Option<u64>,
+    pub warning: Option<u64>,
+    pub error: Option<u64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StatusBarConfig {
+    /// Whether to show the git branch of the active buffer.
+    pub show_git_branch: bool,
+    /// Whether to show the encoding and line ending of the active buffer.
+    pub show_encoding: bool,
+}
+
+impl Default for StatusBarConfig {
+    fn default() -> Self {
+        Self {
+            show_git_branch: true,
+            show_encoding: true,
+        }
+    }
 }
 
 impl Config {
-    /// Loads the config file.
-    pub fn load(path: Option<PathBuf>) -> Result<Self, Error> {
+    /// Returns the prefix of line comments in the given filetype, preferring the configured
+    /// prefix over the built-in one.
+    pub fn comment_token(&self, filetype: &str) -> Option<&str> {
+        self.comments
+            .get(filetype)
+            .map(String::as_str)
+            .or_else(|| filetype::comment_token(filetype))
+    }
+
+    /// Loads the config file. Along with the config, returns a warning for each invalid or
+    /// unknown key, which is left at its default value.
+    pub fn load(path: Option<PathBuf>) -> Result<(Self, Vec<String>), Error> {
         let config = if let Some(path) = path {
             Self::load_from_file(&path)?
         } else {
@@ -50,21 +201,134 @@ impl Config {
         Ok(app_config_dir.join("config.toml"))
     }
 
+    /// Returns the directory of the given configuration file, or of the default one if no path
+    /// is given. Files kept by the editor between sessions are stored there.
+    pub fn get_config_dir(path: Option<&Path>) -> Option<PathBuf> {
+        match path {
+            Some(path) => Some(std::path::absolute(path).ok()?.parent()?.to_path_buf()),
+            None => Some(Self::get_config_path().ok()?.parent()?.to_path_buf()),
+        }
+    }
+
     /// Loads the configuration from the default path.
-    pub fn load_from_config_dir() -> Result<Self, Error> {
+    pub fn load_from_config_dir() -> Result<(Self, Vec<String>), Error> {
         let config_path = Self::get_config_path()?;
         let config_str = match fs::read_to_string(&config_path) {
             Ok(str) => str,
-            Err(_) => return Ok(Self::default()),
+            Err(_) => return Ok((Self::default(), Vec::new())),
         };
-        let config = toml::from_str(&config_str)?;
-        Ok(config)
+        Self::parse(&config_str)
     }
 
     /// Loads a configuration from a given file.
-    pub fn load_from_file(path: &PathBuf) -> Result<Self, Error> {
+    pub fn load_from_file(path: &PathBuf) -> Result<(Self, Vec<String>), Error> {
         let config_str = fs::read_to_string(path)?;
-        let config = toml::from_str(&config_str)?;
-        Ok(config)
+        Self::parse(&config_str)
+    }
+
+    /// Parses a configuration. Syntax errors fail the whole configuration, while invalid and
+    /// unknown keys are skipped with a warning so that the rest still applies.
+    fn parse(input: &str) -> Result<(Self, Vec<String>), Error> {
+        let table: toml::Table = toml::from_str(input)?;
+        let mut config = Self::default();
+        let mut warnings = Vec::new();
+        for (key, value) in table {
+            match key.as_str() {
+                "editor" => config.editor = parse_section(input, &key, value, &mut warnings),
+                "statusbar" => config.statusbar = parse_section(input, &key, value, &mut warnings),
+                "comments" => config.comments = parse_section(input, &key, value, &mut warnings),
+                "lsp" => config.lsp = parse_section(input, &key, value, &mut warnings),
+                "theme" => config.theme = parse_section(input, &key, value, &mut warnings),
+                "snippets" => config.snippets = parse_section(input, &key, value, &mut warnings),
+                "abbreviations" => {
+                    config.abbreviations = parse_section(input, &key, value, &mut warnings)
+                }
+                _ => warnings.push(warning(input, &[&key], "unknown section")),
+            }
+        }
+
+        let groups: HashSet<HighlightGroup> = all_highlight_groups()
+            .into_iter()
+            .map(|(group, _)| group)
+            .collect();
+        config.theme.overrides.retain(|group, _| {
+            let known = groups.contains(group);
+            if !known {
+                let path = ["theme", "overrides", group.name()];
+                warnings.push(warning(input, &path, "unknown highlight group"));
+            }
+            known
+        });
+        Ok((config, warnings))
+    }
+}
+
+/// Deserializes a section of the configuration key by key, skipping the keys that fail to
+/// deserialize with a warning.
+fn parse_section<T: DeserializeOwned + Default>(
+    input: &str,
+    section: &str,
+    value: toml::Value,
+    warnings: &mut Vec<String>,
+) -> T {
+    let toml::Value::Table(table) = value else {
+        warnings.push(warning(input, &[section], "expected a table"));
+        return T::default();
+    };
+
+    let mut valid = toml::Table::new();
+    for (key, value) in table {
+        let single = toml::Table::from_iter([(key.clone(), value.clone())]);
+        match toml::Value::Table(single).try_into::<T>() {
+            Ok(_) => {
+                valid.insert(key, value);
+            }
+            Err(err) => warnings.push(warning(input, &[section, &key], err.message())),
+        }
     }
+
+    toml::Value::Table(valid)
+        .try_into()
+        .unwrap_or_else(|err: toml::de::Error| {
+            warnings.push(warning(input, &[section], err.message()));
+            T::default()
+        })
+}
+
+/// Formats a warning about the key at the given path, prefixed with its position in the input
+/// if it can be found.
+fn warning(input: &str, path: &[&str], message: &str) -> String {
+    let key = path.join(".");
+    match key_position(input, path) {
+        Some((line, col)) => format!("line {line}, column {col}: {key}: {message}"),
+        None => format!("{key}: {message}"),
+    }
+}
+
+/// Returns the line and column, both starting at 1, of the key at the given path.
+fn key_position(input: &str, path: &[&str]) -> Option<(usize, usize)> {
+    let document = toml::de::DeTable::parse(input).ok()?;
+    let mut table = document.get_ref();
+    let mut span = None;
+    for (i, segment) in path.iter().enumerate() {
+        let (key, value) = table.iter().find(|(key, _)| key.get_ref() == segment)?;
+        span = Some(key.span());
+        if i + 1 < path.len() {
+            let toml::de::DeValue::Table(inner) = value.get_ref() else {
+                return None;
+            };
+            table = inner;
+        }
+    }
+
+    let before = input.get(..span?.start)?;
+    let line = before.matches('\n').count() + 1;
+    let col = before
+        .rsplit('\n')
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .count()
+        + 1;
+    Some((line, col))
 }
@@ -0,0 +1,157 @@
+/// The number of unchanged lines shown around each change.
+const CONTEXT: usize = 3;
+/// The largest number of line pairs compared to find the common lines. Larger changes are shown
+/// as all old lines removed followed by all new lines added.
+const MAX_COMPARISONS: usize = 4_000_000;
+
+/// How a line of the diff relates the old and new text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Equal,
+    Delete,
+    Insert,
+}
+
+/// A line of the diff, along with the number of old and new lines before it.
+#[derive(Debug, Clone, Copy)]
+struct Edit {
+    op: Op,
+    old: usize,
+    new: usize,
+}
+
+/// Splits text into lines, accepting both LF and CRLF line endings. Empty text has no lines.
+pub fn lines(text: &str) -> Vec<&str> {
+    if text.is_empty() {
+        return Vec::new();
+    }
+    text.split('\n')
+        .map(|line| line.strip_suffix('\r').unwrap_or(line))
+        .collect()
+}
+
+/// Returns a unified diff turning the old lines into the new lines, or `None` if they are equal.
+pub fn unified_diff(old: &[&str], new: &[&str], old_name: &str, new_name: &str) -> Option<String> {
+    let edits = edits(old, new);
+    let changes: Vec<usize> = edits
+        .iter()
+        .enumerate()
+        .filter(|(_, edit)| edit.op != Op::Equal)
+        .map(|(i, _)| i)
+        .collect();
+    if changes.is_empty() {
+        return None;
+    }
+
+    let mut diff = format!("--- {old_name}\n+++ {new_name}\n");
+    let mut i = 0;
+    while i < changes.len() {
+        // Changes whose context would overlap share a hunk.
+        let mut j = i;
+        while j + 1 < changes.len() && changes[j + 1] - changes[j] <= 2 * CONTEXT {
+            j += 1;
+        }
+        let hunk =
+            &edits[changes[i].saturating_sub(CONTEXT)..(changes[j] + CONTEXT + 1).min(edits.len())];
+        let old_len = hunk.iter().filter(|edit| edit.op != Op::Insert).count();
+        let new_len = hunk.iter().filter(|edit| edit.op != Op::Delete).count();
+        diff.push_str(&format!(
+            "@@ -{} +{} @@\n",
+            hunk_range(hunk[0].old, old_len),
+            hunk_range(hunk[0].new, new_len)
+        ));
+        for edit in hunk {
+            let line = match edit.op {
+                Op::Equal => format!(" {}", old[edit.old]),
+                Op::Delete => format!("-{}", old[edit.old]),
+                Op::Insert => format!("+{}", new[edit.new]),
+            };
+            diff.push_str(&line);
+            diff.push('\n');
+        }
+        i = j + 1;
+    }
+    Some(diff)
+}
+
+/// Returns the range of a hunk header, e.g. `12,4`, from the number of lines before the hunk and
+/// the number of lines in it.
+fn hunk_range(start: usize, len: usize) -> String {
+    match len {
+        // Empty ranges refer to the line before them.
+        0 => format!("{start},0"),
+        1 => format!("{}", start + 1),
+        _ => format!("{},{len}", start + 1),
+    }
+}
+
+/// Returns the lines of the diff, keeping the longest common subsequence of lines unchanged.
+fn edits(old: &[&str], new: &[&str]) -> Vec<Edit> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let a = &old[prefix..old.len() - suffix];
+    let b = &new[prefix..new.len() - suffix];
+    let (n, m) = (a.len(), b.len());
+
+    // The length of the longest common subsequence of `a[i..]` and `b[j..]` at `i * (m + 1) + j`.
+    let lcs = if n.saturating_mul(m) <= MAX_COMPARISONS {
+        let mut table = vec![0u32; (n + 1) * (m + 1)];
+        for i in (0..n).rev() {
+            for j in (0..m).rev() {
+                table[i * (m + 1) + j] = if a[i] == b[j] {
+                    table[(i + 1) * (m + 1) + j + 1] + 1
+                } else {
+                    table[(i + 1) * (m + 1) + j].max(table[i * (m + 1) + j + 1])
+                };
+            }
+        }
+        Some(table)
+    } else {
+        None
+    };
+
+    let mut edits: Vec<Edit> = (0..prefix)
+        .map(|i| Edit {
+            op: Op::Equal,
+            old: i,
+            new: i,
+        })
+        .collect();
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        let op = match &lcs {
+            Some(_) if i < n && j < m && a[i] == b[j] => Op::Equal,
+            // Removed lines are listed before the lines replacing them.
+            Some(table)
+                if i < n
+                    && (j == m || table[(i + 1) * (m + 1) + j] >= table[i * (m + 1) + j + 1]) =>
+            {
+                Op::Delete
+            }
+            Some(_) => Op::Insert,
+            None if i < n => Op::Delete,
+            None => Op::Insert,
+        };
+        edits.push(Edit {
+            op,
+            old: prefix + i,
+            new: prefix + j,
+        });
+        match op {
+            Op::Equal => (i, j) = (i + 1, j + 1),
+            Op::Delete => i += 1,
+            Op::Insert => j += 1,
+        }
+    }
+    edits.extend((0..suffix).map(|k| Edit {
+        op: Op::Equal,
+        old: prefix + n + k,
+        new: prefix + m + k,
+    }));
+    edits
+}
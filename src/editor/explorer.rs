@@ -0,0 +1,142 @@
+use std::path::{Path, PathBuf};
+
+use ignore::WalkBuilder;
+
+/// A file or directory listed in the file explorer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExplorerEntry {
+    pub path: PathBuf,
+    /// The number of directories between the entry and the root of the explorer.
+    pub depth: usize,
+    pub is_dir: bool,
+    /// Whether the contents of the directory are listed below it.
+    pub expanded: bool,
+}
+
+impl ExplorerEntry {
+    /// Returns the file name of the entry.
+    pub fn name(&self) -> String {
+        self.path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| self.path.display().to_string())
+    }
+}
+
+/// A tree of the files in a directory, excluding files ignored by `.gitignore`. The contents of a
+/// directory are only read when it is expanded.
+#[derive(Debug, Clone)]
+pub struct Explorer {
+    /// The listed entries, with the contents of expanded directories following them.
+    entries: Vec<ExplorerEntry>,
+    /// The index of the selected entry.
+    selected: usize,
+}
+
+impl Explorer {
+    /// Returns an explorer listing the contents of the given directory.
+    pub fn new<P: AsRef<Path>>(root: P) -> Self {
+        Self {
+            entries: read_dir(root.as_ref(), 0),
+            selected: 0,
+        }
+    }
+
+    /// Selects the next entry, stopping at the last one.
+    pub fn select_next(&mut self) {
+        if self.selected + 1 < self.entries.len() {
+            self.selected += 1;
+        }
+    }
+
+    /// Selects the previous entry, stopping at the first one.
+    pub fn select_prev(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    /// Returns the selected entry, or `None` if the directory is empty.
+    pub fn selected(&self) -> Option<&ExplorerEntry> {
+        self.entries.get(self.selected)
+    }
+
+    /// Returns the index of the selected entry.
+    pub fn selected_index(&self) -> usize {
+        self.selected
+    }
+
+    /// Returns an iterator over the listed entries.
+    pub fn iter(&self) -> impl Iterator<Item = &ExplorerEntry> {
+        self.entries.iter()
+    }
+
+    /// Lists the contents of the selected directory below it, reading them from disk.
+    pub fn expand(&mut self) {
+        let Some(entry) = self.entries.get_mut(self.selected) else {
+            return;
+        };
+        if !entry.is_dir || entry.expanded {
+            return;
+        }
+        entry.expanded = true;
+        let children = read_dir(&entry.path, entry.depth + 1);
+        let at = self.selected + 1;
+        self.entries.splice(at..at, children);
+    }
+
+    /// Hides the contents of the selected directory. If the selected entry is not an expanded
+    /// directory, its parent directory is selected instead.
+    pub fn collapse(&mut self) {
+        let Some(entry) = self.entries.get_mut(self.selected) else {
+            return;
+        };
+        if !entry.is_dir || !entry.expanded {
+            let depth = entry.depth;
+            if let Some(parent) = self.entries[..self.selected]
+                .iter()
+                .rposition(|e| e.depth < depth)
+            {
+                self.selected = parent;
+            }
+            return;
+        }
+
+        entry.expanded = false;
+        let depth = entry.depth;
+        let start = self.selected + 1;
+        let end = self.entries[start..]
+            .iter()
+            .position(|e| e.depth <= depth)
+            .map_or(self.entries.len(), |i| start + i);
+        self.entries.drain(start..end);
+    }
+
+    /// Expands the selected directory, or collapses it if it is expanded.
+    pub fn toggle(&mut self) {
+        if self.selected().is_some_and(|entry| entry.expanded) {
+            self.collapse();
+        } else {
+            self.expand();
+        }
+    }
+}
+
+/// Reads the contents of a directory without descending into subdirectories, excluding files
+/// ignored by `.gitignore`. Directories are listed before files, and both are sorted by name.
+fn read_dir(dir: &Path, depth: usize) -> Vec<ExplorerEntry> {
+    let walker = WalkBuilder::new(dir)
+        .git_ignore(true)
+        .max_depth(Some(1))
+        .build();
+    let mut entries: Vec<ExplorerEntry> = walker
+        .filter_map(Result::ok)
+        .filter(|entry| entry.depth() > 0)
+        .map(|entry| ExplorerEntry {
+            is_dir: entry.file_type().is_some_and(|t| t.is_dir()),
+            path: entry.into_path(),
+            depth,
+            expanded: false,
+        })
+        .collect();
+    entries.sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then_with(|| a.path.cmp(&b.path)));
+    entries
+}
@@ -0,0 +1,118 @@
+use std::path::Path;
+
+/// The filetype used for buffers without a recognizable type.
+pub const DEFAULT_FILETYPE: &str = "text";
+
+/// A mapping from file extensions to filetypes.
+const EXTENSIONS: &[(&str, &str)] = &[
+    ("rs", "rust"),
+    ("toml", "toml"),
+    ("md", "markdown"),
+    ("markdown", "markdown"),
+    ("json", "json"),
+    ("yaml", "yaml"),
+    ("yml", "yaml"),
+    ("c", "c"),
+    ("h", "c"),
+    ("cpp", "cpp"),
+    ("cc", "cpp"),
+    ("hpp", "cpp"),
+    ("go", "go"),
+    ("py", "python"),
+    ("js", "javascript"),
+    ("ts", "typescript"),
+    ("html", "html"),
+    ("css", "css"),
+    ("sh", "sh"),
+    ("bash", "bash"),
+    ("zsh", "zsh"),
+    ("fish", "fish"),
+    ("lua", "lua"),
+    ("diff", "diff"),
+    ("patch", "diff"),
+    ("txt", "text"),
+];
+
+/// A mapping from file names without extensions to filetypes.
+const FILE_NAMES: &[(&str, &str)] = &[
+    ("Makefile", "make"),
+    ("Dockerfile", "dockerfile"),
+    ("Cargo.lock", "toml"),
+];
+
+/// A mapping from shebang interpreters to filetypes.
+const INTERPRETERS: &[(&str, &str)] = &[
+    ("sh", "sh"),
+    ("bash", "bash"),
+    ("zsh", "zsh"),
+    ("fish", "fish"),
+    ("python", "python"),
+    ("python3", "python"),
+    ("node", "javascript"),
+    ("lua", "lua"),
+];
+
+/// A mapping from filetypes to the prefix of their line comments.
+const COMMENT_TOKENS: &[(&str, &str)] = &[
+    ("rust", "//"),
+    ("c", "//"),
+    ("cpp", "//"),
+    ("go", "//"),
+    ("javascript", "//"),
+    ("typescript", "//"),
+    ("toml", "#"),
+    ("yaml", "#"),
+    ("python", "#"),
+    ("sh", "#"),
+    ("bash", "#"),
+    ("zsh", "#"),
+    ("fish", "#"),
+    ("make", "#"),
+    ("dockerfile", "#"),
+    ("lua", "--"),
+];
+
+/// Returns the prefix of line comments in the given filetype, or `None` if it is unknown.
+pub fn comment_token(filetype: &str) -> Option<&'static str> {
+    COMMENT_TOKENS
+        .iter()
+        .find(|(name, _)| *name == filetype)
+        .map(|(_, token)| *token)
+}
+
+/// Detects the filetype from the file name and extension of the given path, falling back to the
+/// shebang in `first_line` for extensionless scripts. Returns [`DEFAULT_FILETYPE`] if the
+/// filetype could not be detected.
+pub fn detect(path: Option<&Path>, first_line: Option<&str>) -> &'static str {
+    path.and_then(detect_from_path)
+        .or_else(|| first_line.and_then(detect_from_shebang))
+        .unwrap_or(DEFAULT_FILETYPE)
+}
+
+/// Detects the filetype from the file name or extension of the given path.
+fn detect_from_path(path: &Path) -> Option<&'static str> {
+    let file_name = path.file_name()?.to_str()?;
+    if let Some((_, filetype)) = FILE_NAMES.iter().find(|(name, _)| *name == file_name) {
+        return Some(filetype);
+    }
+
+    let extension = path.extension()?.to_str()?.to_lowercase();
+    EXTENSIONS
+        .iter()
+        .find(|(ext, _)| *ext == extension)
+        .map(|(_, filetype)| *filetype)
+}
+
+/// Detects the filetype from a shebang line such as `#!/bin/sh` or `#!/usr/bin/env python3`.
+fn detect_from_shebang(line: &str) -> Option<&'static str> {
+    let mut parts = line.strip_prefix("#!")?.split_whitespace();
+    let mut interpreter = parts.next()?.rsplit('/').next()?;
+    if interpreter == "env" {
+        interpreter = parts.find(|arg| !arg.starts_with('-'))?;
+    }
+
+    INTERPRETERS
+        .iter()
+        .find(|(name, _)| *name == interpreter)
+        .map(|(_, filetype)| *filetype)
+}
@@ -0,0 +1,91 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+/// Returns the path of the git directory of the repository containing `path`, or `None` if the
+/// path is not inside a repository.
+fn find_git_dir(path: &Path) -> Option<PathBuf> {
+    let start = path.canonicalize().ok()?;
+    for dir in start.ancestors() {
+        let dot_git = dir.join(".git");
+        if dot_git.is_dir() {
+            return Some(dot_git);
+        } else if dot_git.is_file() {
+            // Worktrees and submodules use a `.git` file pointing to the actual git directory.
+            let contents = fs::read_to_string(&dot_git).ok()?;
+            let git_dir = contents.strip_prefix("gitdir:")?.trim();
+            return Some(dir.join(git_dir));
+        }
+    }
+    None
+}
+
+/// Reads the current branch of the repository containing `path`. Returns a short commit hash if
+/// HEAD is detached, or `None` if the path is not inside a repository.
+pub fn read_branch(path: &Path) -> Option<String> {
+    /// The number of characters to show of a detached commit hash.
+    const SHORT_HASH_LEN: usize = 7;
+
+    let git_dir = find_git_dir(path)?;
+    let head = fs::read_to_string(git_dir.join("HEAD")).ok()?;
+    let head = head.trim();
+
+    if let Some(reference) = head.strip_prefix("ref:") {
+        let reference = reference.trim();
+        let branch = reference.strip_prefix("refs/heads/").unwrap_or(reference);
+        Some(branch.to_string())
+    } else {
+        Some(head.chars().take(SHORT_HASH_LEN).collect())
+    }
+}
+
+/// A lazily refreshed cache of the git branch for the active buffer.
+#[derive(Debug, Default, Clone)]
+pub struct GitBranchCache {
+    /// The directory the branch was last read for.
+    dir: Option<PathBuf>,
+    /// The cached branch name.
+    branch: Option<String>,
+    /// The time of the last refresh, or `None` if the cache is invalidated.
+    last_refresh: Option<Instant>,
+}
+
+impl GitBranchCache {
+    /// The maximum age of the cached branch before it is read again.
+    const REFRESH_INTERVAL: Duration = Duration::from_secs(3);
+
+    /// Refreshes the cached branch if the given file is in a different directory than the last
+    /// one, or if the cache is invalidated or outdated. Returns `true` if the branch changed.
+    pub fn refresh(&mut self, file_path: Option<&Path>) -> bool {
+        let dir = file_path.map(|path| match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+            _ => PathBuf::from("."),
+        });
+
+        let outdated = self
+            .last_refresh
+            .is_none_or(|time| time.elapsed() >= Self::REFRESH_INTERVAL);
+        if dir == self.dir && !outdated {
+            return false;
+        }
+
+        let branch = dir.as_deref().and_then(read_branch);
+        let changed = branch != self.branch;
+        self.branch = branch;
+        self.dir = dir;
+        self.last_refresh = Some(Instant::now());
+        changed
+    }
+
+    /// Invalidates the cache so that the branch is read again on the next refresh.
+    pub fn invalidate(&mut self) {
+        self.last_refresh = None;
+    }
+
+    /// Returns the cached branch.
+    pub fn branch(&self) -> Option<&str> {
+        self.branch.as_deref()
+    }
+}
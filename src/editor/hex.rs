@@ -0,0 +1,129 @@
+use std::{
+    fs::{self, File},
+    io::{self, Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+};
+
+/// A file shown as a hex dump. Only the lines being shown are read from the file, so files of
+/// any size can be viewed.
+#[derive(Debug, Clone)]
+pub struct HexFile {
+    /// The path of the file.
+    path: PathBuf,
+    /// The size of the file in bytes when it was opened.
+    len: u64,
+    /// The index of the selected line.
+    selected: usize,
+}
+
+impl HexFile {
+    /// The number of bytes shown on each line.
+    pub const BYTES_PER_LINE: usize = 16;
+
+    /// Opens the file at the given path.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let len = fs::metadata(&path)?.len();
+        Ok(Self {
+            path: path.as_ref().to_path_buf(),
+            len,
+            selected: 0,
+        })
+    }
+
+    /// Returns the path of the file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the number of lines of the dump. An empty file has a single empty line.
+    pub fn num_lines(&self) -> usize {
+        (self.len as usize).div_ceil(Self::BYTES_PER_LINE).max(1)
+    }
+
+    /// Returns the index of the selected line.
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// Moves the selection by the given number of lines, stopping at the first and last line.
+    pub fn move_by(&mut self, offset: isize) {
+        let selected = self.selected.saturating_add_signed(offset);
+        self.selected = selected.min(self.num_lines() - 1);
+    }
+
+    /// Moves the selection to the given line, or to the last line if it is out of bounds.
+    pub fn move_to(&mut self, line: usize) {
+        self.selected = line.min(self.num_lines() - 1);
+    }
+
+    /// Reads and formats up to `count` lines starting at the line `first`.
+    pub fn read_lines(&self, first: usize, count: usize) -> io::Result<Vec<String>> {
+        let start = (first * Self::BYTES_PER_LINE) as u64;
+        let mut bytes = Vec::with_capacity(count * Self::BYTES_PER_LINE);
+        let mut file = File::open(&self.path)?;
+        file.seek(SeekFrom::Start(start))?;
+        file.take((count * Self::BYTES_PER_LINE) as u64)
+            .read_to_end(&mut bytes)?;
+
+        Ok(bytes
+            .chunks(Self::BYTES_PER_LINE)
+            .enumerate()
+            .map(|(i, chunk)| format_line(start as usize + i * Self::BYTES_PER_LINE, chunk))
+            .collect())
+    }
+}
+
+/// Formats a line of the dump as the offset, the bytes in hex in two groups of eight, and the
+/// bytes as ASCII with unprintable bytes shown as dots.
+fn format_line(offset: usize, bytes: &[u8]) -> String {
+    let mut hex = String::new();
+    for i in 0..HexFile::BYTES_PER_LINE {
+        if i == HexFile::BYTES_PER_LINE / 2 {
+            hex.push(' ');
+        }
+        match bytes.get(i) {
+            Some(byte) => hex.push_str(&format!("{byte:02x} ")),
+            None => hex.push_str("   "),
+        }
+    }
+    let ascii: String = bytes
+        .iter()
+        .map(|&byte| {
+            if byte.is_ascii_graphic() || byte == b' ' {
+                byte as char
+            } else {
+                '.'
+            }
+        })
+        .collect();
+    format!("{offset:08x}  {hex} |{ascii}|")
+}
+
+/// Returns `true` if the file at the given path is valid UTF-8. The file is read in chunks, so
+/// that large files are not loaded into memory at once.
+pub fn is_utf8<P: AsRef<Path>>(path: P) -> io::Result<bool> {
+    /// The number of bytes read at once.
+    const CHUNK: usize = 64 * 1024;
+
+    let mut file = File::open(path)?;
+    let mut buf = vec![0; CHUNK];
+    // The bytes of a character split between two chunks.
+    let mut carry = 0;
+    loop {
+        let read = file.read(&mut buf[carry..])?;
+        if read == 0 {
+            return Ok(carry == 0);
+        }
+        let len = carry + read;
+        carry = match std::str::from_utf8(&buf[..len]) {
+            Ok(_) => 0,
+            // The chunk ends in the middle of a character.
+            Err(err) if err.error_len().is_none() => {
+                let valid = err.valid_up_to();
+                buf.copy_within(valid..len, 0);
+                len - valid
+            }
+            Err(_) => return Ok(false),
+        };
+    }
+}
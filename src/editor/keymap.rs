@@ -4,58 +4,293 @@ use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 
 use crate::editor::command::*;
 
-/// Macro to bind keys to commands or actions.
+/// Macro to bind keys to commands or actions. Binding a key that is already bound records a
+/// conflict and keeps the existing binding, unless the binding is prefixed with `override`.
 macro_rules! bind_keys {
-    ( $map:ident, $( $keycode:expr, $modifiers:expr => $command:expr ),* $(,)? ) => {
-        $(
-            $map.insert(
-                KeyEvent::new($keycode, $modifiers),
-                Rc::new(Box::new($command) as Box<dyn Command>),
-            );
-        )*
+    ( $keymap:ident $(,)? ) => {};
+    ( $keymap:ident, override $keycode:expr, $modifiers:expr => $command:expr $(, $($rest:tt)*)? ) => {
+        $keymap.rebind(KeyEvent::new($keycode, $modifiers), $command);
+        bind_keys!($keymap $(, $($rest)*)?);
     };
+    ( $keymap:ident, $keycode:expr, $modifiers:expr => $command:expr $(, $($rest:tt)*)? ) => {
+        $keymap.bind(KeyEvent::new($keycode, $modifiers), $command);
+        bind_keys!($keymap $(, $($rest)*)?);
+    };
+}
+
+/// Returns the canonical form of a key, under which it is bound and described. Terminals report
+/// the same key in different ways, so that:
+///
+/// - Letters typed with Shift are upper case with the SHIFT modifier, whether they were reported
+///   as upper case, with SHIFT, or both.
+/// - Other characters drop the SHIFT modifier, as the character already tells it, e.g. `?`.
+/// - Shift+Tab is `BackTab` without the SHIFT modifier.
+/// - The keypad state, the lock states and the kind of the event are dropped, so that keypad
+///   keys and repeats match the standard keys.
+pub fn normalize_key(key: &KeyEvent) -> KeyEvent {
+    let mut modifiers = key.modifiers;
+    let code = match key.code {
+        KeyCode::Char(c) if c.is_alphabetic() => {
+            if c.is_uppercase() {
+                modifiers.insert(KeyModifiers::SHIFT);
+            }
+            let mut upper = c.to_uppercase();
+            match (upper.next(), upper.next()) {
+                (Some(upper), None) if modifiers.contains(KeyModifiers::SHIFT) => {
+                    KeyCode::Char(upper)
+                }
+                _ => KeyCode::Char(c),
+            }
+        }
+        KeyCode::Char(c) => {
+            modifiers.remove(KeyModifiers::SHIFT);
+            KeyCode::Char(c)
+        }
+        KeyCode::BackTab => {
+            modifiers.remove(KeyModifiers::SHIFT);
+            KeyCode::BackTab
+        }
+        code => code,
+    };
+    KeyEvent::new(code, modifiers)
+}
+
+/// Returns a human readable description of a key, such as `Control+s`.
+pub fn describe_key(key: &KeyEvent) -> String {
+    let key = normalize_key(key);
+    if key.modifiers.is_empty() {
+        key.code.to_string()
+    } else {
+        format!("{}+{}", key.modifiers, key.code)
+    }
+}
+
+/// Returns the chord of a key in the syntax used for key bindings, such as `ctrl-shift-p` or
+/// `backtab`. Modifiers come first in a fixed order, and letters are lower case.
+pub fn key_chord(key: &KeyEvent) -> String {
+    const MODIFIERS: [(KeyModifiers, &str); 6] = [
+        (KeyModifiers::CONTROL, "ctrl"),
+        (KeyModifiers::ALT, "alt"),
+        (KeyModifiers::SHIFT, "shift"),
+        (KeyModifiers::SUPER, "super"),
+        (KeyModifiers::HYPER, "hyper"),
+        (KeyModifiers::META, "meta"),
+    ];
+
+    let key = normalize_key(key);
+    let code = match key.code {
+        KeyCode::Char(' ') => "space".to_string(),
+        KeyCode::Char('-') => "minus".to_string(),
+        KeyCode::Char(c) => c.to_lowercase().collect(),
+        KeyCode::F(n) => format!("f{n}"),
+        KeyCode::PageUp => "pageup".to_string(),
+        KeyCode::PageDown => "pagedown".to_string(),
+        KeyCode::BackTab => "backtab".to_string(),
+        code => format!("{code:?}").to_lowercase(),
+    };
+
+    let mut parts: Vec<String> = MODIFIERS
+        .iter()
+        .filter(|(modifier, _)| key.modifiers.contains(*modifier))
+        .map(|(_, name)| name.to_string())
+        .collect();
+    parts.push(code);
+    parts.join("-")
+}
+
+/// A key that was bound to more than one command.
+#[derive(Debug, Clone)]
+pub struct KeyConflict {
+    /// The conflicting key.
+    pub key: KeyEvent,
+    /// The name of the command that the key stays bound to.
+    pub kept: &'static str,
+    /// The name of the command that was not bound.
+    pub ignored: &'static str,
+}
+
+impl KeyConflict {
+    /// Returns a message describing the conflict.
+    pub fn message(&self) -> String {
+        format!(
+            "{} is bound to both {} and {}, using {}",
+            describe_key(&self.key),
+            self.kept,
+            self.ignored,
+            self.kept
+        )
+    }
 }
 
 pub struct Keymap {
     map: HashMap<KeyEvent, Rc<Box<dyn Command + 'static>>>,
+    /// The conflicts found while binding keys.
+    conflicts: Vec<KeyConflict>,
 }
 
 impl Keymap {
     /// Returns the command name for the given key event, or `None` if no command is bound to the
     /// given event.
     pub fn get(&self, event: &KeyEvent) -> Option<&Rc<Box<dyn Command + 'static>>> {
-        self.map.get(event)
+        self.map.get(&normalize_key(event))
+    }
+
+    /// Returns a key bound to the command with the given name, or `None` if the command is not
+    /// bound. If several keys are bound to the command, the one with the shortest chord is
+    /// returned.
+    pub fn key_for(&self, command: &str) -> Option<KeyEvent> {
+        self.map
+            .iter()
+            .filter(|(_, bound)| bound.name() == command)
+            .map(|(key, _)| *key)
+            .min_by_key(|key| (key_chord(key).len(), key_chord(key)))
+    }
+
+    /// Binds a key to a command. If the key is already bound, the existing binding is kept and
+    /// the conflict is recorded.
+    pub fn bind(&mut self, key: KeyEvent, command: impl Command + 'static) {
+        let key = normalize_key(&key);
+        if let Some(existing) = self.map.get(&key) {
+            self.conflicts.push(KeyConflict {
+                key,
+                kept: existing.name(),
+                ignored: command.name(),
+            });
+            return;
+        }
+        self.rebind(key, command);
+    }
+
+    /// Binds a key to a command, replacing any existing binding.
+    pub fn rebind(&mut self, key: KeyEvent, command: impl Command + 'static) {
+        self.map
+            .insert(normalize_key(&key), Rc::new(Box::new(command)));
+    }
+
+    /// Returns the conflicts found while binding keys.
+    pub fn conflicts(&self) -> &[KeyConflict] {
+        &self.conflicts
     }
 }
 
 #[rustfmt::skip]
 impl Default for Keymap {
     fn default() -> Self {
-        let mut map = HashMap::new();
+        let mut keymap = Self {
+            map: HashMap::new(),
+            conflicts: Vec::new(),
+        };
 
         // TODO: Implement default values for key actions.
-        bind_keys!(map,
+        bind_keys!(keymap,
             // Editor actions.
             KeyCode::Char('q'), KeyModifiers::CONTROL => Quit {},
+            KeyCode::Char('z'), KeyModifiers::CONTROL => Suspend {},
             KeyCode::Char('s'), KeyModifiers::CONTROL => Save { path: None },
             KeyCode::Char('p'), KeyModifiers::CONTROL => EnterCommandMode {},
-            KeyCode::Char('s'), KeyModifiers::CONTROL => OpenSearch {},
-            KeyCode::Char('f'), KeyModifiers::CONTROL => OpenFilesPicker { dir: None },
+            KeyCode::Char('f'), KeyModifiers::CONTROL => OpenSearch {},
+            KeyCode::Char('t'), KeyModifiers::CONTROL => OpenFilesPicker { dir: None },
+            KeyCode::Char('b'), KeyModifiers::CONTROL => ToggleExplorer {},
+            KeyCode::Char('g'), KeyModifiers::CONTROL => MoveCursorToLine { line: None },
             // Cursor movements.
             KeyCode::Left, KeyModifiers::NONE => MoveCursorLeft {},
             KeyCode::Right, KeyModifiers::NONE => MoveCursorRight {},
             KeyCode::Up, KeyModifiers::NONE => MoveCursorUp {},
             KeyCode::Down, KeyModifiers::NONE => MoveCursorDown {},
-            KeyCode::Home, KeyModifiers::NONE => MoveCursorToStartOfRow {},
+            KeyCode::Up, KeyModifiers::CONTROL => MoveCursorParagraphBackward {},
+            KeyCode::Down, KeyModifiers::CONTROL => MoveCursorParagraphForward {},
+            KeyCode::Home, KeyModifiers::NONE => MoveCursorToSmartHome {},
             KeyCode::End, KeyModifiers::NONE => MoveCursorToEndOfRow {},
-            KeyCode::Char('b'), KeyModifiers::CONTROL => MoveCursorToStartOfBuffer {},
+            KeyCode::Home, KeyModifiers::CONTROL => MoveCursorToStartOfBuffer {},
+            KeyCode::End, KeyModifiers::CONTROL => MoveCursorToEndOfBuffer {},
             KeyCode::Char('e'), KeyModifiers::CONTROL => MoveCursorToEndOfBuffer {},
+            KeyCode::Char('a'), KeyModifiers::CONTROL => SelectAll {},
+            KeyCode::Down, KeyModifiers::CONTROL.union(KeyModifiers::ALT) => AddCursorBelow {},
+            KeyCode::Up, KeyModifiers::CONTROL.union(KeyModifiers::ALT) => AddCursorAbove {},
+            KeyCode::Char('d'), KeyModifiers::CONTROL => SelectNextOccurrence {},
+            KeyCode::Char('o'), KeyModifiers::CONTROL => JumpBack {},
+            KeyCode::Char('i'), KeyModifiers::CONTROL => JumpForward {},
             // Text manipulation.
             KeyCode::Enter, KeyModifiers::NONE => InsertNewline {},
+            // Only reported by terminals that distinguish modified Enter keys.
+            KeyCode::Enter, KeyModifiers::CONTROL => InsertLineBelow {},
+            KeyCode::Enter, KeyModifiers::CONTROL.union(KeyModifiers::SHIFT) => InsertLineAbove {},
+            KeyCode::Tab, KeyModifiers::NONE => InsertIndent {},
+            // Most terminals report Ctrl+/ as Ctrl+7.
+            KeyCode::Char('/'), KeyModifiers::CONTROL => ToggleComment {},
+            KeyCode::Char('7'), KeyModifiers::CONTROL => ToggleComment {},
+            KeyCode::Char('c'), KeyModifiers::CONTROL => Yank { register: None },
+            KeyCode::Char('x'), KeyModifiers::CONTROL => Delete { register: None },
+            KeyCode::Char('v'), KeyModifiers::CONTROL => Paste { register: None },
             KeyCode::Delete, KeyModifiers::NONE => DeleteChar {},
             KeyCode::Backspace, KeyModifiers::NONE => DeleteCharBefore {},
+            KeyCode::Esc, KeyModifiers::NONE => DismissMessage {},
+            KeyCode::Char('k'), KeyModifiers::CONTROL => DeleteToEndOfLine {},
+            KeyCode::Char('u'), KeyModifiers::CONTROL => DeleteToStartOfLine {},
+            KeyCode::Char('n'), KeyModifiers::CONTROL => CompleteWord {},
+            // Ctrl+V pastes, so skipping the next abbreviation is bound to Alt+V instead.
+            KeyCode::Char('v'), KeyModifiers::ALT => NoExpandAbbrev {},
+            // Ctrl+A selects all and Ctrl+X deletes, so changing numbers is bound to Alt+A and
+            // Alt+X instead.
+            KeyCode::Char('a'), KeyModifiers::ALT => IncrementNumber { count: None },
+            KeyCode::Char('x'), KeyModifiers::ALT => DecrementNumber { count: None },
         );
 
-        Self { map }
+        keymap
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::event::{KeyEventKind, KeyEventState};
+
+    use super::*;
+
+    #[test]
+    fn normalize_key_gives_one_form_per_key() {
+        const NONE: KeyModifiers = KeyModifiers::NONE;
+        const SHIFT: KeyModifiers = KeyModifiers::SHIFT;
+        const CONTROL: KeyModifiers = KeyModifiers::CONTROL;
+        let press = |code, modifiers| KeyEvent::new(code, modifiers);
+        let keypad = |code| {
+            KeyEvent::new_with_kind_and_state(
+                code,
+                NONE,
+                KeyEventKind::Press,
+                KeyEventState::KEYPAD,
+            )
+        };
+        let repeat =
+            |code, modifiers| KeyEvent::new_with_kind(code, modifiers, KeyEventKind::Repeat);
+
+        let cases = [
+            (press(KeyCode::Char('S'), NONE), (KeyCode::Char('S'), SHIFT)),
+            (
+                press(KeyCode::Char('S'), SHIFT),
+                (KeyCode::Char('S'), SHIFT),
+            ),
+            (
+                press(KeyCode::Char('s'), SHIFT),
+                (KeyCode::Char('S'), SHIFT),
+            ),
+            (press(KeyCode::Char('s'), NONE), (KeyCode::Char('s'), NONE)),
+            (press(KeyCode::Char('?'), SHIFT), (KeyCode::Char('?'), NONE)),
+            (press(KeyCode::BackTab, SHIFT), (KeyCode::BackTab, NONE)),
+            (keypad(KeyCode::Enter), (KeyCode::Enter, NONE)),
+            (keypad(KeyCode::Up), (KeyCode::Up, NONE)),
+            (
+                repeat(KeyCode::Char('s'), CONTROL),
+                (KeyCode::Char('s'), CONTROL),
+            ),
+            (repeat(KeyCode::Down, NONE), (KeyCode::Down, NONE)),
+        ];
+        for (key, expected) in cases {
+            // Key events are equal however Shift was reported for letters, so the fields are
+            // compared instead.
+            let normalized = normalize_key(&key);
+            assert_eq!((normalized.code, normalized.modifiers), expected, "{key:?}");
+            assert_eq!(normalized.kind, KeyEventKind::Press, "{key:?}");
+            assert_eq!(normalized.state, KeyEventState::NONE, "{key:?}");
+        }
     }
 }
@@ -0,0 +1,134 @@
+use std::path::PathBuf;
+
+use crate::editor::ui::geometry::point::Point;
+
+/// A position in a file along with a message describing it, e.g. a compiler error.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Location {
+    pub path: PathBuf,
+    /// The position in the file, starting at 0.
+    pub position: Point,
+    pub message: String,
+}
+
+impl Location {
+    /// Returns the location formatted as `path:line:col: message`, with the line and column
+    /// starting at 1.
+    pub fn text(&self) -> String {
+        let Point { col, row } = self.position;
+        let location = format!("{}:{}:{}", self.path.display(), row + 1, col + 1);
+        if self.message.is_empty() {
+            location
+        } else {
+            format!("{location}: {}", self.message)
+        }
+    }
+}
+
+/// A list of locations that can be walked through, wrapping around at either end.
+#[derive(Debug, Clone)]
+pub struct LocationList {
+    locations: Vec<Location>,
+    /// The index of the current location, or `None` if no location has been visited yet.
+    current: Option<usize>,
+}
+
+impl LocationList {
+    pub fn new(locations: Vec<Location>) -> Self {
+        Self {
+            locations,
+            current: None,
+        }
+    }
+
+    /// Moves to the next location and returns it, along with whether the list wrapped around to
+    /// the first location.
+    pub fn next(&mut self) -> Option<(&Location, bool)> {
+        let (index, wrapped) = match self.current {
+            None => (0, false),
+            Some(i) if i + 1 < self.locations.len() => (i + 1, false),
+            Some(_) => (0, true),
+        };
+        self.select(index, wrapped)
+    }
+
+    /// Moves to the previous location and returns it, along with whether the list wrapped around
+    /// to the last location.
+    pub fn prev(&mut self) -> Option<(&Location, bool)> {
+        let last = self.locations.len().checked_sub(1)?;
+        let (index, wrapped) = match self.current {
+            None => (last, false),
+            Some(0) => (last, true),
+            Some(i) => (i - 1, false),
+        };
+        self.select(index, wrapped)
+    }
+
+    fn select(&mut self, index: usize, wrapped: bool) -> Option<(&Location, bool)> {
+        let location = self.locations.get(index)?;
+        self.current = Some(index);
+        Some((location, wrapped))
+    }
+
+    /// Returns the index of the current location.
+    pub fn current(&self) -> Option<usize> {
+        self.current
+    }
+
+    pub fn len(&self) -> usize {
+        self.locations.len()
+    }
+
+    /// Returns an iterator over all locations.
+    pub fn iter(&self) -> impl Iterator<Item = &Location> {
+        self.locations.iter()
+    }
+}
+
+/// Parses the locations in compiler output. Both `path:line:col: message` lines and the
+/// `--> path:line:col` lines of `cargo build` are recognized. The latter take their message from
+/// the preceding `error` or `warning` line.
+pub fn parse_errorformat(text: &str) -> Vec<Location> {
+    let mut locations = Vec::new();
+    let mut last_message = String::new();
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("error") || trimmed.starts_with("warning") {
+            last_message = trimmed.to_string();
+        }
+
+        let (rest, cargo_style) = match trimmed.strip_prefix("-->") {
+            Some(rest) => (rest.trim_start(), true),
+            None => (trimmed, false),
+        };
+        let Some((path, position, message)) = parse_location(rest) else {
+            continue;
+        };
+        let message = if cargo_style && message.is_empty() {
+            last_message.clone()
+        } else {
+            message.to_string()
+        };
+        locations.push(Location {
+            path,
+            position,
+            message,
+        });
+    }
+    locations
+}
+
+/// Parses `path:line:col` followed by an optional `: message`. The line and column start at 1.
+fn parse_location(s: &str) -> Option<(PathBuf, Point, &str)> {
+    let mut parts = s.splitn(4, ':');
+    let path = parts.next()?;
+    let line: usize = parts.next()?.parse().ok()?;
+    let col: usize = parts.next()?.parse().ok()?;
+    let message = parts.next().unwrap_or_default().trim();
+    if path.is_empty() || path.contains(char::is_whitespace) {
+        return None;
+    }
+
+    let position = Point::new(col.saturating_sub(1), line.saturating_sub(1));
+    Some((PathBuf::from(path), position, message))
+}
@@ -0,0 +1,551 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt, io,
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver, Sender},
+};
+
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::editor::{
+    buffer::{
+        Buffer,
+        modification::{ActionRange, BufferAction},
+    },
+    lsp::client::LspClient,
+    ui::geometry::point::Point,
+};
+
+mod client;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("No command configured for the {0} language server")]
+    InvalidCommand(String),
+    #[allow(clippy::enum_variant_names)]
+    #[error("Failed to start language server {command}: {error}")]
+    SpawnError { command: String, error: io::Error },
+    #[allow(clippy::enum_variant_names)]
+    #[error(transparent)]
+    IoError(#[from] io::Error),
+}
+
+/// Converts an absolute path into a `file://` URI.
+fn path_to_uri(path: &Path) -> String {
+    let mut uri = String::from("file://");
+    for c in path.to_string_lossy().chars() {
+        match c {
+            ' ' => uri.push_str("%20"),
+            '%' => uri.push_str("%25"),
+            '#' => uri.push_str("%23"),
+            '?' => uri.push_str("%3F"),
+            c => uri.push(c),
+        }
+    }
+    uri
+}
+
+/// Converts a `file://` URI into a path. Returns `None` for other schemes.
+fn uri_to_path(uri: &str) -> Option<PathBuf> {
+    let encoded = uri.strip_prefix("file://")?;
+    let mut bytes = Vec::with_capacity(encoded.len());
+    let mut rest = encoded.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        let decoded = (byte == b'%')
+            .then(|| tail.get(..2))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+        match decoded {
+            Some(decoded) => {
+                bytes.push(decoded);
+                rest = &tail[2..];
+            }
+            None => {
+                bytes.push(byte);
+                rest = tail;
+            }
+        }
+    }
+    Some(PathBuf::from(String::from_utf8_lossy(&bytes).into_owned()))
+}
+
+/// The severity of a diagnostic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Error,
+    Warning,
+    Information,
+    Hint,
+}
+
+impl Severity {
+    /// Returns the severity of the given LSP severity number. Diagnostics without a severity are
+    /// treated as errors.
+    fn from_lsp(severity: Option<u64>) -> Self {
+        match severity {
+            Some(2) => Severity::Warning,
+            Some(3) => Severity::Information,
+            Some(4) => Severity::Hint,
+            _ => Severity::Error,
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Information => "info",
+            Severity::Hint => "hint",
+        };
+        write!(f, "{s}")
+    }
+}
+
+/// A diagnostic reported by a language server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// The start of the range the diagnostic applies to. The column is counted in UTF-16 code
+    /// units as reported by the server.
+    pub start: Point,
+    /// The severity of the diagnostic.
+    pub severity: Severity,
+    /// The message of the diagnostic.
+    pub message: String,
+}
+
+impl Diagnostic {
+    /// Parses a diagnostic from its LSP representation.
+    fn parse(value: &Value) -> Option<Self> {
+        let start = &value["range"]["start"];
+        let row = start["line"].as_u64()? as usize;
+        let col = start["character"].as_u64()? as usize;
+        Some(Self {
+            start: Point::new(col, row),
+            severity: Severity::from_lsp(value["severity"].as_u64()),
+            message: value["message"].as_str()?.to_string(),
+        })
+    }
+}
+
+/// A progress report of a long-running operation of a language server, such as indexing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Progress {
+    /// The token identifying the operation.
+    pub token: String,
+    /// The title of the operation, only sent when it begins.
+    pub title: Option<String>,
+    /// A message describing the current state of the operation.
+    pub message: Option<String>,
+    /// The percentage of the operation that is done, if known.
+    pub percentage: Option<u64>,
+    /// Whether the operation has ended.
+    pub done: bool,
+}
+
+impl Progress {
+    /// Parses a progress report from the parameters of a `$/progress` notification. Returns
+    /// `None` for reports that are not work done progress.
+    fn parse(params: &Value) -> Option<Self> {
+        let token = match &params["token"] {
+            Value::String(token) => token.clone(),
+            Value::Number(token) => token.to_string(),
+            _ => return None,
+        };
+        let value = &params["value"];
+        let done = match value["kind"].as_str()? {
+            "begin" | "report" => false,
+            "end" => true,
+            _ => return None,
+        };
+        let text = |key: &str| value[key].as_str().map(str::to_string);
+        Some(Self {
+            token,
+            title: text("title"),
+            message: text("message"),
+            percentage: value["percentage"].as_u64(),
+            done,
+        })
+    }
+}
+
+/// A change to a document, sent to its server in a `didChange` notification.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum DocumentChange {
+    /// The text between two positions was replaced. The columns are counted in UTF-16 code units.
+    Range {
+        start: Point,
+        end: Point,
+        text: String,
+    },
+    /// The whole document was replaced.
+    Full(String),
+}
+
+impl DocumentChange {
+    /// Returns the changes describing the action, given the buffer after the action was applied.
+    /// Single inserts and deletes are sent as ranges. Other actions send the whole text, as the
+    /// UTF-16 columns of their changes depend on the rows as they were between the changes.
+    fn from_action(action: &BufferAction, buffer: &Buffer) -> Vec<Self> {
+        // The text before the start of a change is the same before and after it, so its column
+        // can be converted with the buffer as it is now.
+        let start_of = |start: Point| {
+            let col = buffer.row(start.row).map_or(0, |row| {
+                row.chars().take(start.col).map(char::len_utf16).sum()
+            });
+            Point::new(col, start.row)
+        };
+        match action {
+            BufferAction::None => Vec::new(),
+            BufferAction::Composite(actions) if actions.len() == 1 => {
+                Self::from_action(&actions[0], buffer)
+            }
+            BufferAction::Insert { start, text } => {
+                let start = start_of(*start);
+                vec![Self::Range {
+                    start,
+                    end: start,
+                    text: text.replace('\n', buffer.line_ending().as_str()),
+                }]
+            }
+            BufferAction::Delete {
+                range: ActionRange::PointToPoint { from, .. },
+                text,
+            } => {
+                let start = start_of(*from);
+                vec![Self::Range {
+                    start,
+                    end: end_of(start, text),
+                    text: String::new(),
+                }]
+            }
+            // Joined rows do not record where the joined row ended.
+            BufferAction::Delete {
+                range: ActionRange::Line(_),
+                ..
+            }
+            | BufferAction::Composite(_) => vec![Self::Full(buffer.text())],
+        }
+    }
+}
+
+/// Returns the end of the given text when it starts at `start`, with columns counted in UTF-16
+/// code units.
+fn end_of(start: Point, text: &str) -> Point {
+    match text.rsplit_once('\n') {
+        Some((before, last)) => Point::new(
+            last.encode_utf16().count(),
+            start.row + before.matches('\n').count() + 1,
+        ),
+        None => Point::new(start.col + text.encode_utf16().count(), start.row),
+    }
+}
+
+/// The diagnostics of all documents, keyed by their absolute path.
+#[derive(Debug, Default, Clone)]
+pub struct Diagnostics {
+    documents: HashMap<PathBuf, Vec<Diagnostic>>,
+}
+
+impl Diagnostics {
+    /// Returns the diagnostics of the document at the given path, ordered by position.
+    pub fn get(&self, path: &Path) -> &[Diagnostic] {
+        self.documents
+            .get(path)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Returns the most severe diagnostic on the given row of the document.
+    pub fn most_severe_on_row(&self, path: &Path, row: usize) -> Option<&Diagnostic> {
+        self.get(path)
+            .iter()
+            .filter(|d| d.start.row == row)
+            .min_by_key(|d| d.severity)
+    }
+
+    /// Returns the number of errors and warnings in the document at the given path.
+    pub fn counts(&self, path: &Path) -> (usize, usize) {
+        let diagnostics = self.get(path);
+        let count = |severity| {
+            diagnostics
+                .iter()
+                .filter(|d| d.severity == severity)
+                .count()
+        };
+        (count(Severity::Error), count(Severity::Warning))
+    }
+
+    /// Returns an iterator over all documents with diagnostics, ordered by path.
+    pub fn iter(&self) -> impl Iterator<Item = (&PathBuf, &Vec<Diagnostic>)> {
+        let mut documents: Vec<_> = self
+            .documents
+            .iter()
+            .filter(|(_, diagnostics)| !diagnostics.is_empty())
+            .collect();
+        documents.sort_by_key(|(path, _)| *path);
+        documents.into_iter()
+    }
+
+    /// Replaces the diagnostics of the document at the given path.
+    fn set(&mut self, path: PathBuf, mut diagnostics: Vec<Diagnostic>) {
+        diagnostics.sort_by_key(|d| (d.start.row, d.start.col));
+        self.documents.insert(path, diagnostics);
+    }
+}
+
+/// An event sent by a language server.
+#[derive(Debug)]
+pub enum LspEvent {
+    /// The server published new diagnostics for a document.
+    Diagnostics {
+        path: PathBuf,
+        diagnostics: Vec<Diagnostic>,
+    },
+    /// The server of the given filetype reported progress of a long-running operation.
+    Progress {
+        filetype: String,
+        progress: Progress,
+    },
+    /// The server of the given filetype exited.
+    Exited { filetype: String },
+}
+
+/// A manager of the language servers of each filetype.
+pub struct LspManager {
+    /// The command lines of the configured servers, keyed by filetype.
+    servers: HashMap<String, String>,
+    /// The running servers, keyed by filetype.
+    clients: HashMap<String, LspClient>,
+    /// The filetypes whose server failed to start or exited, which are not started again.
+    failed: HashSet<String>,
+    /// The version of each open document.
+    versions: HashMap<PathBuf, i32>,
+    /// The diagnostics of all documents.
+    diagnostics: Diagnostics,
+    sender: Sender<LspEvent>,
+    receiver: Receiver<LspEvent>,
+}
+
+impl LspManager {
+    /// Returns a new manager with the given servers, keyed by filetype. Servers are started once
+    /// a document of their filetype is opened.
+    pub fn new(servers: HashMap<String, String>) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        Self {
+            servers,
+            clients: HashMap::new(),
+            failed: HashSet::new(),
+            versions: HashMap::new(),
+            diagnostics: Diagnostics::default(),
+            sender,
+            receiver,
+        }
+    }
+
+    /// Replaces the configured servers. Running servers keep running, and servers that failed are
+    /// tried again.
+    pub fn set_servers(&mut self, servers: HashMap<String, String>) {
+        self.servers = servers;
+        self.failed.clear();
+    }
+
+    /// Returns the diagnostics of all documents.
+    pub fn diagnostics(&self) -> &Diagnostics {
+        &self.diagnostics
+    }
+
+    /// Returns the running server of the filetype, or `None` if there is none.
+    fn client(&self, filetype: &str) -> Option<&LspClient> {
+        self.clients.get(filetype)
+    }
+
+    /// Notifies the server of the filetype that a document was opened, starting the server if
+    /// it is not running yet. Does nothing if no server is configured for the filetype.
+    pub fn did_open(&mut self, filetype: &str, path: &Path, text: &str) -> Result<(), Error> {
+        if self.versions.contains_key(path) || self.failed.contains(filetype) {
+            return Ok(());
+        }
+        let Some(command) = self.servers.get(filetype) else {
+            return Ok(());
+        };
+
+        if !self.clients.contains_key(filetype) {
+            let root = std::env::current_dir()?;
+            match LspClient::start(filetype, command, &root, self.sender.clone()) {
+                Ok(client) => {
+                    self.clients.insert(filetype.to_string(), client);
+                }
+                Err(err) => {
+                    self.failed.insert(filetype.to_string());
+                    return Err(err);
+                }
+            }
+        }
+
+        if let Some(client) = self.client(filetype) {
+            client.did_open(path, filetype, text);
+            self.versions.insert(path.to_path_buf(), 0);
+        }
+        Ok(())
+    }
+
+    /// Notifies the server of the filetype that the action changed a document, given the buffer
+    /// after the action. The whole text is sent to servers that do not accept ranged changes.
+    /// Does nothing if the document was not opened with a server.
+    pub fn did_change(
+        &mut self,
+        filetype: &str,
+        path: &Path,
+        action: &BufferAction,
+        buffer: &Buffer,
+    ) {
+        let (Some(version), Some(client)) =
+            (self.versions.get_mut(path), self.clients.get(filetype))
+        else {
+            return;
+        };
+        *version += 1;
+        let changes = if client.accepts_ranges() {
+            DocumentChange::from_action(action, buffer)
+        } else {
+            vec![DocumentChange::Full(buffer.text())]
+        };
+        client.did_change(path, *version, &changes);
+    }
+
+    /// Notifies the server of the filetype that a document was saved.
+    pub fn did_save(&self, filetype: &str, path: &Path) {
+        if self.versions.contains_key(path)
+            && let Some(client) = self.client(filetype)
+        {
+            client.did_save(path);
+        }
+    }
+
+    /// Handles all events received from the servers since the last call and returns them.
+    pub fn poll(&mut self) -> Vec<LspEvent> {
+        let events: Vec<LspEvent> = self.receiver.try_iter().collect();
+        for event in &events {
+            match event {
+                LspEvent::Diagnostics { path, diagnostics } => {
+                    self.diagnostics.set(path.clone(), diagnostics.clone());
+                }
+                LspEvent::Exited { filetype } => {
+                    self.clients.remove(filetype);
+                    self.failed.insert(filetype.clone());
+                }
+                LspEvent::Progress { .. } => {}
+            }
+        }
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::editor::buffer::row::Row;
+
+    /// Returns a buffer with the given rows, as they are after an action.
+    fn buffer_with(rows: &[&str]) -> Buffer {
+        let mut buffer = Buffer::default();
+        buffer.replace_rows(0..1, rows.iter().map(|row| Row::new(*row)).collect());
+        buffer
+    }
+
+    #[test]
+    fn insert_is_a_range_with_utf16_columns() {
+        let buffer = buffer_with(&["😀ab"]);
+        let action = BufferAction::Insert {
+            start: Point::new(1, 0),
+            text: "a".into(),
+        };
+        assert_eq!(
+            DocumentChange::from_action(&action, &buffer),
+            vec![DocumentChange::Range {
+                start: Point::new(2, 0),
+                end: Point::new(2, 0),
+                text: "a".into(),
+            }]
+        );
+    }
+
+    #[test]
+    fn delete_ends_after_the_removed_text() {
+        let buffer = buffer_with(&["a", "c"]);
+        let action = BufferAction::Delete {
+            range: ActionRange::PointToPoint {
+                from: Point::new(1, 0),
+                to: Point::new(1, 0),
+            },
+            text: "😀\nb".into(),
+        };
+        assert_eq!(
+            DocumentChange::from_action(&action, &buffer),
+            vec![DocumentChange::Range {
+                start: Point::new(1, 0),
+                end: Point::new(1, 1),
+                text: String::new(),
+            }]
+        );
+
+        let action = BufferAction::Delete {
+            range: ActionRange::PointToPoint {
+                from: Point::new(1, 0),
+                to: Point::new(3, 0),
+            },
+            text: "😀b".into(),
+        };
+        assert_eq!(
+            DocumentChange::from_action(&action, &buffer),
+            vec![DocumentChange::Range {
+                start: Point::new(1, 0),
+                end: Point::new(4, 0),
+                text: String::new(),
+            }]
+        );
+    }
+
+    #[test]
+    fn inserted_newlines_use_the_line_ending() {
+        let path = std::env::temp_dir().join(format!("tedit-lsp-crlf-{}", std::process::id()));
+        std::fs::write(&path, "ab\r\ncd\r\n").unwrap();
+        let buffer = Buffer::open_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let action = BufferAction::Insert {
+            start: Point::new(1, 0),
+            text: "\n".into(),
+        };
+        assert_eq!(
+            DocumentChange::from_action(&action, &buffer),
+            vec![DocumentChange::Range {
+                start: Point::new(1, 0),
+                end: Point::new(1, 0),
+                text: "\r\n".into(),
+            }]
+        );
+    }
+
+    #[test]
+    fn joins_and_composites_send_the_whole_text() {
+        let buffer = buffer_with(&["ab", "c"]);
+        let join = BufferAction::Delete {
+            range: ActionRange::Line(1),
+            text: "\n".into(),
+        };
+        let full = vec![DocumentChange::Full("ab\nc".into())];
+        assert_eq!(DocumentChange::from_action(&join, &buffer), full);
+
+        let insert = BufferAction::Insert {
+            start: Point::new(0, 0),
+            text: "a".into(),
+        };
+        let composite = BufferAction::Composite(vec![insert.clone(), insert]);
+        assert_eq!(DocumentChange::from_action(&composite, &buffer), full);
+        assert!(DocumentChange::from_action(&BufferAction::None, &buffer).is_empty());
+    }
+}
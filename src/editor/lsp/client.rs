@@ -0,0 +1,285 @@
+use std::{
+    io::{self, BufRead, BufReader, Write},
+    path::Path,
+    process::{Child, ChildStdin, ChildStdout, Command, Stdio},
+    sync::{Arc, Mutex, mpsc::Sender},
+    thread,
+};
+
+use serde_json::{Value, json};
+
+use crate::editor::lsp::{
+    Diagnostic, DocumentChange, Error, LspEvent, Progress, path_to_uri, uri_to_path,
+};
+
+/// The id of the `initialize` request, which is the only request sent by the client.
+const INITIALIZE_ID: u64 = 0;
+
+/// The writing half of the connection to a language server.
+struct Writer {
+    stdin: ChildStdin,
+    /// Whether the server has responded to the `initialize` request.
+    initialized: bool,
+    /// Whether the server accepts changes to ranges of a document rather than only the whole
+    /// text, as reported in its response to `initialize`.
+    accepts_ranges: bool,
+    /// Notifications sent before the server was initialized.
+    pending: Vec<Value>,
+}
+
+impl Writer {
+    /// Writes a message to the server.
+    fn write(&mut self, message: &Value) -> io::Result<()> {
+        let body = message.to_string();
+        write!(self.stdin, "Content-Length: {}\r\n\r\n{body}", body.len())?;
+        self.stdin.flush()
+    }
+
+    /// Sends a notification to the server, or queues it until the server is initialized.
+    fn notify(&mut self, method: &str, params: Value) -> io::Result<()> {
+        let message = json!({ "jsonrpc": "2.0", "method": method, "params": params });
+        if self.initialized {
+            self.write(&message)
+        } else {
+            self.pending.push(message);
+            Ok(())
+        }
+    }
+
+    /// Marks the server as initialized with the given capabilities and sends all queued
+    /// notifications.
+    fn initialize(&mut self, capabilities: &Value) -> io::Result<()> {
+        self.initialized = true;
+        // The sync kind is either given directly or as part of the sync options. Kind 2 is
+        // incremental.
+        let sync = &capabilities["textDocumentSync"];
+        self.accepts_ranges = sync.as_u64().or_else(|| sync["change"].as_u64()) == Some(2);
+        self.write(&json!({ "jsonrpc": "2.0", "method": "initialized", "params": {} }))?;
+        for message in std::mem::take(&mut self.pending) {
+            self.write(&message)?;
+        }
+        Ok(())
+    }
+}
+
+/// Reads a single message from the server. Returns `None` once the server closed its output.
+fn read_message(reader: &mut impl BufRead) -> io::Result<Option<Value>> {
+    let mut content_length = None;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some(length) = header.strip_prefix("Content-Length:") {
+            content_length = length.trim().parse::<usize>().ok();
+        }
+    }
+
+    let Some(content_length) = content_length else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "missing Content-Length header",
+        ));
+    };
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)?;
+    Ok(Some(serde_json::from_slice(&body)?))
+}
+
+/// A connection to a language server running as a child process, speaking JSON-RPC over its
+/// standard input and output.
+pub struct LspClient {
+    child: Child,
+    writer: Arc<Mutex<Writer>>,
+}
+
+impl LspClient {
+    /// Spawns the language server with the given command line and starts initializing it.
+    /// Messages from the server are handled on a background thread and forwarded to `events`.
+    pub fn start(
+        filetype: &str,
+        command: &str,
+        root: &Path,
+        events: Sender<LspEvent>,
+    ) -> Result<Self, Error> {
+        let mut args = command.split_whitespace();
+        let program = args
+            .next()
+            .ok_or_else(|| Error::InvalidCommand(filetype.to_string()))?;
+        let mut child = Command::new(program)
+            .args(args)
+            .current_dir(root)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|error| Error::SpawnError {
+                command: program.to_string(),
+                error,
+            })?;
+
+        let (Some(stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+            let _ = child.kill();
+            return Err(Error::InvalidCommand(filetype.to_string()));
+        };
+        let writer = Arc::new(Mutex::new(Writer {
+            stdin,
+            initialized: false,
+            accepts_ranges: false,
+            pending: Vec::new(),
+        }));
+
+        let initialize = json!({
+            "jsonrpc": "2.0",
+            "id": INITIALIZE_ID,
+            "method": "initialize",
+            "params": {
+                "processId": std::process::id(),
+                "rootUri": path_to_uri(root),
+                "capabilities": {
+                    "textDocument": {
+                        "synchronization": { "didSave": true },
+                        "publishDiagnostics": {},
+                    },
+                    "window": { "workDoneProgress": true },
+                },
+            },
+        });
+        writer.lock().unwrap().write(&initialize)?;
+
+        let reader_writer = Arc::clone(&writer);
+        let filetype = filetype.to_string();
+        thread::spawn(move || {
+            let _ = read_messages(stdout, &filetype, &reader_writer, &events);
+            let _ = events.send(LspEvent::Exited { filetype });
+        });
+
+        Ok(Self { child, writer })
+    }
+
+    /// Sends a notification to the server. Errors are ignored, since a server that stopped
+    /// reading is reported once its output closes.
+    fn notify(&self, method: &str, params: Value) {
+        if let Ok(mut writer) = self.writer.lock() {
+            let _ = writer.notify(method, params);
+        }
+    }
+
+    /// Notifies the server that a document was opened.
+    pub fn did_open(&self, path: &Path, language_id: &str, text: &str) {
+        self.notify(
+            "textDocument/didOpen",
+            json!({
+                "textDocument": {
+                    "uri": path_to_uri(path),
+                    "languageId": language_id,
+                    "version": 0,
+                    "text": text,
+                },
+            }),
+        );
+    }
+
+    /// Returns `true` if the server accepts changes to ranges of a document. Until the server is
+    /// initialized, only whole documents are sent.
+    pub fn accepts_ranges(&self) -> bool {
+        self.writer.lock().is_ok_and(|writer| writer.accepts_ranges)
+    }
+
+    /// Notifies the server that the document changed.
+    pub fn did_change(&self, path: &Path, version: i32, changes: &[DocumentChange]) {
+        let changes: Vec<Value> = changes
+            .iter()
+            .map(|change| match change {
+                DocumentChange::Range { start, end, text } => json!({
+                    "range": {
+                        "start": { "line": start.row, "character": start.col },
+                        "end": { "line": end.row, "character": end.col },
+                    },
+                    "text": text,
+                }),
+                DocumentChange::Full(text) => json!({ "text": text }),
+            })
+            .collect();
+        self.notify(
+            "textDocument/didChange",
+            json!({
+                "textDocument": { "uri": path_to_uri(path), "version": version },
+                "contentChanges": changes,
+            }),
+        );
+    }
+
+    /// Notifies the server that the document was saved.
+    pub fn did_save(&self, path: &Path) {
+        self.notify(
+            "textDocument/didSave",
+            json!({ "textDocument": { "uri": path_to_uri(path) } }),
+        );
+    }
+}
+
+impl Drop for LspClient {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Handles messages from the server until its output closes.
+fn read_messages(
+    stdout: ChildStdout,
+    filetype: &str,
+    writer: &Mutex<Writer>,
+    events: &Sender<LspEvent>,
+) -> io::Result<()> {
+    let mut reader = BufReader::new(stdout);
+    while let Some(message) = read_message(&mut reader)? {
+        let id = message.get("id");
+        match (message.get("method").and_then(Value::as_str), id) {
+            // Requests from the server are not supported, but must be answered.
+            (Some(_), Some(id)) => {
+                let response = json!({ "jsonrpc": "2.0", "id": id, "result": null });
+                writer.lock().unwrap().write(&response)?;
+            }
+            (Some("textDocument/publishDiagnostics"), None) => {
+                let params = &message["params"];
+                let Some(path) = params["uri"].as_str().and_then(uri_to_path) else {
+                    continue;
+                };
+                let diagnostics = params["diagnostics"]
+                    .as_array()
+                    .map(|diagnostics| diagnostics.iter().filter_map(Diagnostic::parse).collect())
+                    .unwrap_or_default();
+                if events
+                    .send(LspEvent::Diagnostics { path, diagnostics })
+                    .is_err()
+                {
+                    return Ok(());
+                }
+            }
+            (Some("$/progress"), None) => {
+                let Some(progress) = Progress::parse(&message["params"]) else {
+                    continue;
+                };
+                let filetype = filetype.to_string();
+                if events
+                    .send(LspEvent::Progress { filetype, progress })
+                    .is_err()
+                {
+                    return Ok(());
+                }
+            }
+            (None, Some(id)) if id.as_u64() == Some(INITIALIZE_ID) => {
+                let capabilities = &message["result"]["capabilities"];
+                writer.lock().unwrap().initialize(capabilities)?;
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,79 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
+use crate::editor::{
+    buffer::modification::{BufferAction, BufferModification},
+    pane::jump_list::Jump,
+};
+
+/// A named position in a buffer.
+#[derive(Debug, Clone)]
+pub struct Mark {
+    /// The buffer and position the mark refers to.
+    pub jump: Jump,
+    /// The path of the buffer, used to reopen the buffer if it has been closed.
+    pub path: Option<PathBuf>,
+}
+
+/// The marks set in the editor, keyed by their name.
+#[derive(Debug, Default, Clone)]
+pub struct Marks {
+    marks: BTreeMap<char, Mark>,
+}
+
+impl Marks {
+    /// Sets the mark with the given name, replacing any previous mark with the same name.
+    pub fn set(&mut self, name: char, mark: Mark) {
+        self.marks.insert(name, mark);
+    }
+
+    /// Gets the mark with the given name. Returns `None` if the mark is not set.
+    pub fn get(&self, name: char) -> Option<&Mark> {
+        self.marks.get(&name)
+    }
+
+    /// Returns an iterator over all marks, ordered by name.
+    pub fn iter(&self) -> impl Iterator<Item = (&char, &Mark)> {
+        self.marks.iter()
+    }
+
+    /// Moves all marks referring to the buffer `from` to the buffer `to`. Used when a closed
+    /// buffer is reopened under a new id.
+    pub fn reassign_buffer(&mut self, from: usize, to: usize) {
+        for mark in self.marks.values_mut() {
+            if mark.jump.buffer_id == from {
+                mark.jump.buffer_id = to;
+            }
+        }
+    }
+
+    /// Handles a buffer modification and shifts the marks of the buffer to stay anchored to
+    /// their line.
+    pub fn handle_buffer_modification(&mut self, modification: &BufferModification) {
+        let Some((offset, row)) = modification.action.row_shift() else {
+            return;
+        };
+
+        for mark in self
+            .marks
+            .values_mut()
+            .filter(|m| m.jump.buffer_id == modification.buffer_id)
+        {
+            let position = &mut mark.jump.position;
+            if offset.is_positive() {
+                // Marks after the split point of the line move along to the new line.
+                let split_col = match &modification.action {
+                    BufferAction::Insert { start, .. } => start.col,
+                    _ => 0,
+                };
+                if position.row > row {
+                    position.row += 1;
+                } else if position.row == row && position.col >= split_col {
+                    position.row += 1;
+                    position.col -= split_col;
+                }
+            } else if position.row >= row {
+                position.row = position.row.saturating_sub(1);
+            }
+        }
+    }
+}
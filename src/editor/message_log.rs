@@ -0,0 +1,69 @@
+use std::{
+    collections::VecDeque,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::editor::ui::component::status_bar::{Message, MessageType};
+
+/// An entry in the message log.
+#[derive(Debug, Clone)]
+pub struct LogEntry {
+    /// The time the message was shown.
+    time: SystemTime,
+    /// The type of the message.
+    message_type: MessageType,
+    /// The content of the message.
+    content: String,
+}
+
+impl LogEntry {
+    /// Returns the time of the entry formatted as `HH:MM:SS` in UTC.
+    fn formatted_time(&self) -> String {
+        let secs = self
+            .time
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        let (hours, minutes, seconds) = ((secs / 3600) % 24, (secs / 60) % 60, secs % 60);
+        format!("{hours:02}:{minutes:02}:{seconds:02}")
+    }
+}
+
+/// A bounded log of all messages shown in the status bar.
+#[derive(Debug, Default, Clone)]
+pub struct MessageLog {
+    entries: VecDeque<LogEntry>,
+}
+
+impl MessageLog {
+    /// The maximum number of entries to keep in the log.
+    const MAX_ENTRIES: usize = 200;
+
+    /// Appends a message to the log, dropping the oldest entry if the log is full.
+    pub fn push(&mut self, message: &Message) {
+        if self.entries.len() >= Self::MAX_ENTRIES {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(LogEntry {
+            time: SystemTime::now(),
+            message_type: message.message_type(),
+            content: message.content().to_string(),
+        });
+    }
+
+    /// Returns the log as text with one entry per line, newest last.
+    pub fn text(&self) -> String {
+        self.entries
+            .iter()
+            .map(|entry| {
+                format!(
+                    "[{}] {:<5} {}",
+                    entry.formatted_time(),
+                    entry.message_type,
+                    entry.content
+                )
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+}
@@ -1,38 +1,118 @@
-// TODO: Error handling for lock operations.
-use std::path::Path;
+use std::{
+    cell::Cell,
+    cmp::Reverse,
+    iter,
+    path::{Path, PathBuf},
+    rc::Rc,
+    time::{Duration, Instant},
+};
 
 use crate::editor::{
     buffer::{
-        BufferEntry, Error,
+        BufferEntry, CharAt, Error,
+        indent::Indent,
+        line_ending::LineEnding,
         modification::{BufferAction, BufferModification},
+        row::Row,
+    },
+    pane::{
+        cursor::{Cursor, CursorMovement},
+        fold::{Fold, Folds},
+        jump_list::{Jump, JumpList},
+        view::ViewState,
     },
-    pane::cursor::{Cursor, CursorMovement},
+    registers::{Register, RegisterKind},
+    snippet::{self, SnippetSession},
     ui::geometry::point::Point,
 };
 
 pub mod cursor;
+pub mod fold;
+pub mod jump_list;
 pub mod manager;
+pub mod view;
 
 #[derive(Debug, Clone)]
 pub struct Pane {
     pub id: usize,
     pub buffer: BufferEntry,
     pub cursor: Cursor,
+    /// The cursors besides the primary cursor, used to edit several places at once.
+    pub secondary_cursors: Vec<Cursor>,
+    /// The positions the cursor has jumped from.
+    pub jump_list: JumpList,
+    /// The fixed end of the selection, or `None` if nothing is selected. The cursor is the other
+    /// end of the selection.
+    pub selection_anchor: Option<Point>,
+    /// Whether the selection is the rectangle between the anchor and the cursor. Movements extend
+    /// a block selection instead of collapsing it.
+    pub block: bool,
+    /// The placeholders of the snippet being filled in, if any.
+    pub snippet: Option<SnippetSession>,
+    /// The folded regions of the buffer.
+    pub folds: Folds,
+    /// Whether the pane scrolls along with the active pane while both are scroll-bound and show
+    /// the same buffer.
+    pub scroll_bound: bool,
+    /// The range highlighted after the last yank or paste, if any.
+    flash: Option<Flash>,
+    /// The number of modifications of the buffer seen by the pane, telling the view when it has
+    /// to render the pane again.
+    pub version: usize,
+    /// The part of the buffer shown by the pane. Clones of the pane, such as the one rendered,
+    /// share the state so that scrolling done while rendering is seen by the editor.
+    view: Rc<Cell<ViewState>>,
+}
+
+/// Why the number under the cursor could not be changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NumberError {
+    /// There is no integer under or after the cursor.
+    Missing,
+    /// The result does not fit in the type of the integer.
+    Overflow,
+}
+
+/// A range of the buffer briefly highlighted after it was yanked or pasted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Flash {
+    pub start: Point,
+    /// The end of the range, exclusive on its row.
+    pub end: Point,
+    /// Whether the range is the rectangle between `start` and `end`.
+    pub block: bool,
+    /// The time the highlight disappears.
+    deadline: Instant,
 }
 
 impl Pane {
+    /// The number of rows the cursor has to move for the movement to be recorded as a jump.
+    const JUMP_THRESHOLD: usize = 10;
+    /// The time a yanked or pasted range stays highlighted.
+    const FLASH_DURATION: Duration = Duration::from_millis(200);
+
     pub fn new(id: usize, buffer: BufferEntry) -> Self {
         Self {
             id,
             buffer,
             cursor: Cursor::default(),
+            secondary_cursors: Vec::new(),
+            jump_list: JumpList::default(),
+            selection_anchor: None,
+            block: false,
+            snippet: None,
+            folds: Folds::default(),
+            scroll_bound: false,
+            flash: None,
+            version: 0,
+            view: Rc::default(),
         }
     }
 
     /// Inserts a character at the current cursor position and attempt to advances the cursor
     /// column. Returns the buffer modification and the buffer id.
     pub fn insert_char(&mut self, c: char) -> BufferModification {
-        let mut buffer = self.buffer.write().unwrap();
+        let mut buffer = self.buffer.write_buf();
         let modification = buffer.insert_char(c, &self.cursor);
         if let BufferAction::Insert { .. } = modification {
             self.cursor.handle_movement(CursorMovement::Right, &buffer);
@@ -41,25 +121,149 @@ impl Pane {
         BufferModification::new(self.buffer.id, modification)
     }
 
+    /// Inserts a level of indentation at the current cursor position, using the indentation of
+    /// the buffer or `default` if it has none.
+    pub fn insert_indent(&mut self, default: Indent) -> BufferModification {
+        let mut buffer = self.buffer.write_buf();
+        let unit = buffer.indent().unwrap_or(default).unit();
+        let modification = buffer.insert_str(&unit, &self.cursor);
+        if let BufferAction::Insert { .. } = modification {
+            for _ in unit.chars() {
+                self.cursor.handle_movement(CursorMovement::Right, &buffer);
+            }
+        }
+
+        BufferModification::new(self.buffer.id, modification)
+    }
+
     /// Inserts a newline at the current cursor position.
     pub fn insert_newline(&mut self) -> BufferModification {
-        let mut buffer = self.buffer.write().unwrap();
+        let mut buffer = self.buffer.write_buf();
         let modification = buffer.insert_newline(&self.cursor);
         self.cursor
             .handle_movement(CursorMovement::StartOfNextRow, &buffer);
         BufferModification::new(self.buffer.id, modification)
     }
 
+    /// Inserts an empty row below the cursor row and moves the cursor onto it. If `auto_indent`
+    /// is set, the new row gets the indentation of the cursor row.
+    pub fn insert_line_below(&mut self, auto_indent: bool) -> BufferModification {
+        let row = self.cursor.row();
+        self.insert_line(row + 1, auto_indent)
+    }
+
+    /// Inserts an empty row above the cursor row and moves the cursor onto it. If `auto_indent`
+    /// is set, the new row gets the indentation of the cursor row.
+    pub fn insert_line_above(&mut self, auto_indent: bool) -> BufferModification {
+        let row = self.cursor.row();
+        self.insert_line(row, auto_indent)
+    }
+
+    /// Inserts an empty row at the given index and moves the cursor onto it.
+    fn insert_line(&mut self, at: usize, auto_indent: bool) -> BufferModification {
+        let mut buffer = self.buffer.write_buf();
+        let indent: String = match buffer.row(self.cursor.row()) {
+            Some(row) if auto_indent => row
+                .chars()
+                .take_while(|c| *c == ' ' || *c == '\t')
+                .collect(),
+            _ => String::new(),
+        };
+        let col = indent.chars().count();
+        let modification = buffer.insert_row(at, Row::new(indent));
+        if modification != BufferAction::None {
+            self.cursor
+                .handle_movement(CursorMovement::Position(col, at), &buffer);
+        }
+        BufferModification::new(self.buffer.id, modification)
+    }
+
+    /// Comments out the selected rows, or the cursor row if nothing is selected, by adding
+    /// `token` at their first non-whitespace column. If all of the rows are already commented,
+    /// the comments are removed instead. Blank rows are left untouched.
+    pub fn toggle_comment(&mut self, token: &str) -> BufferModification {
+        let mut buffer = self.buffer.write_buf();
+        let (first, last) = match self.selection() {
+            Some((start, end)) => (start.row, end.row),
+            None => (self.cursor.row(), self.cursor.row()),
+        };
+        let rows: Vec<String> = (first..=last)
+            .filter_map(|i| buffer.row(i).map(|r| r.text().to_string()))
+            .collect();
+
+        let is_blank = |row: &str| row.trim().is_empty();
+        let uncomment = rows
+            .iter()
+            .filter(|row| !is_blank(row))
+            .all(|row| row.trim_start().starts_with(token));
+        if rows.iter().all(|row| is_blank(row)) {
+            return BufferModification::new(self.buffer.id, BufferAction::None);
+        }
+
+        let commented_token = format!("{token} ");
+        let mut cursor_shift = 0;
+        let new_rows = rows
+            .iter()
+            .enumerate()
+            .map(|(i, row)| {
+                if is_blank(row) {
+                    return Row::new(row.as_str());
+                }
+                let (indent, rest) = row.split_at(row.len() - row.trim_start().len());
+                let new_rest = if uncomment {
+                    let rest = rest.strip_prefix(token).unwrap_or(rest);
+                    rest.strip_prefix(' ').unwrap_or(rest).to_string()
+                } else {
+                    format!("{commented_token}{rest}")
+                };
+
+                // Shift the cursor along with the text after the comment token.
+                if first + i == self.cursor.row() && self.cursor.col() >= indent.chars().count() {
+                    cursor_shift =
+                        new_rest.chars().count() as isize - rest.chars().count() as isize;
+                }
+                Row::new(format!("{indent}{new_rest}"))
+            })
+            .collect();
+
+        let modification = buffer.replace_rows(first..last + 1, new_rows);
+        let (col, row) = self.cursor.position();
+        let col = col.saturating_add_signed(cursor_shift);
+        self.cursor
+            .handle_movement(CursorMovement::Position(col, row), &buffer);
+        BufferModification::new(self.buffer.id, modification)
+    }
+
     /// Deletes a character at the current cursor position.
     pub fn delete_char(&mut self) -> BufferModification {
-        let mut buffer = self.buffer.write().unwrap();
+        let mut buffer = self.buffer.write_buf();
         let modification = buffer.delete_char(&self.cursor);
         BufferModification::new(self.buffer.id, modification)
     }
 
+    /// Deletes everything from the cursor to the end of the current row. Does nothing at the end
+    /// of the row.
+    pub fn delete_to_end_of_line(&mut self) -> BufferModification {
+        let mut buffer = self.buffer.write_buf();
+        let (col, row) = self.cursor.position();
+        let modification = buffer.delete_in_row(row, col..usize::MAX);
+        BufferModification::new(self.buffer.id, modification)
+    }
+
+    /// Deletes everything before the cursor on the current row and moves the cursor to the start
+    /// of the row.
+    pub fn delete_to_start_of_line(&mut self) -> BufferModification {
+        let mut buffer = self.buffer.write_buf();
+        let (col, row) = self.cursor.position();
+        let modification = buffer.delete_in_row(row, 0..col);
+        self.cursor
+            .handle_movement(CursorMovement::Position(0, row), &buffer);
+        BufferModification::new(self.buffer.id, modification)
+    }
+
     /// Deletes a character before the current cursor position.
     pub fn delete_char_before(&mut self) -> BufferModification {
-        let mut buffer = self.buffer.write().unwrap();
+        let mut buffer = self.buffer.write_buf();
         if self.cursor.col() == 0 && self.cursor.row() > 0 {
             let prev_row = self.cursor.row().saturating_sub(1);
             let prev_row_len = buffer.row(prev_row).map(|r| r.len()).unwrap_or_default();
@@ -72,48 +276,903 @@ impl Pane {
             if self.cursor.col() == 0 && self.cursor.row() == 0 {
                 return BufferModification::new(self.buffer_id(), BufferAction::None);
             }
+            // Past the end of the row there is nothing to delete, only virtual space to move over.
+            let past_end = buffer
+                .row(self.cursor.row())
+                .is_some_and(|row| self.cursor.col() > row.len());
             self.cursor.handle_movement(CursorMovement::Left, &buffer);
+            if past_end {
+                return BufferModification::new(self.buffer_id(), BufferAction::None);
+            }
             let modification = buffer.delete_char(&self.cursor);
             BufferModification::new(self.buffer_id(), modification)
         }
     }
 
+    /// Sorts all rows of the buffer by their text. Sorting is stable and compares characters by
+    /// their codepoint. If `unique` is set, adjacent duplicates are removed after sorting.
+    pub fn sort_lines(&mut self, reverse: bool, unique: bool) -> BufferModification {
+        self.replace_lines(|rows| {
+            rows.sort_by(|a, b| a.text().cmp(b.text()));
+            if reverse {
+                rows.reverse();
+            }
+            if unique {
+                rows.dedup_by(|a, b| a.text() == b.text());
+            }
+        })
+    }
+
+    /// Reverses the order of all rows of the buffer.
+    pub fn reverse_lines(&mut self) -> BufferModification {
+        self.replace_lines(|rows| rows.reverse())
+    }
+
+    /// Replaces all occurrences of `pattern` between the given rows, both inclusive, with
+    /// `replacement` and returns the number of replaced occurrences. Occurrences that cross the
+    /// end of the range are left untouched, even if a replacement adds or removes rows.
+    pub fn replace_in_lines(
+        &mut self,
+        start: usize,
+        end: usize,
+        pattern: &str,
+        replacement: &str,
+    ) -> (BufferModification, usize) {
+        let mut buffer = self.buffer.write_buf();
+        let Some(last) = buffer.row(end) else {
+            return (
+                BufferModification::new(self.buffer.id, BufferAction::None),
+                0,
+            );
+        };
+        let (start, end) = (Point::new(0, start), Point::new(last.len(), end));
+        let text = buffer.text_in_range(start, end);
+        let count = if pattern.is_empty() {
+            0
+        } else {
+            text.matches(pattern).count()
+        };
+        if count == 0 {
+            return (
+                BufferModification::new(self.buffer.id, BufferAction::None),
+                0,
+            );
+        }
+
+        let modification = buffer.replace_range(start, end, &text.replace(pattern, replacement));
+        drop(buffer);
+        self.clamp_cursor();
+        (BufferModification::new(self.buffer.id, modification), count)
+    }
+
+    /// Replaces all rows of the buffer with the rows produced by `f`. The cursor stays on a row
+    /// with the same text where possible, and moves to the first row otherwise.
+    fn replace_lines(&mut self, f: impl FnOnce(&mut Vec<Row>)) -> BufferModification {
+        let mut buffer = self.buffer.write_buf();
+        let range = 0..buffer.num_lines();
+        let mut rows: Vec<Row> = range
+            .clone()
+            .filter_map(|i| buffer.row(i).cloned())
+            .collect();
+        let current = buffer.row(self.cursor.row()).map(|r| r.text().to_string());
+        f(&mut rows);
+
+        let new_row = current
+            .and_then(|text| rows.iter().position(|r| r.text() == text))
+            .unwrap_or(range.start);
+        let modification = buffer.replace_rows(range, rows);
+        if modification != BufferAction::None {
+            self.cursor.handle_movement(
+                CursorMovement::Position(self.cursor.col(), new_row),
+                &buffer,
+            );
+        }
+        BufferModification::new(self.buffer.id, modification)
+    }
+
     /// Finds the next occurrence of the given string in the buffer and returns its position or
     /// `None`.
     pub fn find_next(&mut self, s: &str) -> Option<Point> {
-        let buffer = self.buffer.read().unwrap();
+        let buffer = self.buffer.read_buf();
         buffer.find_next(s, &self.cursor)
     }
 
-    /// Moves the cursor based on the provided [`CursorMovement`].
+    /// Finds the next occurrence of the given string between `start` and `end` and returns its
+    /// position or `None`.
+    pub fn find_next_in(&self, s: &str, start: Point, end: Point) -> Option<Point> {
+        let buffer = self.buffer.read_buf();
+        buffer.find_next_in(s, start, end)
+    }
+
+    /// Moves the cursor based on the provided [`CursorMovement`], collapsing the selection unless
+    /// it is a block selection. The previous position is recorded in the jump list if the
+    /// movement is a jump or moves the cursor far enough.
     pub fn move_cursor(&mut self, movement: CursorMovement) {
-        let buffer = self.buffer.read().unwrap();
-        self.cursor.handle_movement(movement, &buffer);
+        if !self.block {
+            self.selection_anchor = None;
+        }
+        let from = self.current_jump();
+        {
+            let buffer = self.buffer.read_buf();
+            self.cursor.handle_movement(movement, &buffer);
+            for cursor in &mut self.secondary_cursors {
+                cursor.handle_movement(movement, &buffer);
+            }
+        }
+        self.skip_folds(self.cursor.row() > from.position.row);
+        self.dedup_cursors();
+
+        let distance = from.position.row.abs_diff(self.cursor.row());
+        if from.position != self.cursor_position().into()
+            && (movement.is_jump() || distance > Self::JUMP_THRESHOLD)
+        {
+            self.jump_list.push(from);
+        }
+    }
+
+    /// Moves the cursors onto the last row if the buffer shrank below them.
+    pub fn clamp_cursor(&mut self) {
+        {
+            let buffer = self.buffer.read_buf();
+            let last_row = buffer.num_lines().saturating_sub(1);
+            for cursor in iter::once(&mut self.cursor).chain(&mut self.secondary_cursors) {
+                let (col, row) = cursor.position();
+                cursor.handle_movement(CursorMovement::Position(col, row.min(last_row)), &buffer);
+            }
+        }
+        self.dedup_cursors();
+    }
+
+    /// Adds a cursor one row below the lowest cursor, or one row above the highest cursor if
+    /// `above` is set. Does nothing if there is no row to add it on.
+    pub fn add_cursor(&mut self, above: bool) {
+        let buffer = self.buffer.read_buf();
+        let cursors = iter::once(&self.cursor).chain(&self.secondary_cursors);
+        let edge = if above {
+            cursors.min_by_key(|cursor| cursor.row())
+        } else {
+            cursors.max_by_key(|cursor| cursor.row())
+        };
+        let mut cursor = *edge.expect("there is always a primary cursor");
+
+        let row = cursor.row();
+        let movement = if above {
+            CursorMovement::Up
+        } else {
+            CursorMovement::Down
+        };
+        cursor.handle_movement(movement, &buffer);
+        if cursor.row() != row {
+            self.secondary_cursors.push(cursor);
+        }
+    }
+
+    /// Adds a cursor on the next occurrence of the word under the primary cursor, after the last
+    /// cursor and wrapping around to the start of the buffer. The cursor is placed at the same
+    /// column within the occurrence as the primary cursor is within its word. Returns `false` if
+    /// the cursor is not on a word or every occurrence already has a cursor.
+    pub fn select_next_occurrence(&mut self) -> bool {
+        let buffer = self.buffer.read_buf();
+        let (col, row) = self.cursor.position();
+        let Some(word) = buffer.row(row).and_then(|r| r.word_at(col)) else {
+            return false;
+        };
+        let offset = col - word.start;
+        let text = buffer
+            .row(row)
+            .unwrap()
+            .chars_in_range(word.start, word.end);
+
+        // Only match whole words.
+        let occurrences: Vec<Point> = buffer
+            .find_all(&text)
+            .into_iter()
+            .filter(|p| {
+                buffer
+                    .row(p.row)
+                    .and_then(|r| r.word_at(p.col))
+                    .is_some_and(|w| w.start == p.col && w.len() == word.len())
+            })
+            .map(|p| Point::new(p.col + offset, p.row))
+            .collect();
+        let key = |p: &Point| (p.row, p.col);
+        let last = iter::once(&self.cursor)
+            .chain(&self.secondary_cursors)
+            .map(|cursor| Point::from(cursor.position()))
+            .max_by_key(key)
+            .expect("there is always a primary cursor");
+        let next = occurrences
+            .iter()
+            .find(|p| key(p) > key(&last))
+            .or_else(|| occurrences.first());
+
+        let Some(&next) = next else {
+            return false;
+        };
+        let taken = iter::once(&self.cursor)
+            .chain(&self.secondary_cursors)
+            .any(|cursor| Point::from(cursor.position()) == next);
+        if taken {
+            return false;
+        }
+
+        let mut cursor = self.cursor;
+        cursor.handle_movement(CursorMovement::Position(next.col, next.row), &buffer);
+        self.secondary_cursors.push(cursor);
+        true
+    }
+
+    /// Removes the secondary cursors, leaving only the primary cursor. Returns `false` if there
+    /// were none.
+    pub fn collapse_cursors(&mut self) -> bool {
+        let had_secondary = !self.secondary_cursors.is_empty();
+        self.secondary_cursors.clear();
+        had_secondary
+    }
+
+    /// Removes the secondary cursors at the same position as another cursor.
+    fn dedup_cursors(&mut self) {
+        let mut positions = vec![self.cursor.position()];
+        self.secondary_cursors.retain(|cursor| {
+            let position = cursor.position();
+            let unique = !positions.contains(&position);
+            positions.push(position);
+            unique
+        });
+    }
+
+    /// Applies the edit at every cursor, moving each cursor into [`Pane::cursor`] while it is
+    /// edited. The cursors are edited in reverse document order so that an edit never moves the
+    /// text at the cursors not edited yet, and the cursors already edited are shifted along with
+    /// their text. The actions of all edits are returned as a single composite action.
+    pub fn edit_at_cursors(
+        &mut self,
+        mut edit: impl FnMut(&mut Pane) -> BufferModification,
+    ) -> BufferModification {
+        if self.secondary_cursors.is_empty() {
+            return edit(self);
+        }
+
+        let mut cursors: Vec<(bool, Cursor)> = iter::once((true, self.cursor))
+            .chain(
+                self.secondary_cursors
+                    .drain(..)
+                    .map(|cursor| (false, cursor)),
+            )
+            .collect();
+        cursors.sort_by_key(|(_, cursor)| Reverse((cursor.row(), cursor.col())));
+
+        let mut actions = Vec::new();
+        let mut edited: Vec<(bool, Cursor)> = Vec::with_capacity(cursors.len());
+        for (primary, cursor) in cursors {
+            self.cursor = cursor;
+            let action = edit(self).action;
+            if action != BufferAction::None {
+                let buffer = self.buffer.read_buf();
+                for (_, edited) in &mut edited {
+                    // Cursors on removed text end up where this edit left the cursor.
+                    let to = action
+                        .shift_point(edited.position().into())
+                        .unwrap_or_else(|| self.cursor.position().into());
+                    edited.handle_movement(CursorMovement::Position(to.col, to.row), &buffer);
+                }
+                actions.push(action);
+            }
+            edited.push((primary, self.cursor));
+        }
+
+        for (primary, cursor) in edited {
+            if primary {
+                self.cursor = cursor;
+            } else {
+                self.secondary_cursors.push(cursor);
+            }
+        }
+        self.dedup_cursors();
+
+        let action = if actions.is_empty() {
+            BufferAction::None
+        } else {
+            BufferAction::Composite(actions)
+        };
+        BufferModification::new(self.buffer.id, action)
+    }
+
+    /// Selects the whole buffer, anchoring the selection at the start of the buffer and moving
+    /// the cursor to the end of the last row.
+    pub fn select_all(&mut self) {
+        let buffer = self.buffer.read_buf();
+        self.selection_anchor = Some(Point::new(0, 0));
+        self.cursor
+            .handle_movement(CursorMovement::EndOfBuffer, &buffer);
+        self.cursor
+            .handle_movement(CursorMovement::EndOfRow, &buffer);
+    }
+
+    /// Collapses the selection.
+    pub fn clear_selection(&mut self) {
+        self.selection_anchor = None;
+        self.block = false;
+    }
+
+    /// Moves the cursors out of folded rows, past the fold if `down` is set and onto its first
+    /// row otherwise.
+    fn skip_folds(&mut self, down: bool) {
+        let buffer = self.buffer.read_buf();
+        for cursor in iter::once(&mut self.cursor).chain(&mut self.secondary_cursors) {
+            let Some(fold) = self.folds.hiding(cursor.row()) else {
+                continue;
+            };
+            let row = if down && fold.end + 1 < buffer.num_lines() {
+                fold.end + 1
+            } else {
+                fold.start
+            };
+            cursor.move_to_row(row, &buffer);
+        }
+    }
+
+    /// Folds the region starting at the cursor row, or opens the fold there if there is one.
+    /// Returns `false` if there is nothing to fold.
+    pub fn toggle_fold(&mut self) -> bool {
+        let row = self.cursor.row();
+        if self.folds.remove(row) {
+            return true;
+        }
+        let Some(fold) = Fold::region(&self.buffer.read_buf(), row) else {
+            return false;
+        };
+        self.folds.add(fold);
+        self.skip_folds(false);
+        self.dedup_cursors();
+        true
+    }
+
+    /// Folds every region that is not inside another region.
+    pub fn close_all_folds(&mut self) {
+        {
+            let buffer = self.buffer.read_buf();
+            let mut row = 0;
+            while row < buffer.num_lines() {
+                match Fold::region(&buffer, row) {
+                    Some(fold) => {
+                        self.folds.add(fold);
+                        row = fold.end + 1;
+                    }
+                    None => row += 1,
+                }
+            }
+        }
+        self.skip_folds(false);
+        self.dedup_cursors();
+    }
+
+    /// Opens all folds.
+    pub fn open_all_folds(&mut self) {
+        self.folds.clear();
+    }
+
+    /// Switches the selection between a character-wise and a block selection. Returns `false` if
+    /// nothing is selected.
+    pub fn toggle_block_selection(&mut self) -> bool {
+        if self.selection_anchor.is_none() {
+            return false;
+        }
+        self.block = !self.block;
+        true
+    }
+
+    /// Returns the top left and bottom right corners of the block selection, or `None` if there
+    /// is no block selection. The right column is exclusive.
+    pub fn block_selection(&self) -> Option<(Point, Point)> {
+        let anchor = self.selection_anchor.filter(|_| self.block)?;
+        let (col, row) = self.cursor.position();
+        Some((
+            Point::new(anchor.col.min(col), anchor.row.min(row)),
+            Point::new(anchor.col.max(col), anchor.row.max(row)),
+        ))
+    }
+
+    /// Returns the start and end of the selection, or `None` if nothing is selected. The end is
+    /// exclusive.
+    pub fn selection(&self) -> Option<(Point, Point)> {
+        let anchor = self.selection_anchor?;
+        let cursor = Point::from(self.cursor_position());
+        let key = |p: &Point| (p.row, p.col);
+        if key(&anchor) <= key(&cursor) {
+            Some((anchor, cursor))
+        } else {
+            Some((cursor, anchor))
+        }
+    }
+
+    /// Converts the selected text, or the word under the cursor if nothing is selected, to upper
+    /// or lower case. The case mapping may change the length of the text, e.g. `ß` becomes `SS`.
+    pub fn change_word_case(&mut self, upper: bool) -> BufferModification {
+        let selection = self.selection();
+        self.clear_selection();
+        let mut buffer = self.buffer.write_buf();
+        let (col, row) = self.cursor.position();
+        let range = selection.or_else(|| {
+            let cols = buffer.row(row)?.word_at(col)?;
+            Some((Point::new(cols.start, row), Point::new(cols.end, row)))
+        });
+        let Some((start, end)) = range else {
+            return BufferModification::new(self.buffer.id, BufferAction::None);
+        };
+
+        let text = buffer.text_in_range(start, end);
+        let changed = if upper {
+            text.to_uppercase()
+        } else {
+            text.to_lowercase()
+        };
+        if changed == text {
+            return BufferModification::new(self.buffer.id, BufferAction::None);
+        }
+        let modification = buffer.replace_range(start, end, &changed);
+        drop(buffer);
+        self.clamp_cursor();
+        BufferModification::new(self.buffer.id, modification)
+    }
+
+    /// Returns the position where the word under the cursor starts, and the word. Returns `None`
+    /// if the cursor is not on a word.
+    pub fn word_under_cursor(&self) -> Option<(Point, String)> {
+        let buffer = self.buffer.read_buf();
+        let (col, row) = self.cursor.position();
+        let text = buffer.row(row)?;
+        let cols = text.word_at(col)?;
+        Some((
+            Point::new(cols.start, row),
+            text.chars_in_range(cols.start, cols.end),
+        ))
+    }
+
+    /// Replaces the text between `start` and `end` and keeps the cursor within the buffer.
+    pub fn replace_range(&mut self, start: Point, end: Point, text: &str) -> BufferModification {
+        let modification = self.buffer.write_buf().replace_range(start, end, text);
+        self.clamp_cursor();
+        BufferModification::new(self.buffer.id, modification)
+    }
+
+    /// Returns the position where the word before the cursor starts, and the part of the word
+    /// before the cursor.
+    pub fn word_prefix(&self) -> (Point, String) {
+        let buffer = self.buffer.read_buf();
+        let (col, row) = self.cursor.position();
+        let Some(text) = buffer.row(row) else {
+            return (Point::new(col, row), String::new());
+        };
+        let start = text.word_start_before(col);
+        (Point::new(start, row), text.chars_in_range(start, col))
+    }
+
+    /// Replaces the text between `start` and the cursor with the word and moves the cursor past
+    /// it.
+    pub fn complete(&mut self, start: Point, word: &str) -> BufferModification {
+        let mut buffer = self.buffer.write_buf();
+        let end = Point::from(self.cursor.position());
+        let modification = buffer.replace_range(start, end, word);
+        let col = start.col + word.chars().count();
+        self.cursor
+            .handle_movement(CursorMovement::Position(col, start.row), &buffer);
+        BufferModification::new(self.buffer.id, modification)
+    }
+
+    /// Replaces the text between `start` and the cursor with the expanded snippet body, indenting
+    /// continuation lines like the cursor row, and moves the cursor to the first placeholder.
+    /// Returns the modification along with a session holding the remaining placeholders, which
+    /// should be set once the modification has been handled.
+    pub fn expand_snippet(
+        &mut self,
+        start: Point,
+        body: &str,
+    ) -> (BufferModification, Option<SnippetSession>) {
+        let mut buffer = self.buffer.write_buf();
+        let end = Point::from(self.cursor.position());
+        let indent: String = buffer
+            .row(end.row)
+            .map(|row| {
+                row.chars()
+                    .take_while(|c| *c == ' ' || *c == '\t')
+                    .collect()
+            })
+            .unwrap_or_default();
+        let expansion = snippet::expand(body, &indent);
+        let modification = buffer.replace_range(start, end, &expansion.text);
+
+        let mut stops = expansion.stops.into_iter().map(|stop| {
+            if stop.row == 0 {
+                Point::new(start.col + stop.col, start.row)
+            } else {
+                Point::new(stop.col, start.row + stop.row)
+            }
+        });
+        if let Some(first) = stops.next() {
+            self.cursor
+                .handle_movement(CursorMovement::Position(first.col, first.row), &buffer);
+        }
+        let session = SnippetSession::new(self.buffer.id, stops);
+        let session = (!session.is_done()).then_some(session);
+        (
+            BufferModification::new(self.buffer.id, modification),
+            session,
+        )
+    }
+
+    /// Moves the cursor to the next placeholder of the snippet session, ending the session after
+    /// the last one. Returns `false` if there is no session.
+    pub fn next_snippet_stop(&mut self) -> bool {
+        let Some(session) = &mut self.snippet else {
+            return false;
+        };
+        let next = session.next();
+        if session.is_done() {
+            self.snippet = None;
+        }
+        if let Some(Point { col, row }) = next {
+            let buffer = self.buffer.read_buf();
+            self.cursor
+                .handle_movement(CursorMovement::Position(col, row), &buffer);
+        }
+        true
+    }
+
+    /// Flips the case of the character under the cursor and moves the cursor past it.
+    pub fn toggle_case_char(&mut self) -> BufferModification {
+        let mut buffer = self.buffer.write_buf();
+        let point = Point::from(self.cursor.position());
+        let CharAt::Char(c) = buffer.char_at(point) else {
+            return BufferModification::new(self.buffer.id, BufferAction::None);
+        };
+
+        let toggled: String = if c.is_uppercase() {
+            c.to_lowercase().collect()
+        } else {
+            c.to_uppercase().collect()
+        };
+        let end = Point::new(point.col + 1, point.row);
+        let modification = if toggled == c.to_string() {
+            BufferAction::None
+        } else {
+            buffer.replace_range(point, end, &toggled)
+        };
+        let next_col = point.col + toggled.chars().count();
+        self.cursor
+            .handle_movement(CursorMovement::Position(next_col, point.row), &buffer);
+        BufferModification::new(self.buffer.id, modification)
+    }
+
+    /// Adds `delta` to the integer under the cursor, or to the first one after it on the cursor
+    /// row, keeping the number of digits of zero-padded integers. The cursor is moved to the last
+    /// digit of the result.
+    pub fn add_to_number(&mut self, delta: i64) -> Result<BufferModification, NumberError> {
+        let mut buffer = self.buffer.write_buf();
+        let (col, row) = self.cursor.position();
+        let text = buffer.row(row).ok_or(NumberError::Missing)?;
+        let cols = text.number_at(col).ok_or(NumberError::Missing)?;
+        let number = add_to_number(&text.chars_in_range(cols.start, cols.end), delta)
+            .ok_or(NumberError::Overflow)?;
+
+        let (start, end) = (Point::new(cols.start, row), Point::new(cols.end, row));
+        let modification = buffer.replace_range(start, end, &number);
+        let last = cols.start + number.chars().count() - 1;
+        self.cursor
+            .handle_movement(CursorMovement::Position(last, row), &buffer);
+        Ok(BufferModification::new(self.buffer.id, modification))
+    }
+
+    /// Returns the selected text, or the current row if nothing is selected, and collapses the
+    /// selection.
+    pub fn yank(&mut self) -> Register {
+        let buffer = self.buffer.read_buf();
+        let register = match (self.block_selection(), self.selection()) {
+            // Rows ending before the block contribute empty lines.
+            (Some((start, end)), _) => {
+                let lines: Vec<String> = (start.row..=end.row)
+                    .map(|row| {
+                        buffer
+                            .row(row)
+                            .map(|r| r.chars_in_range(start.col, end.col))
+                            .unwrap_or_default()
+                    })
+                    .collect();
+                Register::new(lines.join("\n"), RegisterKind::Blockwise)
+            }
+            (None, Some((start, end))) => {
+                Register::new(buffer.text_in_range(start, end), RegisterKind::Charwise)
+            }
+            (None, None) => {
+                let row = buffer.row(self.cursor.row());
+                let text = row.map(|r| r.text().to_string()).unwrap_or_default();
+                Register::new(text, RegisterKind::Linewise)
+            }
+        };
+        drop(buffer);
+        let (start, end) = self
+            .block_selection()
+            .or(self.selection())
+            .unwrap_or_else(|| Self::row_range(self.cursor.row(), self.cursor.row()));
+        self.set_flash(start, end, self.block_selection().is_some());
+        self.clear_selection();
+        register
+    }
+
+    /// Deletes the selected text, or the current row if nothing is selected. A block selection
+    /// deletes its columns from each row it spans. Returns the deleted text along with the buffer
+    /// modification.
+    pub fn delete(&mut self) -> (Register, BufferModification) {
+        let block = self.block_selection();
+        let selection = self.selection();
+        let register = self.yank();
+        // The deleted text is gone, so there is nothing to highlight.
+        self.flash = None;
+        let mut buffer = self.buffer.write_buf();
+        let modification = match (block, selection) {
+            (Some((start, end)), _) => {
+                let actions: Vec<BufferAction> = (start.row..=end.row)
+                    .map(|row| {
+                        let len = buffer.row(row).map(Row::len).unwrap_or_default();
+                        buffer.delete_range(
+                            Point::new(start.col.min(len), row),
+                            Point::new(end.col.min(len), row),
+                        )
+                    })
+                    .filter(|action| *action != BufferAction::None)
+                    .collect();
+                self.cursor
+                    .handle_movement(CursorMovement::Position(start.col, start.row), &buffer);
+                if actions.is_empty() {
+                    BufferAction::None
+                } else {
+                    BufferAction::Composite(actions)
+                }
+            }
+            (None, Some((start, end))) => {
+                let modification = buffer.delete_range(start, end);
+                self.cursor
+                    .handle_movement(CursorMovement::Position(start.col, start.row), &buffer);
+                modification
+            }
+            (None, None) => {
+                let row = self.cursor.row();
+                buffer.replace_rows(row..row + 1, Vec::new())
+            }
+        };
+        drop(buffer);
+        self.clamp_cursor();
+        (
+            register,
+            BufferModification::new(self.buffer.id, modification),
+        )
+    }
+
+    /// Pastes the contents of a register. Line-wise registers are pasted as new rows below the
+    /// cursor, and character-wise registers are pasted at the cursor. Each line of a block-wise
+    /// register is pasted at the cursor column on consecutive rows, padding rows that are too
+    /// short with spaces and adding rows past the end of the buffer.
+    pub fn paste(&mut self, register: &Register) -> BufferModification {
+        let mut buffer = self.buffer.write_buf();
+        let (col, row) = self.cursor.position();
+        let lines = register.text.split('\n').count();
+        let (modification, end, flash) = match register.kind {
+            RegisterKind::Linewise => {
+                let rows = register.text.split('\n').map(Row::new).collect();
+                let modification = buffer.replace_rows(row + 1..row + 1, rows);
+                let flash = Self::row_range(row + 1, row + lines);
+                (modification, Point::new(0, row + 1), flash)
+            }
+            RegisterKind::Charwise => {
+                let modification = buffer.insert_text(Point::new(col, row), &register.text);
+                let mut lines = register.text.split('\n');
+                let last_len = lines.next_back().unwrap_or_default().chars().count();
+                let end = match lines.count() {
+                    0 => Point::new(col + last_len, row),
+                    rows => Point::new(last_len, row + rows),
+                };
+                (modification, end, (Point::new(col, row), end))
+            }
+            RegisterKind::Blockwise => {
+                let mut actions = Vec::new();
+                for (i, line) in register.text.split('\n').enumerate() {
+                    let row = row + i;
+                    if row >= buffer.num_lines() {
+                        actions.push(buffer.replace_rows(row..row, vec![Row::default()]));
+                    }
+                    let len = buffer.row(row).map(Row::len).unwrap_or_default();
+                    let padding = " ".repeat(col.saturating_sub(len));
+                    let point = Point::new(col.min(len), row);
+                    actions.push(buffer.insert_text(point, &format!("{padding}{line}")));
+                }
+                actions.retain(|action| *action != BufferAction::None);
+                let modification = if actions.is_empty() {
+                    BufferAction::None
+                } else {
+                    BufferAction::Composite(actions)
+                };
+                let width = register
+                    .text
+                    .split('\n')
+                    .map(|line| line.chars().count())
+                    .max()
+                    .unwrap_or_default();
+                let flash = (
+                    Point::new(col, row),
+                    Point::new(col + width, row + lines - 1),
+                );
+                (modification, Point::new(col, row), flash)
+            }
+        };
+        if modification != BufferAction::None {
+            self.cursor
+                .handle_movement(CursorMovement::Position(end.col, end.row), &buffer);
+            drop(buffer);
+            let (start, end) = flash;
+            self.set_flash(start, end, register.kind == RegisterKind::Blockwise);
+        }
+        BufferModification::new(self.buffer.id, modification)
+    }
+
+    /// Returns the range covering the whole rows from `start` to `end`.
+    fn row_range(start: usize, end: usize) -> (Point, Point) {
+        (Point::new(0, start), Point::new(usize::MAX, end))
+    }
+
+    /// Highlights the given range until the flash duration has passed, replacing any previous
+    /// highlight.
+    fn set_flash(&mut self, start: Point, end: Point, block: bool) {
+        self.flash = Some(Flash {
+            start,
+            end,
+            block,
+            deadline: Instant::now() + Self::FLASH_DURATION,
+        });
+    }
+
+    /// Returns the highlighted range if its highlight has not expired yet.
+    pub fn flash(&self) -> Option<&Flash> {
+        self.flash
+            .as_ref()
+            .filter(|flash| Instant::now() < flash.deadline)
+    }
+
+    /// Removes the highlighted range once it has expired. Returns `true` if it was removed.
+    pub fn clear_expired_flash(&mut self) -> bool {
+        if self.flash.is_some() && self.flash().is_none() {
+            self.flash = None;
+            return true;
+        }
+        false
+    }
+
+    /// Returns the part of the buffer shown by the pane.
+    pub fn view_state(&self) -> ViewState {
+        self.view.get()
+    }
+
+    /// Changes the part of the buffer shown by the pane, also for the clones of the pane. The
+    /// offsets are moved back to the cursor when the pane is rendered if it would not be visible.
+    pub fn set_view_state(&self, view: ViewState) {
+        self.view.set(view);
+    }
+
+    /// Returns a jump to the current cursor position.
+    pub fn current_jump(&self) -> Jump {
+        Jump::new(self.buffer_id(), self.cursor_position().into())
+    }
+
+    /// Moves the cursor to the position of the jump without recording it, showing the given
+    /// buffer if it differs from the current one. The position is clamped to the buffer in case
+    /// it shrank since the jump was recorded.
+    pub fn restore_jump(&mut self, jump: Jump, buffer: BufferEntry) {
+        if buffer.id != self.buffer_id() {
+            self.buffer = buffer;
+            self.cursor = Cursor::default();
+        }
+
+        let buffer = self.buffer.read_buf();
+        let row = jump.position.row.min(buffer.num_lines().saturating_sub(1));
+        self.cursor
+            .handle_movement(CursorMovement::Position(jump.position.col, row), &buffer);
+    }
+
+    /// Returns the contents of the buffer under the cursor.
+    pub fn char_under_cursor(&self) -> CharAt {
+        let buffer = self.buffer.read_buf();
+        buffer.char_at(self.cursor_position().into())
     }
 
     /// Saves the pane.
     pub fn save(&mut self) -> Result<(), Error> {
-        let mut buffer = self.buffer.write().unwrap();
+        let mut buffer = self.buffer.write_buf();
         buffer.save()?;
         Ok(())
     }
 
     /// Saves the pane to the given path.
     pub fn save_as<P: AsRef<Path>>(&mut self, path: P, force: bool) -> Result<(), Error> {
-        let mut buffer = self.buffer.write().unwrap();
+        let mut buffer = self.buffer.write_buf();
         buffer.save_as(path, force)?;
         Ok(())
     }
 
+    /// Writes a copy of the buffer of the pane to the given path.
+    pub fn write_to<P: AsRef<Path>>(&self, path: P, force: bool) -> Result<(), Error> {
+        let buffer = self.buffer.read_buf();
+        buffer.write_to(path, force)?;
+        Ok(())
+    }
+
     /// Returns the name of the file associated with the pane.
     pub fn file_name(&self) -> String {
-        let buffer = self.buffer.read().unwrap();
+        let buffer = self.buffer.read_buf();
         buffer.file_name()
     }
 
+    /// Returns the path of the file associated with the pane, or `None` if it has no path.
+    pub fn file_path(&self) -> Option<PathBuf> {
+        let buffer = self.buffer.read_buf();
+        buffer.filepath().map(Path::to_path_buf)
+    }
+
+    /// Returns the filetype of the buffer associated with the pane.
+    pub fn filetype(&self) -> String {
+        let buffer = self.buffer.read_buf();
+        buffer.filetype()
+    }
+
+    /// Overrides the filetype of the buffer associated with the pane.
+    pub fn set_filetype(&mut self, filetype: &str) {
+        let mut buffer = self.buffer.write_buf();
+        buffer.set_filetype(filetype);
+    }
+
+    /// Returns the indentation of the buffer associated with the pane, or `None` if it has none.
+    pub fn indent(&self) -> Option<Indent> {
+        let buffer = self.buffer.read_buf();
+        buffer.indent()
+    }
+
+    /// Overrides the indentation of the buffer associated with the pane.
+    pub fn set_indent(&mut self, indent: Indent) {
+        let mut buffer = self.buffer.write_buf();
+        buffer.set_indent(indent);
+    }
+
+    /// Returns the line ending of the buffer associated with the pane.
+    pub fn line_ending(&self) -> LineEnding {
+        let buffer = self.buffer.read_buf();
+        buffer.line_ending()
+    }
+
+    /// Converts the buffer associated with the pane to the given line ending.
+    pub fn set_line_ending(&mut self, line_ending: LineEnding) {
+        let mut buffer = self.buffer.write_buf();
+        buffer.set_line_ending(line_ending);
+    }
+
+    /// Returns true if the buffer associated with the pane was decoded lossily.
+    pub fn is_lossy(&self) -> bool {
+        let buffer = self.buffer.read_buf();
+        buffer.is_lossy()
+    }
+
+    /// Allows saving the buffer associated with the pane even though it was decoded lossily.
+    pub fn discard_invalid_bytes(&mut self) {
+        let mut buffer = self.buffer.write_buf();
+        buffer.discard_invalid_bytes();
+    }
+
+    /// Returns true if the buffer associated with the pane can not be modified.
+    pub fn is_read_only(&self) -> bool {
+        let buffer = self.buffer.read_buf();
+        buffer.is_read_only()
+    }
+
     /// Returns true if the pane has been modified.
     pub fn is_dirty(&self) -> bool {
-        let buffer = self.buffer.read().unwrap();
+        let buffer = self.buffer.read_buf();
         buffer.is_dirty()
     }
 
@@ -128,6 +1187,49 @@ impl Pane {
 
     /// The number of rows in the buffer.
     pub fn buffer_lines(&self) -> usize {
-        self.buffer.read().unwrap().num_lines()
+        self.buffer.read_buf().num_lines()
+    }
+}
+
+/// Adds `delta` to the decimal or `0x` hexadecimal integer, padding the result with zeros to the
+/// number of digits of the integer if it starts with a zero. Hexadecimal integers keep the case of
+/// their digits. Returns `None` if the text is not an integer or the result overflows.
+fn add_to_number(text: &str, delta: i64) -> Option<String> {
+    if let Some(digits) = text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        let value = u64::from_str_radix(digits, 16).ok()?;
+        let width = digits.len();
+        let result = value.checked_add_signed(delta)?;
+        let prefix = &text[..2];
+        return Some(if digits.chars().any(|c| c.is_ascii_uppercase()) {
+            format!("{prefix}{result:0width$X}")
+        } else {
+            format!("{prefix}{result:0width$x}")
+        });
+    }
+
+    let value: i64 = text.parse().ok()?;
+    let result = value.checked_add(delta)?;
+    let digits = text.trim_start_matches('-');
+    let width = if digits.len() > 1 && digits.starts_with('0') {
+        digits.len()
+    } else {
+        0
+    };
+    let sign = if result < 0 { "-" } else { "" };
+    Some(format!("{sign}{:0width$}", result.unsigned_abs()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn add_to_number_reports_overflow() {
+        assert_eq!(add_to_number("007", 5).as_deref(), Some("012"));
+        assert_eq!(add_to_number("0xFF", 1).as_deref(), Some("0x100"));
+        assert_eq!(add_to_number("1", -2).as_deref(), Some("-1"));
+        assert_eq!(add_to_number(&i64::MAX.to_string(), 1), None);
+        assert_eq!(add_to_number("0x0", -1), None);
+        assert_eq!(add_to_number("0xffffffffffffffff", 1), None);
     }
 }
@@ -1,4 +1,4 @@
-use crate::editor::buffer::Buffer;
+use crate::editor::buffer::{Buffer, row::Row};
 
 /// All available cursor movements.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -8,14 +8,35 @@ pub enum CursorMovement {
     Up,
     Down,
     StartOfRow,
+    /// Moves to the first non-whitespace character of the row, or to the start of the row if the
+    /// cursor is already there.
+    SmartHome,
     StartOfNextRow,
     EndOfRow,
     StartOfBuffer,
     EndOfBuffer,
+    /// Moves to the start of the next blank row after the current paragraph, or the last row.
+    ParagraphForward,
+    /// Moves to the start of the previous blank row before the current paragraph, or the first
+    /// row.
+    ParagraphBackward,
     Line(usize),
     Position(usize, usize),
 }
 
+impl CursorMovement {
+    /// Returns true if the movement jumps to a position that is not relative to the cursor.
+    pub fn is_jump(&self) -> bool {
+        matches!(
+            self,
+            CursorMovement::StartOfBuffer
+                | CursorMovement::EndOfBuffer
+                | CursorMovement::Line(_)
+                | CursorMovement::Position(..)
+        )
+    }
+}
+
 #[derive(Debug, Default, Clone, Copy)]
 pub struct Cursor {
     /// The column of the cursor.
@@ -25,6 +46,8 @@ pub struct Cursor {
     /// The last remembered column of the cursor. This value takes precedence over the current
     /// column when moving the cursor vertically and updates on horizontal movements.
     last_col: usize,
+    /// Whether the cursor may move past the end of the row.
+    virtual_edit: bool,
 }
 
 impl Cursor {
@@ -43,6 +66,21 @@ impl Cursor {
         (self.col, self.row)
     }
 
+    /// Sets whether the cursor may move past the end of the row. Turning it off moves the cursor
+    /// back onto the row on the next movement.
+    pub fn set_virtual_edit(&mut self, enabled: bool) {
+        self.virtual_edit = enabled;
+    }
+
+    /// Returns the column clamped to the end of the row, unless virtual editing is enabled.
+    fn clamp_col(&self, col: usize, row: &Row) -> usize {
+        if self.virtual_edit {
+            col
+        } else {
+            col.min(row.len())
+        }
+    }
+
     /// Moves the cursor based on the provided [`CursorMovement`].
     pub fn handle_movement(&mut self, movement: CursorMovement, buffer: &Buffer) {
         match movement {
@@ -51,20 +89,31 @@ impl Cursor {
             CursorMovement::Up => self.move_up(buffer),
             CursorMovement::Down => self.move_down(buffer),
             CursorMovement::StartOfRow => self.move_to_start_of_row(),
+            CursorMovement::SmartHome => self.move_to_smart_home(buffer),
             CursorMovement::StartOfNextRow => self.move_to_start_of_next_row(buffer),
             CursorMovement::EndOfRow => self.move_to_end_of_row(buffer),
             CursorMovement::StartOfBuffer => self.move_to_start_of_buffer(buffer),
             CursorMovement::EndOfBuffer => self.move_to_end_of_buffer(buffer),
+            CursorMovement::ParagraphForward => self.move_paragraph(true, buffer),
+            CursorMovement::ParagraphBackward => self.move_paragraph(false, buffer),
             CursorMovement::Line(line) => self.move_to_line(line, buffer),
             CursorMovement::Position(col, row) => self.move_to(col, row, buffer),
         }
     }
 
+    /// Moves the cursor to the given row, keeping the remembered column like vertical movements.
+    pub fn move_to_row(&mut self, row: usize, buffer: &Buffer) {
+        if let Some(buffer_row) = buffer.row(row) {
+            self.row = row;
+            self.col = self.clamp_col(self.col.max(self.last_col), buffer_row);
+        }
+    }
+
     /// moves the cursor to the given position.
     fn move_to(&mut self, col: usize, row: usize, buffer: &Buffer) {
         if let Some(buffer_row) = buffer.row(row) {
             self.row = row;
-            self.col = col.min(buffer_row.len());
+            self.col = self.clamp_col(col, buffer_row);
             self.last_col = self.col;
         }
     }
@@ -75,7 +124,7 @@ impl Cursor {
         let line = line.saturating_sub(1);
         if let Some(row) = buffer.row(line) {
             self.row = line;
-            self.col = self.col.min(row.len());
+            self.col = self.clamp_col(self.col, row);
         } else {
             self.move_to_end_of_buffer(buffer);
         }
@@ -84,7 +133,7 @@ impl Cursor {
     /// Moves the cursor one column to the left.
     fn move_left(&mut self, buffer: &Buffer) {
         if let Some(row) = buffer.row(self.row) {
-            self.col = row.len().min(self.col.saturating_sub(1));
+            self.col = self.clamp_col(self.col.saturating_sub(1), row);
             self.last_col = self.col;
         }
     }
@@ -92,7 +141,7 @@ impl Cursor {
     /// Moves the cursor one column to the right.
     fn move_right(&mut self, buffer: &Buffer) {
         if let Some(row) = buffer.row(self.row) {
-            self.col = row.len().min(self.col.saturating_add(1));
+            self.col = self.clamp_col(self.col.saturating_add(1), row);
             self.last_col = self.col;
         }
     }
@@ -101,11 +150,7 @@ impl Cursor {
     fn move_up(&mut self, buffer: &Buffer) {
         self.row = self.row.saturating_sub(1);
         if let Some(row) = buffer.row(self.row) {
-            if self.col > row.len() {
-                self.col = row.len();
-            } else if self.col < self.last_col {
-                self.col = self.last_col.min(row.len());
-            }
+            self.col = self.clamp_col(self.col.max(self.last_col), row);
         }
     }
 
@@ -114,12 +159,7 @@ impl Cursor {
         let next_row = self.row.saturating_add(1);
         if let Some(row) = buffer.row(next_row) {
             self.row = next_row;
-
-            if self.col > row.len() {
-                self.col = row.len();
-            } else if self.col < self.last_col {
-                self.col = self.last_col.min(row.len());
-            }
+            self.col = self.clamp_col(self.col.max(self.last_col), row);
         }
     }
 
@@ -137,6 +177,54 @@ impl Cursor {
         self.last_col = 0;
     }
 
+    /// Moves the cursor to the first non-whitespace character of the current row, or to the start
+    /// of the row if the cursor is already there.
+    fn move_to_smart_home(&mut self, buffer: &Buffer) {
+        let first_non_whitespace = buffer
+            .row(self.row)
+            .map(|row| row.chars().take_while(|c| c.is_whitespace()).count())
+            .unwrap_or_default();
+        self.col = if self.col == first_non_whitespace {
+            0
+        } else {
+            first_non_whitespace
+        };
+        self.last_col = self.col;
+    }
+
+    /// Moves the cursor to the start of the next or previous blank row, skipping over the blank
+    /// rows next to the cursor so that consecutive blank rows count as one boundary. Stops at the
+    /// first or last row if there are no more blank rows.
+    fn move_paragraph(&mut self, forward: bool, buffer: &Buffer) {
+        let last_row = buffer.num_lines().saturating_sub(1);
+        let is_blank = |row: usize| buffer.row(row).is_none_or(|r| r.text().trim().is_empty());
+        let step = |row: usize| -> Option<usize> {
+            if forward {
+                (row < last_row).then(|| row + 1)
+            } else {
+                row.checked_sub(1)
+            }
+        };
+
+        let mut row = self.row;
+        // Skip the blank rows next to the cursor, then the paragraph itself.
+        while let Some(next) = step(row)
+            && is_blank(next)
+        {
+            row = next;
+        }
+        while let Some(next) = step(row) {
+            row = next;
+            if is_blank(row) {
+                break;
+            }
+        }
+
+        self.row = row;
+        self.col = 0;
+        self.last_col = 0;
+    }
+
     /// Moves the cursor to the start of the next row.
     fn move_to_start_of_next_row(&mut self, buffer: &Buffer) {
         let next_row = self.row.saturating_add(1);
@@ -151,7 +239,7 @@ impl Cursor {
     fn move_to_start_of_buffer(&mut self, buffer: &Buffer) {
         if let Some(row) = buffer.row(0) {
             self.row = 0;
-            self.col = self.col.min(row.len());
+            self.col = self.clamp_col(self.col, row);
         }
     }
 
@@ -160,7 +248,7 @@ impl Cursor {
         let last_row = buffer.num_lines().saturating_sub(1);
         if let Some(row) = buffer.row(last_row) {
             self.row = last_row;
-            self.col = self.col.min(row.len());
+            self.col = self.clamp_col(self.col, row);
         }
     }
 }
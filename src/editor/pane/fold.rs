@@ -0,0 +1,221 @@
+use std::ops::RangeInclusive;
+
+use crate::editor::{
+    buffer::{
+        Buffer,
+        modification::{ActionRange, BufferAction},
+        row::Row,
+    },
+    ui::geometry::point::Point,
+};
+
+/// A folded region of rows. The first row of the region stays visible and stands in for the
+/// hidden rows below it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fold {
+    /// The row shown in place of the region.
+    pub start: usize,
+    /// The last row of the region.
+    pub end: usize,
+}
+
+impl Fold {
+    /// Returns the rows hidden by the fold.
+    pub fn hidden(&self) -> RangeInclusive<usize> {
+        self.start + 1..=self.end
+    }
+
+    /// Returns the number of rows hidden by the fold.
+    pub fn hidden_len(&self) -> usize {
+        self.end - self.start
+    }
+
+    /// Returns the foldable region starting at the given row, or `None` if there is none. A row
+    /// ending with an opening bracket folds up to the row of the matching closing bracket.
+    /// Otherwise the region holds the rows below that are more indented than the row, along with
+    /// a closing bracket following them at the same indentation.
+    pub fn region(buffer: &Buffer, row: usize) -> Option<Self> {
+        let header = buffer.row(row)?;
+        let end = Self::bracket_end(buffer, row, header)
+            .or_else(|| Self::indent_end(buffer, row, header))?;
+        (end > row).then_some(Self { start: row, end })
+    }
+
+    /// Returns the row of the bracket closing the one at the end of the given row.
+    fn bracket_end(buffer: &Buffer, row: usize, header: &Row) -> Option<usize> {
+        let last = header.text().trim_end().chars().last()?;
+        if !matches!(last, '{' | '(' | '[') {
+            return None;
+        }
+
+        let mut depth = 0usize;
+        for (i, r) in (row..buffer.num_lines()).filter_map(|i| Some((i, buffer.row(i)?))) {
+            for c in r.chars() {
+                match c {
+                    '{' | '(' | '[' => depth += 1,
+                    '}' | ')' | ']' => {
+                        depth = depth.saturating_sub(1);
+                        // Brackets closed on the row itself do not end the region.
+                        if depth == 0 && i > row {
+                            return Some(i);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        None
+    }
+
+    /// Returns the last row of the block of rows below the given row that are more indented,
+    /// ignoring trailing blank rows.
+    fn indent_end(buffer: &Buffer, row: usize, header: &Row) -> Option<usize> {
+        let indent = indent_width(header);
+        let mut end = None;
+        for i in row + 1..buffer.num_lines() {
+            let r = buffer.row(i)?;
+            if r.text().trim().is_empty() {
+                continue;
+            }
+            if indent_width(r) <= indent {
+                // Include a closing bracket ending the block.
+                if end.is_some() && r.text().trim().starts_with(['}', ')', ']']) {
+                    end = Some(i);
+                }
+                break;
+            }
+            end = Some(i);
+        }
+        end
+    }
+}
+
+/// Returns the number of whitespace characters at the start of the row.
+fn indent_width(row: &Row) -> usize {
+    row.chars().take_while(|c| c.is_whitespace()).count()
+}
+
+/// The folds of a pane, sorted by their first row. Folds never overlap.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Folds {
+    folds: Vec<Fold>,
+}
+
+impl Folds {
+    /// Adds a fold, replacing the folds it overlaps.
+    pub fn add(&mut self, fold: Fold) {
+        self.folds
+            .retain(|f| f.end < fold.start || f.start > fold.end);
+        let at = self.folds.partition_point(|f| f.start < fold.start);
+        self.folds.insert(at, fold);
+    }
+
+    /// Removes the fold starting at the given row. Returns `true` if there was one.
+    pub fn remove(&mut self, row: usize) -> bool {
+        let len = self.folds.len();
+        self.folds.retain(|f| f.start != row);
+        self.folds.len() != len
+    }
+
+    /// Removes all folds.
+    pub fn clear(&mut self) {
+        self.folds.clear();
+    }
+
+    /// Returns the fold starting at the given row.
+    pub fn get(&self, row: usize) -> Option<Fold> {
+        self.folds.iter().find(|f| f.start == row).copied()
+    }
+
+    /// Returns the fold hiding the given row, or `None` if the row is visible.
+    pub fn hiding(&self, row: usize) -> Option<Fold> {
+        self.folds
+            .iter()
+            .find(|f| f.hidden().contains(&row))
+            .copied()
+    }
+
+    /// Returns up to `count` visible rows starting at `row`, stopping at the end of the buffer of
+    /// `len` rows.
+    pub fn visible_from(&self, row: usize, count: usize, len: usize) -> Vec<usize> {
+        let mut rows = Vec::with_capacity(count);
+        let mut row = self.hiding(row).map_or(row, |f| f.start);
+        while rows.len() < count && row < len {
+            rows.push(row);
+            row = self.get(row).map_or(row, |f| f.end) + 1;
+        }
+        rows
+    }
+
+    /// Returns the visible row before the given row, or `None` if there is none.
+    pub fn prev_visible(&self, row: usize) -> Option<usize> {
+        let prev = row.checked_sub(1)?;
+        Some(self.hiding(prev).map_or(prev, |f| f.start))
+    }
+
+    /// Returns the number of visible rows from `from` up to, but not including, `to`.
+    pub fn visible_between(&self, from: usize, to: usize) -> usize {
+        let hidden: usize = self
+            .folds
+            .iter()
+            .map(|f| {
+                let start = f.start + 1;
+                let end = f.end + 1;
+                end.min(to).saturating_sub(start.max(from))
+            })
+            .sum();
+        to.saturating_sub(from) - hidden
+    }
+
+    /// Handles a buffer action, opening the folds whose hidden rows it touches so that nothing is
+    /// modified unseen, and shifting the other folds along with their rows.
+    pub fn handle_action(&mut self, action: &BufferAction) {
+        if let BufferAction::Composite(actions) = action {
+            for action in actions {
+                self.handle_action(action);
+            }
+            return;
+        }
+        let Some((rows, multiline)) = touched_rows(action) else {
+            return;
+        };
+
+        // Splitting or joining the first row would also change the hidden rows.
+        self.folds.retain(|f| {
+            let touches_hidden = *rows.start() <= f.end && *rows.end() > f.start;
+            !(touches_hidden || (multiline && rows.contains(&f.start)))
+        });
+        self.folds = self
+            .folds
+            .iter()
+            .filter_map(|f| {
+                let start = action.shift_point(Point::new(0, f.start))?;
+                let end = action.shift_point(Point::new(0, f.end))?;
+                Some(Fold {
+                    start: start.row,
+                    end: end.row,
+                })
+            })
+            .collect();
+    }
+}
+
+/// Returns the rows touched by the action, and whether the action splits or joins rows.
+fn touched_rows(action: &BufferAction) -> Option<(RangeInclusive<usize>, bool)> {
+    match action {
+        BufferAction::Insert { start, text } => Some((start.row..=start.row, text.contains('\n'))),
+        // The row is joined onto the previous one.
+        BufferAction::Delete {
+            range: ActionRange::Line(row),
+            ..
+        } => Some((row.saturating_sub(1)..=*row, true)),
+        BufferAction::Delete {
+            range: ActionRange::PointToPoint { from, .. },
+            text,
+        } => {
+            let lines = text.matches('\n').count();
+            Some((from.row..=from.row + lines, lines > 0))
+        }
+        BufferAction::Composite(_) | BufferAction::None => None,
+    }
+}
@@ -0,0 +1,75 @@
+use crate::editor::ui::geometry::point::Point;
+
+/// A cursor position that can be jumped back to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Jump {
+    /// The id of the buffer the position refers to.
+    pub buffer_id: usize,
+    /// The position of the cursor in the buffer.
+    pub position: Point,
+}
+
+impl Jump {
+    pub fn new(buffer_id: usize, position: Point) -> Self {
+        Self {
+            buffer_id,
+            position,
+        }
+    }
+}
+
+/// A history of cursor positions that can be walked backwards and forwards.
+#[derive(Debug, Default, Clone)]
+pub struct JumpList {
+    /// The recorded jumps, from oldest to newest.
+    jumps: Vec<Jump>,
+    /// The index of the current jump. Equal to the number of jumps when not walking the list.
+    index: usize,
+}
+
+impl JumpList {
+    /// The maximum number of jumps kept in the list.
+    const CAPACITY: usize = 100;
+
+    /// Records a jump from the given position. Jumps ahead of the current one are discarded, and
+    /// the jump is not recorded if it is identical to the previous one.
+    pub fn push(&mut self, jump: Jump) {
+        self.jumps.truncate(self.index);
+        if self.jumps.last() != Some(&jump) {
+            self.jumps.push(jump);
+        }
+        if self.jumps.len() > Self::CAPACITY {
+            self.jumps.remove(0);
+        }
+        self.index = self.jumps.len();
+    }
+
+    /// Steps back in the list and returns the previous jump, or `None` if there is none. The
+    /// current position is recorded when starting to walk the list so that it can be returned to
+    /// with [`JumpList::forward`].
+    pub fn back(&mut self, current: Jump) -> Option<Jump> {
+        if self.index == self.jumps.len() {
+            self.push(current);
+            self.index = self.jumps.len() - 1;
+        }
+
+        // Skip over jumps to the position the cursor is already at.
+        while self.index > 0 {
+            self.index -= 1;
+            let jump = self.jumps[self.index];
+            if jump != current {
+                return Some(jump);
+            }
+        }
+        None
+    }
+
+    /// Steps forward in the list and returns the next jump, or `None` if there is none.
+    pub fn forward(&mut self) -> Option<Jump> {
+        if self.index + 1 >= self.jumps.len() {
+            return None;
+        }
+        self.index += 1;
+        self.jumps.get(self.index).copied()
+    }
+}
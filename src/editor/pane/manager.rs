@@ -3,7 +3,8 @@ use thiserror::Error;
 use crate::editor::{
     buffer::{
         BufferEntry,
-        modification::{ActionRange, BufferAction, BufferModification},
+        manager::BufferManager,
+        modification::{BufferAction, BufferModification},
     },
     pane::{Pane, cursor::CursorMovement},
 };
@@ -14,7 +15,8 @@ pub enum Error {
     IndexOutOfRange { index: usize, len: usize },
 }
 
-/// A manager for multiple panes.
+/// A manager for multiple panes. The manager always has at least one pane, so there is always an
+/// active pane.
 #[derive(Debug, Clone)]
 pub struct PaneManager {
     /// The next id to assign to a new pane.
@@ -23,21 +25,35 @@ pub struct PaneManager {
     active_pane: usize,
     /// All panes in the manager.
     panes: Vec<Pane>,
+    /// Whether the cursors of the panes may move past the end of the row.
+    virtual_edit: bool,
 }
 
 impl PaneManager {
-    pub fn new() -> Self {
-        let panes = Vec::new();
+    /// Returns a new pane manager with a single pane showing the given buffer.
+    pub fn new(buffer: BufferEntry) -> Self {
         Self {
-            next_id: 0,
-            panes,
+            next_id: 1,
+            panes: vec![Pane::new(0, buffer)],
             active_pane: 0,
+            virtual_edit: false,
+        }
+    }
+
+    /// Sets whether the cursors of all panes may move past the end of the row, moving cursors
+    /// that are past it back onto the row when turned off.
+    pub fn set_virtual_edit(&mut self, enabled: bool) {
+        self.virtual_edit = enabled;
+        for pane in &mut self.panes {
+            pane.cursor.set_virtual_edit(enabled);
+            pane.clamp_cursor();
         }
     }
 
     /// Opens a new pane and updates all viewports.
     pub fn open_pane(&mut self, buffer: BufferEntry) {
-        let pane = Pane::new(self.next_id, buffer);
+        let mut pane = Pane::new(self.next_id, buffer);
+        pane.cursor.set_virtual_edit(self.virtual_edit);
 
         self.next_id += 1;
         self.panes.push(pane);
@@ -47,18 +63,61 @@ impl PaneManager {
             .expect("index is always in range");
     }
 
+    /// Opens a new pane sharing the buffer of the active pane, with the cursor at the same
+    /// position, and makes it the active pane.
+    pub fn duplicate_active(&mut self) {
+        let active = self.active();
+        let buffer = active.buffer.clone();
+        let cursor = active.cursor;
+
+        self.open_pane(buffer);
+        self.active_mut().cursor = cursor;
+    }
+
     /// Handles a buffer modification and scrolls the viewports of all panes to stay anchored
     /// relative to their view before the modification.
     pub fn handle_buffer_modification(&mut self, modification: &BufferModification) {
-        let (scroll_offset, row): (isize, _) = match &modification.action {
-            BufferAction::Insert { start, .. } if modification.action.is_insert_newline() => {
-                (1, start.row)
+        // Open the folds touched by the modification and shift the others along.
+        for pane in self
+            .iter_mut()
+            .filter(|p| p.buffer_id() == modification.buffer_id)
+        {
+            pane.folds.handle_action(&modification.action);
+            if modification.action != BufferAction::None {
+                pane.version += 1;
             }
-            BufferAction::Delete(ActionRange::Line(row)) => (-1, *row),
-            _ => return,
-        };
+        }
+
+        // Keep the snippet placeholders on their text, ending sessions whose placeholders were
+        // removed.
+        for pane in self.iter_mut() {
+            if let Some(session) = &mut pane.snippet
+                && session.buffer_id == modification.buffer_id
+                && !session.handle_action(&modification.action)
+            {
+                pane.snippet = None;
+            }
+        }
 
         let active_pane = self.active_pane;
+        // Rows may have been removed by a composite action, so keep the cursors in bounds.
+        if let BufferAction::Composite(_) = modification.action {
+            for pane in self
+                .iter_mut()
+                .filter(|p| p.id != active_pane && p.buffer_id() == modification.buffer_id)
+            {
+                pane.clamp_cursor();
+            }
+            return;
+        }
+
+        let Some((scroll_offset, row)) = modification.action.row_shift() else {
+            return;
+        };
+
+        // Panes following the active pane take its viewport instead.
+        let active = self.active();
+        let leads = active.scroll_bound && active.buffer_id() == modification.buffer_id;
         for pane in self
             .iter_mut()
             .filter(|p| p.id != active_pane && p.buffer_id() == modification.buffer_id)
@@ -70,11 +129,12 @@ impl PaneManager {
                 pane.move_cursor(CursorMovement::Up)
             }
 
-            // TODO: Handle in UI layer.
-            // Anchor the viewport if the viewport is farther down than the affected row.
-            // if pane.viewport.row_offset > row + pane.viewport.height() {
-            //     pane.scroll_vertically(scroll_offset);
-            // }
+            // Anchor the viewport if the affected row is above it.
+            let mut view = pane.view_state();
+            if view.row_offset > row && !(leads && pane.scroll_bound) {
+                view.scroll_vertically(scroll_offset);
+                pane.set_view_state(view);
+            }
         }
     }
 
@@ -94,7 +154,7 @@ impl PaneManager {
     /// Sets the active pane to the next pane in the list. Looping around to the first
     /// entry if active pane is the last.
     pub fn next_pane(&mut self) {
-        let next_index = self.active_pane.saturating_add(1) % self.panes.len();
+        let next_index = self.active_pane.saturating_add(1) % self.panes.len().max(1);
         self.active_pane = next_index;
     }
 
@@ -109,7 +169,6 @@ impl PaneManager {
         self.active_pane = prev_index;
     }
 
-    // TODO: Rethink how to make this never panic.
     /// Returns the active pane as an immutable reference.
     pub fn active(&self) -> &Pane {
         &self.panes[self.active_pane]
@@ -120,23 +179,24 @@ impl PaneManager {
         &mut self.panes[self.active_pane]
     }
 
-    /// Removes a pane from the list.
-    pub fn remove(&mut self, index: usize) -> Pane {
+    /// Removes a pane from the list. If the last pane is removed, a pane with a new empty buffer
+    /// is opened in its place.
+    pub fn remove(&mut self, index: usize, buffer_manager: &mut BufferManager) -> Pane {
         let removed = self.panes.remove(index);
 
-        // TODO: Figure out a better way to handle non existing panes.
         // Make sure that we still have an active pane.
-        // if self.is_empty() {
-        //     self.add(Pane::default());
-        // }
+        if self.panes.is_empty() {
+            self.open_pane(buffer_manager.open_empty_file());
+        }
 
-        self.active_pane = self.active_pane.min(self.panes.len().saturating_sub(1));
+        self.active_pane = self.active_pane.min(self.panes.len() - 1);
         removed
     }
 
-    /// Closes the active pane.
-    pub fn close_active(&mut self) -> Pane {
-        self.remove(self.active_pane)
+    /// Closes the active pane. If it is the last pane, a pane with a new empty buffer is opened in
+    /// its place.
+    pub fn close_active(&mut self, buffer_manager: &mut BufferManager) -> Pane {
+        self.remove(self.active_pane, buffer_manager)
     }
 
     /// Iterate through all panes.
@@ -149,6 +209,27 @@ impl PaneManager {
         self.panes.iter_mut()
     }
 
+    /// Returns the index of a pane showing the buffer with the given id, preferring the active
+    /// pane, or `None` if no pane shows it.
+    pub fn find_buffer(&self, buffer_id: usize) -> Option<usize> {
+        if self.active().buffer_id() == buffer_id {
+            return Some(self.active_pane);
+        }
+        self.iter().position(|p| p.buffer_id() == buffer_id)
+    }
+
+    /// Returns the active pane if the pane at the given index scrolls along with it, i.e. both
+    /// panes are scroll-bound and show the same buffer.
+    pub fn scroll_leader(&self, index: usize) -> Option<&Pane> {
+        let pane = self.panes.get(index)?;
+        let active = self.active();
+        (index != self.active_pane
+            && pane.scroll_bound
+            && active.scroll_bound
+            && pane.buffer_id() == active.buffer_id())
+        .then_some(active)
+    }
+
     /// Returns `true` if only one pane has the given buffer id.
     pub fn is_unique(&self, buffer_id: usize) -> bool {
         self.iter().filter(|p| p.buffer_id() == buffer_id).count() == 1
@@ -164,3 +245,104 @@ impl PaneManager {
         self.active_pane
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn closing_every_pane_leaves_an_empty_one() {
+        let mut buffers = BufferManager::default();
+        let mut panes = PaneManager::new(buffers.open_empty_file());
+        panes.open_pane(buffers.open_scratch("a", "a"));
+        panes.open_pane(buffers.open_scratch("b", "b"));
+
+        for _ in 0..5 {
+            let closed = panes.close_active(&mut buffers);
+            buffers.close(closed.buffer_id());
+        }
+        assert_eq!(panes.num_panes(), 1);
+        assert_eq!(panes.active_pane(), 0);
+
+        // The replacement pane shows a new empty buffer that can be edited.
+        let buffer_id = panes.active().buffer_id();
+        assert!(buffers.get(buffer_id).is_some());
+        panes.next_pane();
+        panes.prev_pane();
+        let modification = panes.active_mut().insert_char('x');
+        panes.handle_buffer_modification(&modification);
+        assert_eq!(buffers.get(buffer_id).unwrap().read_buf().text(), "x");
+        assert_eq!(panes.active().cursor_position(), (1, 0));
+    }
+
+    #[test]
+    fn duplicated_unsaved_buffer_is_shared() {
+        let mut buffers = BufferManager::default();
+        let mut panes = PaneManager::new(buffers.open_empty_file());
+        for c in "ab".chars() {
+            let modification = panes.active_mut().insert_char(c);
+            panes.handle_buffer_modification(&modification);
+        }
+
+        panes.duplicate_active();
+        assert_eq!(panes.num_panes(), 2);
+        assert_eq!(panes.active_pane(), 1);
+        assert_eq!(panes.active().cursor_position(), (2, 0));
+        let buffer_id = panes.active().buffer_id();
+        assert!(!panes.is_unique(buffer_id));
+
+        let modification = panes.active_mut().insert_char('c');
+        panes.handle_buffer_modification(&modification);
+        panes.close_active(&mut buffers);
+        assert_eq!(panes.active().buffer_id(), buffer_id);
+        assert!(panes.active().is_dirty());
+        assert_eq!(buffers.get(buffer_id).unwrap().read_buf().text(), "abc");
+    }
+
+    #[test]
+    fn other_panes_keep_their_view_when_rows_shift_above_it() {
+        let mut buffers = BufferManager::default();
+        let mut panes = PaneManager::new(buffers.open_empty_file());
+        for _ in 0..30 {
+            let modification = panes.active_mut().insert_newline();
+            panes.handle_buffer_modification(&modification);
+        }
+        panes.duplicate_active();
+
+        panes.set_active(0).unwrap();
+        panes
+            .active_mut()
+            .move_cursor(CursorMovement::Position(0, 15));
+        let mut view = panes.active().view_state();
+        view.row_offset = 10;
+        panes.active().set_view_state(view);
+
+        // A row inserted above the view moves it down along with the cursor.
+        panes.set_active(1).unwrap();
+        panes
+            .active_mut()
+            .move_cursor(CursorMovement::Position(0, 2));
+        let modification = panes.active_mut().insert_newline();
+        panes.handle_buffer_modification(&modification);
+        let other = panes.iter().next().unwrap();
+        assert_eq!(other.view_state().row_offset, 11);
+        assert_eq!(other.cursor_position(), (0, 16));
+
+        // A row joined above the view moves it back up.
+        let modification = panes.active_mut().delete_char_before();
+        panes.handle_buffer_modification(&modification);
+        let other = panes.iter().next().unwrap();
+        assert_eq!(other.view_state().row_offset, 10);
+        assert_eq!(other.cursor_position(), (0, 15));
+
+        // Rows below the view leave it in place.
+        panes
+            .active_mut()
+            .move_cursor(CursorMovement::Position(0, 25));
+        let modification = panes.active_mut().insert_newline();
+        panes.handle_buffer_modification(&modification);
+        let other = panes.iter().next().unwrap();
+        assert_eq!(other.view_state().row_offset, 10);
+        assert_eq!(other.cursor_position(), (0, 15));
+    }
+}
@@ -0,0 +1,145 @@
+use crate::editor::pane::{cursor::Cursor, fold::Folds};
+
+/// The part of the buffer shown by a pane. The offsets are kept visible to the cursor when the
+/// pane is rendered, and the size is the one of the last rendered frame.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ViewState {
+    /// The first visible column of the buffer.
+    pub col_offset: usize,
+    /// The first visible row of the buffer.
+    pub row_offset: usize,
+    /// The number of visible columns, excluding the gutter.
+    pub width: usize,
+    /// The number of visible rows, excluding the title bar.
+    pub height: usize,
+}
+
+impl ViewState {
+    /// Scroll the viewport to the given cursor such that the cursor is visible, counting only
+    /// the rows that are not hidden by folds. Returns `true` if the viewport was scrolled.
+    pub fn scroll_to_cursor(&mut self, cursor: &Cursor, folds: &Folds) -> bool {
+        let mut scrolled = false;
+
+        // Keep the first row visible if it was folded away.
+        if let Some(fold) = folds.hiding(self.row_offset) {
+            self.row_offset = fold.start;
+            scrolled = true;
+        }
+
+        // Vertical scrolling.
+        if cursor.row() < self.row_offset {
+            self.row_offset = cursor.row();
+            scrolled = true;
+        } else if folds.visible_between(self.row_offset, cursor.row()) >= self.height {
+            // Scroll such that the cursor is on the last line.
+            let mut row = cursor.row();
+            for _ in 1..self.height {
+                match folds.prev_visible(row) {
+                    Some(prev) => row = prev,
+                    None => break,
+                }
+            }
+            self.row_offset = row;
+            scrolled = true;
+        }
+
+        // Horizontal scrolling.
+        if cursor.col() < self.col_offset {
+            self.col_offset = cursor.col();
+            scrolled = true;
+        } else if cursor.col() >= self.col_offset.saturating_add(self.width) {
+            self.col_offset = cursor.col() - self.width + 1;
+            scrolled = true;
+        }
+
+        scrolled
+    }
+
+    /// Scrolls the viewport vertically by the given offset.
+    pub fn scroll_vertically(&mut self, offset: isize) {
+        if offset.is_positive() {
+            self.row_offset = self.row_offset.saturating_add(offset as usize);
+        } else if offset.is_negative() {
+            self.row_offset = self.row_offset.saturating_sub(offset.unsigned_abs());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::editor::{
+        buffer::{Buffer, row::Row},
+        pane::{cursor::CursorMovement, fold::Fold},
+    };
+
+    /// Returns a buffer of 100 rows of 30 columns each.
+    fn buffer() -> Buffer {
+        let mut buffer = Buffer::default();
+        let rows = (0..100).map(|_| Row::new("x".repeat(30))).collect();
+        buffer.replace_rows(0..1, rows);
+        buffer
+    }
+
+    /// Returns a cursor at the given position in the buffer.
+    fn cursor_at(col: usize, row: usize, buffer: &Buffer) -> Cursor {
+        let mut cursor = Cursor::default();
+        cursor.handle_movement(CursorMovement::Position(col, row), buffer);
+        cursor
+    }
+
+    /// Returns a view of the given size at the top of the buffer.
+    fn view(width: usize, height: usize) -> ViewState {
+        ViewState {
+            width,
+            height,
+            ..ViewState::default()
+        }
+    }
+
+    #[test]
+    fn cursor_past_an_edge_is_scrolled_to_that_edge() {
+        let buffer = buffer();
+        let folds = Folds::default();
+        let mut view = view(10, 10);
+
+        assert!(!view.scroll_to_cursor(&cursor_at(9, 9, &buffer), &folds));
+        assert_eq!((view.col_offset, view.row_offset), (0, 0));
+
+        // Rows below the view put the cursor on the last row, columns past it on the last column.
+        assert!(view.scroll_to_cursor(&cursor_at(25, 15, &buffer), &folds));
+        assert_eq!((view.col_offset, view.row_offset), (16, 6));
+
+        // Rows and columns before the view put the cursor on the first row and column.
+        assert!(view.scroll_to_cursor(&cursor_at(3, 2, &buffer), &folds));
+        assert_eq!((view.col_offset, view.row_offset), (3, 2));
+    }
+
+    #[test]
+    fn folded_rows_are_not_counted_when_scrolling() {
+        let buffer = buffer();
+        let mut folds = Folds::default();
+        folds.add(Fold { start: 2, end: 20 });
+        let mut view = view(10, 5);
+
+        // Rows 0, 1, 2, 21 and 22 fit in the view.
+        assert!(!view.scroll_to_cursor(&cursor_at(0, 22, &buffer), &folds));
+        assert_eq!(view.row_offset, 0);
+        assert!(view.scroll_to_cursor(&cursor_at(0, 23, &buffer), &folds));
+        assert_eq!(view.row_offset, 1);
+
+        // A first row hidden by the fold is moved up to the fold.
+        view.row_offset = 10;
+        assert!(view.scroll_to_cursor(&cursor_at(0, 23, &buffer), &folds));
+        assert_eq!(view.row_offset, 2);
+    }
+
+    #[test]
+    fn vertical_scrolling_stops_at_the_top() {
+        let mut view = view(10, 10);
+        view.scroll_vertically(3);
+        assert_eq!(view.row_offset, 3);
+        view.scroll_vertically(-5);
+        assert_eq!(view.row_offset, 0);
+    }
+}
@@ -1,10 +1,15 @@
 use std::path::PathBuf;
 
-use crossterm::event::KeyEvent;
+use crossterm::event::{KeyCode, KeyEvent};
 
 use crate::editor::{
     self, Editor,
-    prompt::{confirm::ConfirmPrompt, files::FilesPrompt, search::SearchPrompt},
+    pane::jump_list::Jump,
+    prompt::{
+        choice::ChoicePrompt, confirm::ConfirmPrompt, describe_key::DescribeKeyPrompt,
+        files::FilesPrompt, input::InputPrompt, matches::MatchesPrompt, number::NumberPrompt,
+        search::SearchPrompt,
+    },
     ui::{
         component::{Component, RenderingContext},
         geometry::{point::Point, rect::Rect},
@@ -12,8 +17,13 @@ use crate::editor::{
     },
 };
 
+pub mod choice;
 pub mod confirm;
+pub mod describe_key;
 pub mod files;
+pub mod input;
+pub mod matches;
+pub mod number;
 pub mod search;
 
 /// A trait for defining prompts.
@@ -22,6 +32,20 @@ pub trait Prompt: Clone + Component {
     /// return or continue.
     fn process_key(&mut self, event: &KeyEvent) -> PromptStatus;
 
+    /// Handles pasted text. By default, each character is handled as if it was typed, skipping
+    /// line breaks and other control characters.
+    fn process_paste(&mut self, text: &str) -> PromptStatus {
+        let mut status = PromptStatus::Pending;
+        for c in text.chars().filter(|c| !c.is_control()) {
+            match self.process_key(&KeyEvent::from(KeyCode::Char(c))) {
+                PromptStatus::Done(response) => return PromptStatus::Done(response),
+                PromptStatus::Changed => status = PromptStatus::Changed,
+                PromptStatus::Pending => {}
+            }
+        }
+        status
+    }
+
     /// Returns an action to be executed when the prompts state has changed.
     fn on_changed(&mut self) -> PromptAction {
         PromptAction::None
@@ -32,11 +56,30 @@ pub trait Prompt: Clone + Component {
 #[derive(Debug, Clone)]
 pub enum PromptType {
     Confirm(ConfirmPrompt),
-    Search(SearchPrompt),
+    Search(Box<SearchPrompt>),
     Files(FilesPrompt),
+    DescribeKey(DescribeKeyPrompt),
+    Input(InputPrompt),
+    Matches(MatchesPrompt),
+    Number(NumberPrompt),
+    Choice(ChoicePrompt),
 }
 
 impl PromptType {
+    /// Returns the name of the type of prompt.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Confirm(_) => "confirm",
+            Self::Search(_) => "search",
+            Self::Files(_) => "files",
+            Self::DescribeKey(_) => "describe_key",
+            Self::Input(_) => "input",
+            Self::Matches(_) => "matches",
+            Self::Number(_) => "number",
+            Self::Choice(_) => "choice",
+        }
+    }
+
     /// Processes an input event and returns a [`PromptStatus`] indicating whether the prompt
     /// should return or continue.
     pub fn process_key(&mut self, event: &KeyEvent) -> PromptStatus {
@@ -44,6 +87,26 @@ impl PromptType {
             Self::Confirm(prompt) => prompt.process_key(event),
             Self::Search(prompt) => prompt.process_key(event),
             Self::Files(prompt) => prompt.process_key(event),
+            Self::DescribeKey(prompt) => prompt.process_key(event),
+            Self::Input(prompt) => prompt.process_key(event),
+            Self::Matches(prompt) => prompt.process_key(event),
+            Self::Number(prompt) => prompt.process_key(event),
+            Self::Choice(prompt) => prompt.process_key(event),
+        }
+    }
+
+    /// Handles pasted text and returns a [`PromptStatus`] indicating whether the prompt should
+    /// return or continue.
+    pub fn process_paste(&mut self, text: &str) -> PromptStatus {
+        match self {
+            Self::Confirm(prompt) => prompt.process_paste(text),
+            Self::Search(prompt) => prompt.process_paste(text),
+            Self::Files(prompt) => prompt.process_paste(text),
+            Self::DescribeKey(prompt) => prompt.process_paste(text),
+            Self::Input(prompt) => prompt.process_paste(text),
+            Self::Matches(prompt) => prompt.process_paste(text),
+            Self::Number(prompt) => prompt.process_paste(text),
+            Self::Choice(prompt) => prompt.process_paste(text),
         }
     }
 
@@ -53,24 +116,39 @@ impl PromptType {
             Self::Confirm(prompt) => prompt.on_changed(),
             Self::Search(prompt) => prompt.on_changed(),
             Self::Files(prompt) => prompt.on_changed(),
+            Self::DescribeKey(prompt) => prompt.on_changed(),
+            Self::Input(prompt) => prompt.on_changed(),
+            Self::Matches(prompt) => prompt.on_changed(),
+            Self::Number(prompt) => prompt.on_changed(),
+            Self::Choice(prompt) => prompt.on_changed(),
         }
     }
+}
 
-    /// Calculates the area of the prompt.
-    pub fn rect(&self, parent: Rect) -> Rect {
+impl Component for PromptType {
+    fn rect(&self, parent: Rect) -> Rect {
         match self {
             Self::Confirm(prompt) => prompt.rect(parent),
             Self::Search(prompt) => prompt.rect(parent),
             Self::Files(prompt) => prompt.rect(parent),
+            Self::DescribeKey(prompt) => prompt.rect(parent),
+            Self::Input(prompt) => prompt.rect(parent),
+            Self::Matches(prompt) => prompt.rect(parent),
+            Self::Number(prompt) => prompt.rect(parent),
+            Self::Choice(prompt) => prompt.rect(parent),
         }
     }
 
-    /// Calls the types render method.
-    pub fn render(&mut self, ctx: &RenderingContext, viewport: Viewport) {
+    fn render(&mut self, ctx: &RenderingContext, viewport: Viewport) {
         match self {
             Self::Confirm(prompt) => prompt.render(ctx, viewport),
             Self::Search(prompt) => prompt.render(ctx, viewport),
             Self::Files(prompt) => prompt.render(ctx, viewport),
+            Self::DescribeKey(prompt) => prompt.render(ctx, viewport),
+            Self::Input(prompt) => prompt.render(ctx, viewport),
+            Self::Matches(prompt) => prompt.render(ctx, viewport),
+            Self::Number(prompt) => prompt.render(ctx, viewport),
+            Self::Choice(prompt) => prompt.render(ctx, viewport),
         }
     }
 }
@@ -86,6 +164,13 @@ pub enum PromptResponse {
     Cancel,
     Text(String),
     File(PathBuf),
+    /// The paths of a pasted list of files, some of which may not exist.
+    Files(Vec<PathBuf>),
+    Key(KeyEvent),
+    Jump(Jump),
+    Number(i64),
+    /// The index of the selected option.
+    Choice(usize),
 }
 
 /// An action that can be returned by the prompt to be handled by the editor.
@@ -111,20 +196,49 @@ pub struct ActivePrompt {
     pub callback: Box<PromptCallback>,
 }
 
+/// A stack of the shown prompts. Only the topmost prompt receives input and is rendered, so a
+/// prompt shown from the callback of another prompt is answered before the ones below it.
 #[derive(Default)]
 pub struct PromptManager {
-    pub active_prompt: Option<ActivePrompt>,
+    prompts: Vec<ActivePrompt>,
 }
 
 impl PromptManager {
-    /// Shows a new prompt.
+    /// Shows a new prompt on top of the shown prompts.
     pub fn show_prompt<F>(&mut self, prompt: PromptType, callback: F)
     where
         F: FnMut(&mut Editor, PromptResponse) -> Result<(), editor::Error> + 'static,
     {
-        self.active_prompt = Some(ActivePrompt {
+        tracing::debug!(
+            prompt = prompt.name(),
+            depth = self.prompts.len(),
+            "prompt opened"
+        );
+        self.prompts.push(ActivePrompt {
             prompt,
             callback: Box::new(callback),
-        })
+        });
+    }
+
+    /// Returns `true` if a prompt is shown.
+    pub fn is_active(&self) -> bool {
+        !self.prompts.is_empty()
+    }
+
+    /// Returns the topmost prompt, which is the one receiving input.
+    pub fn active_mut(&mut self) -> Option<&mut ActivePrompt> {
+        self.prompts.last_mut()
+    }
+
+    /// Removes the topmost prompt and returns it so its callback can be run.
+    pub fn pop(&mut self) -> Option<ActivePrompt> {
+        self.prompts.pop()
+    }
+
+    /// Removes all prompts without running their callbacks.
+    pub fn clear(&mut self) {
+        for active in self.prompts.drain(..).rev() {
+            tracing::debug!(prompt = active.prompt.name(), "prompt dropped");
+        }
     }
 }
@@ -0,0 +1,134 @@
+use crossterm::event::{KeyCode, KeyEvent};
+
+use crate::editor::{
+    prompt::{Prompt, PromptResponse, PromptStatus},
+    ui::{
+        component::{Component, RenderingContext},
+        geometry::{anchor::Anchor, rect::Rect},
+        list_window::ListWindow,
+        theme::highlight_group::{
+            HL_UI_COMMAND_PROMPT, HL_UI_COMMAND_PROMPT_SELECTED, HL_UI_OVERLAY,
+        },
+        viewport::Viewport,
+        widget::{
+            Widget,
+            column::{ColumnBuilder, VerticalAlignment},
+            container::ContainerBuilder,
+            span::Span,
+        },
+    },
+};
+
+/// A prompt listing options, returning the index of the selected one.
+#[derive(Debug, Clone)]
+pub struct ChoicePrompt {
+    /// The title shown below the options.
+    title: String,
+    options: Vec<String>,
+    selected_index: usize,
+    /// The part of the options that is visible.
+    window: ListWindow,
+}
+
+impl ChoicePrompt {
+    const MAX_ENTRIES: usize = 20;
+
+    pub fn new(title: &str, options: Vec<String>) -> Self {
+        Self {
+            title: title.to_string(),
+            options,
+            selected_index: 0,
+            window: ListWindow::default(),
+        }
+    }
+}
+
+impl Prompt for ChoicePrompt {
+    fn process_key(&mut self, event: &KeyEvent) -> PromptStatus {
+        match event.code {
+            KeyCode::Esc => PromptStatus::Done(PromptResponse::Cancel),
+            KeyCode::Enter if self.selected_index < self.options.len() => {
+                PromptStatus::Done(PromptResponse::Choice(self.selected_index))
+            }
+            KeyCode::Enter => PromptStatus::Done(PromptResponse::Cancel),
+            KeyCode::Down => {
+                self.selected_index = self.selected_index.saturating_sub(1);
+                PromptStatus::Changed
+            }
+            KeyCode::Up => {
+                if self.selected_index + 1 < self.options.len() {
+                    self.selected_index += 1;
+                }
+                PromptStatus::Changed
+            }
+            _ => PromptStatus::Pending,
+        }
+    }
+}
+
+impl Component for ChoicePrompt {
+    fn render(&mut self, ctx: &RenderingContext, mut viewport: Viewport) {
+        let style = ctx.state.theme.resolve(&HL_UI_OVERLAY);
+        let text_style = ctx.state.theme.resolve(&HL_UI_COMMAND_PROMPT);
+        let focused_style = ctx.state.theme.resolve(&HL_UI_COMMAND_PROMPT_SELECTED);
+
+        let title_container = ContainerBuilder::default()
+            .with_child(Span::new(&self.title).with_style(style))
+            .with_width(Some(viewport.width()))
+            .with_style(style)
+            .build();
+
+        // List the visible options above the title, with the first option closest to it and the
+        // position indicator above them if not all options fit.
+        let rows = viewport.height().saturating_sub(1);
+        let len = self.options.len();
+        let visible = self.window.scroll(self.selected_index, len, rows);
+        let mut entries: Vec<Box<dyn Widget>> = Vec::new();
+        for (i, option) in self.options[visible.clone()]
+            .iter()
+            .enumerate()
+            .map(|(offset, option)| (visible.start + offset, option))
+        {
+            let entry_style = if i == self.selected_index {
+                focused_style
+            } else {
+                text_style
+            };
+            let container = ContainerBuilder::default()
+                .with_child(Span::new(option).with_style(entry_style))
+                .with_width(Some(viewport.width()))
+                .with_style(if i == self.selected_index {
+                    focused_style
+                } else {
+                    style
+                })
+                .build();
+            entries.push(Box::new(container));
+        }
+        if let Some(indicator) = self.window.indicator(self.selected_index, len, rows) {
+            let container = ContainerBuilder::default()
+                .with_child(Span::new(&indicator).with_style(text_style))
+                .with_width(Some(viewport.width()))
+                .with_style(style)
+                .build();
+            entries.push(Box::new(container));
+        }
+        entries.reverse();
+
+        let column = ColumnBuilder::default()
+            .with_children(entries)
+            .with_child(title_container)
+            .with_alignment(VerticalAlignment::Bottom)
+            .with_style(style)
+            .build();
+        viewport.put_column(Rect::new(0, 0, viewport.width(), viewport.height()), column);
+    }
+
+    fn rect(&self, parent: Rect) -> Rect {
+        let height = (self.options.len() + 1).min(Self::MAX_ENTRIES);
+
+        Rect::new(0, 0, parent.width, height)
+            .anchored_on(parent, Anchor::BottomLeft)
+            .offset(0, -1)
+    }
+}
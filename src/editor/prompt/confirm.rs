@@ -5,32 +5,142 @@ use crate::editor::{
     ui::{
         component::{Component, RenderingContext},
         geometry::{anchor::Anchor, rect::Rect},
-        theme::highlight_group::HL_UI_OVERLAY,
+        style::Style,
+        theme::highlight_group::{
+            HL_UI_COMMAND_PROMPT_SELECTED, HL_UI_OVERLAY, HL_UI_OVERLAY_BORDER,
+        },
+        unicode::str_width,
         viewport::Viewport,
-        widget::{container::ContainerBuilder, span::Span},
+        widget::{
+            Widget,
+            border::Border,
+            container::{Container, ContainerBuilder},
+            paragraph::Paragraph,
+            span::Span,
+        },
     },
 };
 
+/// A choice of a [`ConfirmPrompt`] that is made by pressing its key.
+#[derive(Debug, Clone)]
+pub struct ConfirmChoice {
+    /// The label of the choice, e.g. `save`.
+    pub label: String,
+    /// The key that makes the choice.
+    pub key: KeyCode,
+    /// The response of the prompt when the choice is made.
+    pub response: PromptResponse,
+}
+
+impl ConfirmChoice {
+    pub fn new(label: &str, key: KeyCode, response: PromptResponse) -> Self {
+        Self {
+            label: label.to_string(),
+            key,
+            response,
+        }
+    }
+
+    /// Returns the key and the rest of the label shown in the hint of the choice. A label
+    /// starting with the key is shown as `[s]ave`, and any other label as `[esc] cancel`.
+    fn hint(&self) -> (String, String) {
+        let key = match self.key {
+            KeyCode::Char(c) => c.to_string(),
+            code => code.to_string().to_lowercase(),
+        };
+        let mut chars = self.label.chars();
+        match (self.key, chars.next()) {
+            (KeyCode::Char(c), Some(first)) if first.eq_ignore_ascii_case(&c) => {
+                (format!("[{first}]"), chars.collect())
+            }
+            _ => (format!("[{key}]"), format!(" {}", self.label)),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ConfirmPrompt {
     message: String,
+    /// The choices listed below the message.
+    choices: Vec<ConfirmChoice>,
 }
 
 impl ConfirmPrompt {
+    /// The title shown in the border of the prompt.
+    const TITLE: &str = "Confirm";
+    /// The maximum width of the prompt, including the border.
+    const MAX_WIDTH: usize = 60;
+    /// The number of columns between the choices in the hint.
+    const CHOICE_GAP: usize = 2;
+
+    /// Returns a prompt asking a yes or no question, which can be cancelled with `Esc`.
     pub fn new(message: &str) -> Self {
+        Self::with_choices(
+            message,
+            vec![
+                ConfirmChoice::new("yes", KeyCode::Char('y'), PromptResponse::Yes),
+                ConfirmChoice::new("no", KeyCode::Char('n'), PromptResponse::No),
+                ConfirmChoice::new("cancel", KeyCode::Esc, PromptResponse::Cancel),
+            ],
+        )
+    }
+
+    /// Returns a prompt offering the given choices. `Enter` makes the first choice, and `Esc`
+    /// cancels the prompt unless a choice uses it.
+    pub fn with_choices(message: &str, choices: Vec<ConfirmChoice>) -> Self {
         Self {
             message: message.to_string(),
+            choices,
+        }
+    }
+
+    /// Returns the border drawn around the prompt.
+    fn border() -> Border {
+        Border::default().with_title(Self::TITLE).with_padding(1, 0)
+    }
+
+    /// Returns the width of the hint listing the choices.
+    fn hint_width(&self) -> usize {
+        let gaps = Self::CHOICE_GAP * self.choices.len().saturating_sub(1);
+        self.choices
+            .iter()
+            .map(|choice| {
+                let (key, label) = choice.hint();
+                str_width(&key) + str_width(&label)
+            })
+            .sum::<usize>()
+            + gaps
+    }
+
+    /// Returns the hint listing the choices, e.g. `[y]es  [n]o  [esc] cancel`.
+    fn hint(&self, key_style: Style, style: Style) -> Container {
+        let mut children: Vec<Box<dyn Widget>> = Vec::new();
+        for (i, choice) in self.choices.iter().enumerate() {
+            if i > 0 {
+                children.push(Box::new(
+                    Span::new(&" ".repeat(Self::CHOICE_GAP)).with_style(style),
+                ));
+            }
+            let (key, label) = choice.hint();
+            children.push(Box::new(Span::new(&key).with_style(key_style)));
+            children.push(Box::new(Span::new(&label).with_style(style)));
         }
+        ContainerBuilder::default()
+            .with_children(children)
+            .with_style(style)
+            .build()
     }
 }
 
 impl Prompt for ConfirmPrompt {
     fn process_key(&mut self, event: &KeyEvent) -> PromptStatus {
-        match event.code {
-            KeyCode::Enter => PromptStatus::Done(PromptResponse::Yes),
-            KeyCode::Char('y') => PromptStatus::Done(PromptResponse::Yes),
-            KeyCode::Char('n') => PromptStatus::Done(PromptResponse::No),
-            KeyCode::Esc => PromptStatus::Done(PromptResponse::Cancel),
+        let choice = match event.code {
+            KeyCode::Enter => self.choices.first(),
+            code => self.choices.iter().find(|choice| choice.key == code),
+        };
+        match (choice, event.code) {
+            (Some(choice), _) => PromptStatus::Done(choice.response.clone()),
+            (None, KeyCode::Esc) => PromptStatus::Done(PromptResponse::Cancel),
             _ => PromptStatus::Pending,
         }
     }
@@ -38,21 +148,38 @@ impl Prompt for ConfirmPrompt {
 
 impl Component for ConfirmPrompt {
     fn rect(&self, parent: Rect) -> Rect {
-        Rect::new(0, 0, parent.width, 1)
-            .anchored_on(parent, Anchor::BottomLeft)
-            .offset(0, -1)
+        // Size the prompt to fit the wrapped message and the hint below it.
+        let border = Self::border();
+        let max_width = parent.width.min(Self::MAX_WIDTH);
+        let paragraph = Paragraph::new(
+            &self.message,
+            max_width.saturating_sub(border.horizontal_size()),
+        )
+        .with_max_height(parent.height.saturating_sub(border.vertical_size() + 1));
+
+        let width = paragraph.width().max(self.hint_width()) + border.horizontal_size();
+        let height = paragraph.height() + 1 + border.vertical_size();
+        Rect::new(0, 0, width.min(max_width), height.min(parent.height))
+            .anchored_on(parent, Anchor::Center)
     }
 
     fn render(&mut self, ctx: &RenderingContext, mut viewport: Viewport) {
-        let style = ctx.theme.resolve(&HL_UI_OVERLAY);
-        let message_str = format!("{} [y/n] ", self.message);
+        let style = ctx.state.theme.resolve(&HL_UI_OVERLAY);
+        let border = Self::border()
+            .with_chars(ctx.border_chars())
+            .with_style(ctx.state.theme.resolve(&HL_UI_OVERLAY_BORDER));
+        let mut inner = viewport.put_border(&border);
 
-        let span = Span::new(&message_str);
-        let widget = ContainerBuilder::default()
-            .with_width(Some(viewport.width()))
-            .with_child(span)
-            .with_style(style)
-            .build();
-        viewport.put_widget(0, widget);
+        let message_height = inner.height().saturating_sub(1);
+        let paragraph = Paragraph::new(&self.message, inner.width())
+            .with_max_height(message_height)
+            .with_style(style);
+        inner.put_column(
+            Rect::new(0, 0, inner.width(), message_height),
+            paragraph.into_column(),
+        );
+
+        let key_style = ctx.state.theme.resolve(&HL_UI_COMMAND_PROMPT_SELECTED);
+        inner.put_widget(message_height, self.hint(key_style, style));
     }
 }
@@ -0,0 +1,58 @@
+use crossterm::event::KeyEvent;
+
+use crate::editor::{
+    prompt::{Prompt, PromptResponse, PromptStatus},
+    ui::{
+        component::{Component, RenderingContext},
+        geometry::{anchor::Anchor, rect::Rect},
+        theme::highlight_group::HL_UI_OVERLAY,
+        viewport::Viewport,
+        widget::{container::ContainerBuilder, span::Span},
+    },
+};
+
+/// A prompt that waits for a single key press and returns it.
+#[derive(Debug, Clone)]
+pub struct DescribeKeyPrompt {
+    /// The message shown while waiting for a key.
+    message: &'static str,
+}
+
+impl DescribeKeyPrompt {
+    /// Returns a prompt showing the given message while waiting for a key.
+    pub fn new(message: &'static str) -> Self {
+        Self { message }
+    }
+}
+
+impl Default for DescribeKeyPrompt {
+    fn default() -> Self {
+        Self::new("Press a key to describe...")
+    }
+}
+
+impl Prompt for DescribeKeyPrompt {
+    fn process_key(&mut self, event: &KeyEvent) -> PromptStatus {
+        PromptStatus::Done(PromptResponse::Key(*event))
+    }
+}
+
+impl Component for DescribeKeyPrompt {
+    fn rect(&self, parent: Rect) -> Rect {
+        Rect::new(0, 0, parent.width, 1)
+            .anchored_on(parent, Anchor::BottomLeft)
+            .offset(0, -1)
+    }
+
+    fn render(&mut self, ctx: &RenderingContext, mut viewport: Viewport) {
+        let style = ctx.state.theme.resolve(&HL_UI_OVERLAY);
+
+        let span = Span::new(self.message);
+        let widget = ContainerBuilder::default()
+            .with_width(Some(viewport.width()))
+            .with_child(span)
+            .with_style(style)
+            .build();
+        viewport.put_widget(0, widget);
+    }
+}
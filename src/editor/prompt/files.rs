@@ -9,11 +9,17 @@ use crate::editor::{
     ui::{
         component::{Component, RenderingContext},
         geometry::rect::Rect,
+        list_window::ListWindow,
         theme::highlight_group::{
             HL_UI_COMMAND_PROMPT, HL_UI_COMMAND_PROMPT_SELECTED, HL_UI_OVERLAY,
         },
         viewport::Viewport,
-        widget::{container::ContainerBuilder, span::Span},
+        widget::{
+            Widget,
+            column::{ColumnBuilder, VerticalAlignment},
+            container::ContainerBuilder,
+            span::Span,
+        },
     },
 };
 
@@ -38,10 +44,16 @@ pub struct FilesPrompt {
     files: Vec<PathBuf>,
     filtered_files: Vec<PathBuf>,
     selected_index: usize,
+    /// The part of the filtered files that is visible.
+    window: ListWindow,
+    /// The paths of a pasted list of files offered to be opened, or `None` if nothing was pasted.
+    pasted: Option<Vec<PathBuf>>,
 }
 
 impl FilesPrompt {
     const QUERY_PROMPT: &str = "Find file: ";
+    /// The marker shown after pasted paths that are not files.
+    const NOT_FOUND: &str = " (not found)";
     const MAX_ENTRIES: usize = 20;
 
     pub fn new(dir: Option<&str>) -> Result<Self, ignore::Error> {
@@ -57,6 +69,8 @@ impl FilesPrompt {
             files,
             filtered_files,
             selected_index: 0,
+            window: ListWindow::default(),
+            pasted: None,
         })
     }
 
@@ -78,11 +92,27 @@ impl FilesPrompt {
                 .collect();
         }
         self.selected_index = 0;
+        self.window.reset();
     }
 }
 
 impl Prompt for FilesPrompt {
     fn process_key(&mut self, event: &KeyEvent) -> PromptStatus {
+        // A pasted list of files is either opened or searched for as typed.
+        if let Some(paths) = &self.pasted {
+            return match event.code {
+                KeyCode::Enter => PromptStatus::Done(PromptResponse::Files(paths.clone())),
+                KeyCode::Esc => {
+                    let paths = self.pasted.take().unwrap_or_default();
+                    let text: Vec<String> = paths.iter().map(|p| p.display().to_string()).collect();
+                    self.query.push_str(&text.join(" "));
+                    self.filter_files();
+                    PromptStatus::Changed
+                }
+                _ => PromptStatus::Pending,
+            };
+        }
+
         match event.code {
             KeyCode::Esc => PromptStatus::Done(PromptResponse::Cancel),
             KeyCode::Enter => {
@@ -120,13 +150,69 @@ impl Prompt for FilesPrompt {
             _ => PromptStatus::Pending,
         }
     }
+
+    /// Offers to open a pasted list of several paths if any of them is a file. Other pastes are
+    /// added to the query.
+    fn process_paste(&mut self, text: &str) -> PromptStatus {
+        let paths: Vec<PathBuf> = text.split_whitespace().map(PathBuf::from).collect();
+        if paths.len() > 1 && paths.iter().any(|path| path.is_file()) {
+            self.pasted = Some(paths);
+            return PromptStatus::Changed;
+        }
+
+        self.query.extend(text.chars().filter(|c| !c.is_control()));
+        self.filter_files();
+        PromptStatus::Changed
+    }
+}
+
+impl FilesPrompt {
+    /// Renders the pasted list of files along with the keys to open or search for them.
+    fn render_pasted(&self, ctx: &RenderingContext, mut viewport: Viewport, paths: &[PathBuf]) {
+        let style = ctx.state.theme.resolve(&HL_UI_OVERLAY);
+        let text_style = ctx.state.theme.resolve(&HL_UI_COMMAND_PROMPT);
+
+        let mut entries: Vec<Box<dyn Widget>> = Vec::new();
+        for path in paths.iter().take(viewport.height().saturating_sub(1)) {
+            let marker = if path.is_file() { "" } else { Self::NOT_FOUND };
+            let text = format!("{}{marker}", path.display());
+            let container = ContainerBuilder::default()
+                .with_child(Span::new(&text).with_style(text_style))
+                .with_width(Some(viewport.width()))
+                .with_style(style)
+                .build();
+            entries.push(Box::new(container));
+        }
+
+        let query = format!(
+            "Open {} pasted paths? (enter: open, esc: search)",
+            paths.len()
+        );
+        let query_container = ContainerBuilder::default()
+            .with_child(Span::new(&query).with_style(style))
+            .with_width(Some(viewport.width()))
+            .with_style(style)
+            .build();
+        let column = ColumnBuilder::default()
+            .with_children(entries)
+            .with_child(query_container)
+            .with_alignment(VerticalAlignment::Bottom)
+            .with_style(style)
+            .build();
+        viewport.put_column(Rect::new(0, 0, viewport.width(), viewport.height()), column);
+    }
 }
 
 impl Component for FilesPrompt {
     fn render(&mut self, ctx: &RenderingContext, mut viewport: Viewport) {
-        let style = ctx.theme.resolve(&HL_UI_OVERLAY);
-        let text_style = ctx.theme.resolve(&HL_UI_COMMAND_PROMPT);
-        let focused_style = ctx.theme.resolve(&HL_UI_COMMAND_PROMPT_SELECTED);
+        if let Some(paths) = &self.pasted {
+            self.render_pasted(ctx, viewport, paths);
+            return;
+        }
+
+        let style = ctx.state.theme.resolve(&HL_UI_OVERLAY);
+        let text_style = ctx.state.theme.resolve(&HL_UI_COMMAND_PROMPT);
+        let focused_style = ctx.state.theme.resolve(&HL_UI_COMMAND_PROMPT_SELECTED);
 
         let query_str = format!("{}{}", Self::QUERY_PROMPT, self.query);
         let query_span = Span::new(&query_str).with_style(style);
@@ -136,16 +222,18 @@ impl Component for FilesPrompt {
             .with_width(Some(viewport.width()))
             .with_style(style)
             .build();
-        viewport.put_widget(viewport.height().saturating_sub(1), query_container);
 
-        for (i, file) in self
-            .filtered_files
+        // List the visible files above the query, with the first match closest to it and the
+        // position indicator above them if not all files fit.
+        let rows = viewport.height().saturating_sub(1);
+        let len = self.filtered_files.len();
+        let visible = self.window.scroll(self.selected_index, len, rows);
+        let mut entries: Vec<Box<dyn Widget>> = Vec::new();
+        for (i, file) in self.filtered_files[visible.clone()]
             .iter()
             .enumerate()
-            .take(viewport.height() - 1)
+            .map(|(offset, file)| (visible.start + offset, file))
         {
-            let row = viewport.height().saturating_sub(i + 2);
-
             let file_name = file
                 .to_str()
                 .unwrap_or("[invalid file name]")
@@ -165,14 +253,33 @@ impl Component for FilesPrompt {
                     style
                 })
                 .build();
-            viewport.put_widget(row, container);
+            entries.push(Box::new(container));
         }
+        if let Some(indicator) = self.window.indicator(self.selected_index, len, rows) {
+            let container = ContainerBuilder::default()
+                .with_child(Span::new(&indicator).with_style(text_style))
+                .with_width(Some(viewport.width()))
+                .with_style(style)
+                .build();
+            entries.push(Box::new(container));
+        }
+        entries.reverse();
+
+        let column = ColumnBuilder::default()
+            .with_children(entries)
+            .with_child(query_container)
+            .with_alignment(VerticalAlignment::Bottom)
+            .with_style(style)
+            .build();
+        viewport.put_column(Rect::new(0, 0, viewport.width(), viewport.height()), column);
     }
 
     fn rect(&self, parent: Rect) -> Rect {
-        let height = (self.filtered_files.len() + 1)
-            .min(Self::MAX_ENTRIES)
-            .max(1);
+        let entries = match &self.pasted {
+            Some(paths) => paths.len(),
+            None => self.filtered_files.len(),
+        };
+        let height = (entries + 1).clamp(1, Self::MAX_ENTRIES);
 
         Rect::new(0, 0, parent.width, height)
             .anchored_on(parent, Anchor::BottomLeft)
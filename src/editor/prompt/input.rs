@@ -0,0 +1,70 @@
+use crossterm::event::{KeyCode, KeyEvent};
+
+use crate::editor::{
+    prompt::{Prompt, PromptResponse, PromptStatus},
+    ui::{
+        component::{Component, RenderingContext},
+        geometry::{anchor::Anchor, rect::Rect},
+        theme::highlight_group::HL_UI_OVERLAY,
+        viewport::Viewport,
+        widget::{container::ContainerBuilder, span::Span},
+    },
+};
+
+/// A prompt that reads a line of text, returning it on enter and cancelling on escape.
+#[derive(Debug, Clone)]
+pub struct InputPrompt {
+    /// The label shown before the input.
+    label: String,
+    /// The text entered so far.
+    input: String,
+}
+
+impl InputPrompt {
+    /// Returns a prompt with the given label and initial input.
+    pub fn new(label: &str, input: &str) -> Self {
+        Self {
+            label: label.to_string(),
+            input: input.to_string(),
+        }
+    }
+}
+
+impl Prompt for InputPrompt {
+    fn process_key(&mut self, event: &KeyEvent) -> PromptStatus {
+        match event.code {
+            KeyCode::Esc => PromptStatus::Done(PromptResponse::Cancel),
+            KeyCode::Enter => PromptStatus::Done(PromptResponse::Text(self.input.clone())),
+            KeyCode::Char(c) => {
+                self.input.push(c);
+                PromptStatus::Changed
+            }
+            KeyCode::Backspace => {
+                self.input.pop();
+                PromptStatus::Changed
+            }
+            _ => PromptStatus::Pending,
+        }
+    }
+}
+
+impl Component for InputPrompt {
+    fn rect(&self, parent: Rect) -> Rect {
+        Rect::new(0, 0, parent.width, 1)
+            .anchored_on(parent, Anchor::BottomLeft)
+            .offset(0, -1)
+    }
+
+    fn render(&mut self, ctx: &RenderingContext, mut viewport: Viewport) {
+        let style = ctx.state.theme.resolve(&HL_UI_OVERLAY);
+        let message_str = format!("{}: {}", self.label, self.input);
+
+        let span = Span::new(&message_str);
+        let widget = ContainerBuilder::default()
+            .with_width(Some(viewport.width()))
+            .with_child(span)
+            .with_style(style)
+            .build();
+        viewport.put_widget(0, widget);
+    }
+}
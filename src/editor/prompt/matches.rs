@@ -0,0 +1,132 @@
+use crossterm::event::{KeyCode, KeyEvent};
+
+use crate::editor::{
+    pane::jump_list::Jump,
+    prompt::{Prompt, PromptResponse, PromptStatus},
+    ui::{
+        component::{Component, RenderingContext},
+        geometry::{anchor::Anchor, rect::Rect},
+        theme::highlight_group::{
+            HL_UI_COMMAND_PROMPT, HL_UI_COMMAND_PROMPT_SELECTED, HL_UI_OVERLAY,
+        },
+        viewport::Viewport,
+        widget::{
+            Widget,
+            column::{ColumnBuilder, VerticalAlignment},
+            container::ContainerBuilder,
+            span::Span,
+        },
+    },
+};
+
+/// A match of a search, along with a description of where it was found.
+#[derive(Debug, Clone)]
+pub struct SearchMatch {
+    /// The buffer and position of the match.
+    pub jump: Jump,
+    /// The name of the buffer, and the line and column of the match, e.g. `src/main.rs:3:14`.
+    pub location: String,
+    /// The line containing the match.
+    pub preview: String,
+}
+
+/// A prompt listing the matches of a search, returning the jump to the selected match.
+#[derive(Debug, Clone)]
+pub struct MatchesPrompt {
+    /// The query the matches were found for.
+    query: String,
+    matches: Vec<SearchMatch>,
+    selected_index: usize,
+}
+
+impl MatchesPrompt {
+    const MAX_ENTRIES: usize = 20;
+
+    pub fn new(query: &str, matches: Vec<SearchMatch>) -> Self {
+        Self {
+            query: query.to_string(),
+            matches,
+            selected_index: 0,
+        }
+    }
+}
+
+impl Prompt for MatchesPrompt {
+    fn process_key(&mut self, event: &KeyEvent) -> PromptStatus {
+        match event.code {
+            KeyCode::Esc => PromptStatus::Done(PromptResponse::Cancel),
+            KeyCode::Enter => match self.matches.get(self.selected_index) {
+                Some(selected) => PromptStatus::Done(PromptResponse::Jump(selected.jump)),
+                None => PromptStatus::Done(PromptResponse::Cancel),
+            },
+            KeyCode::Down => {
+                self.selected_index = self.selected_index.saturating_sub(1);
+                PromptStatus::Changed
+            }
+            KeyCode::Up => {
+                if self.selected_index + 1 < self.matches.len() {
+                    self.selected_index += 1;
+                }
+                PromptStatus::Changed
+            }
+            _ => PromptStatus::Pending,
+        }
+    }
+}
+
+impl Component for MatchesPrompt {
+    fn render(&mut self, ctx: &RenderingContext, mut viewport: Viewport) {
+        let style = ctx.state.theme.resolve(&HL_UI_OVERLAY);
+        let text_style = ctx.state.theme.resolve(&HL_UI_COMMAND_PROMPT);
+        let focused_style = ctx.state.theme.resolve(&HL_UI_COMMAND_PROMPT_SELECTED);
+
+        let title = format!("Matches for {}: {}", self.query, self.matches.len());
+        let title_container = ContainerBuilder::default()
+            .with_child(Span::new(&title).with_style(style))
+            .with_width(Some(viewport.width()))
+            .with_style(style)
+            .build();
+
+        // Keep the selected match visible when there are more matches than lines.
+        let visible = viewport.height().saturating_sub(1);
+        let skip = (self.selected_index + 1).saturating_sub(visible);
+
+        // List the matches above the title, with the first match closest to it.
+        let mut entries: Vec<Box<dyn Widget>> = Vec::new();
+        for (i, entry) in self.matches.iter().enumerate().skip(skip).take(visible) {
+            let entry_style = if i == self.selected_index {
+                focused_style
+            } else {
+                text_style
+            };
+            let text = format!("{}: {}", entry.location, entry.preview.trim());
+            let container = ContainerBuilder::default()
+                .with_child(Span::new(&text).with_style(entry_style))
+                .with_width(Some(viewport.width()))
+                .with_style(if i == self.selected_index {
+                    focused_style
+                } else {
+                    style
+                })
+                .build();
+            entries.push(Box::new(container));
+        }
+        entries.reverse();
+
+        let column = ColumnBuilder::default()
+            .with_children(entries)
+            .with_child(title_container)
+            .with_alignment(VerticalAlignment::Bottom)
+            .with_style(style)
+            .build();
+        viewport.put_column(Rect::new(0, 0, viewport.width(), viewport.height()), column);
+    }
+
+    fn rect(&self, parent: Rect) -> Rect {
+        let height = (self.matches.len() + 1).min(Self::MAX_ENTRIES);
+
+        Rect::new(0, 0, parent.width, height)
+            .anchored_on(parent, Anchor::BottomLeft)
+            .offset(0, -1)
+    }
+}
@@ -0,0 +1,110 @@
+use crossterm::event::{KeyCode, KeyEvent};
+
+use crate::editor::{
+    prompt::{Prompt, PromptResponse, PromptStatus},
+    ui::{
+        component::{Component, RenderingContext},
+        geometry::{anchor::Anchor, rect::Rect},
+        theme::highlight_group::{HL_UI_OVERLAY, HL_UI_OVERLAY_INVALID},
+        viewport::Viewport,
+        widget::{container::ContainerBuilder, span::Span},
+    },
+};
+
+/// A prompt that reads a number, only accepting digits and a leading minus. The input is shown
+/// as invalid while it is not a number within the bounds, and enter is ignored until it is.
+#[derive(Debug, Clone)]
+pub struct NumberPrompt {
+    /// The label shown before the input.
+    label: String,
+    /// The digits entered so far.
+    input: String,
+    /// The smallest accepted number.
+    min: Option<i64>,
+    /// The largest accepted number.
+    max: Option<i64>,
+}
+
+impl NumberPrompt {
+    /// Returns a prompt with the given label and an empty input.
+    pub fn new(label: &str) -> Self {
+        Self {
+            label: label.to_string(),
+            input: String::new(),
+            min: None,
+            max: None,
+        }
+    }
+
+    /// Only accepts numbers between `min` and `max`, inclusive.
+    pub fn with_bounds(mut self, min: Option<i64>, max: Option<i64>) -> Self {
+        self.min = min;
+        self.max = max;
+        self
+    }
+
+    /// Returns the entered number, or `None` if the input is not a number within the bounds.
+    fn value(&self) -> Option<i64> {
+        let value = self.input.parse::<i64>().ok()?;
+        let in_bounds =
+            self.min.is_none_or(|min| value >= min) && self.max.is_none_or(|max| value <= max);
+        in_bounds.then_some(value)
+    }
+
+    /// Returns the label along with the bounds, e.g. `Go to line (1-120)`.
+    fn label(&self) -> String {
+        match (self.min, self.max) {
+            (Some(min), Some(max)) => format!("{} ({min}-{max})", self.label),
+            (Some(min), None) => format!("{} (>= {min})", self.label),
+            (None, Some(max)) => format!("{} (<= {max})", self.label),
+            (None, None) => self.label.clone(),
+        }
+    }
+}
+
+impl Prompt for NumberPrompt {
+    fn process_key(&mut self, event: &KeyEvent) -> PromptStatus {
+        match event.code {
+            KeyCode::Esc => PromptStatus::Done(PromptResponse::Cancel),
+            KeyCode::Enter => match self.value() {
+                Some(value) => PromptStatus::Done(PromptResponse::Number(value)),
+                None => PromptStatus::Pending,
+            },
+            KeyCode::Char(c) if c.is_ascii_digit() || (c == '-' && self.input.is_empty()) => {
+                self.input.push(c);
+                PromptStatus::Changed
+            }
+            KeyCode::Backspace => {
+                self.input.pop();
+                PromptStatus::Changed
+            }
+            _ => PromptStatus::Pending,
+        }
+    }
+}
+
+impl Component for NumberPrompt {
+    fn rect(&self, parent: Rect) -> Rect {
+        Rect::new(0, 0, parent.width, 1)
+            .anchored_on(parent, Anchor::BottomLeft)
+            .offset(0, -1)
+    }
+
+    fn render(&mut self, ctx: &RenderingContext, mut viewport: Viewport) {
+        let style = ctx.state.theme.resolve(&HL_UI_OVERLAY);
+        let input_style = if self.input.is_empty() || self.value().is_some() {
+            style
+        } else {
+            ctx.state.theme.resolve(&HL_UI_OVERLAY_INVALID)
+        };
+
+        let label = format!("{}: ", self.label());
+        let widget = ContainerBuilder::default()
+            .with_width(Some(viewport.width()))
+            .with_child(Span::new(&label).with_style(style))
+            .with_child(Span::new(&self.input).with_style(input_style))
+            .with_style(style)
+            .build();
+        viewport.put_widget(0, widget);
+    }
+}
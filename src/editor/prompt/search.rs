@@ -5,7 +5,7 @@ use crate::editor::{
     prompt::{Prompt, PromptAction, PromptResponse, PromptStatus},
     ui::{
         component::{Component, RenderingContext},
-        geometry::{anchor::Anchor, rect::Rect},
+        geometry::{anchor::Anchor, point::Point, rect::Rect},
         theme::highlight_group::HL_UI_OVERLAY,
         viewport::Viewport,
         widget::{container::ContainerBuilder, span::Span},
@@ -18,15 +18,50 @@ pub struct SearchPrompt {
     // TODO: Should not be copied.
     /// The pane to search within.
     pane: Pane,
+    /// The previous queries from newest to oldest.
+    history: Vec<String>,
+    /// The index of the recalled entry of the history, or `None` while typing a new query.
+    history_index: Option<usize>,
+    /// The query typed before recalling an entry of the history, restored when moving past the
+    /// newest entry.
+    draft: String,
+    /// The start and end of the selection of the pane when the prompt was opened. Only matches
+    /// within it are found.
+    scope: Option<(Point, Point)>,
 }
 
 impl SearchPrompt {
-    pub fn new(pane: Pane) -> Self {
+    pub fn new(pane: Pane, history: Vec<String>) -> Self {
         Self {
             query: String::new(),
+            scope: pane.selection(),
             pane,
+            history,
+            history_index: None,
+            draft: String::new(),
         }
     }
+
+    /// Recalls the next older query of the history, or the next newer one if `older` is
+    /// `false`. Moving past the newest entry restores the typed query.
+    fn recall(&mut self, older: bool) -> PromptStatus {
+        let index = match (self.history_index, older) {
+            (None, true) if !self.history.is_empty() => {
+                self.draft = self.query.clone();
+                Some(0)
+            }
+            (Some(i), true) if i + 1 < self.history.len() => Some(i + 1),
+            (Some(0), false) => None,
+            (Some(i), false) => Some(i - 1),
+            _ => return PromptStatus::Pending,
+        };
+        self.history_index = index;
+        self.query = match index {
+            Some(i) => self.history[i].clone(),
+            None => std::mem::take(&mut self.draft),
+        };
+        PromptStatus::Changed
+    }
 }
 
 impl Prompt for SearchPrompt {
@@ -34,11 +69,16 @@ impl Prompt for SearchPrompt {
         match event.code {
             KeyCode::Esc => PromptStatus::Done(PromptResponse::Text(self.query.to_string())),
             KeyCode::Enter => PromptStatus::Done(PromptResponse::Text(self.query.to_string())),
+            KeyCode::Up => self.recall(true),
+            KeyCode::Down => self.recall(false),
+            // Editing a recalled query makes it a new query.
             KeyCode::Char(c) => {
+                self.history_index = None;
                 self.query.push(c);
                 PromptStatus::Changed
             }
             KeyCode::Backspace => {
+                self.history_index = None;
                 self.query.pop();
                 PromptStatus::Changed
             }
@@ -47,7 +87,11 @@ impl Prompt for SearchPrompt {
     }
 
     fn on_changed(&mut self) -> PromptAction {
-        if let Some(point) = self.pane.find_next(&self.query) {
+        let found = match self.scope {
+            Some((start, end)) => self.pane.find_next_in(&self.query, start, end),
+            None => self.pane.find_next(&self.query),
+        };
+        if let Some(point) = found {
             PromptAction::MoveCursor(point)
         } else {
             PromptAction::None
@@ -63,8 +107,12 @@ impl Component for SearchPrompt {
     }
 
     fn render(&mut self, ctx: &RenderingContext, mut viewport: Viewport) {
-        let style = ctx.theme.resolve(&HL_UI_OVERLAY);
-        let message_str = format!("search: {}", self.query);
+        let style = ctx.state.theme.resolve(&HL_UI_OVERLAY);
+        let prefix = match self.scope {
+            Some(_) => "search (selection)",
+            None => "search",
+        };
+        let message_str = format!("{prefix}: {}", self.query);
 
         let span = Span::new(&message_str);
         let widget = ContainerBuilder::default()
@@ -0,0 +1,126 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    fmt,
+    str::FromStr,
+};
+
+/// How the contents of a register were taken from a buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegisterKind {
+    /// Whole lines, which are pasted as new lines below the cursor.
+    Linewise,
+    /// A range of characters, which are pasted at the cursor.
+    Charwise,
+    /// A rectangle of columns, whose lines are pasted at the cursor column on consecutive rows.
+    Blockwise,
+}
+
+impl fmt::Display for RegisterKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            RegisterKind::Linewise => "line",
+            RegisterKind::Charwise => "char",
+            RegisterKind::Blockwise => "block",
+        };
+        write!(f, "{s}")
+    }
+}
+
+/// The contents of a register.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Register {
+    /// The text of the register. Lines are separated by `\n`.
+    pub text: String,
+    /// How the text was taken from the buffer.
+    pub kind: RegisterKind,
+}
+
+impl Register {
+    pub fn new(text: String, kind: RegisterKind) -> Self {
+        Self { text, kind }
+    }
+}
+
+/// The name of a register.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegisterName {
+    /// The register written by every yank and delete.
+    Unnamed,
+    /// An entry of the history of yanks and deletes, where `1` is the most recent one.
+    Numbered(usize),
+    /// A register named by a letter.
+    Named(char),
+}
+
+impl FromStr for RegisterName {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(index) = s.parse::<usize>() {
+            return match index {
+                1..=Registers::HISTORY_LEN => Ok(Self::Numbered(index)),
+                _ => Err(format!(
+                    "numbered registers range from 1 to {}, got `{s}`",
+                    Registers::HISTORY_LEN
+                )),
+            };
+        }
+
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some('"'), None) => Ok(Self::Unnamed),
+            (Some(c), None) if c.is_alphabetic() => Ok(Self::Named(c)),
+            _ => Err(format!("expected a letter or a number, got `{s}`")),
+        }
+    }
+}
+
+/// The registers holding yanked and deleted text.
+#[derive(Debug, Default, Clone)]
+pub struct Registers {
+    /// The most recently yanked or deleted text.
+    unnamed: Option<Register>,
+    /// The most recently yanked or deleted texts, from newest to oldest.
+    history: VecDeque<Register>,
+    /// The registers named by a letter.
+    named: BTreeMap<char, Register>,
+}
+
+impl Registers {
+    /// The number of yanks and deletes kept in the numbered registers.
+    pub const HISTORY_LEN: usize = 10;
+
+    /// Stores yanked or deleted text in the unnamed register and the history, and in the given
+    /// register if one is named.
+    pub fn store(&mut self, name: Option<RegisterName>, register: Register) {
+        if let Some(RegisterName::Named(c)) = name {
+            self.named.insert(c, register.clone());
+        }
+        self.history.push_front(register.clone());
+        self.history.truncate(Self::HISTORY_LEN);
+        self.unnamed = Some(register);
+    }
+
+    /// Gets the contents of the register with the given name, or of the unnamed register if no
+    /// name is given.
+    pub fn get(&self, name: Option<RegisterName>) -> Option<&Register> {
+        match name.unwrap_or(RegisterName::Unnamed) {
+            RegisterName::Unnamed => self.unnamed.as_ref(),
+            RegisterName::Numbered(index) => self.history.get(index.checked_sub(1)?),
+            RegisterName::Named(c) => self.named.get(&c),
+        }
+    }
+
+    /// Returns an iterator over all non-empty registers and their display names, starting with
+    /// the unnamed register, followed by the numbered and named registers.
+    pub fn iter(&self) -> impl Iterator<Item = (String, &Register)> {
+        let unnamed = self.unnamed.iter().map(|r| ("\"".to_string(), r));
+        let numbered = self
+            .history
+            .iter()
+            .enumerate()
+            .map(|(i, r)| ((i + 1).to_string(), r));
+        let named = self.named.iter().map(|(c, r)| (c.to_string(), r));
+        unnamed.chain(numbered).chain(named)
+    }
+}
@@ -1,51 +1,116 @@
 use crate::editor::{
-    backend::{self, RenderingBackend},
+    backend::{self, RenderBackend},
     ui::{
         frame::{Cell, Frame, FrameDiff},
         geometry::point::Point,
+        style::ResolvedStyle,
     },
 };
 
 pub mod compositor;
+pub mod stats;
 
 // Responsible for rendering frames to the terminal.
 #[derive(Debug)]
 pub struct Renderer {
-    backend: RenderingBackend,
+    backend: Box<dyn RenderBackend>,
     last_frame: Option<Frame>,
+    /// The last style emitted to the terminal in the current render.
+    last_style: Option<ResolvedStyle>,
+    /// The terminal title set by the renderer, if any.
+    title: Option<String>,
+    /// Whether mouse events are captured.
+    mouse_capture: bool,
+    /// The number of cells written in the last render.
+    changed_cells: usize,
 }
 
 impl Renderer {
-    /// Initializes a new compositor.
-    pub fn initialize() -> Result<Self, backend::Error> {
-        let backend = RenderingBackend::initialize()?;
+    /// Initializes a new renderer with the given backend.
+    pub fn initialize(mut backend: Box<dyn RenderBackend>) -> Result<Self, backend::Error> {
+        backend.initialize()?;
         Ok(Self {
             backend,
             last_frame: None,
+            last_style: None,
+            title: None,
+            mouse_capture: false,
+            changed_cells: 0,
         })
     }
 
-    /// deinitialize the compositor.
+    /// Deinitializes the renderer.
     pub fn deinitialize(&mut self) -> Result<(), backend::Error> {
+        // Clear the title if it was set by the editor.
+        if self.title.take().is_some() {
+            self.backend.set_title("")?;
+        }
         self.backend.deinitialize()
     }
 
+    /// Reinitializes the terminal after it was deinitialized, forcing the next frame to be
+    /// rendered in full.
+    pub fn reinitialize(&mut self) -> Result<(), backend::Error> {
+        self.backend.initialize()?;
+        self.backend.set_mouse_capture(self.mouse_capture)?;
+        self.invalidate();
+        Ok(())
+    }
+
+    /// Enables or disables capturing of mouse events.
+    pub fn set_mouse_capture(&mut self, enabled: bool) -> Result<(), backend::Error> {
+        self.backend.set_mouse_capture(enabled)?;
+        self.mouse_capture = enabled;
+        Ok(())
+    }
+
+    /// Returns the number of cells written in the last render.
+    pub fn changed_cells(&self) -> usize {
+        self.changed_cells
+    }
+
+    /// Returns `true` if mouse events are captured.
+    pub fn mouse_capture(&self) -> bool {
+        self.mouse_capture
+    }
+
+    /// Invalidates the last rendered frame, forcing the next frame to be rendered in full.
+    pub fn invalidate(&mut self) {
+        self.last_frame = None;
+    }
+
+    /// Sets the terminal title. The title is only emitted if it differs from the current one.
+    pub fn set_title(&mut self, title: &str) -> Result<(), backend::Error> {
+        if self.title.as_deref() == Some(title) {
+            return Ok(());
+        }
+        self.backend.set_title(title)?;
+        self.title = Some(title.to_string());
+        Ok(())
+    }
+
     /// Renders the editor to the terminal.
     pub fn render(&mut self, frame: Frame) -> Result<(), backend::Error> {
+        // Bracket the whole frame in a synchronized update to avoid tearing. Terminals without
+        // support for synchronized updates ignore the sequences.
+        self.backend.begin_synchronized_update()?;
         self.backend.hide_cursor()?;
         self.backend.move_cursor(0, 0)?;
+        // Always emit the style of the first cell in case the terminal state has changed.
+        self.last_style = None;
 
         // If there is a previous frame, diff the current frame with it and render the differing
         // rows. Otherwise, render the entire frame row by row.
         if let Some(last) = &self.last_frame {
             let diff = FrameDiff::compute(last, &frame);
+            self.changed_cells = diff.cells.len();
             self.render_frame_diff(diff)?;
         } else {
+            let (width, height) = frame.size();
+            self.changed_cells = width * height;
             for (row, cells) in frame.rows().enumerate() {
                 self.backend.move_cursor(0, row)?;
-                for cell in cells {
-                    self.render_cell(cell)?;
-                }
+                self.render_cells(cells)?;
             }
         }
 
@@ -54,7 +119,7 @@ impl Renderer {
             self.backend.show_cursor()?;
         }
 
-        self.backend.show_cursor()?;
+        self.backend.end_synchronized_update()?;
         self.backend.flush()?;
 
         self.last_frame = Some(frame);
@@ -70,9 +135,7 @@ impl Renderer {
             if Some(diff_cell.row) != current_row {
                 // Flush buffer if we moved to a new row.
                 if !buffer.is_empty() {
-                    for cell in &buffer {
-                        self.render_cell(cell)?;
-                    }
+                    self.render_cells(&buffer)?;
                     buffer.clear();
                 }
                 // Move cursor to start of the new row.
@@ -84,34 +147,44 @@ impl Renderer {
             if diff_cell.col > last_col + 1 {
                 // Flush buffer if non-adjacent.
                 if !buffer.is_empty() {
-                    for cell in &buffer {
-                        self.render_cell(cell)?;
-                    }
+                    self.render_cells(&buffer)?;
                     buffer.clear();
                 }
                 self.backend.move_cursor(diff_cell.col, diff_cell.row)?;
             }
 
-            buffer.push(*diff_cell.cell);
-            last_col = diff_cell.col;
+            buffer.push(diff_cell.cell.clone());
+            // Wide cells also cover the following columns.
+            last_col = diff_cell.col + diff_cell.cell.width().max(1) - 1;
         }
 
         // Flush last buffer
         if !buffer.is_empty() {
-            for cell in &buffer {
-                self.render_cell(cell)?;
-            }
+            self.render_cells(&buffer)?;
         }
         Ok(())
     }
 
-    /// Renders a single cell to the terminal.
-    fn render_cell(&mut self, cell: &Cell) -> Result<(), backend::Error> {
-        // TODO: Optimize calls to `set_style` by diffing with previous and only queuing the
-        // changes.
-        let style = cell.style.resolve();
-        self.backend.set_style(style)?;
-        self.backend.write_char(cell.char)?;
+    /// Renders a run of adjacent cells to the terminal. Runs of identically-styled cells are
+    /// written at once, and the style is only emitted when it differs from the last one.
+    fn render_cells(&mut self, cells: &[Cell]) -> Result<(), backend::Error> {
+        let mut text = String::new();
+        for cell in cells {
+            let style = cell.style.resolve();
+            if self.last_style != Some(style) {
+                if !text.is_empty() {
+                    self.backend.write(&text)?;
+                    text.clear();
+                }
+                self.backend.set_style(style)?;
+                self.last_style = Some(style);
+            }
+            text.push_str(&cell.symbol);
+        }
+
+        if !text.is_empty() {
+            self.backend.write(&text)?;
+        }
         Ok(())
     }
 }
@@ -4,26 +4,152 @@ use crate::editor::{
     Mode,
     command_palette::CommandPalette,
     prompt::PromptManager,
+    renderer::compositor::layer::{Layer, LayerArea, LayerId, Z_BARS, Z_OVERLAY, Z_PANES},
     ui::{
         component::{
-            Component, RenderingContext, pane_manager::PaneManagerView, status_bar::StatusBar,
+            ClickAction, Component, RenderingContext, completion_popup::CompletionPopup,
+            explorer::ExplorerView, hex_view::HexView, location_list::LocationListView,
+            pane_manager::PaneManagerView, status_bar::StatusBar, tab_line::TabLine,
+            welcome::WelcomeView,
         },
         frame::{Cell, Frame},
-        geometry::rect::Rect,
+        geometry::{point::Point, rect::Rect},
         theme::highlight_group::HL_UI,
         viewport::Viewport,
     },
 };
 
-// TODO: Store each Renderable here once view model separation is complete.
-/// A compositor that organizes the rendering of multiple objects on the terminal.
-#[derive(Debug, Default, Clone)]
+pub mod layer;
+
+/// A compositor that organizes the rendering of multiple objects on the terminal. Components are
+/// rendered as layers from the lowest to the highest z-index, and layers with the same z-index
+/// are rendered in the order they were pushed.
+#[derive(Debug)]
 pub struct Compositor {
-    pane_manager_view: PaneManagerView,
-    status_bar: StatusBar,
+    /// The layers owned by the compositor, sorted by z-index.
+    layers: Vec<Layer>,
+    /// The identifier of the next pushed layer.
+    next_id: usize,
+    /// The layers rendered in the last frame and their rects, from bottom to top.
+    rendered: Vec<(LayerId, Rect)>,
+}
+
+impl Default for Compositor {
+    fn default() -> Self {
+        let mut compositor = Self {
+            layers: Vec::new(),
+            next_id: LayerId::FIRST_DYNAMIC,
+            rendered: Vec::new(),
+        };
+        compositor.insert_layer(
+            LayerId::PANES,
+            Z_PANES,
+            LayerArea::Content,
+            PaneManagerView::default(),
+        );
+        compositor.push_layer(Z_PANES, LayerArea::Content, WelcomeView);
+        compositor.push_layer(Z_PANES, LayerArea::Content, HexView::default());
+        compositor.insert_layer(
+            LayerId::EXPLORER,
+            Z_BARS,
+            LayerArea::Sidebar,
+            ExplorerView::default(),
+        );
+        compositor.push_layer(Z_BARS, LayerArea::Editor, TabLine::default());
+        compositor.push_layer(Z_BARS, LayerArea::Editor, StatusBar::default());
+        compositor.push_layer(Z_BARS, LayerArea::Editor, LocationListView::default());
+        compositor
+    }
 }
 
 impl Compositor {
+    /// Adds a component as a new layer at the given z-index and returns its identifier. The
+    /// layer is rendered above all existing layers with the same z-index.
+    pub fn push_layer(
+        &mut self,
+        z_index: i32,
+        area: LayerArea,
+        component: impl Component + 'static,
+    ) -> LayerId {
+        let id = LayerId(self.next_id);
+        self.next_id += 1;
+        self.insert_layer(id, z_index, area, component);
+        id
+    }
+
+    /// Inserts a layer with the given identifier above all existing layers with the same z-index.
+    fn insert_layer(
+        &mut self,
+        id: LayerId,
+        z_index: i32,
+        area: LayerArea,
+        component: impl Component + 'static,
+    ) {
+        let index = self
+            .layers
+            .partition_point(|layer| layer.z_index <= z_index);
+        self.layers.insert(
+            index,
+            Layer {
+                id,
+                z_index,
+                area,
+                component: Box::new(component),
+                rect: Rect::default(),
+            },
+        );
+    }
+
+    /// Removes the layer with the given identifier and returns its component, or `None` if there
+    /// is no such layer.
+    pub fn remove_layer(&mut self, id: LayerId) -> Option<Box<dyn Component>> {
+        let index = self.layers.iter().position(|layer| layer.id == id)?;
+        self.rendered.retain(|(rendered_id, _)| *rendered_id != id);
+        Some(self.layers.remove(index).component)
+    }
+
+    /// Invalidates the state the layers keep from the last frame, forcing the next frame to be
+    /// composed in full.
+    pub fn invalidate(&mut self) {
+        for layer in &mut self.layers {
+            layer.component.invalidate();
+        }
+    }
+
+    /// Returns the topmost layer rendered at the given screen position in the last frame, or
+    /// `None` if no layer covers it.
+    pub fn topmost_at(&self, point: Point) -> Option<LayerId> {
+        self.rendered
+            .iter()
+            .rev()
+            .find(|(_, rect)| rect.contains(point))
+            .map(|(id, _)| *id)
+    }
+
+    /// Passes a mouse click to the topmost layer at the given screen position and returns the
+    /// action it results in, if any.
+    pub fn handle_click(&mut self, point: Point) -> Option<ClickAction> {
+        let id = self.topmost_at(point)?;
+        self.layers
+            .iter_mut()
+            .find(|layer| layer.id == id)?
+            .component
+            .handle_click(point)
+    }
+
+    /// Returns the index of the pane at the given screen position, or `None` if the position is
+    /// covered by another layer or not in any pane.
+    pub fn pane_at(&self, point: Point, num_panes: usize) -> Option<usize> {
+        if self.topmost_at(point)? != LayerId::PANES {
+            return None;
+        }
+        let layer = self
+            .layers
+            .iter()
+            .find(|layer| layer.id == LayerId::PANES)?;
+        PaneManagerView::pane_at(layer.rect, num_panes, point)
+    }
+
     /// Composes a frame from the given context.
     pub fn compose_frame(
         &mut self,
@@ -39,35 +165,109 @@ impl Compositor {
             Rect::new(0, 0, editor_view.width, editor_view.height),
             &frame,
         );
-        editor_viewport.fill(Cell::default().with_style(ctx.theme.resolve(&HL_UI)));
+        editor_viewport.fill(Cell::default().with_style(ctx.state.theme.resolve(&HL_UI)));
 
-        // Render the views.
-        self.pane_manager_view.render(
-            ctx,
-            Viewport::new(self.pane_manager_view.rect(editor_view), &frame),
-        );
-        self.status_bar.render(
-            ctx,
-            Viewport::new(self.status_bar.rect(editor_view), &frame),
-        );
+        // The tab line takes the top row if it is enabled, leaving the rest for the content.
+        let content_view = if ctx.state.config.editor.show_tabline {
+            editor_view.split_horizontally_exact(TabLine::HEIGHT).1
+        } else {
+            editor_view
+        };
+        // The location list takes the rows at the bottom of the content, above the status bar.
+        let content_view = if ctx.location_list.is_some() {
+            let height = content_view.height.saturating_sub(LocationListView::HEIGHT);
+            content_view.split_horizontally_exact(height).0
+        } else {
+            content_view
+        };
+        // The file explorer takes the columns on the left of the content.
+        let (sidebar_view, content_view) = if ctx.explorer.is_some() {
+            let width = ExplorerView::WIDTH.min(content_view.width / 2);
+            content_view.split_vertically_exact(width)
+        } else {
+            (Rect::default(), content_view)
+        };
 
-        if let Some(active) = prompt_manager.active_prompt.as_mut() {
-            active
-                .prompt
-                .render(ctx, Viewport::new(active.prompt.rect(editor_view), &frame));
-        } else if ctx.mode == Mode::Command {
-            command_palette.render(
-                ctx,
-                Viewport::new(command_palette.rect(editor_view), &frame),
-            );
+        // Collect the owned layers and the overlay of the editor, if any, in rendering order.
+        let mut stack: Vec<(LayerId, i32, LayerArea, &mut dyn Component)> = self
+            .layers
+            .iter_mut()
+            .map(|layer| {
+                (
+                    layer.id,
+                    layer.z_index,
+                    layer.area,
+                    layer.component.as_mut(),
+                )
+            })
+            .collect();
+        let mut completion_popup = ctx.completion.cloned().map(CompletionPopup::new);
+        if let Some(popup) = completion_popup.as_mut() {
+            stack.push((
+                LayerId::COMPLETION,
+                Z_OVERLAY,
+                LayerArea::BelowCursor,
+                popup,
+            ));
         }
+        if let Some(active) = prompt_manager.active_mut() {
+            stack.push((
+                LayerId::PROMPT,
+                Z_OVERLAY,
+                LayerArea::Editor,
+                &mut active.prompt,
+            ));
+        } else if ctx.state.mode == Mode::Command {
+            stack.push((
+                LayerId::COMMAND_PALETTE,
+                Z_OVERLAY,
+                LayerArea::Editor,
+                command_palette,
+            ));
+        }
+        stack.sort_by_key(|(_, z_index, _, _)| *z_index);
+
+        // All layer rects are clamped to the editor view so that oversized components are
+        // clipped instead of writing outside the frame.
+        let mut rendered = Vec::with_capacity(stack.len());
+        let mut cursor_position = None;
+        for (id, _, area, component) in stack {
+            if !component.is_visible(ctx) {
+                continue;
+            }
+
+            let parent = match (area, cursor_position) {
+                (LayerArea::Editor, _) => editor_view,
+                (LayerArea::Content, _) => content_view,
+                (LayerArea::Sidebar, _) => sidebar_view,
+                (LayerArea::BelowCursor, Some(Point { col, row })) => Rect::new(
+                    col,
+                    row + 1,
+                    editor_view.width.saturating_sub(col),
+                    editor_view.height.saturating_sub(row + 1),
+                ),
+                (LayerArea::BelowCursor, None) => continue,
+            };
+            let rect = component.rect(parent).clamp_within(editor_view);
+            component.render(ctx, Viewport::new(rect, &frame));
+            rendered.push((id, rect));
+
+            if let Some(position) = component.cursor_position(ctx) {
+                cursor_position = Some(position);
+            }
+        }
+
+        for layer in &mut self.layers {
+            if let Some((_, rect)) = rendered.iter().find(|(id, _)| *id == layer.id) {
+                layer.rect = *rect;
+            }
+        }
+        self.rendered = rendered;
 
-        // Update the cursor position based on its screen position in the pane manager view.
-        let cursor_position = self
-            .pane_manager_view
-            .get_active_cursor_screen_position(&ctx.pane_manager);
         let mut frame = frame.into_inner();
-        frame.set_cursor_position(cursor_position);
+        if let Some(position) = cursor_position {
+            frame.set_cursor_position(position);
+        }
         frame
     }
 }
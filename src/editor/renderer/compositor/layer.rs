@@ -0,0 +1,71 @@
+use std::fmt;
+
+use crate::editor::ui::{component::Component, geometry::rect::Rect};
+
+/// The z-index of the pane layer.
+pub const Z_PANES: i32 = 0;
+/// The z-index of bars around the panes, such as the status bar and the tab line.
+pub const Z_BARS: i32 = 10;
+/// The z-index of prompts and the command palette.
+pub const Z_OVERLAY: i32 = 100;
+/// The z-index of the debug overlay, which is drawn above everything else.
+pub const Z_DEBUG: i32 = 1000;
+
+/// A unique identifier of a layer in the compositor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LayerId(pub(super) usize);
+
+impl LayerId {
+    /// The layer of the active prompt.
+    pub const PROMPT: LayerId = LayerId(0);
+    /// The layer of the command palette.
+    pub const COMMAND_PALETTE: LayerId = LayerId(1);
+    /// The layer of the panes.
+    pub const PANES: LayerId = LayerId(2);
+    /// The layer of the completion popup.
+    pub const COMPLETION: LayerId = LayerId(3);
+    /// The layer of the file explorer.
+    pub const EXPLORER: LayerId = LayerId(4);
+    /// The first identifier available for layers pushed to the compositor.
+    pub(super) const FIRST_DYNAMIC: usize = 5;
+}
+
+/// The area of the editor that a layer is laid out in.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum LayerArea {
+    /// The whole editor view.
+    #[default]
+    Editor,
+    /// The editor view without the tab line and the file explorer.
+    Content,
+    /// The column on the left of the content taken by the file explorer.
+    Sidebar,
+    /// The part of the editor view below the cursor of the layers beneath, starting at the cursor
+    /// column. Layers in this area are not rendered if no cursor is shown.
+    BelowCursor,
+}
+
+/// A component rendered by the compositor at a given z-index.
+pub struct Layer {
+    /// The identifier of the layer.
+    pub id: LayerId,
+    /// The z-index of the layer. Layers with a higher z-index are rendered on top.
+    pub z_index: i32,
+    /// The area the layer is laid out in.
+    pub area: LayerArea,
+    /// The component of the layer.
+    pub component: Box<dyn Component>,
+    /// The rect the layer was last rendered to.
+    pub rect: Rect,
+}
+
+impl fmt::Debug for Layer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Layer")
+            .field("id", &self.id)
+            .field("z_index", &self.z_index)
+            .field("area", &self.area)
+            .field("rect", &self.rect)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,49 @@
+use std::{collections::VecDeque, time::Duration};
+
+/// The measurements of a single rendered frame.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct FrameStats {
+    /// The time spent composing the frame from the layers.
+    pub compose: Duration,
+    /// The time spent diffing the frame and writing it to the backend, including the flush.
+    pub render: Duration,
+    /// The number of cells written to the backend.
+    pub changed_cells: usize,
+    /// The width and height of the frame.
+    pub size: (usize, usize),
+}
+
+/// Rendering statistics of the most recent frames.
+#[derive(Debug, Default, Clone)]
+pub struct RenderStats {
+    /// The most recent frames, from oldest to newest.
+    frames: VecDeque<FrameStats>,
+    /// The kind of the last event handled by the editor.
+    pub last_event: Option<&'static str>,
+}
+
+impl RenderStats {
+    /// The number of frames that averages are taken over.
+    const WINDOW: usize = 30;
+
+    /// Records the measurements of a rendered frame.
+    pub fn record(&mut self, frame: FrameStats) {
+        if self.frames.len() >= Self::WINDOW {
+            self.frames.pop_front();
+        }
+        self.frames.push_back(frame);
+    }
+
+    /// Returns the measurements of the last rendered frame.
+    pub fn last(&self) -> Option<&FrameStats> {
+        self.frames.back()
+    }
+
+    /// Returns the average compose and render time of the recent frames.
+    pub fn average(&self) -> (Duration, Duration) {
+        let len = self.frames.len().max(1) as u32;
+        let compose: Duration = self.frames.iter().map(|f| f.compose).sum();
+        let render: Duration = self.frames.iter().map(|f| f.render).sum();
+        (compose / len, render / len)
+    }
+}
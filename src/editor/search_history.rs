@@ -0,0 +1,117 @@
+use std::{
+    collections::VecDeque,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// The previous search queries, kept in a file next to the configuration file so that they
+/// survive restarts.
+#[derive(Debug, Default, Clone)]
+pub struct SearchHistory {
+    /// The queries from newest to oldest, without duplicates.
+    entries: VecDeque<String>,
+    /// The file the history is kept in, or `None` if it is only kept in memory.
+    path: Option<PathBuf>,
+}
+
+impl SearchHistory {
+    /// The number of queries kept in the history.
+    pub const MAX_LEN: usize = 100;
+
+    /// Loads the history from its file in the given configuration directory, or returns an empty
+    /// history if it cannot be read.
+    pub fn load(config_dir: Option<&Path>) -> Self {
+        let path = config_dir.map(|dir| dir.join("search_history"));
+        let entries = path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .map(|contents| {
+                contents
+                    .lines()
+                    .filter(|line| !line.is_empty())
+                    .take(Self::MAX_LEN)
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self { entries, path }
+    }
+
+    /// Adds a query as the most recent entry, moving it to the front if it is already in the
+    /// history, and writes the history to its file. Empty and multiline queries are ignored.
+    pub fn push(&mut self, query: &str) {
+        if query.is_empty() || query.contains('\n') {
+            return;
+        }
+        self.entries.retain(|entry| entry != query);
+        self.entries.push_front(query.to_string());
+        self.entries.truncate(Self::MAX_LEN);
+        self.save();
+    }
+
+    /// Returns the most recent query, if any.
+    pub fn last(&self) -> Option<&str> {
+        self.entries.front().map(String::as_str)
+    }
+
+    /// Returns the queries from newest to oldest.
+    pub fn entries(&self) -> Vec<String> {
+        self.entries.iter().cloned().collect()
+    }
+
+    /// Writes the history to its file, one query per line.
+    fn save(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let contents: String = self
+            .entries
+            .iter()
+            .map(|entry| format!("{entry}\n"))
+            .collect();
+        if let Err(err) = fs::write(path, contents) {
+            tracing::warn!(path = %path.display(), %err, "failed to write search history");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn queries_are_kept_newest_first_without_duplicates() {
+        let mut history = SearchHistory::default();
+        for query in ["a", "b", "", "two\nlines", "a"] {
+            history.push(query);
+        }
+        assert_eq!(history.entries(), ["a", "b"]);
+
+        for i in 0..SearchHistory::MAX_LEN + 5 {
+            history.push(&i.to_string());
+        }
+        assert_eq!(history.entries().len(), SearchHistory::MAX_LEN);
+        assert_eq!(history.last(), Some("104"));
+    }
+
+    #[test]
+    fn history_is_saved_in_the_configuration_directory() {
+        let dir = std::env::temp_dir().join(format!("tedit-search-history-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let mut history = SearchHistory::load(Some(&dir));
+        assert!(history.entries().is_empty());
+        history.push("first");
+        history.push("second");
+        assert_eq!(
+            fs::read_to_string(dir.join("search_history")).unwrap(),
+            "second\nfirst\n"
+        );
+        assert_eq!(
+            SearchHistory::load(Some(&dir)).entries(),
+            ["second", "first"]
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,101 @@
+use std::collections::VecDeque;
+
+use crate::editor::{buffer::modification::BufferAction, ui::geometry::point::Point};
+
+/// The text of an expanded snippet and the positions of its placeholders.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Expansion {
+    /// The text to insert.
+    pub text: String,
+    /// The positions of the placeholders relative to the start of the text, in the order they are
+    /// visited: `$1`, `$2` and so on, followed by `$0`. The row is relative to the first line of
+    /// the text, and the column is relative to the insertion point only on the first line.
+    pub stops: Vec<Point>,
+}
+
+/// Expands a snippet body. `$1`, `$2` and so on mark the placeholders visited in order, `$0` the
+/// final cursor position, and `$$` a literal `$`. Continuation lines are prefixed with the given
+/// indentation. Without `$0`, the final cursor position is after the text.
+pub fn expand(body: &str, indent: &str) -> Expansion {
+    let mut text = String::new();
+    let mut placeholders: Vec<(u32, Point)> = Vec::new();
+    let mut position = Point::new(0, 0);
+
+    let mut chars = body.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '$' if chars.peek() == Some(&'$') => {
+                chars.next();
+                text.push('$');
+                position.col += 1;
+            }
+            '$' if chars.peek().is_some_and(char::is_ascii_digit) => {
+                let mut index = 0;
+                while let Some(digit) = chars.peek().and_then(|c| c.to_digit(10)) {
+                    index = index * 10 + digit;
+                    chars.next();
+                }
+                placeholders.push((index, position));
+            }
+            '\n' => {
+                text.push('\n');
+                text.push_str(indent);
+                position = Point::new(indent.chars().count(), position.row + 1);
+            }
+            c => {
+                text.push(c);
+                position.col += 1;
+            }
+        }
+    }
+
+    // Visit the numbered placeholders in order, ending on `$0`.
+    if !placeholders.iter().any(|(index, _)| *index == 0) {
+        placeholders.push((0, position));
+    }
+    placeholders.sort_by_key(|(index, _)| if *index == 0 { u32::MAX } else { *index });
+    let stops = placeholders.into_iter().map(|(_, p)| p).collect();
+    Expansion { text, stops }
+}
+
+/// The remaining placeholders of an expanded snippet in a pane.
+#[derive(Debug, Clone)]
+pub struct SnippetSession {
+    /// The buffer the snippet was expanded in.
+    pub buffer_id: usize,
+    /// The positions of the placeholders not visited yet, in order.
+    stops: VecDeque<Point>,
+}
+
+impl SnippetSession {
+    pub fn new(buffer_id: usize, stops: impl IntoIterator<Item = Point>) -> Self {
+        Self {
+            buffer_id,
+            stops: stops.into_iter().collect(),
+        }
+    }
+
+    /// Returns the position of the next placeholder and removes it from the session.
+    pub fn next(&mut self) -> Option<Point> {
+        self.stops.pop_front()
+    }
+
+    /// Returns `true` if all placeholders have been visited.
+    pub fn is_done(&self) -> bool {
+        self.stops.is_empty()
+    }
+
+    /// Shifts the placeholders to stay on the same text after the action. Returns `false` if a
+    /// placeholder was inside text that was removed, in which case the session should end.
+    pub fn handle_action(&mut self, action: &BufferAction) -> bool {
+        self.stops
+            .iter_mut()
+            .all(|stop| match action.shift_point(*stop) {
+                Some(shifted) => {
+                    *stop = shifted;
+                    true
+                }
+                None => false,
+            })
+    }
+}
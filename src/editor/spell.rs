@@ -0,0 +1,343 @@
+use std::{
+    collections::HashSet,
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+/// The bundled list of English words, one per line.
+const BUNDLED_WORDS: &str =
+    include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/dictionaries/en.txt"));
+
+/// The filetypes whose whole text is checked. Other filetypes are only checked in comments.
+const TEXT_FILETYPES: &[&str] = &["text", "markdown"];
+
+/// Suffixes that may be removed from a word, along with the text replacing them, to find the
+/// word in the dictionary, e.g. `tries` is accepted because `try` is known.
+const SUFFIXES: &[(&str, &str)] = &[
+    ("s", ""),
+    ("es", ""),
+    ("ies", "y"),
+    ("d", ""),
+    ("ed", ""),
+    ("ied", "y"),
+    ("ing", ""),
+    ("ing", "e"),
+    ("er", ""),
+    ("er", "e"),
+    ("est", ""),
+    ("ly", ""),
+    ("ness", ""),
+    ("ment", ""),
+];
+
+/// The largest edit distance between a misspelled word and its suggestions, counting two edits
+/// for each inserted, removed or substituted character. See [`edit_distance`].
+const MAX_DISTANCE: usize = 4;
+/// The largest number of suggestions for a word.
+const MAX_SUGGESTIONS: usize = 10;
+
+/// Checks the spelling of words against the bundled word list and the user dictionary.
+#[derive(Debug, Clone)]
+pub struct SpellChecker {
+    /// The known words in lower case.
+    words: HashSet<String>,
+    /// The file words are added to, or `None` if it could not be determined.
+    user_dictionary: Option<PathBuf>,
+}
+
+impl SpellChecker {
+    /// Loads the bundled word list along with the words of the user dictionary at the given
+    /// path, which defaults to a file in the given configuration directory. A missing user
+    /// dictionary is created when the first word is added to it.
+    pub fn load(user_dictionary: Option<&Path>, config_dir: Option<&Path>) -> Self {
+        let user_dictionary = user_dictionary
+            .map(Path::to_path_buf)
+            .or_else(|| config_dir.map(|dir| dir.join("dictionary")));
+        let user_words = user_dictionary
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .unwrap_or_default();
+        let words = BUNDLED_WORDS
+            .lines()
+            .chain(user_words.lines())
+            .map(|word| word.trim().to_lowercase())
+            .filter(|word| !word.is_empty())
+            .collect();
+        Self {
+            words,
+            user_dictionary,
+        }
+    }
+
+    /// Returns `true` if the word is in the dictionary, ignoring case, possessive `'s` and
+    /// common suffixes.
+    pub fn is_correct(&self, word: &str) -> bool {
+        let word = word.to_lowercase();
+        let word = word.strip_suffix("'s").unwrap_or(&word);
+        if self.words.contains(word) {
+            return true;
+        }
+        SUFFIXES.iter().any(|(suffix, replacement)| {
+            let Some(stem) = word.strip_suffix(suffix) else {
+                return false;
+            };
+            // Words ending in `s` take `es` instead, e.g. `classes`.
+            if stem.chars().count() < 2 || (*suffix == "s" && stem.ends_with('s')) {
+                return false;
+            }
+            if self.words.contains(&format!("{stem}{replacement}")) {
+                return true;
+            }
+            // Consonants are doubled before some suffixes, e.g. `stopped`.
+            let mut chars = stem.chars().rev();
+            replacement.is_empty()
+                && chars.next().is_some_and(|last| {
+                    Some(last) == chars.next()
+                        && self.words.contains(&stem[..stem.len() - last.len_utf8()])
+                })
+        })
+    }
+
+    /// Returns the column ranges of the misspelled words in the text, starting at the given
+    /// column.
+    pub fn misspelled(&self, text: &str, from: usize) -> Vec<Range<usize>> {
+        words(text)
+            .into_iter()
+            .filter(|range| range.start >= from)
+            .filter(|range| {
+                let word: String = text.chars().skip(range.start).take(range.len()).collect();
+                should_check(&word) && !self.is_correct(&word)
+            })
+            .collect()
+    }
+
+    /// Returns the known words closest to the given word, the closest first. The first letter of
+    /// the suggestions is capitalized like the word.
+    pub fn suggestions(&self, word: &str) -> Vec<String> {
+        let lower: Vec<char> = word.to_lowercase().chars().collect();
+        let mut candidates: Vec<(usize, &String)> = self
+            .words
+            .iter()
+            .filter(|candidate| 2 * candidate.chars().count().abs_diff(lower.len()) <= MAX_DISTANCE)
+            .filter_map(|candidate| {
+                let chars: Vec<char> = candidate.chars().collect();
+                let distance = edit_distance(&lower, &chars);
+                (distance <= MAX_DISTANCE).then_some((distance, candidate))
+            })
+            .collect();
+        candidates.sort();
+
+        let capitalized = word.chars().next().is_some_and(char::is_uppercase);
+        candidates
+            .into_iter()
+            .take(MAX_SUGGESTIONS)
+            .map(|(_, candidate)| {
+                if capitalized {
+                    capitalize(candidate)
+                } else {
+                    candidate.clone()
+                }
+            })
+            .collect()
+    }
+
+    /// Adds the word to the dictionary and appends it to the user dictionary file.
+    pub fn add_word(&mut self, word: &str) -> io::Result<()> {
+        let Some(path) = &self.user_dictionary else {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                "no user dictionary path",
+            ));
+        };
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        writeln!(file, "{word}")?;
+        self.words.insert(word.to_lowercase());
+        Ok(())
+    }
+}
+
+/// Returns the column where spell checking starts in a row of the given filetype: the start of
+/// the row in text files, and the start of the line comment in other files. Returns `None` if the
+/// row is not checked.
+pub fn check_start(text: &str, filetype: &str, comment_token: Option<&str>) -> Option<usize> {
+    if TEXT_FILETYPES.contains(&filetype) {
+        return Some(0);
+    }
+    let index = text.find(comment_token?)?;
+    Some(text[..index].chars().count())
+}
+
+/// Returns the column ranges of the words of the text. A word is a run of alphanumeric
+/// characters and underscores, along with apostrophes between letters.
+fn words(text: &str) -> Vec<Range<usize>> {
+    let chars: Vec<char> = text.chars().collect();
+    let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
+    let mut words = Vec::new();
+    let mut col = 0;
+    while col < chars.len() {
+        if !is_word_char(chars[col]) {
+            col += 1;
+            continue;
+        }
+        let start = col;
+        while col < chars.len()
+            && (is_word_char(chars[col])
+                || (chars[col] == '\''
+                    && col > start
+                    && chars.get(col + 1).is_some_and(|&c| c.is_alphabetic())))
+        {
+            col += 1;
+        }
+        words.push(start..col);
+    }
+    words
+}
+
+/// Returns `true` if the word looks like prose rather than an identifier, acronym or number, so
+/// that it should be checked.
+fn should_check(word: &str) -> bool {
+    word.chars().count() > 1
+        && word.chars().all(|c| c.is_alphabetic() || c == '\'')
+        && !word.chars().skip(1).any(char::is_uppercase)
+}
+
+/// Returns the cost of turning `a` into `b`. Inserting, removing or substituting a character
+/// costs 2, while swapping adjacent characters only costs 1 as it is the most common typo.
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+    let width = b.len() + 1;
+    let mut table = vec![0; (a.len() + 1) * width];
+    for i in 0..=a.len() {
+        for j in 0..=b.len() {
+            table[i * width + j] = if i == 0 || j == 0 {
+                2 * (i + j)
+            } else {
+                let cost = 2 * usize::from(a[i - 1] != b[j - 1]);
+                let mut distance = (table[(i - 1) * width + j] + 2)
+                    .min(table[i * width + j - 1] + 2)
+                    .min(table[(i - 1) * width + j - 1] + cost);
+                if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                    distance = distance.min(table[(i - 2) * width + j - 2] + 1);
+                }
+                distance
+            };
+        }
+    }
+    table[a.len() * width + b.len()]
+}
+
+/// Returns the word with its first letter in upper case.
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a spell checker knowing only the given words, without a user dictionary.
+    fn checker(words: &[&str]) -> SpellChecker {
+        SpellChecker {
+            words: words.iter().map(|word| word.to_string()).collect(),
+            user_dictionary: None,
+        }
+    }
+
+    /// Returns the words of the text.
+    fn split(text: &str) -> Vec<String> {
+        let chars: Vec<char> = text.chars().collect();
+        words(text)
+            .into_iter()
+            .map(|range| chars[range].iter().collect())
+            .collect()
+    }
+
+    #[test]
+    fn suffixes_and_possessives_of_known_words_are_correct() {
+        let checker = checker(&["try", "stop", "make", "class", "cat"]);
+        for word in [
+            "try", "Try", "tries", "tried", "trying", "making", "maker", "stopped", "classes",
+            "cats", "cat's",
+        ] {
+            assert!(checker.is_correct(word), "{word}");
+        }
+        for word in ["classs", "mak", "tryy", "dog", "ies"] {
+            assert!(!checker.is_correct(word), "{word}");
+        }
+    }
+
+    #[test]
+    fn swapped_characters_are_the_cheapest_edit() {
+        let distance = |a: &str, b: &str| {
+            let a: Vec<char> = a.chars().collect();
+            let b: Vec<char> = b.chars().collect();
+            edit_distance(&a, &b)
+        };
+        assert_eq!(distance("the", "the"), 0);
+        assert_eq!(distance("teh", "the"), 1);
+        assert_eq!(distance("thx", "the"), 2);
+        assert_eq!(distance("th", "the"), 2);
+        assert_eq!(distance("", "the"), 6);
+    }
+
+    #[test]
+    fn suggestions_are_sorted_by_distance_and_keep_the_capital() {
+        let checker = checker(&["the", "tea", "ten", "banana"]);
+        assert_eq!(checker.suggestions("Teh"), ["The", "Tea", "Ten"]);
+        assert_eq!(checker.suggestions("teh"), ["the", "tea", "ten"]);
+        assert!(checker.suggestions("xyzzy").is_empty());
+    }
+
+    #[test]
+    fn words_keep_apostrophes_between_letters() {
+        assert_eq!(
+            split("don't 'quoted' snake_case, cats' é2"),
+            ["don't", "quoted", "snake_case", "cats", "é2"]
+        );
+    }
+
+    #[test]
+    fn identifiers_acronyms_and_numbers_are_not_checked() {
+        let checker = checker(&["the", "cat"]);
+        let text = "the cta HTTP fooBar x1 a Teh";
+        let misspelled: Vec<String> = checker
+            .misspelled(text, 0)
+            .into_iter()
+            .map(|range| text[range].to_string())
+            .collect();
+        assert_eq!(misspelled, ["cta", "Teh"]);
+        assert_eq!(checker.misspelled(text, 5).len(), 1);
+    }
+
+    #[test]
+    fn comments_are_checked_in_code_and_every_row_in_text() {
+        assert_eq!(check_start("let x = 1;", "markdown", None), Some(0));
+        assert_eq!(check_start("é = 1 // note", "rust", Some("//")), Some(6));
+        assert_eq!(check_start("let x = 1;", "rust", Some("//")), None);
+        assert_eq!(check_start("# note", "unknown", None), None);
+    }
+
+    #[test]
+    fn added_words_are_kept_in_the_configuration_directory() {
+        let dir = std::env::temp_dir().join(format!("tedit-spell-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let mut checker = SpellChecker::load(None, Some(&dir));
+        assert!(!checker.is_correct("teditor"));
+        checker.add_word("teditor").unwrap();
+        assert!(checker.is_correct("teditor"));
+        assert_eq!(
+            fs::read_to_string(dir.join("dictionary")).unwrap(),
+            "teditor\n"
+        );
+        assert!(SpellChecker::load(None, Some(&dir)).is_correct("teditor"));
+        assert!(SpellChecker::load(None, None).add_word("teditor").is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
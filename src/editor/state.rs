@@ -0,0 +1,40 @@
+use std::sync::Arc;
+
+use crate::editor::{
+    Focus, Mode,
+    config::Config,
+    ui::{component::status_bar::Message, theme::Theme},
+};
+
+/// The state of the editor that components read while rendering. It is kept apart from the
+/// other parts of the editor so that it can be borrowed by the rendering context while the
+/// compositor and the prompts are borrowed mutably.
+#[derive(Debug)]
+pub struct EditorState {
+    /// The current mode.
+    pub mode: Mode,
+    /// The part of the editor receiving key input.
+    pub focus: Focus,
+    /// The editor configuration.
+    pub config: Config,
+    /// The current theme.
+    pub theme: Arc<Theme>,
+    /// An optional message to display in the status bar.
+    pub status_message: Option<Message>,
+    /// Whether the terminal is focused.
+    pub focused: bool,
+}
+
+impl EditorState {
+    /// Returns a new state in the given mode, focusing the panes.
+    pub fn new(mode: Mode, config: Config, theme: Arc<Theme>) -> Self {
+        Self {
+            mode,
+            focus: Focus::default(),
+            config,
+            theme,
+            status_message: None,
+            focused: true,
+        }
+    }
+}
@@ -0,0 +1,167 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fs, io,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use crate::editor::buffer::manager::BufferManager;
+
+/// Copies of the contents of dirty buffers, written periodically so that unsaved changes can be
+/// recovered after a crash.
+#[derive(Debug, Default)]
+pub struct SwapFiles {
+    /// The directory holding the swap files, or `None` if no swap files are written.
+    dir: Option<PathBuf>,
+    /// The buffers modified since their swap file was last written.
+    changed: HashSet<usize>,
+    /// The swap files that have been written, keyed by buffer id.
+    written: HashMap<usize, PathBuf>,
+    /// The time swap files were last written.
+    last_write: Option<Instant>,
+    /// The buffers that were opened with a newer swap file, waiting to be offered for recovery.
+    recoverable: VecDeque<usize>,
+}
+
+impl SwapFiles {
+    /// The minimum time between writes of the swap files.
+    const INTERVAL: Duration = Duration::from_secs(2);
+
+    /// Returns swap files kept in the `swap` directory inside the given configuration directory.
+    pub fn new(config_dir: Option<&Path>) -> Self {
+        Self {
+            dir: config_dir.map(|dir| dir.join("swap")),
+            ..Self::default()
+        }
+    }
+
+    /// Records that the buffer with the given id was modified.
+    pub fn mark_changed(&mut self, buffer_id: usize) {
+        self.changed.insert(buffer_id);
+    }
+
+    /// Queues the buffer with the given id to be offered for recovery if its file has a swap file
+    /// newer than the file itself.
+    pub fn check(&mut self, buffer_id: usize, file: &Path) {
+        if self.recoverable(file).is_some() {
+            self.recoverable.push_back(buffer_id);
+        }
+    }
+
+    /// Returns the next buffer queued for recovery.
+    pub fn next_recoverable(&mut self) -> Option<usize> {
+        self.recoverable.pop_front()
+    }
+
+    /// Writes the swap files of the modified dirty buffers, at most once per interval, and removes
+    /// the swap files of buffers that have since been saved or closed.
+    pub fn update(&mut self, buffers: &BufferManager) {
+        self.written.retain(|id, swap| {
+            let dirty = buffers
+                .get(*id)
+                .is_some_and(|entry| entry.read_buf().is_dirty());
+            if !dirty {
+                remove_file(swap);
+            }
+            dirty
+        });
+
+        if self.changed.is_empty()
+            || self
+                .last_write
+                .is_some_and(|time| time.elapsed() < Self::INTERVAL)
+        {
+            return;
+        }
+        self.last_write = Some(Instant::now());
+
+        for id in std::mem::take(&mut self.changed) {
+            let Some(entry) = buffers.get(id) else {
+                continue;
+            };
+            let buffer = entry.read_buf();
+            if !buffer.is_dirty() {
+                continue;
+            }
+            let Some(swap) = buffer.filepath().and_then(|path| self.swap_path(path)) else {
+                continue;
+            };
+
+            if let Err(err) = write_atomic(&swap, &buffer.text()) {
+                tracing::warn!(path = %swap.display(), %err, "failed to write swap file");
+                continue;
+            }
+            // The buffer may have been saved under a different path since the last write.
+            if let Some(previous) = self.written.insert(id, swap.clone())
+                && previous != swap
+            {
+                remove_file(&previous);
+            }
+        }
+    }
+
+    /// Writes the swap files of the modified dirty buffers without waiting for the interval, and
+    /// removes the swap files of buffers that have been saved or closed. Called when the editor
+    /// exits, so that changes discarded by a forced quit can still be recovered.
+    pub fn flush(&mut self, buffers: &BufferManager) {
+        self.last_write = None;
+        self.update(buffers);
+    }
+
+    /// Returns the path of the swap file of the given file. The name of the swap file is a hash
+    /// of the absolute path of the file.
+    pub fn swap_path(&self, file: &Path) -> Option<PathBuf> {
+        let file = std::path::absolute(file).ok()?;
+        Some(self.dir.as_ref()?.join(format!("{:016x}.swp", hash(&file))))
+    }
+
+    /// Returns the path of the swap file of the given file if it was modified more recently than
+    /// the file, or if the file does not exist.
+    pub fn recoverable(&self, file: &Path) -> Option<PathBuf> {
+        let swap = self.swap_path(file)?;
+        let swap_modified = fs::metadata(&swap).and_then(|m| m.modified()).ok()?;
+        match fs::metadata(file).and_then(|m| m.modified()) {
+            Ok(file_modified) if file_modified >= swap_modified => None,
+            _ => Some(swap),
+        }
+    }
+
+    /// Removes the swap file of the given file, if any.
+    pub fn remove(&self, file: &Path) {
+        if let Some(swap) = self.swap_path(file) {
+            remove_file(&swap);
+        }
+    }
+}
+
+/// Removes a swap file, logging failures other than the file not existing.
+fn remove_file(swap: &Path) {
+    match fs::remove_file(swap) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => {
+            tracing::warn!(path = %swap.display(), %err, "failed to remove swap file");
+        }
+        _ => {}
+    }
+}
+
+/// Writes the contents to a temporary file and renames it over the path, so that the file at the
+/// path is never partially written.
+fn write_atomic(path: &Path, contents: &str) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let tmp = path.with_extension("swp.tmp");
+    fs::write(&tmp, contents)?;
+    fs::rename(&tmp, path)
+}
+
+/// Returns the 64-bit FNV-1a hash of the path. Unlike the hasher of the standard library, the
+/// hash is stable across releases, so swap files are found again after updating the editor.
+fn hash(path: &Path) -> u64 {
+    path.as_os_str()
+        .as_encoded_bytes()
+        .iter()
+        .fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+            (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+        })
+}
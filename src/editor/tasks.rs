@@ -0,0 +1,95 @@
+use std::time::{Duration, Instant};
+
+/// An identifier of a registered background task.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TaskId(usize);
+
+/// A background task shown in the status bar while it runs.
+#[derive(Debug, Clone)]
+pub struct Task {
+    /// A short description of what the task is doing, e.g. `scanning files…`.
+    pub label: String,
+    /// The fraction of the task that is done, between 0 and 1, if known.
+    pub progress: Option<f32>,
+}
+
+/// A registry of the running background tasks.
+#[derive(Debug, Clone)]
+pub struct Tasks {
+    /// The identifier of the next registered task.
+    next_id: usize,
+    /// The running tasks in the order they were registered.
+    tasks: Vec<(TaskId, Task)>,
+    /// The time the spinner started, used to pick its frame.
+    started: Instant,
+}
+
+impl Default for Tasks {
+    fn default() -> Self {
+        Self {
+            next_id: 0,
+            tasks: Vec::new(),
+            started: Instant::now(),
+        }
+    }
+}
+
+impl Tasks {
+    /// The frames of the spinner shown next to the running task.
+    const SPINNER: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+    /// The time each frame of the spinner is shown for.
+    pub const SPINNER_INTERVAL: Duration = Duration::from_millis(80);
+
+    /// Registers a running task with the given label and returns its identifier.
+    pub fn register(&mut self, label: &str) -> TaskId {
+        let id = TaskId(self.next_id);
+        self.next_id += 1;
+        self.tasks.push((
+            id,
+            Task {
+                label: label.to_string(),
+                progress: None,
+            },
+        ));
+        id
+    }
+
+    /// Returns the task with the given identifier, or `None` if it has been unregistered.
+    pub fn get_mut(&mut self, id: TaskId) -> Option<&mut Task> {
+        self.tasks
+            .iter_mut()
+            .find(|(task_id, _)| *task_id == id)
+            .map(|(_, task)| task)
+    }
+
+    /// Sets the fraction of the task that is done, clamped between 0 and 1.
+    pub fn set_progress(&mut self, id: TaskId, progress: Option<f32>) {
+        if let Some(task) = self.get_mut(id) {
+            task.progress = progress.map(|p| p.clamp(0.0, 1.0));
+        }
+    }
+
+    /// Removes a task once it has completed.
+    pub fn unregister(&mut self, id: TaskId) {
+        self.tasks.retain(|(task_id, _)| *task_id != id);
+    }
+
+    /// Returns `true` if no tasks are running.
+    pub fn is_empty(&self) -> bool {
+        self.tasks.is_empty()
+    }
+
+    /// Returns the spinner and label of the most recently registered task, along with its
+    /// progress, e.g. `⠋ scanning files… 42%`. Returns `None` if no tasks are running.
+    pub fn status(&self) -> Option<String> {
+        let (_, task) = self.tasks.last()?;
+        let frame = (self.started.elapsed().as_millis() / Self::SPINNER_INTERVAL.as_millis())
+            as usize
+            % Self::SPINNER.len();
+        let spinner = Self::SPINNER[frame];
+        Some(match task.progress {
+            Some(progress) => format!("{spinner} {} {:.0}%", task.label, progress * 100.0),
+            None => format!("{spinner} {}", task.label),
+        })
+    }
+}
@@ -1,7 +1,9 @@
 pub mod component;
 pub mod frame;
 pub mod geometry;
+pub mod list_window;
 pub mod style;
 pub mod theme;
+pub mod unicode;
 pub mod viewport;
 pub mod widget;
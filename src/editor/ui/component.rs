@@ -1,36 +1,73 @@
-use std::sync::Arc;
-
 use crate::editor::{
-    Editor, Mode,
+    buffer::{indent::Indent, manager::BufferManager},
+    completion::CompletionMenu,
+    explorer::Explorer,
+    hex::HexFile,
+    locations::LocationList,
+    lsp::Diagnostics,
     pane::manager::PaneManager,
-    ui::{component::status_bar::Message, geometry::rect::Rect, theme::Theme, viewport::Viewport},
+    renderer::stats::RenderStats,
+    spell::SpellChecker,
+    state::EditorState,
+    tasks::Tasks,
+    ui::{
+        geometry::{point::Point, rect::Rect},
+        viewport::Viewport,
+        widget::border::BorderChars,
+    },
 };
 
+pub mod completion_popup;
+pub mod debug_overlay;
+pub mod explorer;
 pub mod gutter;
+pub mod hex_view;
+pub mod location_list;
 pub mod pane;
 pub mod pane_manager;
 pub mod status_bar;
+pub mod tab_line;
+pub mod welcome;
 
-// TODO: Make this cheaper to create. Instead of cloning everything, just clone the state needed
-// for rendering.
-/// A context for rendering objects.
-pub struct RenderingContext {
-    pub mode: Mode,
-    pub theme: Arc<Theme>,
-    pub pane_manager: PaneManager,
-    pub status_message: Option<Message>,
+/// A context for rendering objects, borrowing the parts of the editor that components read.
+pub struct RenderingContext<'a> {
+    /// The mode, focus, configuration, theme and status message of the editor.
+    pub state: &'a EditorState,
+    pub pane_manager: &'a PaneManager,
+    pub buffer_manager: &'a BufferManager,
+    pub git_branch: Option<&'a str>,
+    pub diagnostics: &'a Diagnostics,
+    /// The rendering statistics, or `None` if the debug overlay is hidden.
+    pub render_stats: Option<&'a RenderStats>,
+    /// The running background tasks.
+    pub tasks: &'a Tasks,
+    /// The location list, or `None` if it is closed.
+    pub location_list: Option<&'a LocationList>,
+    /// The open completion menu, if any.
+    pub completion: Option<&'a CompletionMenu>,
+    /// The file explorer, or `None` if it is hidden.
+    pub explorer: Option<&'a Explorer>,
+    /// The file shown as a hex dump, or `None` if the hex view is closed.
+    pub hex_file: Option<&'a HexFile>,
+    /// The spell checker, or `None` if spell checking is disabled.
+    pub spell: Option<&'a SpellChecker>,
+    /// The description of the command selected in the command palette, shown in the status bar
+    /// instead of the status message while in command mode.
+    pub palette_hint: Option<String>,
+    /// The keys and descriptions listed on the welcome screen, or `None` if it is hidden.
+    pub welcome: Option<Vec<(String, &'static str)>>,
     pub editor_view: Rect,
 }
 
-impl RenderingContext {
-    pub fn new(editor: &Editor, editor_view: Rect) -> Self {
-        Self {
-            mode: editor.mode,
-            theme: editor.theme.clone(),
-            pane_manager: editor.pane_manager.clone(),
-            status_message: editor.status_message.clone(),
-            editor_view,
-        }
+impl RenderingContext<'_> {
+    /// Returns the characters to draw borders with.
+    pub fn border_chars(&self) -> BorderChars {
+        self.state.config.editor.border_style.into()
+    }
+
+    /// Returns the indentation used for buffers without a detected indentation.
+    pub fn default_indent(&self) -> Indent {
+        self.state.config.editor.default_indent()
     }
 }
 
@@ -41,4 +78,35 @@ pub trait Component {
 
     /// Renders the object to the terminal.
     fn render(&mut self, ctx: &RenderingContext, viewport: Viewport);
+
+    /// Returns whether the component should be rendered.
+    fn is_visible(&self, _ctx: &RenderingContext) -> bool {
+        true
+    }
+
+    /// Drops any state kept from the last render, forcing the component to render in full. Called
+    /// when something the component does not track, such as the theme, has changed.
+    fn invalidate(&mut self) {}
+
+    /// Returns the screen position of the cursor if the component shows one. The cursor of the
+    /// topmost component that shows one is used.
+    fn cursor_position(&self, _ctx: &RenderingContext) -> Option<Point> {
+        None
+    }
+
+    /// Handles a mouse click at the given screen position, returning what the editor should do in
+    /// response, if anything. Only called on the topmost component at the position.
+    fn handle_click(&mut self, _point: Point) -> Option<ClickAction> {
+        None
+    }
+}
+
+/// An action for the editor to perform in response to a click on a component.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClickAction {
+    /// Moves the cursor of the pane with the given index to the start of the given row, clamped
+    /// to the last row, and makes the pane active.
+    GotoRow { pane: usize, row: usize },
+    /// Shows the buffer with the given id in the active pane.
+    ShowBuffer { buffer_id: usize },
 }
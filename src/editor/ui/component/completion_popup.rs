@@ -0,0 +1,83 @@
+use crate::editor::{
+    completion::CompletionMenu,
+    ui::{
+        component::{Component, RenderingContext},
+        geometry::rect::Rect,
+        theme::highlight_group::{HL_UI_COMMAND_PROMPT_SELECTED, HL_UI_OVERLAY},
+        viewport::Viewport,
+        widget::{container::ContainerBuilder, span::Span},
+    },
+};
+
+/// A popup below the cursor listing the candidates of a completion menu.
+#[derive(Debug, Clone)]
+pub struct CompletionPopup {
+    menu: CompletionMenu,
+}
+
+impl CompletionPopup {
+    /// The maximum number of candidates shown at once.
+    const MAX_HEIGHT: usize = 10;
+    /// The maximum width of the popup.
+    const MAX_WIDTH: usize = 40;
+
+    pub fn new(menu: CompletionMenu) -> Self {
+        Self { menu }
+    }
+
+    /// Returns the width fitting the longest candidate, padded by a space on either side.
+    fn width(&self) -> usize {
+        self.menu
+            .candidates
+            .iter()
+            .map(|candidate| candidate.chars().count() + 2)
+            .max()
+            .unwrap_or_default()
+            .min(Self::MAX_WIDTH)
+    }
+}
+
+impl Component for CompletionPopup {
+    fn rect(&self, parent: Rect) -> Rect {
+        // Shift the popup left if it does not fit to the right of the cursor.
+        let right = parent.col + parent.width;
+        let width = self.width().min(right);
+        let height = self.menu.candidates.len().min(Self::MAX_HEIGHT);
+        Rect::new(
+            right.saturating_sub(width).min(parent.col),
+            parent.row,
+            width,
+            height,
+        )
+    }
+
+    fn render(&mut self, ctx: &RenderingContext, mut viewport: Viewport) {
+        let style = ctx.state.theme.resolve(&HL_UI_OVERLAY);
+        let selected_style = ctx.state.theme.resolve(&HL_UI_COMMAND_PROMPT_SELECTED);
+
+        // Scroll such that the selected candidate is visible.
+        let visible = viewport.height();
+        let first_visible = (self.menu.selected + 1).saturating_sub(visible);
+        for (row, (i, candidate)) in self
+            .menu
+            .candidates
+            .iter()
+            .enumerate()
+            .skip(first_visible)
+            .take(visible)
+            .enumerate()
+        {
+            let style = if i == self.menu.selected {
+                selected_style
+            } else {
+                style
+            };
+            let line = ContainerBuilder::default()
+                .with_child(Span::new(&format!(" {candidate} ")).with_style(style))
+                .with_width(Some(viewport.width()))
+                .with_style(style)
+                .build();
+            viewport.put_widget(row, line);
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use std::time::Duration;
+
+use crate::editor::ui::{
+    component::{Component, RenderingContext},
+    geometry::{anchor::Anchor, rect::Rect},
+    theme::highlight_group::{HL_UI_OVERLAY, HL_UI_OVERLAY_BORDER},
+    viewport::Viewport,
+    widget::{border::Border, paragraph::Paragraph},
+};
+
+/// A box in the top right corner showing the rendering statistics of recent frames.
+#[derive(Debug, Default, Clone)]
+pub struct DebugOverlay;
+
+impl DebugOverlay {
+    /// The title shown in the border of the overlay.
+    const TITLE: &str = "Debug";
+    /// The width of the overlay, including the border.
+    const WIDTH: usize = 34;
+    /// The number of lines of statistics shown in the overlay.
+    const LINES: usize = 5;
+
+    /// Returns the border drawn around the overlay.
+    fn border() -> Border {
+        Border::default().with_title(Self::TITLE).with_padding(1, 0)
+    }
+
+    /// Formats a duration in milliseconds with microsecond precision.
+    fn millis(duration: Duration) -> String {
+        format!("{:.3}ms", duration.as_secs_f64() * 1000.0)
+    }
+
+    /// Returns the lines showing the statistics in the context. There are always
+    /// [`Self::LINES`] lines.
+    fn text(ctx: &RenderingContext) -> Vec<String> {
+        let Some(stats) = &ctx.render_stats else {
+            return Vec::new();
+        };
+        let last = stats.last().copied().unwrap_or_default();
+        let (compose, render) = stats.average();
+        vec![
+            format!(
+                "compose: {} (avg {})",
+                Self::millis(last.compose),
+                Self::millis(compose)
+            ),
+            format!(
+                "render: {} (avg {})",
+                Self::millis(last.render),
+                Self::millis(render)
+            ),
+            format!("changed cells: {}", last.changed_cells),
+            format!("size: {}x{}", last.size.0, last.size.1),
+            format!("last event: {}", stats.last_event.unwrap_or("-")),
+        ]
+    }
+}
+
+impl Component for DebugOverlay {
+    fn rect(&self, parent: Rect) -> Rect {
+        let height = Self::LINES + Self::border().vertical_size();
+        Rect::new(
+            0,
+            0,
+            Self::WIDTH.min(parent.width),
+            height.min(parent.height),
+        )
+        .anchored_on(parent, Anchor::TopRight)
+    }
+
+    fn render(&mut self, ctx: &RenderingContext, mut viewport: Viewport) {
+        let border = Self::border()
+            .with_chars(ctx.border_chars())
+            .with_style(ctx.state.theme.resolve(&HL_UI_OVERLAY_BORDER));
+        let mut inner = viewport.put_border(&border);
+
+        let paragraph = Paragraph::new(&Self::text(ctx).join("\n"), inner.width())
+            .with_max_height(inner.height())
+            .with_style(ctx.state.theme.resolve(&HL_UI_OVERLAY));
+        inner.put_column(
+            Rect::new(0, 0, inner.width(), inner.height()),
+            paragraph.into_column(),
+        );
+    }
+
+    fn is_visible(&self, ctx: &RenderingContext) -> bool {
+        ctx.render_stats.is_some()
+    }
+}
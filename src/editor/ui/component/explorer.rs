@@ -0,0 +1,109 @@
+use crate::editor::{
+    Focus,
+    ui::{
+        component::{Component, RenderingContext},
+        frame::Cell,
+        geometry::{anchor::Anchor, point::Point, rect::Rect},
+        theme::highlight_group::{HL_UI_PANE, HL_UI_PANE_GUTTER, HL_UI_PANE_SELECTION},
+        viewport::Viewport,
+        widget::{container::ContainerBuilder, span::Span},
+    },
+};
+
+/// A sidebar on the left of the panes listing the file tree of the explorer.
+#[derive(Debug, Default, Clone)]
+pub struct ExplorerView {
+    /// The index of the first visible entry.
+    first_visible: usize,
+    /// The screen position of the selected entry in the last frame.
+    selected_position: Option<Point>,
+}
+
+impl ExplorerView {
+    /// The width of the sidebar, including the separator on its right.
+    pub const WIDTH: usize = 30;
+
+    /// Scrolls the view such that the selected entry is visible.
+    fn scroll_to_selected(&mut self, selected: usize, visible: usize) {
+        if selected < self.first_visible {
+            self.first_visible = selected;
+        } else if selected >= self.first_visible + visible {
+            self.first_visible = selected + 1 - visible;
+        }
+    }
+}
+
+impl Component for ExplorerView {
+    fn rect(&self, parent: Rect) -> Rect {
+        // Leave the bottom row for the status bar.
+        Rect::new(0, 0, parent.width, parent.height.saturating_sub(1))
+            .anchored_on(parent, Anchor::TopLeft)
+    }
+
+    fn render(&mut self, ctx: &RenderingContext, mut viewport: Viewport) {
+        self.selected_position = None;
+        let Some(explorer) = &ctx.explorer else {
+            return;
+        };
+        let style = ctx.state.theme.resolve(&HL_UI_PANE);
+        let selected_style = ctx.state.theme.resolve(&HL_UI_PANE_SELECTION);
+        let separator_style = ctx.state.theme.resolve(&HL_UI_PANE_GUTTER);
+
+        let width = viewport.width().saturating_sub(1);
+        let separator = ctx.border_chars().vertical.to_string();
+        for row in 0..viewport.height() {
+            viewport.merge_cell(
+                width,
+                row,
+                Cell::new(&separator).with_style(separator_style),
+            );
+        }
+
+        let visible = viewport.height();
+        self.scroll_to_selected(explorer.selected_index(), visible);
+        for (row, (i, entry)) in explorer
+            .iter()
+            .enumerate()
+            .skip(self.first_visible)
+            .take(visible)
+            .enumerate()
+        {
+            let style = if i == explorer.selected_index() {
+                selected_style
+            } else {
+                style
+            };
+            let marker = match (entry.is_dir, entry.expanded) {
+                (true, true) => "▾ ",
+                (true, false) => "▸ ",
+                (false, _) => "  ",
+            };
+            let indent = "  ".repeat(entry.depth);
+            let label = format!("{indent}{marker}{}", entry.name());
+            let line = ContainerBuilder::default()
+                .with_child(Span::new(&label).with_style(style))
+                .with_width(Some(width))
+                .with_style(style)
+                .build();
+            viewport.put_widget(row, line);
+
+            if i == explorer.selected_index() {
+                let rect = viewport.rect();
+                self.selected_position = Some(Point::new(rect.col, rect.row + row));
+            }
+        }
+    }
+
+    fn is_visible(&self, ctx: &RenderingContext) -> bool {
+        ctx.explorer.is_some()
+    }
+
+    fn cursor_position(&self, ctx: &RenderingContext) -> Option<Point> {
+        // Only take the cursor from the panes while the explorer has focus.
+        if ctx.state.focus == Focus::Explorer {
+            self.selected_position
+        } else {
+            None
+        }
+    }
+}
@@ -1,8 +1,13 @@
 use crate::editor::{
+    lsp::Severity,
     pane::Pane,
     ui::{
-        component::RenderingContext,
-        theme::highlight_group::{HL_UI_PANE_GUTTER, HL_UI_PANE_GUTTER_CURSOR},
+        component::{ClickAction, RenderingContext},
+        frame::Cell,
+        theme::highlight_group::{
+            HL_UI_PANE_GUTTER, HL_UI_PANE_GUTTER_CURSOR, HL_UI_PANE_GUTTER_ERROR,
+            HL_UI_PANE_GUTTER_INFO, HL_UI_PANE_GUTTER_WARNING,
+        },
         viewport::Viewport,
         widget::{
             container::{Alignment, ContainerBuilder},
@@ -28,45 +33,50 @@ impl Gutter {
     // TODO: Gutter padding should be configurable.
     /// The minimum width of the gutter.
     const GUTTER_PADDING: usize = 4;
-    const END_OF_BUFFER_MARKER: &'static str = "~";
-
-    pub fn new(width: usize) -> Self {
-        Self { width }
-    }
+    /// The sign shown in the first column of rows with a diagnostic.
+    const DIAGNOSTIC_SIGN: &'static str = "●";
 
     /// Returns the width of the gutter.
     pub fn width(&self) -> usize {
         self.width
     }
 
-    /// Updates the width to be at least as wide as the digits of `buffer_lines`.
-    pub fn update_width(&mut self, buffer_lines: usize) {
-        let digits = buffer_lines
+    /// Returns the width of the gutter of a buffer with `buffer_lines` lines, which is the number
+    /// of digits of the last line number plus the padding.
+    pub fn width_for(buffer_lines: usize) -> usize {
+        buffer_lines
             .to_string()
             .len()
-            .saturating_add(Self::GUTTER_PADDING);
-        self.width = self.width.max(digits);
+            .saturating_add(Self::GUTTER_PADDING)
+    }
+
+    /// Updates the width to fit the line numbers of a buffer with `buffer_lines` lines.
+    pub fn update_width(&mut self, buffer_lines: usize) {
+        self.width = Self::width_for(buffer_lines);
+    }
+
+    /// Handles a click on the gutter of the pane with the given index, where `row` is the buffer
+    /// row shown on the clicked line.
+    pub fn handle_click(&self, pane: usize, row: usize) -> Option<ClickAction> {
+        Some(ClickAction::GotoRow { pane, row })
     }
 
-    /// Renders the gutter.
+    /// Renders the gutter next to the given buffer rows, leaving the lines past the end of the
+    /// buffer empty.
     pub fn render(
         &self,
         ctx: &RenderingContext,
         pane: &Pane,
-        row_offset: usize,
+        rows: &[usize],
         mut viewport: Viewport,
     ) {
         let cursor_row = pane.cursor_position().1;
-        let buffer_lines = pane.buffer_lines();
+        let path = pane.file_path().and_then(|p| std::path::absolute(p).ok());
         for row in 0..viewport.height() {
-            let pane_row = row_offset + row;
-            let line_number = if pane_row < buffer_lines {
-                pane_row.saturating_add(1).to_string()
-            } else if pane_row == buffer_lines.saturating_sub(1) {
-                Self::END_OF_BUFFER_MARKER.to_string()
-            } else {
-                "".to_string()
-            };
+            let pane_row = rows.get(row).copied();
+            let line_number = pane_row
+                .map(|pane_row| pane_row.saturating_add(1).to_string())
+                .unwrap_or_default();
 
             let s = format!(
                 "{:>width$}",
@@ -74,10 +84,10 @@ impl Gutter {
                 width = self.width.saturating_sub(self.width / 2)
             );
 
-            let style = if cursor_row == pane_row {
-                ctx.theme.resolve(&HL_UI_PANE_GUTTER_CURSOR)
+            let style = if pane_row == Some(cursor_row) {
+                ctx.state.theme.resolve(&HL_UI_PANE_GUTTER_CURSOR)
             } else {
-                ctx.theme.resolve(&HL_UI_PANE_GUTTER)
+                ctx.state.theme.resolve(&HL_UI_PANE_GUTTER)
             };
 
             let span = Span::new(&s);
@@ -88,6 +98,41 @@ impl Gutter {
                 .with_style(style)
                 .build();
             viewport.put_widget(row, widget);
+
+            // Show a sign colored by the most severe diagnostic on the row.
+            if let Some(diagnostic) = path
+                .as_deref()
+                .zip(pane_row)
+                .and_then(|(path, pane_row)| ctx.diagnostics.most_severe_on_row(path, pane_row))
+            {
+                let group = match diagnostic.severity {
+                    Severity::Error => &HL_UI_PANE_GUTTER_ERROR,
+                    Severity::Warning => &HL_UI_PANE_GUTTER_WARNING,
+                    Severity::Information | Severity::Hint => &HL_UI_PANE_GUTTER_INFO,
+                };
+                let cell =
+                    Cell::new(Self::DIAGNOSTIC_SIGN).with_style(ctx.state.theme.resolve(group));
+                viewport.merge_cell(0, row, cell);
+            }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn width_grows_and_shrinks_with_the_number_of_digits() {
+        let widths: Vec<_> = [1, 9, 10, 99, 100, 10_000, 50]
+            .into_iter()
+            .map(Gutter::width_for)
+            .collect();
+        assert_eq!(widths, [5, 5, 6, 6, 7, 9, 6]);
+
+        let mut gutter = Gutter::default();
+        gutter.update_width(10_000);
+        gutter.update_width(50);
+        assert_eq!(gutter.width(), 6);
+    }
+}
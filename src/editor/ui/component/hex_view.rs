@@ -0,0 +1,114 @@
+use crate::editor::{
+    Focus,
+    ui::{
+        component::{Component, RenderingContext},
+        frame::Cell,
+        geometry::{anchor::Anchor, point::Point, rect::Rect},
+        theme::highlight_group::{
+            HL_UI_PANE, HL_UI_PANE_GUTTER, HL_UI_PANE_SELECTION, HL_UI_PANE_TITLE_ACTIVE,
+        },
+        viewport::Viewport,
+        widget::{container::ContainerBuilder, span::Span},
+    },
+};
+
+/// A view covering the panes with a hex dump of a file. Only the visible lines are read from the
+/// file on each frame.
+#[derive(Debug, Default, Clone)]
+pub struct HexView {
+    /// The index of the first visible line.
+    first_visible: usize,
+    /// The screen position of the selected line in the last frame.
+    selected_position: Option<Point>,
+}
+
+impl HexView {
+    /// The number of columns of the offset at the start of each line.
+    const OFFSET_WIDTH: usize = 8;
+
+    /// Scrolls the view such that the selected line is visible.
+    fn scroll_to_selected(&mut self, selected: usize, visible: usize) {
+        if selected < self.first_visible {
+            self.first_visible = selected;
+        } else if selected >= self.first_visible + visible {
+            self.first_visible = selected + 1 - visible;
+        }
+    }
+}
+
+impl Component for HexView {
+    fn rect(&self, parent: Rect) -> Rect {
+        // Leave the bottom row for the status bar.
+        Rect::new(0, 0, parent.width, parent.height.saturating_sub(1))
+            .anchored_on(parent, Anchor::TopLeft)
+    }
+
+    fn render(&mut self, ctx: &RenderingContext, mut viewport: Viewport) {
+        self.selected_position = None;
+        let Some(file) = &ctx.hex_file else {
+            return;
+        };
+        let style = ctx.state.theme.resolve(&HL_UI_PANE);
+        let selected_style = ctx.state.theme.resolve(&HL_UI_PANE_SELECTION);
+        let offset_style = ctx.state.theme.resolve(&HL_UI_PANE_GUTTER);
+        let title_style = ctx.state.theme.resolve(&HL_UI_PANE_TITLE_ACTIVE);
+        viewport.fill(Cell::default().with_style(style));
+        let width = viewport.width();
+
+        let title = format!(" {} [hex, read-only]", file.path().display());
+        let title = ContainerBuilder::default()
+            .with_child(Span::new(&title).with_style(title_style))
+            .with_width(Some(width))
+            .with_style(title_style)
+            .build();
+        viewport.put_widget(0, title);
+
+        let visible = viewport.height().saturating_sub(1);
+        if visible == 0 {
+            return;
+        }
+        self.scroll_to_selected(file.selected(), visible);
+        let lines = match file.read_lines(self.first_visible, visible) {
+            Ok(lines) => lines,
+            Err(err) => vec![format!("failed to read file: {err}")],
+        };
+        for (row, line) in lines.iter().enumerate() {
+            let i = self.first_visible + row;
+            let style = if i == file.selected() {
+                selected_style
+            } else {
+                style
+            };
+            let split = line
+                .char_indices()
+                .nth(Self::OFFSET_WIDTH)
+                .map_or(line.len(), |(i, _)| i);
+            let (offset, bytes) = line.split_at(split);
+            let line = ContainerBuilder::default()
+                .with_child(Span::new(offset).with_style(offset_style))
+                .with_child(Span::new(bytes).with_style(style))
+                .with_width(Some(width))
+                .with_style(style)
+                .build();
+            viewport.put_widget(row + 1, line);
+
+            if i == file.selected() {
+                let rect = viewport.rect();
+                self.selected_position = Some(Point::new(rect.col, rect.row + row + 1));
+            }
+        }
+    }
+
+    fn is_visible(&self, ctx: &RenderingContext) -> bool {
+        ctx.hex_file.is_some()
+    }
+
+    fn cursor_position(&self, ctx: &RenderingContext) -> Option<Point> {
+        // The explorer takes the cursor while it has focus.
+        if ctx.state.focus == Focus::Panes {
+            self.selected_position
+        } else {
+            None
+        }
+    }
+}
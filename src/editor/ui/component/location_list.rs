@@ -0,0 +1,82 @@
+use crate::editor::ui::{
+    component::{Component, RenderingContext},
+    geometry::{anchor::Anchor, rect::Rect},
+    theme::highlight_group::{HL_UI_PANE, HL_UI_PANE_SELECTION, HL_UI_TABLINE},
+    viewport::Viewport,
+    widget::{container::ContainerBuilder, span::Span},
+};
+
+/// A read-only view of the location list below the panes, highlighting the current location.
+#[derive(Debug, Default, Clone)]
+pub struct LocationListView {
+    /// The index of the first visible location.
+    first_visible: usize,
+}
+
+impl LocationListView {
+    /// The height of the view, including the title.
+    pub const HEIGHT: usize = 8;
+
+    /// Scrolls the view such that the current location is visible.
+    fn scroll_to_current(&mut self, current: usize, visible: usize) {
+        if current < self.first_visible {
+            self.first_visible = current;
+        } else if current >= self.first_visible + visible {
+            self.first_visible = current + 1 - visible;
+        }
+    }
+}
+
+impl Component for LocationListView {
+    fn rect(&self, parent: Rect) -> Rect {
+        Rect::new(0, 0, parent.width, Self::HEIGHT)
+            .anchored_on(parent, Anchor::BottomLeft)
+            .offset(0, -1)
+    }
+
+    fn render(&mut self, ctx: &RenderingContext, mut viewport: Viewport) {
+        let Some(list) = &ctx.location_list else {
+            return;
+        };
+        let style = ctx.state.theme.resolve(&HL_UI_PANE);
+        let selected_style = ctx.state.theme.resolve(&HL_UI_PANE_SELECTION);
+        let title_style = ctx.state.theme.resolve(&HL_UI_TABLINE);
+
+        let position = list
+            .current()
+            .map_or_else(|| "-".to_string(), |i| (i + 1).to_string());
+        let title = format!(" Locations ({position}/{})", list.len());
+        let title_line = ContainerBuilder::default()
+            .with_child(Span::new(&title).with_style(title_style))
+            .with_width(Some(viewport.width()))
+            .with_style(title_style)
+            .build();
+        viewport.put_widget(0, title_line);
+
+        let visible = viewport.height().saturating_sub(1);
+        self.scroll_to_current(list.current().unwrap_or(0), visible);
+        for (row, (i, location)) in list
+            .iter()
+            .enumerate()
+            .skip(self.first_visible)
+            .take(visible)
+            .enumerate()
+        {
+            let style = if list.current() == Some(i) {
+                selected_style
+            } else {
+                style
+            };
+            let line = ContainerBuilder::default()
+                .with_child(Span::new(&location.text()).with_style(style))
+                .with_width(Some(viewport.width()))
+                .with_style(style)
+                .build();
+            viewport.put_widget(row + 1, line);
+        }
+    }
+
+    fn is_visible(&self, ctx: &RenderingContext) -> bool {
+        ctx.location_list.is_some()
+    }
+}
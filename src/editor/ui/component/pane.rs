@@ -1,9 +1,18 @@
+use std::{iter, path::PathBuf};
+
 use crate::editor::{
-    pane::{Pane, cursor::Cursor},
+    pane::{Flash, Pane, cursor::Cursor, fold::Folds, view::ViewState},
+    spell,
     ui::{
         component::{RenderingContext, gutter::Gutter},
+        frame::Cell,
         geometry::{point::Point, rect::Rect},
-        theme::highlight_group::HL_UI_PANE,
+        style::Style,
+        theme::highlight_group::{
+            HL_SYNTAX_DIFF_ADDED, HL_SYNTAX_DIFF_REMOVED, HL_UI_PANE, HL_UI_PANE_FLASH,
+            HL_UI_PANE_FOLD, HL_UI_PANE_SELECTION, HL_UI_PANE_SPELL, HL_UI_PANE_TITLE,
+            HL_UI_PANE_TITLE_ACTIVE,
+        },
         viewport::Viewport,
         widget::{container::ContainerBuilder, span::Span},
     },
@@ -25,126 +34,381 @@ impl BarsLayout {
     }
 }
 
-#[derive(Debug, Default, Clone, Copy)]
+#[derive(Debug, Default, Clone)]
 pub struct PaneView {
     /// The rect.
     pub rect: Rect,
     /// The gutter of the pane.
     pub gutter: Gutter,
-    /// The column offset of the viewport.
-    pub col_offset: usize,
-    /// The row offset of the viewport.
-    pub row_offset: usize,
-    /// The width of the viewport.
-    pub width: usize,
-    /// The height of the viewport.
-    pub height: usize,
+    /// Whether the top row of the pane shows a title bar.
+    pub title_bar: bool,
+    /// The part of the buffer shown in the last frame, synced with the view state of the pane.
+    pub state: ViewState,
+    /// The buffer rows shown on each line of the viewport in the last frame, skipping the rows
+    /// hidden by folds.
+    pub rows: Vec<usize>,
+    /// The inputs and the cells of the last render, reused while the inputs stay the same.
+    cache: Option<(RenderKey, Vec<Cell>)>,
 }
 
-impl PaneView {
-    /// Scroll the viewport to the given cursor such that the cursor is visible. Returns
-    /// `true` if the viewport was scrolled.
-    pub fn scroll_to_cursor(&mut self, cursor: &Cursor) -> bool {
-        let mut scrolled = false;
-
-        // Vertical scrolling.
-        if cursor.row() < self.row_offset {
-            self.row_offset = cursor.row();
-            scrolled = true;
-        } else if cursor.row() >= self.row_offset.saturating_add(self.height) {
-            self.row_offset = cursor.row() - self.height + 1;
-            scrolled = true;
-        }
+/// The state a pane view is rendered from, besides the text of the buffer, which is tracked by
+/// the version of the pane instead.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct RenderKey {
+    rect: Rect,
+    index: usize,
+    active: bool,
+    buffer_id: usize,
+    version: usize,
+    dirty: bool,
+    file_path: Option<PathBuf>,
+    filetype: String,
+    /// The positions of the primary cursor followed by the secondary cursors.
+    cursors: Vec<(usize, usize)>,
+    selection_anchor: Option<Point>,
+    block: bool,
+    scroll_bound: bool,
+    folds: Folds,
+    flash: Option<Flash>,
+    row_offset: usize,
+    col_offset: usize,
+}
 
-        // Horizontal scrolling.
-        if cursor.col() < self.col_offset {
-            self.col_offset = cursor.col();
-            scrolled = true;
-        } else if cursor.col() >= self.col_offset.saturating_add(self.width) {
-            self.col_offset = cursor.col() - self.width + 1;
-            scrolled = true;
+impl RenderKey {
+    fn new(ctx: &RenderingContext, pane: &Pane, index: usize, view: &PaneView) -> Self {
+        Self {
+            rect: view.rect,
+            index,
+            active: index == ctx.pane_manager.active_pane(),
+            buffer_id: pane.buffer_id(),
+            version: pane.version,
+            dirty: pane.is_dirty(),
+            file_path: pane.file_path(),
+            filetype: pane.filetype(),
+            cursors: iter::once(&pane.cursor)
+                .chain(&pane.secondary_cursors)
+                .map(Cursor::position)
+                .collect(),
+            selection_anchor: pane.selection_anchor,
+            block: pane.block,
+            scroll_bound: pane.scroll_bound,
+            folds: pane.folds.clone(),
+            flash: pane.flash().copied(),
+            row_offset: view.state.row_offset,
+            col_offset: view.state.col_offset,
         }
-
-        scrolled
     }
+}
 
-    /// Scrolls the viewport vertically by the given offset.
-    pub fn scroll_vertically(&mut self, offset: isize) {
-        if offset.is_positive() {
-            self.row_offset = self.row_offset.saturating_add(offset as usize);
-        } else if offset.is_negative() {
-            self.row_offset = self.row_offset.saturating_sub(offset.unsigned_abs());
-        }
-    }
+impl PaneView {
+    /// The height of the title bar.
+    const TITLE_BAR_HEIGHT: usize = 1;
 
-    /// Updates the viewport to match the given dimensions.
-    pub fn update_size(&mut self, rect: Rect) {
+    /// Updates the viewport to match the given dimensions, reserving the top row for a title bar
+    /// if `title_bar` is set and the columns on the left for the gutter of a buffer with
+    /// `buffer_lines` lines.
+    pub fn update_size(&mut self, rect: Rect, title_bar: bool, buffer_lines: usize) {
+        self.gutter.update_width(buffer_lines);
         let (_gutter, buffer) = rect.split_vertically_exact(self.gutter.width());
         self.rect = rect;
-        self.width = buffer.width;
-        self.height = buffer.height;
-    }
-
-    /// Return the width of the viewport.
-    pub fn width(&self) -> usize {
-        self.width
+        self.title_bar = title_bar;
+        self.state.width = buffer.width;
+        self.state.height = buffer.height.saturating_sub(self.title_bar_height());
     }
 
-    /// Return the height of the viewport.
-    pub fn height(&self) -> usize {
-        self.height
+    /// Returns the height of the title bar, which is zero if the pane has none.
+    pub fn title_bar_height(&self) -> usize {
+        if self.title_bar {
+            Self::TITLE_BAR_HEIGHT
+        } else {
+            0
+        }
     }
 
-    /// Returns a point coordinate relative to the viewport and the gutter.
-    pub fn coord_to_screen(&self, Point { mut col, mut row }: Point) -> Point {
-        col = col.saturating_sub(self.col_offset) + self.gutter.width();
-        row = row.saturating_sub(self.row_offset);
+    /// Returns a point coordinate relative to the viewport and the gutter, skipping the rows
+    /// hidden by folds.
+    pub fn coord_to_screen(&self, Point { mut col, mut row }: Point, folds: &Folds) -> Point {
+        col = col.saturating_sub(self.state.col_offset) + self.gutter.width();
+        row = folds.visible_between(self.state.row_offset, row) + self.title_bar_height();
         Point::new(col, row)
     }
 
-    /// Returns the visible rows of the pane.
+    /// Returns the visible part of the rows shown in the last frame. Folded regions are shown as
+    /// their first row followed by the number of hidden rows, e.g. `fn main() { … 24 lines }`.
     pub fn visible_rows(&self, pane: &Pane) -> Vec<String> {
-        let mut rows = Vec::with_capacity(self.height());
-
-        let start_row = self.row_offset;
-        for row_idx in 0..self.height() {
-            let buffer_row = start_row + row_idx;
-            let row_content = pane
-                .buffer
-                .read()
-                .unwrap()
-                .row(buffer_row)
-                .map(|r| r.chars_in_range(self.col_offset, self.width))
-                .unwrap_or_default();
-            rows.push(row_content);
+        let buffer = pane.buffer.read_buf();
+        self.rows
+            .iter()
+            .map(|&buffer_row| {
+                let Some(row) = buffer.row(buffer_row) else {
+                    return String::new();
+                };
+                match pane.folds.get(buffer_row) {
+                    Some(fold) => {
+                        let end = buffer.row(fold.end).map(|r| r.text().trim()).unwrap_or("");
+                        let closing = if end.starts_with(['}', ')', ']']) {
+                            format!(" {end}")
+                        } else {
+                            String::new()
+                        };
+                        let text = format!(
+                            "{} … {} lines{closing}",
+                            row.text().trim_end(),
+                            fold.hidden_len()
+                        );
+                        text.chars()
+                            .skip(self.state.col_offset)
+                            .take(self.state.width)
+                            .collect()
+                    }
+                    None => row.chars_in_range(self.state.col_offset, self.state.width),
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the visible columns of the given buffer row that are inside the selection, or
+    /// `None` if the row is not selected. The end column is exclusive.
+    fn selected_cols(&self, (start, end): (Point, Point), row: usize) -> Option<(usize, usize)> {
+        if row < start.row || row > end.row {
+            return None;
         }
+        let from = if row == start.row { start.col } else { 0 };
+        // Rows that continue the selection are selected up to their end.
+        let to = if row == end.row { end.col } else { usize::MAX };
+        Some((
+            from.saturating_sub(self.state.col_offset),
+            to.saturating_sub(self.state.col_offset),
+        ))
+    }
 
-        rows
+    /// Returns the visible columns of the given buffer row that are inside the block selection,
+    /// or `None` if the row is not selected. The end column is exclusive.
+    fn block_cols(&self, (start, end): (Point, Point), row: usize) -> Option<(usize, usize)> {
+        (start.row..=end.row).contains(&row).then(|| {
+            (
+                start.col.saturating_sub(self.state.col_offset),
+                end.col.saturating_sub(self.state.col_offset),
+            )
+        })
     }
 
-    /// Renders the pane view.
-    pub fn render(&mut self, ctx: &RenderingContext, pane: &Pane, mut viewport: Viewport) {
-        self.scroll_to_cursor(&pane.cursor);
+    /// Renders the title bar of the pane with the given index, showing the index, the file name
+    /// of the buffer and whether it has unsaved changes.
+    fn render_title_bar(
+        &self,
+        ctx: &RenderingContext,
+        pane: &Pane,
+        index: usize,
+        mut viewport: Viewport,
+    ) {
+        let group = if index == ctx.pane_manager.active_pane() {
+            &HL_UI_PANE_TITLE_ACTIVE
+        } else {
+            &HL_UI_PANE_TITLE
+        };
+        let style = ctx.state.theme.resolve(group);
+        let name = pane
+            .file_path()
+            .and_then(|path| {
+                path.file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+            })
+            .unwrap_or_else(|| pane.file_name());
+        let marker = if pane.is_dirty() { "*" } else { "" };
+        let bound = if pane.scroll_bound { " ⇅" } else { "" };
+        let title = format!(" {} {name}{marker}{bound}", index + 1);
+        let widget = ContainerBuilder::default()
+            .with_width(Some(viewport.width()))
+            .with_child(Span::new(&title))
+            .with_style(style)
+            .build();
+        viewport.put_widget(0, widget);
+    }
+
+    /// Renders the view of the pane with the given index.
+    pub fn render(
+        &mut self,
+        ctx: &RenderingContext,
+        pane: &Pane,
+        index: usize,
+        mut viewport: Viewport,
+    ) {
+        // Scroll the pane to its cursor in the current size, and share the result with the
+        // editor.
+        let mut state = pane.view_state();
+        state.width = self.state.width;
+        state.height = self.state.height;
+        match ctx.pane_manager.scroll_leader(index) {
+            // Take the rows the active pane shows in this frame, leaving the cursor where it is.
+            Some(leader) => {
+                let mut leader_state = leader.view_state();
+                leader_state.scroll_to_cursor(&leader.cursor, &leader.folds);
+                state.row_offset = leader_state.row_offset;
+            }
+            None => {
+                state.scroll_to_cursor(&pane.cursor, &pane.folds);
+            }
+        }
+        pane.set_view_state(state);
+        self.state = state;
+
+        // Reuse the cells of the last render if nothing it depends on has changed.
+        let key = RenderKey::new(ctx, pane, index, self);
+        if let Some((cached, cells)) = &self.cache
+            && *cached == key
+        {
+            viewport.put_cells(cells);
+            return;
+        }
+        let pane_viewport = viewport.clone();
+
+        self.rows = pane.folds.visible_from(
+            self.state.row_offset,
+            self.state.height,
+            pane.buffer_lines(),
+        );
+
+        if self.title_bar {
+            let (title, rest) = viewport
+                .rect()
+                .split_horizontally_exact(Self::TITLE_BAR_HEIGHT);
+            if let Some(title_viewport) = viewport.sub_rect(title) {
+                self.render_title_bar(ctx, pane, index, title_viewport);
+            }
+            if let Some(rest) = viewport.sub_rect(rest) {
+                viewport = rest;
+            }
+        }
 
-        self.gutter.update_width(pane.buffer_lines());
         let (gutter_viewport, mut buffer_viewport) =
             viewport.split_horizontally_exact(self.gutter.width());
 
         // Render the gutter.
-        self.gutter
-            .render(ctx, pane, self.row_offset, gutter_viewport);
+        self.gutter.render(ctx, pane, &self.rows, gutter_viewport);
 
         // Render the buffer content.
         let rows = self.visible_rows(pane);
-        let style = ctx.theme.resolve(&HL_UI_PANE);
-        for (i, row) in rows.iter().enumerate() {
-            let span = Span::new(row);
+        let style = ctx.state.theme.resolve(&HL_UI_PANE);
+        let selection_style = ctx.state.theme.resolve(&HL_UI_PANE_SELECTION);
+        let flash_style = ctx.state.theme.resolve(&HL_UI_PANE_FLASH);
+        let fold_style = ctx.state.theme.resolve(&HL_UI_PANE_FOLD);
+        // Keep the background of selections below misspelled words.
+        let spell_style = Style {
+            bg: None,
+            ..ctx.state.theme.resolve(&HL_UI_PANE_SPELL)
+        };
+        let selection = pane.selection();
+        let block = pane.block_selection();
+        let flash = pane.flash();
+        let filetype = pane.filetype();
+        let diff = filetype == "diff";
+        let comment_token = ctx.state.config.comment_token(&filetype);
+        let buffer = pane.buffer.read_buf();
+        for (i, (row, &buffer_row)) in rows.iter().zip(&self.rows).enumerate() {
+            if pane.folds.get(buffer_row).is_some() {
+                let widget = ContainerBuilder::default()
+                    .with_width(Some(buffer_viewport.width()))
+                    .with_child(Span::new(row))
+                    .with_style(fold_style)
+                    .build();
+                buffer_viewport.put_widget(i, widget);
+                continue;
+            }
+
+            // Split the row into the parts before, inside and after the selection, or the range
+            // highlighted after a yank or paste.
+            let flash_cols = flash.and_then(|flash| {
+                let range = (flash.start, flash.end);
+                if flash.block {
+                    self.block_cols(range, buffer_row)
+                } else {
+                    self.selected_cols(range, buffer_row)
+                }
+            });
+            let (highlight_style, (from, to)) = match flash_cols {
+                Some(cols) => (flash_style, cols),
+                None => (
+                    selection_style,
+                    match block {
+                        Some(block) => self.block_cols(block, buffer_row),
+                        None => selection
+                            .and_then(|selection| self.selected_cols(selection, buffer_row)),
+                    }
+                    .unwrap_or_default(),
+                ),
+            };
+            // Color the added and removed lines of diffs, leaving the file headers as they are.
+            let row_style = match buffer.row(buffer_row).map(|r| r.text()) {
+                Some(text) if diff && text.starts_with('+') && !text.starts_with("+++") => {
+                    ctx.state.theme.resolve(&HL_SYNTAX_DIFF_ADDED)
+                }
+                Some(text) if diff && text.starts_with('-') && !text.starts_with("---") => {
+                    ctx.state.theme.resolve(&HL_SYNTAX_DIFF_REMOVED)
+                }
+                _ => style,
+            };
+            let chars: Vec<char> = row.chars().collect();
+            let part = |range: std::ops::Range<usize>| -> String {
+                chars[range.start.min(chars.len())..range.end.min(chars.len())]
+                    .iter()
+                    .collect()
+            };
+
             let widget = ContainerBuilder::default()
                 .with_width(Some(buffer_viewport.width()))
-                .with_child(span)
-                .with_style(style)
+                .with_child(Span::new(&part(0..from)))
+                .with_child(Span::new(&part(from..to)).with_style(highlight_style))
+                .with_child(Span::new(&part(to..chars.len())))
+                .with_style(row_style)
                 .build();
             buffer_viewport.put_widget(i, widget);
+
+            // Only the visible rows are checked, so that large files are not checked per frame.
+            if let Some(spell) = ctx.spell
+                && let Some(text) = buffer.row(buffer_row).map(|r| r.text())
+                && let Some(start) = spell::check_start(text, &filetype, comment_token)
+            {
+                for col in spell.misspelled(text, start).into_iter().flatten() {
+                    let Some(col) = col.checked_sub(self.state.col_offset) else {
+                        continue;
+                    };
+                    if let Some(c) = chars.get(col) {
+                        buffer_viewport.merge_cell(
+                            col,
+                            i,
+                            Cell::new(&c.to_string()).with_style(spell_style),
+                        );
+                    }
+                }
+            }
+        }
+
+        // The terminal only has a single cursor, so draw the secondary cursors as reversed cells.
+        for cursor in &pane.secondary_cursors {
+            let (col, row) = cursor.position();
+            let (Some(col), Some(row)) = (
+                col.checked_sub(self.state.col_offset),
+                self.rows.iter().position(|&r| r == row),
+            ) else {
+                continue;
+            };
+            let symbol = rows
+                .get(row)
+                .and_then(|r| r.chars().nth(col))
+                .map_or_else(|| " ".to_string(), String::from);
+            buffer_viewport.merge_cell(
+                col,
+                row,
+                Cell::new(&symbol).with_style(Style::new().reverse()),
+            );
         }
+
+        self.cache = Some((key, pane_viewport.cells()));
+    }
+
+    /// Drops the cells of the last render, forcing the next frame to render the pane again.
+    pub fn invalidate(&mut self) {
+        self.cache = None;
     }
 }
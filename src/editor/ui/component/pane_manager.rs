@@ -2,10 +2,10 @@ use crate::editor::{
     pane::manager::PaneManager,
     ui::{
         component::{
-            Component, RenderingContext,
+            ClickAction, Component, RenderingContext,
             pane::{BarsLayout, PaneView},
         },
-        geometry::{point::Point, rect::Rect},
+        geometry::{anchor::Anchor, point::Point, rect::Rect},
         viewport::Viewport,
     },
 };
@@ -31,41 +31,90 @@ impl PaneManagerView {
 
         // Update the rects based on layout.
         let layout = BarsLayout::calculate_layout(num_panes, rect);
-        for (view, rect) in self.pane_views.iter_mut().zip(layout.rects.iter()) {
-            view.update_size(*rect);
+        for ((view, rect), pane) in self
+            .pane_views
+            .iter_mut()
+            .zip(layout.rects.iter())
+            .zip(manager.iter())
+        {
+            view.update_size(*rect, num_panes > 1, pane.buffer_lines());
         }
     }
 
+    /// Returns the index of the pane containing the given screen position when `num_panes` panes
+    /// are laid out in `rect`, or `None` if no pane contains it.
+    pub fn pane_at(rect: Rect, num_panes: usize, point: Point) -> Option<usize> {
+        BarsLayout::calculate_layout(num_panes, rect)
+            .rects
+            .iter()
+            .position(|pane_rect| pane_rect.contains(point))
+    }
+
     /// Returns the screen position of the active pane's cursor.
     pub fn get_active_cursor_screen_position(&self, manager: &PaneManager) -> Point {
         let active_index = manager.active_pane();
-        let active_view = self.pane_views[active_index];
-        let local_cursor_position = manager.active().cursor_position();
+        let active_view = &self.pane_views[active_index];
+        let active = manager.active();
+        let local_cursor_position = active.cursor_position();
 
-        let Point { mut col, mut row } = active_view.coord_to_screen(local_cursor_position.into());
-        col += active_view.rect.col + self.rect.col;
-        row += active_view.rect.row + self.rect.row;
+        // The rects of the pane views are already in screen coordinates.
+        let Point { mut col, mut row } =
+            active_view.coord_to_screen(local_cursor_position.into(), &active.folds);
+        col += active_view.rect.col;
+        row += active_view.rect.row;
         Point::new(col, row)
     }
 }
 
 impl Component for PaneManagerView {
-    fn rect(&self, editor_view: Rect) -> Rect {
-        Rect::new(
-            0,
-            0,
-            editor_view.width,
-            editor_view.height.saturating_sub(1),
-        )
+    fn rect(&self, parent: Rect) -> Rect {
+        // Leave the bottom row for the status bar.
+        Rect::new(0, 0, parent.width, parent.height.saturating_sub(1))
+            .anchored_on(parent, Anchor::TopLeft)
     }
 
     fn render(&mut self, ctx: &RenderingContext, mut viewport: Viewport) {
         self.rect = viewport.rect();
-        self.sync_panes(&ctx.pane_manager, self.rect);
+        self.sync_panes(ctx.pane_manager, self.rect);
 
-        for (pane, pane_view) in ctx.pane_manager.iter().zip(self.pane_views.iter_mut()) {
+        for (index, (pane, pane_view)) in ctx
+            .pane_manager
+            .iter()
+            .zip(self.pane_views.iter_mut())
+            .enumerate()
+        {
             let pane_viewport = viewport.sub_rect(pane_view.rect).unwrap();
-            pane_view.render(ctx, pane, pane_viewport);
+            pane_view.render(ctx, pane, index, pane_viewport);
+        }
+    }
+
+    fn invalidate(&mut self) {
+        for view in &mut self.pane_views {
+            view.invalidate();
+        }
+    }
+
+    fn cursor_position(&self, ctx: &RenderingContext) -> Option<Point> {
+        // The welcome screen covers the panes.
+        if ctx.welcome.is_some() {
+            return None;
+        }
+        Some(self.get_active_cursor_screen_position(ctx.pane_manager))
+    }
+
+    fn handle_click(&mut self, point: Point) -> Option<ClickAction> {
+        let (index, view) = self
+            .pane_views
+            .iter()
+            .enumerate()
+            .find(|(_, view)| view.rect.contains(point))?;
+        // Clicks on the title bar are not handled.
+        let row = (point.row - view.rect.row).checked_sub(view.title_bar_height())?;
+        if point.col - view.rect.col < view.gutter.width() {
+            // Lines past the end of the buffer go to the last row.
+            let row = view.rows.get(row).copied().unwrap_or(usize::MAX);
+            return view.gutter.handle_click(index, row);
         }
+        None
     }
 }
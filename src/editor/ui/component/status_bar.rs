@@ -1,14 +1,20 @@
-use std::time::{Duration, Instant};
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
 
 mod widget;
 
 use crate::editor::ui::{
     component::{
         Component, RenderingContext,
-        status_bar::widget::{CursorWidget, FileWidget, MessageWidget, ModeWidget},
+        status_bar::widget::{
+            CursorWidget, DiagnosticsWidget, EncodingWidget, FileWidget, FiletypeWidget, GitWidget,
+            MessageWidget, ModeWidget, PositionWidget, TaskWidget,
+        },
     },
     geometry::{anchor::Anchor, rect::Rect},
-    theme::highlight_group::HL_UI_STATUSBAR,
+    theme::highlight_group::{HL_UI_STATUSBAR, HL_UI_STATUSBAR_UNFOCUSED},
     viewport::Viewport,
     widget::container::{Alignment, ContainerBuilder},
 };
@@ -17,9 +23,32 @@ use crate::editor::ui::{
 pub enum MessageType {
     #[default]
     Info,
+    Warning,
     Error,
 }
 
+impl MessageType {
+    /// Returns the default duration for which messages of this type are displayed.
+    pub fn default_duration(&self) -> Duration {
+        match self {
+            MessageType::Info => Duration::from_secs(5),
+            MessageType::Warning => Duration::from_secs(7),
+            MessageType::Error => Duration::from_secs(10),
+        }
+    }
+}
+
+impl fmt::Display for MessageType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            MessageType::Info => "info",
+            MessageType::Warning => "warn",
+            MessageType::Error => "error",
+        };
+        write!(f, "{s}")
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Message {
     /// The content of the message.
@@ -33,16 +62,15 @@ pub struct Message {
 }
 
 impl Message {
-    const DEFAULT_MESSAGE_TIMEOUT: Duration = Duration::from_secs(5);
-
-    /// Creates a new message with the given content. By default, the messages duration will be set
-    /// to [`DEFAULT_MESSAGE_TIMEOUT`].
+    /// Creates a new message with the given content. By default, the message will be an info
+    /// message displayed for the default duration of [`MessageType::Info`].
     pub fn new(content: &str) -> Self {
+        let message_type = MessageType::default();
         Self {
             content: content.to_string(),
             set_time: Instant::now(),
-            duration: Self::DEFAULT_MESSAGE_TIMEOUT,
-            message_type: MessageType::default(),
+            duration: message_type.default_duration(),
+            message_type,
         }
     }
 
@@ -52,20 +80,48 @@ impl Message {
         self
     }
 
-    /// Sets the message type.
+    /// Makes the message stay until it is replaced or dismissed.
+    pub fn sticky(self) -> Self {
+        self.with_duration(Duration::MAX)
+    }
+
+    /// Sets the message type and resets the duration to the default duration of the type.
     pub fn with_type(mut self, message_type: MessageType) -> Self {
         self.message_type = message_type;
+        self.duration = message_type.default_duration();
         self
     }
 
-    /// Returns the content of the message.
+    /// Returns the first line of the content of the message, prefixed with a marker for its
+    /// type. Messages with multiple lines refer to the message log for the rest.
     pub fn text(&self) -> String {
+        let mut lines = self.content.lines();
+        let mut content = lines.next().unwrap_or_default().to_string();
+        if lines.next().is_some() {
+            content.push_str(" (see message log)");
+        }
         match self.message_type {
-            MessageType::Info => self.content.clone(),
-            MessageType::Error => format!("Error: {}", self.content),
+            MessageType::Info => content,
+            MessageType::Warning => format!("! {content}"),
+            MessageType::Error => format!("✗ {content}"),
         }
     }
 
+    /// Returns the type of the message.
+    pub fn message_type(&self) -> MessageType {
+        self.message_type
+    }
+
+    /// Returns the raw content of the message without any marker.
+    pub fn content(&self) -> &str {
+        &self.content
+    }
+
+    /// Restarts the display timer of the message.
+    pub fn restart(&mut self) {
+        self.set_time = Instant::now();
+    }
+
     /// Returns true if the message has timed out.
     pub fn timed_out(&self) -> bool {
         self.set_time.elapsed() > self.duration
@@ -101,21 +157,45 @@ impl Component for StatusBar {
     }
 
     fn render(&mut self, ctx: &RenderingContext, mut viewport: Viewport) {
-        let style = ctx.theme.resolve(&HL_UI_STATUSBAR);
-        let left_container = ContainerBuilder::default()
+        let style = if ctx.state.focused {
+            ctx.state.theme.resolve(&HL_UI_STATUSBAR)
+        } else {
+            ctx.state.theme.resolve(&HL_UI_STATUSBAR_UNFOCUSED)
+        };
+        let mut left_builder = ContainerBuilder::default()
             .with_child(ModeWidget::new(ctx))
-            .with_child(FileWidget::new(ctx))
-            .build()
-            .with_whitespace_separator(1);
+            .with_child(FileWidget::new(ctx));
+        // Only show the git widget if the active buffer is inside a repository.
+        if ctx.state.config.statusbar.show_git_branch
+            && let Some(git_widget) = GitWidget::new(ctx)
+        {
+            left_builder = left_builder.with_child(git_widget);
+        }
+        // Only show the task widget while background tasks are running.
+        if let Some(task_widget) = TaskWidget::new(ctx) {
+            left_builder = left_builder.with_child(task_widget);
+        }
+        let left_container = left_builder.build().with_whitespace_separator(1);
         // TODO: Make this expand.
         let center_container = ContainerBuilder::default()
             .with_child(MessageWidget::new(ctx))
             .with_alignment(Alignment::Center)
             .build();
-        let right_container = ContainerBuilder::default()
+        let mut right_builder = ContainerBuilder::default();
+        // Only show the diagnostics widget if the active buffer has errors or warnings.
+        if let Some(diagnostics_widget) = DiagnosticsWidget::new(ctx) {
+            right_builder = right_builder.with_child(diagnostics_widget);
+        }
+        if ctx.state.config.statusbar.show_encoding {
+            right_builder = right_builder.with_child(EncodingWidget::new(ctx));
+        }
+        let right_container = right_builder
+            .with_child(FiletypeWidget::new(ctx))
             .with_child(CursorWidget::new(ctx))
+            .with_child(PositionWidget::new(ctx))
             .with_alignment(Alignment::Right)
-            .build();
+            .build()
+            .with_whitespace_separator(2);
 
         // Main widget container.
         let widget = ContainerBuilder::default()
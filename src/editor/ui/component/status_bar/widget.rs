@@ -2,7 +2,11 @@ use crate::editor::ui::{
     component::{RenderingContext, status_bar::MessageType},
     frame::Cell,
     style::Style,
-    theme::highlight_group::HL_UI_STATUSBAR_MESSAGE_ERROR,
+    theme::highlight_group::{
+        HL_UI_STATUSBAR_DIAGNOSTICS_ERROR, HL_UI_STATUSBAR_DIAGNOSTICS_WARNING,
+        HL_UI_STATUSBAR_ENCODING_LOSSY, HL_UI_STATUSBAR_MESSAGE_ERROR,
+        HL_UI_STATUSBAR_MESSAGE_WARNING,
+    },
     widget::{
         Widget,
         container::{Container, ContainerBuilder},
@@ -18,11 +22,11 @@ pub struct ModeWidget {
 
 impl ModeWidget {
     pub fn new(ctx: &RenderingContext) -> Self {
-        let style = ctx.theme.resolve(ctx.mode.into());
+        let style = ctx.state.theme.resolve(ctx.state.mode.into());
         Self {
             container: ContainerBuilder::default()
                 .with_child(WhitespaceSeparator::default())
-                .with_child(Span::new(&ctx.mode.to_string()))
+                .with_child(Span::new(&ctx.state.mode.to_string()))
                 .with_child(WhitespaceSeparator::default())
                 .with_style(style)
                 .build(),
@@ -56,7 +60,10 @@ pub struct FileWidget {
 impl FileWidget {
     pub fn new(ctx: &RenderingContext) -> Self {
         let active_pane = ctx.pane_manager.active();
-        let file_name = active_pane.file_name();
+        let mut file_name = active_pane.file_name();
+        if active_pane.is_read_only() {
+            file_name.push_str(" [RO]");
+        }
         let style = if active_pane.is_dirty() {
             Style::new().bold().underline()
         } else {
@@ -90,6 +97,76 @@ impl Widget for FileWidget {
     }
 }
 
+/// A widget that displays the git branch of the active buffer.
+pub struct GitWidget {
+    container: Container,
+}
+
+impl GitWidget {
+    /// Returns a new git widget, or `None` if the active buffer is not inside a repository.
+    pub fn new(ctx: &RenderingContext) -> Option<Self> {
+        let branch = ctx.git_branch.as_ref()?;
+        Some(Self {
+            container: ContainerBuilder::default()
+                .with_child(Span::new(branch))
+                .build(),
+        })
+    }
+}
+
+impl Widget for GitWidget {
+    fn as_cells(&mut self) -> Vec<Cell> {
+        self.container.as_cells()
+    }
+
+    fn width(&self) -> usize {
+        self.container.width()
+    }
+
+    fn set_width(&mut self, width: Option<usize>) {
+        self.container.set_width(width);
+    }
+
+    fn set_style(&mut self, style: Style) {
+        self.container.set_style(style);
+    }
+}
+
+/// A widget that displays a spinner and the label of the most recent background task.
+pub struct TaskWidget {
+    container: Container,
+}
+
+impl TaskWidget {
+    /// Returns a new task widget, or `None` if no background tasks are running.
+    pub fn new(ctx: &RenderingContext) -> Option<Self> {
+        let status = ctx.tasks.status()?;
+        Some(Self {
+            container: ContainerBuilder::default()
+                .with_child(Span::new(&status))
+                .build(),
+        })
+    }
+}
+
+impl Widget for TaskWidget {
+    fn as_cells(&mut self) -> Vec<Cell> {
+        self.container.as_cells()
+    }
+
+    fn width(&self) -> usize {
+        self.container.width()
+    }
+
+    fn set_width(&mut self, width: Option<usize>) {
+        self.container.set_width(width);
+    }
+
+    fn set_style(&mut self, style: Style) {
+        self.container.set_style(style);
+    }
+}
+
 /// A widget that displays the current message.
 pub struct MessageWidget {
     container: Container,
@@ -97,17 +174,23 @@ pub struct MessageWidget {
 
 impl MessageWidget {
     pub fn new(ctx: &RenderingContext) -> Self {
-        let status_messsage = ctx.status_message.as_ref();
-        let message = status_messsage
-            .map(|m| m.text().to_string())
-            .unwrap_or_default();
-
-        let style = if let Some(message) = status_messsage
-            && message.message_type == MessageType::Error
-        {
-            ctx.theme.resolve(&HL_UI_STATUSBAR_MESSAGE_ERROR)
-        } else {
-            Style::default()
+        // The palette hint only covers the status message, which is shown again once command
+        // mode is left.
+        if let Some(hint) = &ctx.palette_hint {
+            return Self {
+                container: ContainerBuilder::default()
+                    .with_child(Span::new(hint))
+                    .build(),
+            };
+        }
+
+        let status_message = ctx.state.status_message.as_ref();
+        let message = status_message.map(|m| m.text()).unwrap_or_default();
+
+        let style = match status_message.map(|m| m.message_type()) {
+            Some(MessageType::Error) => ctx.state.theme.resolve(&HL_UI_STATUSBAR_MESSAGE_ERROR),
+            Some(MessageType::Warning) => ctx.state.theme.resolve(&HL_UI_STATUSBAR_MESSAGE_WARNING),
+            Some(MessageType::Info) | None => Style::default(),
         };
 
         Self {
@@ -137,15 +220,166 @@ impl Widget for MessageWidget {
     }
 }
 
+/// A widget that displays the filetype and indentation of the active buffer.
+pub struct FiletypeWidget {
+    container: Container,
+}
+
+impl FiletypeWidget {
+    pub fn new(ctx: &RenderingContext) -> Self {
+        let active_pane = ctx.pane_manager.active();
+        let filetype = active_pane.filetype();
+        let indent = active_pane
+            .indent()
+            .unwrap_or(ctx.default_indent())
+            .to_string();
+        Self {
+            container: ContainerBuilder::default()
+                .with_child(Span::new(&filetype))
+                .with_child(Span::new(&indent))
+                .build()
+                .with_whitespace_separator(1),
+        }
+    }
+}
+
+impl Widget for FiletypeWidget {
+    fn as_cells(&mut self) -> Vec<Cell> {
+        self.container.as_cells()
+    }
+
+    fn width(&self) -> usize {
+        self.container.width()
+    }
+
+    fn set_width(&mut self, width: Option<usize>) {
+        self.container.set_width(width);
+    }
+
+    fn set_style(&mut self, style: Style) {
+        self.container.set_style(style);
+    }
+}
+
+/// A widget that displays the number of errors and warnings in the active buffer.
+pub struct DiagnosticsWidget {
+    container: Container,
+}
+
+impl DiagnosticsWidget {
+    /// Returns a new diagnostics widget, or `None` if the active buffer has no errors or
+    /// warnings.
+    pub fn new(ctx: &RenderingContext) -> Option<Self> {
+        let path = std::path::absolute(ctx.pane_manager.active().file_path()?).ok()?;
+        let (errors, warnings) = ctx.diagnostics.counts(&path);
+        if errors == 0 && warnings == 0 {
+            return None;
+        }
+
+        let mut builder = ContainerBuilder::default();
+        if errors > 0 {
+            let style = ctx.state.theme.resolve(&HL_UI_STATUSBAR_DIAGNOSTICS_ERROR);
+            builder = builder.with_child(Span::new(&format!("✗ {errors}")).with_style(style));
+        }
+        if warnings > 0 {
+            let style = ctx
+                .state
+                .theme
+                .resolve(&HL_UI_STATUSBAR_DIAGNOSTICS_WARNING);
+            builder = builder.with_child(Span::new(&format!("! {warnings}")).with_style(style));
+        }
+        Some(Self {
+            container: builder.build().with_whitespace_separator(1),
+        })
+    }
+}
+
+impl Widget for DiagnosticsWidget {
+    fn as_cells(&mut self) -> Vec<Cell> {
+        self.container.as_cells()
+    }
+
+    fn width(&self) -> usize {
+        self.container.width()
+    }
+
+    fn set_width(&mut self, width: Option<usize>) {
+        self.container.set_width(width);
+    }
+
+    fn set_style(&mut self, style: Style) {
+        self.container.set_style(style);
+    }
+}
+
+/// A widget that displays the encoding and line ending of the active buffer.
+pub struct EncodingWidget {
+    container: Container,
+}
+
+impl EncodingWidget {
+    pub fn new(ctx: &RenderingContext) -> Self {
+        let active_pane = ctx.pane_manager.active();
+        let (encoding, style) = if active_pane.is_lossy() {
+            (
+                "utf-8 (lossy)",
+                ctx.state.theme.resolve(&HL_UI_STATUSBAR_ENCODING_LOSSY),
+            )
+        } else {
+            ("utf-8", Style::default())
+        };
+        let line_ending = format!("| {}", active_pane.line_ending());
+
+        Self {
+            container: ContainerBuilder::default()
+                .with_child(Span::new(encoding).with_style(style))
+                .with_child(Span::new(&line_ending))
+                .build()
+                .with_whitespace_separator(1),
+        }
+    }
+}
+
+impl Widget for EncodingWidget {
+    fn as_cells(&mut self) -> Vec<Cell> {
+        self.container.as_cells()
+    }
+
+    fn width(&self) -> usize {
+        self.container.width()
+    }
+
+    fn set_width(&mut self, width: Option<usize>) {
+        self.container.set_width(width);
+    }
+
+    fn set_style(&mut self, style: Style) {
+        self.container.set_style(style);
+    }
+}
+
 /// A widget that displays the cursor position.
 pub struct CursorWidget {
     container: Container,
 }
 
 impl CursorWidget {
+    /// The minimum width of the column, which fits the columns of most rows.
+    const MIN_COL_WIDTH: usize = 3;
+
     pub fn new(ctx: &RenderingContext) -> Self {
-        let (cursor_col, cursor_row) = ctx.pane_manager.active().cursor_position();
-        let cursor_position = format!("{}:{}", cursor_row + 1, cursor_col + 1);
+        let active_pane = ctx.pane_manager.active();
+        let (cursor_col, cursor_row) = active_pane.cursor_position();
+
+        // Pad the row to the width of the last row and the column to a fixed width, so that the
+        // widget does not jitter as the cursor moves between rows of different lengths.
+        let row_width = num_digits(active_pane.buffer_lines());
+        let col_width = num_digits(cursor_col + 1).max(Self::MIN_COL_WIDTH);
+        let cursor_position = format!(
+            "{:>row_width$}:{:<col_width$}",
+            cursor_row + 1,
+            cursor_col + 1
+        );
         Self {
             container: ContainerBuilder::default()
                 .with_child(Span::new(&cursor_position))
@@ -171,3 +405,60 @@ impl Widget for CursorWidget {
         self.container.set_style(style);
     }
 }
+
+/// A widget that displays the cursor position relative to the buffer and the number of lines.
+pub struct PositionWidget {
+    container: Container,
+}
+
+impl PositionWidget {
+    pub fn new(ctx: &RenderingContext) -> Self {
+        let active_pane = ctx.pane_manager.active();
+        let visible_rows = active_pane.view_state().height;
+        let cursor_row = active_pane.cursor_position().1;
+        let buffer_lines = active_pane.buffer_lines();
+
+        let position = if buffer_lines <= visible_rows {
+            "All".to_string()
+        } else if cursor_row == 0 {
+            "Top".to_string()
+        } else if cursor_row + 1 >= buffer_lines {
+            "Bot".to_string()
+        } else {
+            let percentage = (cursor_row + 1) * 100 / buffer_lines;
+            format!("{percentage:>2}%")
+        };
+        let lines = format!("{buffer_lines} lines");
+
+        Self {
+            container: ContainerBuilder::default()
+                .with_child(Span::new(&position))
+                .with_child(Span::new(&lines))
+                .build()
+                .with_whitespace_separator(2),
+        }
+    }
+}
+
+impl Widget for PositionWidget {
+    fn as_cells(&mut self) -> Vec<Cell> {
+        self.container.as_cells()
+    }
+
+    fn width(&self) -> usize {
+        self.container.width()
+    }
+
+    fn set_width(&mut self, width: Option<usize>) {
+        self.container.set_width(width);
+    }
+
+    fn set_style(&mut self, style: Style) {
+        self.container.set_style(style);
+    }
+}
+
+/// Returns the number of decimal digits in `n`.
+fn num_digits(n: usize) -> usize {
+    n.checked_ilog10().unwrap_or_default() as usize + 1
+}
@@ -0,0 +1,130 @@
+use crate::editor::ui::{
+    component::{ClickAction, Component, RenderingContext},
+    geometry::{anchor::Anchor, point::Point, rect::Rect},
+    theme::highlight_group::{HL_UI_TABLINE, HL_UI_TABLINE_ACTIVE},
+    unicode::str_width,
+    viewport::Viewport,
+    widget::{container::ContainerBuilder, span::Span},
+};
+
+/// A single tab in the tab line.
+struct Tab {
+    /// The id of the buffer the tab represents.
+    buffer_id: usize,
+    /// The label of the tab.
+    label: String,
+    /// Whether the tab represents the buffer of the active pane.
+    active: bool,
+}
+
+/// A tab line listing all open buffers across the top of the editor.
+#[derive(Debug, Default, Clone)]
+pub struct TabLine {
+    /// The index of the first visible tab.
+    first_visible: usize,
+    /// The screen area of each visible tab in the last frame and the id of its buffer.
+    tab_rects: Vec<(Rect, usize)>,
+}
+
+impl TabLine {
+    /// The height of the tab line.
+    pub const HEIGHT: usize = 1;
+    /// The label to use for buffers without a file.
+    const NO_FILENAME: &str = "[No Filename]";
+
+    /// Returns the tabs for all open buffers.
+    fn tabs(ctx: &RenderingContext) -> Vec<Tab> {
+        let active_buffer_id = ctx.pane_manager.active().buffer_id();
+        ctx.buffer_manager
+            .iter()
+            .map(|entry| {
+                let (name, dirty) = entry
+                    .buffer
+                    .try_read()
+                    .map(|buffer| {
+                        let name = buffer
+                            .filepath()
+                            .and_then(|path| path.file_name())
+                            .map(|name| name.to_string_lossy().to_string())
+                            .unwrap_or_else(|| buffer.file_name());
+                        (name, buffer.is_dirty())
+                    })
+                    .unwrap_or((Self::NO_FILENAME.to_string(), false));
+
+                let marker = if dirty { "*" } else { "" };
+                Tab {
+                    buffer_id: entry.id,
+                    label: format!(" {name}{marker} "),
+                    active: entry.id == active_buffer_id,
+                }
+            })
+            .collect()
+    }
+
+    /// Scrolls the tab line such that the active tab is visible within the given width.
+    fn scroll_to_active(&mut self, tabs: &[Tab], width: usize) {
+        let Some(active) = tabs.iter().position(|tab| tab.active) else {
+            self.first_visible = 0;
+            return;
+        };
+
+        self.first_visible = self.first_visible.min(active);
+        while self.first_visible < active
+            && tabs[self.first_visible..=active]
+                .iter()
+                .map(|tab| tab.label.chars().count())
+                .sum::<usize>()
+                > width
+        {
+            self.first_visible += 1;
+        }
+    }
+}
+
+impl Component for TabLine {
+    fn rect(&self, parent: Rect) -> Rect {
+        Rect::new(0, 0, parent.width, Self::HEIGHT).anchored_on(parent, Anchor::TopLeft)
+    }
+
+    fn is_visible(&self, ctx: &RenderingContext) -> bool {
+        ctx.state.config.editor.show_tabline
+    }
+
+    fn render(&mut self, ctx: &RenderingContext, mut viewport: Viewport) {
+        let tabs = Self::tabs(ctx);
+        self.scroll_to_active(&tabs, viewport.width());
+
+        let style = ctx.state.theme.resolve(&HL_UI_TABLINE);
+        let active_style = ctx.state.theme.resolve(&HL_UI_TABLINE_ACTIVE);
+
+        let mut builder = ContainerBuilder::default()
+            .with_width(Some(viewport.width()))
+            .with_style(style);
+        let rect = viewport.rect();
+        let mut col = rect.col;
+        self.tab_rects.clear();
+        for tab in tabs.iter().skip(self.first_visible) {
+            let tab_style = if tab.active { active_style } else { style };
+            builder = builder.with_child(Span::new(&tab.label).with_style(tab_style));
+
+            // Tabs cut off at the right edge are only clickable where they are visible.
+            let width = str_width(&tab.label);
+            if let Some(tab_rect) = Rect::new(col, rect.row, width, Self::HEIGHT).intersection(rect)
+            {
+                self.tab_rects.push((tab_rect, tab.buffer_id));
+            }
+            col += width;
+        }
+        viewport.put_widget(0, builder.build());
+    }
+
+    fn handle_click(&mut self, point: Point) -> Option<ClickAction> {
+        let (_, buffer_id) = self
+            .tab_rects
+            .iter()
+            .find(|(rect, _)| rect.contains(point))?;
+        Some(ClickAction::ShowBuffer {
+            buffer_id: *buffer_id,
+        })
+    }
+}
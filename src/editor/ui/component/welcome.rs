@@ -0,0 +1,101 @@
+use crate::editor::ui::{
+    component::{Component, RenderingContext},
+    frame::Cell,
+    geometry::{anchor::Anchor, rect::Rect},
+    theme::highlight_group::HL_UI_PANE,
+    viewport::Viewport,
+    widget::{
+        Widget,
+        column::{ColumnBuilder, VerticalAlignment},
+        container::{Alignment, ContainerBuilder},
+        span::Span,
+    },
+};
+
+/// A screen covering the panes while the empty buffer the editor started with is untouched,
+/// showing the version and the keys of a few commands.
+#[derive(Debug, Default, Clone)]
+pub struct WelcomeView;
+
+impl WelcomeView {
+    /// The number of columns between a key and the description of its command.
+    const KEY_GAP: usize = 2;
+
+    /// Returns the lines listing the given keys and descriptions below the title, with the
+    /// descriptions aligned.
+    fn hint_lines(hints: &[(String, &str)]) -> Vec<String> {
+        let key_width = hints
+            .iter()
+            .map(|(key, _)| key.chars().count())
+            .max()
+            .unwrap_or_default()
+            + Self::KEY_GAP;
+        hints
+            .iter()
+            .map(|(key, description)| format!("{key:<key_width$}{description}"))
+            .collect()
+    }
+}
+
+impl Component for WelcomeView {
+    fn rect(&self, parent: Rect) -> Rect {
+        // Leave the bottom row for the status bar.
+        Rect::new(0, 0, parent.width, parent.height.saturating_sub(1))
+            .anchored_on(parent, Anchor::TopLeft)
+    }
+
+    fn render(&mut self, ctx: &RenderingContext, mut viewport: Viewport) {
+        let Some(hints) = &ctx.welcome else {
+            return;
+        };
+        let style = ctx.state.theme.resolve(&HL_UI_PANE);
+        viewport.fill(Cell::default().with_style(style));
+
+        let title = format!("{} v{}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"));
+        let hints = Self::hint_lines(hints);
+        let width = hints
+            .iter()
+            .chain([&title])
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or_default()
+            .min(viewport.width());
+        // Center the lines horizontally in a strip as wide as the longest line, and vertically
+        // by the column.
+        let rect = Rect::new(0, 0, width, viewport.height()).anchored_on(
+            Rect::new(0, 0, viewport.width(), viewport.height()),
+            Anchor::TopCenter,
+        );
+
+        let line = |text: &str, alignment: Alignment| {
+            ContainerBuilder::default()
+                .with_width(Some(width))
+                .with_child(Span::new(text))
+                .with_alignment(alignment)
+                .with_style(style)
+                .build()
+        };
+        // The title and the hints are separated by an empty line. The title is kept when the
+        // hints do not fit.
+        let alignment = if hints.len() + 2 > viewport.height() {
+            VerticalAlignment::Top
+        } else {
+            VerticalAlignment::Center
+        };
+        let column = ColumnBuilder::default()
+            .with_sized_child(line(&title, Alignment::Center), 2)
+            .with_children(
+                hints
+                    .iter()
+                    .map(|hint| Box::new(line(hint, Alignment::Left)) as Box<dyn Widget + 'static>),
+            )
+            .with_alignment(alignment)
+            .with_style(style)
+            .build();
+        viewport.put_column(rect, column);
+    }
+
+    fn is_visible(&self, ctx: &RenderingContext) -> bool {
+        ctx.welcome.is_some()
+    }
+}
@@ -1,9 +1,11 @@
-use crate::editor::ui::{geometry::point::Point, style::Style};
+use crate::editor::ui::{geometry::point::Point, style::Style, unicode::grapheme_width};
 
 /// A frame is a rectangular region of the terminal.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Cell {
-    pub char: char,
+    /// The grapheme cluster shown in the cell. Cells covered by the second column of a wide
+    /// cluster hold an empty symbol.
+    pub symbol: String,
     pub style: Style,
 }
 
@@ -11,20 +13,33 @@ impl Default for Cell {
     fn default() -> Self {
         Self {
             // Use a space as the default character to overwrite the previous character.
-            char: ' ',
+            symbol: " ".to_string(),
             style: Default::default(),
         }
     }
 }
 
 impl Cell {
-    pub fn new(char: char) -> Self {
+    pub fn new(symbol: &str) -> Self {
         Self {
-            char,
+            symbol: symbol.to_string(),
             style: Style::default(),
         }
     }
 
+    /// Returns a cell covered by the second column of a wide grapheme cluster.
+    pub fn continuation() -> Self {
+        Self {
+            symbol: String::new(),
+            style: Style::default(),
+        }
+    }
+
+    /// Returns the number of columns the symbol of the cell occupies in the terminal.
+    pub fn width(&self) -> usize {
+        grapheme_width(&self.symbol)
+    }
+
     /// Sets the style of the cell.
     pub fn with_style(mut self, style: Style) -> Self {
         self.style = style;
@@ -33,7 +48,7 @@ impl Cell {
 
     /// Applies the given cell over the current cell.
     pub fn apply(&mut self, other: &Cell) {
-        self.char = other.char;
+        self.symbol.clone_from(&other.symbol);
         self.style.force_apply(other.style);
     }
 }
@@ -60,25 +75,44 @@ impl Frame {
         }
     }
 
-    /// Puts a new cell in the given position.
+    /// Returns the index of the cell at the given position, or `None` if the position is out of
+    /// bounds.
+    fn index(&self, col: usize, row: usize) -> Option<usize> {
+        if col >= self.width || row >= self.height {
+            return None;
+        }
+        Some(row * self.width + col)
+    }
+
+    /// Returns the width and height of the frame.
+    pub fn size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    /// Puts a new cell in the given position. If the position is out of bounds, it will be
+    /// ignored.
     pub fn put_cell(&mut self, col: usize, row: usize, cell: Cell) {
-        let index = row * self.width + col;
-        self.cells[index] = cell;
+        if let Some(index) = self.index(col, row) {
+            self.cells[index] = cell;
+        }
     }
 
-    pub fn cell_mut(&mut self, col: usize, row: usize) -> &mut Cell {
-        let index = row * self.width + col;
-        &mut self.cells[index]
+    /// Returns the cell at the given position, or `None` if the position is out of bounds.
+    pub fn cell(&self, col: usize, row: usize) -> Option<&Cell> {
+        let index = self.index(col, row)?;
+        self.cells.get(index)
     }
 
-    /// Sets the cursor position for this frame.
-    pub fn set_cursor_position(&mut self, point: Point) {
-        self.cursor_position = Some(point);
+    /// Returns a mutable reference to the cell at the given position, or `None` if the position
+    /// is out of bounds.
+    pub fn cell_mut(&mut self, col: usize, row: usize) -> Option<&mut Cell> {
+        let index = self.index(col, row)?;
+        self.cells.get_mut(index)
     }
 
-    /// Hides the cursor for this frame.
-    pub fn hide_cursor(&mut self) {
-        self.cursor_position = None;
+    /// Sets the cursor position for this frame. Positions outside the frame hide the cursor.
+    pub fn set_cursor_position(&mut self, point: Point) {
+        self.cursor_position = self.index(point.col, point.row).map(|_| point);
     }
 
     /// Returns the cursor position for this frame.
@@ -88,7 +122,8 @@ impl Frame {
 
     /// Returns this frame as a vector of rows.
     pub fn rows(&self) -> impl Iterator<Item = &[Cell]> {
-        self.cells.chunks_exact(self.width)
+        // A frame without width has no cells, but `chunks_exact` panics on a chunk size of zero.
+        self.cells.chunks_exact(self.width.max(1))
     }
 }
 
@@ -112,19 +147,104 @@ pub struct FrameDiff<'a> {
 }
 
 impl<'a> FrameDiff<'a> {
-    /// Returns the diff between two frames.
+    /// Returns the diff between two frames. If the frames have different dimensions, every cell
+    /// of the next frame is considered changed. A wide cell is considered changed if any of the
+    /// columns it covers changed, and the columns it covers are never part of the diff.
     pub fn compute(prev: &Frame, next: &'a Frame) -> Self {
         let mut cells = Vec::new();
+        let same_size = prev.width == next.width && prev.height == next.height;
 
         for row in 0..next.height {
-            for col in 0..next.width {
+            let mut col = 0;
+            while col < next.width {
                 let idx = row * next.width + col;
-                if prev.cells[idx] != next.cells[idx] {
-                    cells.push(RowDiff::new(col, row, &next.cells[idx]));
+                let cell = &next.cells[idx];
+                let width = cell.width().clamp(1, next.width - col);
+                let changed =
+                    !same_size || (idx..idx + width).any(|i| prev.cells[i] != next.cells[i]);
+                if changed && !cell.symbol.is_empty() {
+                    cells.push(RowDiff::new(col, row, cell));
                 }
+                col += width;
             }
         }
 
         Self { cells }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use super::*;
+    use crate::editor::ui::{geometry::rect::Rect, viewport::Viewport};
+
+    /// Returns the symbols of each row of the frame.
+    fn rows(frame: &Frame) -> Vec<String> {
+        frame
+            .rows()
+            .map(|row| row.iter().map(|cell| cell.symbol.as_str()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn out_of_bounds_cells_are_ignored() {
+        let mut frame = Frame::new(5, 3);
+        frame.put_cell(5, 0, Cell::new("x"));
+        frame.put_cell(0, 3, Cell::new("x"));
+        frame.put_cell(usize::MAX, usize::MAX, Cell::new("x"));
+        assert!(frame.cell(5, 0).is_none());
+        assert!(frame.cell(0, 3).is_none());
+        assert!(frame.cell_mut(5, 2).is_none());
+        assert_eq!(rows(&frame), ["     "; 3]);
+
+        frame.set_cursor_position(Point::new(5, 1));
+        assert_eq!(frame.cursor_position(), None);
+        frame.set_cursor_position(Point::new(4, 2));
+        assert_eq!(frame.cursor_position(), Some(Point::new(4, 2)));
+
+        let empty = Frame::new(0, 3);
+        assert!(empty.cell(0, 0).is_none());
+        assert_eq!(empty.rows().count(), 0);
+    }
+
+    #[test]
+    fn oversized_viewports_are_clipped_to_the_frame() {
+        let frame = RefCell::new(Frame::new(5, 3));
+        let mut viewport = Viewport::new(Rect::new(2, 1, 20, 10), &frame);
+        viewport.fill(Cell::new("#"));
+        viewport.merge_cell(19, 9, Cell::new("x"));
+        assert_eq!(viewport.cells().len(), 200);
+
+        assert_eq!(rows(&frame.borrow()), ["     ", "  ###", "  ###"]);
+    }
+
+    #[test]
+    fn diff_of_frames_with_different_sizes_has_every_cell() {
+        let prev = Frame::new(5, 3);
+        let next = Frame::new(4, 2);
+        assert_eq!(FrameDiff::compute(&prev, &next).cells.len(), 8);
+    }
+
+    #[test]
+    fn diff_covers_wide_cells_once() {
+        let prev = Frame::new(5, 3);
+        let mut next = Frame::new(5, 3);
+        next.put_cell(1, 0, Cell::new("界"));
+        next.put_cell(2, 0, Cell::continuation());
+        // A wide cell in the last column only changes the column it fits in.
+        next.put_cell(4, 2, Cell::new("界"));
+
+        let diff = FrameDiff::compute(&prev, &next);
+        let changed: Vec<_> = diff.cells.iter().map(|diff| (diff.col, diff.row)).collect();
+        assert_eq!(changed, [(1, 0), (4, 2)]);
+
+        // Changing the column covered by a wide cell redraws the wide cell.
+        let mut after = next.clone();
+        after.put_cell(2, 0, Cell::new("x"));
+        let diff = FrameDiff::compute(&next, &after);
+        let changed: Vec<_> = diff.cells.iter().map(|diff| (diff.col, diff.row)).collect();
+        assert_eq!(changed, [(1, 0)]);
+    }
+}
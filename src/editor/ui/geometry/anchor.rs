@@ -4,4 +4,10 @@ pub enum Anchor {
     TopRight,
     BottomLeft,
     BottomRight,
+    /// Centers the rect horizontally along the top edge.
+    TopCenter,
+    /// Centers the rect horizontally along the bottom edge.
+    BottomCenter,
+    /// Centers the rect both horizontally and vertically.
+    Center,
 }
@@ -31,6 +31,9 @@ impl Sub for Point {
     type Output = Point;
 
     fn sub(self, rhs: Self) -> Self::Output {
-        Point::new(self.col - rhs.col, self.row - rhs.row)
+        Point::new(
+            self.col.saturating_sub(rhs.col),
+            self.row.saturating_sub(rhs.row),
+        )
     }
 }
@@ -29,9 +29,30 @@ impl Rect {
             Anchor::BottomRight => {
                 self.move_to(parent.bottom_right() - Point::new(self.width, self.height))
             }
+            Anchor::TopCenter => self.move_to(Point::new(self.centered_col(parent), parent.row)),
+            Anchor::BottomCenter => self.move_to(Point::new(
+                self.centered_col(parent),
+                (parent.row + parent.height).saturating_sub(self.height),
+            )),
+            Anchor::Center => self.move_to(Point::new(
+                self.centered_col(parent),
+                self.centered_row(parent),
+            )),
         }
     }
 
+    /// Returns the column that centers this rect horizontally in the parent. When the leftover
+    /// space is odd, the extra column is put on the right.
+    fn centered_col(&self, parent: Rect) -> usize {
+        parent.col + parent.width.saturating_sub(self.width) / 2
+    }
+
+    /// Returns the row that centers this rect vertically in the parent. When the leftover space
+    /// is odd, the extra row is put at the bottom.
+    fn centered_row(&self, parent: Rect) -> usize {
+        parent.row + parent.height.saturating_sub(self.height) / 2
+    }
+
     /// Splits the rectangle into n equally-sized parts vertically.
     pub fn split_vertically_n(self, n: usize) -> Vec<Rect> {
         if n == 0 {
@@ -145,16 +166,52 @@ impl Rect {
         self
     }
 
-    /// Moves this rect by the given offset.
+    /// Moves this rect by the given offset, saturating at zero.
     pub fn offset(mut self, col: isize, row: isize) -> Rect {
-        self.col = (self.col as isize + col) as usize;
-        self.row = (self.row as isize + row) as usize;
+        self.col = self.col.saturating_add_signed(col);
+        self.row = self.row.saturating_add_signed(row);
         self
     }
 
-    /// Returns the size of the rect.
-    pub fn size(&self) -> (usize, usize) {
-        (self.width, self.height)
+    /// Clamps this rect to lie within the given parent rect.
+    pub fn clamp_within(self, parent: Rect) -> Rect {
+        let col = self.col.clamp(parent.col, parent.col + parent.width);
+        let row = self.row.clamp(parent.row, parent.row + parent.height);
+        let width = self.width.min(parent.col + parent.width - col);
+        let height = self.height.min(parent.row + parent.height - row);
+        Rect::new(col, row, width, height)
+    }
+
+    /// Shrinks the rect by the given margin on every side, saturating at an empty rect.
+    pub fn shrink(self, margin: usize) -> Rect {
+        let col_margin = margin.min(self.width / 2);
+        let row_margin = margin.min(self.height / 2);
+        Rect::new(
+            self.col + col_margin,
+            self.row + row_margin,
+            self.width.saturating_sub(margin.saturating_mul(2)),
+            self.height.saturating_sub(margin.saturating_mul(2)),
+        )
+    }
+
+    /// Returns `true` if the point lies within the rect. Points on the right and bottom edges
+    /// are outside of the rect.
+    pub fn contains(&self, Point { col, row }: Point) -> bool {
+        (self.col..self.col + self.width).contains(&col)
+            && (self.row..self.row + self.height).contains(&row)
+    }
+
+    /// Returns the overlapping area of this rect and the other rect, or `None` if they do not
+    /// overlap.
+    pub fn intersection(&self, other: Rect) -> Option<Rect> {
+        let col = self.col.max(other.col);
+        let row = self.row.max(other.row);
+        let right = (self.col + self.width).min(other.col + other.width);
+        let bottom = (self.row + self.height).min(other.row + other.height);
+        if col >= right || row >= bottom {
+            return None;
+        }
+        Some(Rect::new(col, row, right - col, bottom - row))
     }
 
     /// Returns the top left point of the rect.
@@ -177,3 +234,108 @@ impl Rect {
         Point::new(self.col + self.width, self.row + self.height)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn contains_excludes_right_and_bottom_edges() {
+        let rect = Rect::new(2, 3, 4, 2);
+        assert!(rect.contains(Point::new(2, 3)));
+        assert!(rect.contains(Point::new(5, 4)));
+        assert!(!rect.contains(Point::new(6, 3)));
+        assert!(!rect.contains(Point::new(2, 5)));
+        assert!(!rect.contains(Point::new(1, 3)));
+        assert!(!Rect::new(2, 3, 0, 2).contains(Point::new(2, 3)));
+    }
+
+    #[test]
+    fn intersection_of_touching_rects_is_none() {
+        let rect = Rect::new(0, 0, 4, 4);
+        assert_eq!(rect.intersection(Rect::new(4, 0, 2, 2)), None);
+        assert_eq!(rect.intersection(Rect::new(0, 4, 2, 2)), None);
+        assert_eq!(
+            rect.intersection(Rect::new(3, 3, 5, 5)),
+            Some(Rect::new(3, 3, 1, 1))
+        );
+        assert_eq!(
+            rect.intersection(Rect::new(1, 1, 2, 2)),
+            Some(Rect::new(1, 1, 2, 2))
+        );
+    }
+
+    #[test]
+    fn clamp_within_keeps_rects_inside_the_parent() {
+        let parent = Rect::new(2, 2, 5, 3);
+        assert_eq!(
+            Rect::new(3, 3, 2, 1).clamp_within(parent),
+            Rect::new(3, 3, 2, 1)
+        );
+        assert_eq!(
+            Rect::new(0, 0, 20, 20).clamp_within(parent),
+            Rect::new(2, 2, 5, 3)
+        );
+        assert_eq!(
+            Rect::new(6, 4, 4, 4).clamp_within(parent),
+            Rect::new(6, 4, 1, 1)
+        );
+        // A rect past the bottom right corner is empty on the edge of the parent.
+        assert_eq!(
+            Rect::new(9, 9, 2, 2).clamp_within(parent),
+            Rect::new(7, 5, 0, 0)
+        );
+    }
+
+    #[test]
+    fn shrink_saturates_at_an_empty_rect() {
+        let rect = Rect::new(2, 3, 5, 4);
+        assert_eq!(rect.shrink(0), rect);
+        assert_eq!(rect.shrink(1), Rect::new(3, 4, 3, 2));
+        assert_eq!(rect.shrink(2), Rect::new(4, 5, 1, 0));
+        // The empty rect stays within the original one.
+        assert_eq!(rect.shrink(3), Rect::new(4, 5, 0, 0));
+        assert_eq!(rect.shrink(usize::MAX), Rect::new(4, 5, 0, 0));
+    }
+
+    #[test]
+    fn anchors_place_rects_in_odd_and_even_parents() {
+        let child = Rect::new(0, 0, 2, 2);
+        // The extra column and row of odd leftover space goes to the right and bottom.
+        let cases = [
+            (
+                Rect::new(1, 1, 6, 4),
+                [(1, 1), (5, 1), (1, 3), (5, 3), (3, 1), (3, 3), (3, 2)],
+            ),
+            (
+                Rect::new(1, 1, 7, 5),
+                [(1, 1), (6, 1), (1, 4), (6, 4), (3, 1), (3, 4), (3, 2)],
+            ),
+        ];
+        let anchors = [
+            Anchor::TopLeft,
+            Anchor::TopRight,
+            Anchor::BottomLeft,
+            Anchor::BottomRight,
+            Anchor::TopCenter,
+            Anchor::BottomCenter,
+            Anchor::Center,
+        ];
+        for (parent, expected) in cases {
+            for (anchor, (col, row)) in anchors.into_iter().zip(expected) {
+                let rect = child.anchored_on(parent, anchor);
+                assert_eq!((rect.col, rect.row), (col, row), "{anchor:?} in {parent:?}");
+                assert_eq!((rect.width, rect.height), (2, 2));
+            }
+        }
+    }
+
+    #[test]
+    fn centered_rects_larger_than_the_parent_start_at_its_edge() {
+        let parent = Rect::new(1, 1, 3, 3);
+        let rect = Rect::new(0, 0, 6, 6).anchored_on(parent, Anchor::Center);
+        assert_eq!(rect, Rect::new(1, 1, 6, 6));
+        let rect = Rect::new(0, 0, 6, 1).anchored_on(parent, Anchor::TopCenter);
+        assert_eq!(rect, Rect::new(1, 1, 6, 1));
+    }
+}
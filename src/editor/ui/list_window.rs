@@ -0,0 +1,58 @@
+use std::ops::Range;
+
+/// The part of a list of selectable entries that fits on screen. The window follows the
+/// selected entry, and lists that do not fit reserve a row for an indicator of the position in
+/// the list.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ListWindow {
+    /// The index of the first visible entry.
+    first_visible: usize,
+}
+
+impl ListWindow {
+    /// Returns the number of entries shown when `rows` rows are available for a list of `len`
+    /// entries, leaving a row for the indicator if not all entries fit.
+    pub fn visible(len: usize, rows: usize) -> usize {
+        if len > rows {
+            rows.saturating_sub(1)
+        } else {
+            len
+        }
+    }
+
+    /// Scrolls the window such that the selected entry is visible when `rows` rows are
+    /// available, and returns the range of the visible entries.
+    pub fn scroll(&mut self, selected: usize, len: usize, rows: usize) -> Range<usize> {
+        let visible = Self::visible(len, rows);
+        if selected < self.first_visible {
+            self.first_visible = selected;
+        } else if selected >= self.first_visible + visible {
+            self.first_visible = (selected + 1).saturating_sub(visible);
+        }
+        // Keep the window filled after the list shrinks.
+        self.first_visible = self.first_visible.min(len.saturating_sub(visible));
+        self.first_visible..self.first_visible + visible
+    }
+
+    /// Resets the window to the start of the list.
+    pub fn reset(&mut self) {
+        self.first_visible = 0;
+    }
+
+    /// Returns the indicator of the position of the selected entry, such as `▲ 12/153 ▼`, or
+    /// `None` if all entries fit in `rows` rows. The lists are drawn upwards, so `▲` marks
+    /// entries after the window and `▼` entries before it.
+    pub fn indicator(&self, selected: usize, len: usize, rows: usize) -> Option<String> {
+        if len <= rows {
+            return None;
+        }
+        let visible = Self::visible(len, rows);
+        let after = if self.first_visible + visible < len {
+            "▲"
+        } else {
+            " "
+        };
+        let before = if self.first_visible > 0 { "▼" } else { " " };
+        Some(format!("{after} {}/{len} {before}", selected + 1))
+    }
+}
@@ -73,6 +73,8 @@ pub struct Style {
     pub bg: Option<Color>,
     pub intensity: Option<FontIntensity>,
     pub underline: Option<bool>,
+    /// Whether the foreground and background colors are swapped.
+    pub reverse: Option<bool>,
 }
 
 impl Style {
@@ -92,12 +94,6 @@ impl Style {
         self
     }
 
-    /// Sets the normal style.
-    pub fn normal(mut self) -> Self {
-        self.intensity = Some(FontIntensity::Normal);
-        self
-    }
-
     /// Sets the bold style.
     pub fn bold(mut self) -> Self {
         self.intensity = Some(FontIntensity::Bold);
@@ -116,6 +112,12 @@ impl Style {
         self
     }
 
+    /// Sets the reverse video style, swapping the foreground and background colors.
+    pub fn reverse(mut self) -> Self {
+        self.reverse = Some(true);
+        self
+    }
+
     /// Applies the given style to this style. Only unset values in the current style will get
     /// overwritten by the given style.
     pub fn apply(&mut self, other: Self) {
@@ -123,6 +125,7 @@ impl Style {
         self.bg = self.bg.or(other.bg);
         self.intensity = self.intensity.or(other.intensity);
         self.underline = self.underline.or(other.underline);
+        self.reverse = self.reverse.or(other.reverse);
     }
 
     /// Applies the given style to this style and overwrites all set values from the given style.
@@ -131,16 +134,7 @@ impl Style {
         self.bg = other.bg.or(self.bg);
         self.intensity = other.intensity.or(self.intensity);
         self.underline = other.underline.or(self.underline);
-    }
-
-    /// Applies the given style with this style. Only unset values in the current style will get
-    /// overwritten by the given style.
-    pub fn applied(mut self, other: Self) -> Self {
-        self.fg = self.fg.or(other.fg);
-        self.bg = self.bg.or(other.bg);
-        self.intensity = self.intensity.or(other.intensity);
-        self.underline = self.underline.or(other.underline);
-        self
+        self.reverse = other.reverse.or(self.reverse);
     }
 
     /// Applies the given style to this style and overwrites all set values from the given style.
@@ -149,6 +143,7 @@ impl Style {
         self.bg = other.bg.or(self.bg);
         self.intensity = other.intensity.or(self.intensity);
         self.underline = other.underline.or(self.underline);
+        self.reverse = other.reverse.or(self.reverse);
         self
     }
 
@@ -159,6 +154,7 @@ impl Style {
             bg: self.bg.unwrap_or_default(),
             intensity: self.intensity.unwrap_or_default(),
             underline: self.underline.unwrap_or_default(),
+            reverse: self.reverse.unwrap_or_default(),
         }
     }
 }
@@ -170,4 +166,5 @@ pub struct ResolvedStyle {
     pub bg: Color,
     pub intensity: FontIntensity,
     pub underline: bool,
+    pub reverse: bool,
 }
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc};
 
 use serde::Deserialize;
 
@@ -66,6 +66,22 @@ impl Theme {
         style
     }
 
+    /// Returns the theme with the given entries merged over it, taking precedence over the
+    /// entries of the theme.
+    pub fn with_overrides(
+        theme: &Arc<Theme>,
+        overrides: &HashMap<HighlightGroup, ThemeEntry>,
+    ) -> Arc<Theme> {
+        if overrides.is_empty() {
+            return theme.clone();
+        }
+        let mut overridden = Theme {
+            groups: overrides.clone(),
+        };
+        overridden.merge_onto(theme);
+        Arc::new(overridden)
+    }
+
     /// Merges this theme over another theme.
     pub fn merge_onto(&mut self, other: &Theme) {
         for (k, v) in other.groups.clone() {
@@ -65,23 +65,48 @@ const BG_0: Color = Color::rgb(30, 30, 30);
 const BG_1: Color = Color::rgb(40, 40, 40);
 const BG_2: Color = Color::rgb(50, 50, 50);
 const GREEN: Color = Color::rgb(100, 200, 0);
+const YELLOW: Color = Color::rgb(230, 200, 0);
 const ORANGE: Color = Color::rgb(255, 100, 0);
 const RED: Color = Color::rgb(255, 0, 0);
+const BLUE: Color = Color::rgb(80, 150, 255);
 
 highlight_groups! {
     // Base UI Colors.
     (HL_UI => "ui", Style::new().bg(BG_0).fg(FG_0)),
     // Status bar.
     (HL_UI_STATUSBAR => "ui.statusbar", Style::new().bg(BG_1), parent: "ui"),
+    (HL_UI_STATUSBAR_UNFOCUSED => "ui.statusbar.unfocused", Style::new().fg(FG_1).dim(), parent: "ui.statusbar"),
     (HL_UI_STATUSBAR_MODE_INSERT => "ui.statusbar.mode.insert", Style::new().bg(GREEN).fg(BG_0).bold(), parent: "ui.statusbar"),
     (HL_UI_STATUSBAR_MODE_COMMAND => "ui.statusbar.mode.command", Style::new().bg(ORANGE).fg(BG_0).bold(), parent: "ui.statusbar"),
+    (HL_UI_STATUSBAR_MESSAGE_WARNING => "ui.statusbar.message.warning", Style::new().bg(YELLOW).fg(BG_0).bold(), parent: "ui.statusbar"),
     (HL_UI_STATUSBAR_MESSAGE_ERROR => "ui.statusbar.message.error", Style::new().bg(RED).fg(BG_0).bold(), parent: "ui.statusbar"),
+    (HL_UI_STATUSBAR_ENCODING_LOSSY => "ui.statusbar.encoding.lossy", Style::new().fg(YELLOW).bold(), parent: "ui.statusbar"),
+    (HL_UI_STATUSBAR_DIAGNOSTICS_ERROR => "ui.statusbar.diagnostics.error", Style::new().fg(RED).bold(), parent: "ui.statusbar"),
+    (HL_UI_STATUSBAR_DIAGNOSTICS_WARNING => "ui.statusbar.diagnostics.warning", Style::new().fg(YELLOW).bold(), parent: "ui.statusbar"),
     // Pane.
     (HL_UI_PANE => "ui.pane", Style::default(), parent: "ui"),
+    (HL_UI_PANE_SELECTION => "ui.pane.selection", Style::new().bg(BG_2), parent: "ui.pane"),
+    (HL_UI_PANE_FLASH => "ui.pane.flash", Style::new().bg(YELLOW).fg(BG_0), parent: "ui.pane"),
+    (HL_UI_PANE_FOLD => "ui.pane.fold", Style::new().bg(BG_1).fg(FG_1), parent: "ui.pane"),
+    (HL_UI_PANE_SPELL => "ui.pane.spell", Style::new().fg(RED).underline(), parent: "ui.pane"),
     (HL_UI_PANE_GUTTER => "ui.pane.gutter", Style::new().bg(BG_1).fg(FG_1), parent: "ui.pane"),
     (HL_UI_PANE_GUTTER_CURSOR => "ui.pane.gutter.cursor", Style::new().fg(GREEN).bold(), parent: "ui.pane.gutter"),
+    (HL_UI_PANE_GUTTER_ERROR => "ui.pane.gutter.error", Style::new().fg(RED).bold(), parent: "ui.pane.gutter"),
+    (HL_UI_PANE_GUTTER_WARNING => "ui.pane.gutter.warning", Style::new().fg(YELLOW).bold(), parent: "ui.pane.gutter"),
+    (HL_UI_PANE_GUTTER_INFO => "ui.pane.gutter.info", Style::new().fg(BLUE), parent: "ui.pane.gutter"),
+    (HL_UI_PANE_TITLE => "ui.pane.title", Style::new().bg(BG_1).fg(FG_1), parent: "ui.pane"),
+    (HL_UI_PANE_TITLE_ACTIVE => "ui.pane.title.active", Style::new().bg(BG_2).fg(FG_0).bold(), parent: "ui.pane.title"),
+    // Syntax.
+    (HL_SYNTAX_DIFF_ADDED => "syntax.diff.added", Style::new().fg(GREEN), parent: "ui.pane"),
+    (HL_SYNTAX_DIFF_REMOVED => "syntax.diff.removed", Style::new().fg(RED), parent: "ui.pane"),
+    // Tab line.
+    (HL_UI_TABLINE => "ui.tabline", Style::new().bg(BG_1).fg(FG_1), parent: "ui"),
+    (HL_UI_TABLINE_ACTIVE => "ui.tabline.active", Style::new().bg(BG_0).fg(FG_0).bold(), parent: "ui.tabline"),
     // Overlay layers.
     (HL_UI_OVERLAY => "ui.overlay", Style::new().bg(BG_2), parent: "ui"),
+    (HL_UI_OVERLAY_BORDER => "ui.overlay.border", Style::new().fg(FG_1), parent: "ui.overlay"),
+    (HL_UI_OVERLAY_INVALID => "ui.overlay.invalid", Style::new().fg(RED), parent: "ui.overlay"),
     (HL_UI_COMMAND_PROMPT => "ui.overlay.command_prompt", Style::default(), parent: "ui.overlay"),
     (HL_UI_COMMAND_PROMPT_SELECTED => "ui.overlay.command_prompt.selected", Style::new().fg(ORANGE).bold(), parent: "ui.overlay.command_prompt"),
+    (HL_UI_COMMAND_PROMPT_EMPTY => "ui.overlay.command_prompt.empty", Style::new().fg(FG_1).dim(), parent: "ui.overlay.command_prompt"),
 }
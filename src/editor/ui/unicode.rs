@@ -0,0 +1,104 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Ranges of characters that occupy two columns in the terminal, sorted by start.
+const WIDE_RANGES: &[(u32, u32)] = &[
+    (0x1100, 0x115F),   // Hangul Jamo
+    (0x231A, 0x231B),   // Watch, hourglass
+    (0x2329, 0x232A),   // Angle brackets
+    (0x23E9, 0x23EC),   // Media controls
+    (0x23F0, 0x23F0),   // Alarm clock
+    (0x23F3, 0x23F3),   // Hourglass with flowing sand
+    (0x25FD, 0x25FE),   // Medium small squares
+    (0x2614, 0x2615),   // Umbrella, hot beverage
+    (0x2648, 0x2653),   // Zodiac signs
+    (0x267F, 0x267F),   // Wheelchair
+    (0x2693, 0x2693),   // Anchor
+    (0x26A1, 0x26A1),   // High voltage
+    (0x26AA, 0x26AB),   // Circles
+    (0x26BD, 0x26BE),   // Soccer ball, baseball
+    (0x26C4, 0x26C5),   // Snowman, sun behind cloud
+    (0x26CE, 0x26CE),   // Ophiuchus
+    (0x26D4, 0x26D4),   // No entry
+    (0x26EA, 0x26EA),   // Church
+    (0x26F2, 0x26F3),   // Fountain, golf
+    (0x26F5, 0x26F5),   // Sailboat
+    (0x26FA, 0x26FA),   // Tent
+    (0x26FD, 0x26FD),   // Fuel pump
+    (0x2705, 0x2705),   // Check mark button
+    (0x270A, 0x270B),   // Raised fists
+    (0x2728, 0x2728),   // Sparkles
+    (0x274C, 0x274C),   // Cross mark
+    (0x274E, 0x274E),   // Cross mark button
+    (0x2753, 0x2755),   // Question marks
+    (0x2757, 0x2757),   // Exclamation mark
+    (0x2795, 0x2797),   // Math signs
+    (0x27B0, 0x27B0),   // Curly loop
+    (0x27BF, 0x27BF),   // Double curly loop
+    (0x2B1B, 0x2B1C),   // Large squares
+    (0x2B50, 0x2B50),   // Star
+    (0x2B55, 0x2B55),   // Circle
+    (0x2E80, 0x303E),   // CJK radicals and symbols
+    (0x3041, 0x33FF),   // Hiragana, Katakana and CJK compatibility
+    (0x3400, 0x4DBF),   // CJK unified ideographs extension A
+    (0x4E00, 0x9FFF),   // CJK unified ideographs
+    (0xA000, 0xA4CF),   // Yi
+    (0xA960, 0xA97F),   // Hangul Jamo extended A
+    (0xAC00, 0xD7A3),   // Hangul syllables
+    (0xF900, 0xFAFF),   // CJK compatibility ideographs
+    (0xFE10, 0xFE19),   // Vertical forms
+    (0xFE30, 0xFE6F),   // CJK compatibility forms and small forms
+    (0xFF00, 0xFF60),   // Fullwidth forms
+    (0xFFE0, 0xFFE6),   // Fullwidth signs
+    (0x16FE0, 0x18AFF), // Tangut
+    (0x1B000, 0x1B2FF), // Kana supplement and extensions
+    (0x1F004, 0x1F004), // Mahjong tile
+    (0x1F0CF, 0x1F0CF), // Playing card
+    (0x1F18E, 0x1F18E), // AB button
+    (0x1F191, 0x1F19A), // Squared words
+    (0x1F1E6, 0x1F1FF), // Regional indicators
+    (0x1F200, 0x1F2FF), // Enclosed ideographic supplement
+    (0x1F300, 0x1F64F), // Miscellaneous symbols, pictographs and emoticons
+    (0x1F680, 0x1F6FF), // Transport and map symbols
+    (0x1F7E0, 0x1F7EB), // Colored circles and squares
+    (0x1F90C, 0x1F9FF), // Supplemental symbols and pictographs
+    (0x1FA70, 0x1FAFF), // Symbols and pictographs extended A
+    (0x20000, 0x3FFFD), // CJK unified ideographs extensions
+];
+
+/// Variation selector 16, which requests the emoji presentation of the preceding character.
+const EMOJI_PRESENTATION: char = '\u{FE0F}';
+
+/// Returns `true` if the character occupies two columns in the terminal.
+fn is_wide(c: char) -> bool {
+    let c = c as u32;
+    WIDE_RANGES
+        .binary_search_by(|&(start, end)| {
+            if c < start {
+                std::cmp::Ordering::Greater
+            } else if c > end {
+                std::cmp::Ordering::Less
+            } else {
+                std::cmp::Ordering::Equal
+            }
+        })
+        .is_ok()
+}
+
+/// Returns the number of columns the grapheme cluster occupies in the terminal. The width is
+/// decided by the first character of the cluster, so combining marks and joined characters do not
+/// add to it. Clusters are at least one column wide.
+pub fn grapheme_width(grapheme: &str) -> usize {
+    let Some(first) = grapheme.chars().next() else {
+        return 0;
+    };
+    if is_wide(first) || grapheme.contains(EMOJI_PRESENTATION) {
+        2
+    } else {
+        1
+    }
+}
+
+/// Returns the number of columns the string occupies in the terminal.
+pub fn str_width(s: &str) -> usize {
+    s.graphemes(true).map(grapheme_width).sum()
+}
@@ -3,7 +3,7 @@ use std::cell::RefCell;
 use crate::editor::ui::{
     frame::{Cell, Frame},
     geometry::rect::Rect,
-    widget::Widget,
+    widget::{Widget, border::Border, column::Column},
 };
 
 /// A viewport of a rectangular region of the terminal that can be written to.
@@ -25,8 +25,33 @@ impl<'a> Viewport<'a> {
             return;
         }
         let mut frame = self.frame.borrow_mut();
-        let frame_cell = frame.cell_mut(col + self.rect.col, row + self.rect.row);
-        frame_cell.apply(&cell);
+        if let Some(frame_cell) = frame.cell_mut(col + self.rect.col, row + self.rect.row) {
+            frame_cell.apply(&cell);
+        }
+    }
+
+    /// Returns a copy of the cells of the viewport in row-major order.
+    pub fn cells(&self) -> Vec<Cell> {
+        let frame = self.frame.borrow();
+        (0..self.rect.height)
+            .flat_map(|row| (0..self.rect.width).map(move |col| (col, row)))
+            .map(|(col, row)| {
+                frame
+                    .cell(col + self.rect.col, row + self.rect.row)
+                    .cloned()
+                    .unwrap_or_default()
+            })
+            .collect()
+    }
+
+    /// Replaces the cells of the viewport with cells previously returned by [`Viewport::cells`]
+    /// for a viewport of the same size.
+    pub fn put_cells(&mut self, cells: &[Cell]) {
+        let mut frame = self.frame.borrow_mut();
+        for (i, cell) in cells.iter().enumerate() {
+            let (col, row) = (i % self.rect.width, i / self.rect.width);
+            frame.put_cell(col + self.rect.col, row + self.rect.row, cell.clone());
+        }
     }
 
     /// Puts a new widget in the given position. If the position is out of bounds, it will be
@@ -37,11 +62,30 @@ impl<'a> Viewport<'a> {
         }
     }
 
+    /// Puts a column in the given rect, relative to the viewport. Parts of the rect outside of the
+    /// viewport are clipped.
+    pub fn put_column(&mut self, rect: Rect, mut column: Column) {
+        let width = rect.width.min(self.rect.width.saturating_sub(rect.col));
+        let height = rect.height.min(self.rect.height.saturating_sub(rect.row));
+        for (i, cells) in column.as_rows(width, height).into_iter().enumerate() {
+            for (col, cell) in cells.into_iter().take(width).enumerate() {
+                self.merge_cell(rect.col + col, rect.row + i, cell);
+            }
+        }
+    }
+
+    /// Draws the border around the edges of the viewport and returns the viewport inside of the
+    /// border and its padding.
+    pub fn put_border(&mut self, border: &Border) -> Viewport<'a> {
+        border.render(self);
+        Viewport::new(border.inner(self.rect), self.frame)
+    }
+
     /// Fills the viewport with the given cell.
     pub fn fill(&mut self, cell: Cell) {
         let cells = self.rect.width * self.rect.height;
         for i in 0..cells {
-            self.merge_cell(i % self.rect.width, i / self.rect.width, cell);
+            self.merge_cell(i % self.rect.width, i / self.rect.width, cell.clone());
         }
     }
 
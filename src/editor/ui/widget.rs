@@ -1,6 +1,9 @@
 use crate::editor::ui::{frame::Cell, style::Style};
 
+pub mod border;
+pub mod column;
 pub mod container;
+pub mod paragraph;
 pub mod separator;
 pub mod span;
 
@@ -0,0 +1,166 @@
+use crate::editor::ui::{
+    frame::Cell,
+    geometry::rect::Rect,
+    style::Style,
+    viewport::Viewport,
+    widget::{Widget, span::Span},
+};
+
+/// The characters used to draw a border.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BorderChars {
+    pub top_left: char,
+    pub top_right: char,
+    pub bottom_left: char,
+    pub bottom_right: char,
+    pub horizontal: char,
+    pub vertical: char,
+}
+
+impl BorderChars {
+    /// A border with square corners.
+    pub const PLAIN: BorderChars = BorderChars {
+        top_left: '┌',
+        top_right: '┐',
+        bottom_left: '└',
+        bottom_right: '┘',
+        horizontal: '─',
+        vertical: '│',
+    };
+
+    /// A border with rounded corners.
+    pub const ROUNDED: BorderChars = BorderChars {
+        top_left: '╭',
+        top_right: '╮',
+        bottom_left: '╰',
+        bottom_right: '╯',
+        horizontal: '─',
+        vertical: '│',
+    };
+
+    /// A border drawn with double lines.
+    pub const DOUBLE: BorderChars = BorderChars {
+        top_left: '╔',
+        top_right: '╗',
+        bottom_left: '╚',
+        bottom_right: '╝',
+        horizontal: '═',
+        vertical: '║',
+    };
+}
+
+impl Default for BorderChars {
+    fn default() -> Self {
+        Self::ROUNDED
+    }
+}
+
+/// A box drawn around a region, with an optional title in the top border.
+#[derive(Debug, Default, Clone)]
+pub struct Border {
+    /// The characters to draw the border with.
+    pub chars: BorderChars,
+    /// The title shown in the top border.
+    pub title: Option<String>,
+    /// The number of empty columns between the border and the content on each side.
+    pub horizontal_padding: usize,
+    /// The number of empty rows between the border and the content on each side.
+    pub vertical_padding: usize,
+    /// The style of the border and the padding.
+    pub style: Style,
+}
+
+impl Border {
+    /// The number of cells the border itself takes on each side.
+    const THICKNESS: usize = 1;
+
+    /// Sets the characters to draw the border with.
+    pub fn with_chars(mut self, chars: BorderChars) -> Self {
+        self.chars = chars;
+        self
+    }
+
+    /// Sets the title shown in the top border.
+    pub fn with_title(mut self, title: &str) -> Self {
+        self.title = Some(title.to_string());
+        self
+    }
+
+    /// Sets the padding between the border and the content.
+    pub fn with_padding(mut self, horizontal: usize, vertical: usize) -> Self {
+        self.horizontal_padding = horizontal;
+        self.vertical_padding = vertical;
+        self
+    }
+
+    /// Sets the style of the border.
+    pub fn with_style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Returns the number of columns the border and padding take in total.
+    pub fn horizontal_size(&self) -> usize {
+        (Self::THICKNESS + self.horizontal_padding) * 2
+    }
+
+    /// Returns the number of rows the border and padding take in total.
+    pub fn vertical_size(&self) -> usize {
+        (Self::THICKNESS + self.vertical_padding) * 2
+    }
+
+    /// Returns the area inside the border and padding of the given rect. The area is empty if the
+    /// rect is too small to fit any content.
+    pub fn inner(&self, rect: Rect) -> Rect {
+        let col_offset = (Self::THICKNESS + self.horizontal_padding).min(rect.width);
+        let row_offset = (Self::THICKNESS + self.vertical_padding).min(rect.height);
+        Rect::new(
+            rect.col + col_offset,
+            rect.row + row_offset,
+            rect.width.saturating_sub(self.horizontal_size()),
+            rect.height.saturating_sub(self.vertical_size()),
+        )
+    }
+
+    /// Draws the border along the edges of the viewport and fills the inside with the border
+    /// style. Parts of the border that do not fit in the viewport are clipped.
+    pub fn render(&self, viewport: &mut Viewport) {
+        let (width, height) = (viewport.width(), viewport.height());
+        if width == 0 || height == 0 {
+            return;
+        }
+        viewport.fill(Cell::default().with_style(self.style));
+
+        let (last_col, last_row) = (width - 1, height - 1);
+        let chars = &self.chars;
+        let mut put = |col: usize, row: usize, char: char| {
+            let cell = Cell::new(&char.to_string()).with_style(self.style);
+            viewport.merge_cell(col, row, cell);
+        };
+
+        for col in 1..last_col {
+            put(col, 0, chars.horizontal);
+            put(col, last_row, chars.horizontal);
+        }
+        for row in 1..last_row {
+            put(0, row, chars.vertical);
+            put(last_col, row, chars.vertical);
+        }
+        put(last_col, 0, chars.top_right);
+        put(last_col, last_row, chars.bottom_right);
+        put(0, last_row, chars.bottom_left);
+        put(0, 0, chars.top_left);
+
+        // Show the title after the top left corner if there is room for at least one character.
+        let title_width = width.saturating_sub(4);
+        if let Some(title) = &self.title
+            && title_width > 0
+        {
+            let mut span = Span::new(&format!(" {title} ")).with_style(self.style);
+            span.set_width(Some(title_width));
+            for (i, cell) in span.as_cells().into_iter().enumerate() {
+                viewport.merge_cell(i + 2, 0, cell);
+            }
+        }
+    }
+}
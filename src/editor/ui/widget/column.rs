@@ -0,0 +1,136 @@
+use crate::editor::ui::{frame::Cell, style::Style, widget::Widget};
+
+/// The vertical alignment strategy of a column.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum VerticalAlignment {
+    /// Aligns all children to the top.
+    #[default]
+    Top,
+    /// Aligns all children to the bottom.
+    Bottom,
+    /// Aligns all children in the middle.
+    Center,
+}
+
+/// A child of a column with the number of rows it takes.
+pub struct ColumnChild {
+    /// The widget rendered in the first row of the child.
+    pub widget: Box<dyn Widget + 'static>,
+    /// The number of rows the child takes. Rows below the widget are left empty.
+    pub height: usize,
+}
+
+#[derive(Default)]
+pub struct ColumnBuilder {
+    /// The children of the column, from top to bottom.
+    pub children: Vec<ColumnChild>,
+    /// The style of the empty rows of the column.
+    pub style: Style,
+    /// How the column aligns it's children.
+    pub alignment: VerticalAlignment,
+}
+
+impl ColumnBuilder {
+    /// Adds a new child that takes a single row to the column.
+    pub fn with_child(self, child: impl Widget + 'static) -> Self {
+        self.with_sized_child(child, 1)
+    }
+
+    /// Adds a new child that takes the given number of rows to the column.
+    pub fn with_sized_child(mut self, child: impl Widget + 'static, height: usize) -> Self {
+        self.children.push(ColumnChild {
+            widget: Box::new(child),
+            height,
+        });
+        self
+    }
+
+    /// Adds multiple children that take a single row each to the column.
+    pub fn with_children(
+        mut self,
+        children: impl IntoIterator<Item = Box<dyn Widget + 'static>>,
+    ) -> Self {
+        self.children.extend(
+            children
+                .into_iter()
+                .map(|widget| ColumnChild { widget, height: 1 }),
+        );
+        self
+    }
+
+    /// Sets the alignment of the column.
+    pub fn with_alignment(mut self, alignment: VerticalAlignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    /// Sets the style of the empty rows of the column.
+    pub fn with_style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Builds the column.
+    pub fn build(self) -> Column {
+        Column {
+            children: self.children,
+            style: self.style,
+            alignment: self.alignment,
+        }
+    }
+}
+
+/// A column that stacks widgets vertically. The column takes the height of the area it is put
+/// in.
+#[derive(Default)]
+pub struct Column {
+    /// The children of the column, from top to bottom.
+    pub children: Vec<ColumnChild>,
+    /// The style of the empty rows of the column.
+    pub style: Style,
+    /// How the column aligns it's children.
+    pub alignment: VerticalAlignment,
+}
+
+impl Column {
+    /// Returns the total number of rows of the children.
+    pub fn content_height(&self) -> usize {
+        self.children.iter().map(|child| child.height).sum()
+    }
+
+    /// Converts the column into rows of cells that fit in the given size. Children that do not
+    /// fit are clipped from the side opposite to the alignment, and empty rows are filled with
+    /// the style of the column.
+    pub fn as_rows(&mut self, width: usize, height: usize) -> Vec<Vec<Cell>> {
+        let empty_row = || vec![Cell::default().with_style(self.style); width];
+
+        let mut rows = Vec::with_capacity(self.content_height());
+        for child in &mut self.children {
+            if child.height == 0 {
+                continue;
+            }
+            rows.push(child.widget.as_cells());
+            rows.extend((1..child.height).map(|_| empty_row()));
+        }
+
+        // Clip the rows that do not fit, keeping the ones closest to the alignment.
+        let overflow = rows.len().saturating_sub(height);
+        let clipped_top = match self.alignment {
+            VerticalAlignment::Top => 0,
+            VerticalAlignment::Bottom => overflow,
+            VerticalAlignment::Center => overflow / 2,
+        };
+        let mut rows: Vec<_> = rows.into_iter().skip(clipped_top).take(height).collect();
+
+        // Fill the remaining space around the rows based on the alignment.
+        let padding = height - rows.len();
+        let top = match self.alignment {
+            VerticalAlignment::Top => 0,
+            VerticalAlignment::Bottom => padding,
+            VerticalAlignment::Center => padding / 2,
+        };
+        rows.splice(0..0, (0..top).map(|_| empty_row()));
+        rows.extend((0..padding - top).map(|_| empty_row()));
+        rows
+    }
+}
@@ -113,22 +113,15 @@ impl Container {
     /// [`Alignment::SpaceEvenly`] and the container has children.
     fn calculate_child_widths(&self) -> Vec<Option<usize>> {
         let n = self.children.len();
-        if self.alignment == Alignment::SpaceEvenly {
-            if n == 0 {
-                return vec![None; n];
-            }
-
-            if let Some(width) = self.width {
+        match self.width {
+            Some(width) if self.alignment == Alignment::SpaceEvenly && n > 0 => {
                 let slot_base = width / n;
                 let extra = width % n;
                 (0..n)
                     .map(|i| Some(slot_base + usize::from(i < extra)))
                     .collect()
-            } else {
-                return vec![None; n];
             }
-        } else {
-            return vec![None; n];
+            _ => vec![None; n],
         }
     }
 }
@@ -150,33 +143,48 @@ impl Widget for Container {
             return Vec::new();
         }
 
+        // Truncate oversized content based on the alignment. Left-aligned content keeps its
+        // head, right-aligned content keeps its tail and centered content keeps its middle.
+        let overflow = cells.len().saturating_sub(width);
+        let skip = match self.alignment {
+            Alignment::Left | Alignment::SpaceEvenly => 0,
+            Alignment::Right => overflow,
+            Alignment::Center => overflow / 2,
+        };
+        let mut cells: Vec<Cell> = cells.into_iter().skip(skip).take(width).collect();
+
+        // Replace wide graphemes that were cut in half.
+        if cells.first().is_some_and(|cell| cell.symbol.is_empty()) {
+            cells[0] = pad_cell.clone();
+        }
+        if let Some(last) = cells.last_mut()
+            && last.width() > 1
+        {
+            *last = pad_cell.clone();
+        }
+
+        let padding = width - cells.len();
         match self.alignment {
             Alignment::Left => {
-                let padding = width.saturating_sub(cells.len());
                 let mut out = Vec::with_capacity(width);
                 out.extend(cells);
-                out.extend(std::iter::repeat(pad_cell).take(padding));
-                out.truncate(width);
+                out.extend(std::iter::repeat_n(pad_cell, padding));
                 out
             }
             Alignment::Right => {
-                let padding = width.saturating_sub(cells.len());
                 let mut out = Vec::with_capacity(width);
-                out.extend(std::iter::repeat(pad_cell).take(padding));
+                out.extend(std::iter::repeat_n(pad_cell, padding));
                 out.extend(cells);
-                out.truncate(width);
                 out
             }
             Alignment::Center => {
-                let padding = width.saturating_sub(cells.len());
                 let left = padding / 2;
                 let right = padding - left;
 
                 let mut out = Vec::with_capacity(width);
-                out.extend(std::iter::repeat(pad_cell.clone()).take(left));
+                out.extend(std::iter::repeat_n(pad_cell.clone(), left));
                 out.extend(cells);
-                out.extend(std::iter::repeat(pad_cell).take(right));
-                out.truncate(width);
+                out.extend(std::iter::repeat_n(pad_cell, right));
                 out
             }
             Alignment::SpaceEvenly => cells,
@@ -195,3 +203,55 @@ impl Widget for Container {
         self.style.apply(style);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::editor::ui::widget::span::Span;
+
+    /// Returns the symbols of a container with the given children, alignment and width.
+    fn render(children: &[&str], alignment: Alignment, width: usize) -> String {
+        let mut container = ContainerBuilder::default()
+            .with_children(
+                children
+                    .iter()
+                    .map(|text| Box::new(Span::new(text)) as Box<dyn Widget>),
+            )
+            .with_alignment(alignment)
+            .with_width(Some(width))
+            .build();
+        container
+            .as_cells()
+            .iter()
+            .map(|cell| cell.symbol.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn oversized_content_is_truncated_by_alignment() {
+        let cases = [
+            (Alignment::Left, ["abcdef", "abcde", "ab"]),
+            (Alignment::Right, ["abcdef", "bcdef", "ef"]),
+            (Alignment::Center, ["abcdef", "abcde", "cd"]),
+            (Alignment::SpaceEvenly, ["abcdef", "abcde", "ad"]),
+        ];
+        for (alignment, expected) in cases {
+            for (width, expected) in [6, 5, 2].into_iter().zip(expected) {
+                assert_eq!(
+                    render(&["abc", "def"], alignment, width),
+                    expected,
+                    "{alignment:?} at width {width}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn wide_graphemes_cut_in_half_are_padded() {
+        // The continuation cell of a wide grapheme has an empty symbol.
+        assert_eq!(render(&["a界b"], Alignment::Left, 2), "a ");
+        assert_eq!(render(&["a界b"], Alignment::Right, 2), " b");
+        assert_eq!(render(&["ab界cd"], Alignment::Center, 2), "界");
+        assert_eq!(render(&["界界"], Alignment::Center, 2), "  ");
+    }
+}
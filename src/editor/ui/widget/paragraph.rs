@@ -0,0 +1,138 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::editor::ui::{
+    style::Style,
+    unicode::{grapheme_width, str_width},
+    widget::{
+        Widget,
+        column::{Column, ColumnBuilder},
+        span::Span,
+    },
+};
+
+/// Splits a word that is wider than `max_width` into chunks that fit on a line.
+fn hard_break(word: &str, max_width: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut chunk = String::new();
+    let mut chunk_width = 0;
+    for grapheme in word.graphemes(true) {
+        let width = grapheme_width(grapheme);
+        if chunk_width + width > max_width && !chunk.is_empty() {
+            chunks.push(std::mem::take(&mut chunk));
+            chunk_width = 0;
+        }
+        chunk.push_str(grapheme);
+        chunk_width += width;
+    }
+    if !chunk.is_empty() {
+        chunks.push(chunk);
+    }
+    chunks
+}
+
+/// Wraps the text into lines no wider than `max_width`, breaking on whitespace where possible.
+fn wrap(text: &str, max_width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    if max_width == 0 {
+        return lines;
+    }
+
+    for hard_line in text.lines() {
+        let mut line = String::new();
+        let mut line_width = 0;
+        for word in hard_line.split_whitespace() {
+            let word_width = str_width(word);
+            if !line.is_empty() && line_width + 1 + word_width <= max_width {
+                line.push(' ');
+                line.push_str(word);
+                line_width += 1 + word_width;
+                continue;
+            }
+
+            // The word does not fit on the current line, so start a new one.
+            if !line.is_empty() {
+                lines.push(std::mem::take(&mut line));
+            }
+            let mut chunks = hard_break(word, max_width);
+            line = chunks.pop().unwrap_or_default();
+            line_width = str_width(&line);
+            lines.extend(chunks);
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+/// A block of text that is wrapped to a maximum width.
+#[derive(Debug, Default, Clone)]
+pub struct Paragraph {
+    /// The wrapped lines of the paragraph.
+    lines: Vec<String>,
+    /// The maximum width of the lines.
+    max_width: usize,
+    /// The style of the paragraph.
+    style: Style,
+}
+
+impl Paragraph {
+    /// The character shown at the end of the last line when the paragraph is truncated.
+    const ELLIPSIS: &str = "…";
+
+    /// Returns a new paragraph of the text wrapped to `max_width` columns. Lines are broken on
+    /// whitespace, and words wider than `max_width` are broken wherever they need to be.
+    pub fn new(text: &str, max_width: usize) -> Self {
+        Self {
+            lines: wrap(text, max_width),
+            max_width,
+            style: Style::default(),
+        }
+    }
+
+    /// Limits the paragraph to `max_height` lines. If lines are removed, the last remaining line
+    /// ends with an ellipsis.
+    pub fn with_max_height(mut self, max_height: usize) -> Self {
+        if self.lines.len() <= max_height {
+            return self;
+        }
+
+        self.lines.truncate(max_height);
+        if let Some(last) = self.lines.last_mut() {
+            let mut span = Span::new(last);
+            span.set_width(Some(self.max_width.saturating_sub(1)));
+            *last = format!("{}{}", span.text, Self::ELLIPSIS);
+        }
+        self
+    }
+
+    /// Sets the style of the paragraph.
+    pub fn with_style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Returns the number of lines of the paragraph.
+    pub fn height(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// Returns the width of the widest line of the paragraph.
+    pub fn width(&self) -> usize {
+        self.lines
+            .iter()
+            .map(|line| str_width(line))
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// Converts the paragraph into a column with one row per line.
+    pub fn into_column(self) -> Column {
+        let style = self.style;
+        self.lines
+            .into_iter()
+            .fold(ColumnBuilder::default(), |builder, line| {
+                builder.with_child(Span::new(&line).with_style(style))
+            })
+            .with_style(style)
+            .build()
+    }
+}
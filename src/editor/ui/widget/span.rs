@@ -1,4 +1,11 @@
-use crate::editor::ui::{frame::Cell, style::Style, widget::Widget};
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::editor::ui::{
+    frame::Cell,
+    style::Style,
+    unicode::{grapheme_width, str_width},
+    widget::Widget,
+};
 
 /// A string with a particular style.
 #[derive(Debug, Default, Clone)]
@@ -26,19 +33,35 @@ impl Span {
 
 impl Widget for Span {
     fn as_cells(&mut self) -> Vec<Cell> {
-        self.text
-            .chars()
-            .map(|c| Cell::new(c).with_style(self.style))
-            .collect()
+        let mut cells = Vec::new();
+        for grapheme in self.text.graphemes(true) {
+            let cell = Cell::new(grapheme).with_style(self.style);
+            // Wide graphemes are followed by a cell for each extra column they cover.
+            let width = cell.width();
+            cells.push(cell);
+            for _ in 1..width {
+                cells.push(Cell::continuation().with_style(self.style));
+            }
+        }
+        cells
     }
 
     fn width(&self) -> usize {
-        self.text.len()
+        str_width(&self.text)
     }
 
     fn set_width(&mut self, width: Option<usize>) {
-        if let Some(width) = width {
-            self.text.truncate(width);
+        // Truncate on a grapheme boundary, dropping a wide grapheme that does not fit entirely.
+        let Some(width) = width else {
+            return;
+        };
+        let mut used = 0;
+        for (index, grapheme) in self.text.grapheme_indices(true) {
+            used += grapheme_width(grapheme);
+            if used > width {
+                self.text.truncate(index);
+                return;
+            }
         }
     }
 
@@ -0,0 +1,44 @@
+use std::{
+    fs,
+    path::Path,
+    time::{Duration, Instant, SystemTime},
+};
+
+/// Detects changes to a file by polling its modification time.
+#[derive(Debug, Default, Clone)]
+pub struct FileWatcher {
+    /// The last seen modification time of the file.
+    modified: Option<SystemTime>,
+    /// The time a change was first seen, or `None` if there is no pending change.
+    changed_at: Option<Instant>,
+}
+
+impl FileWatcher {
+    /// The time to wait after a change before reporting it, so that editors writing the file in
+    /// several steps only trigger a single reload.
+    const DEBOUNCE: Duration = Duration::from_millis(200);
+
+    /// Checks the modification time of the file at the given path. Returns `true` once a change
+    /// has settled for the debounce interval. The first call only records the modification time.
+    pub fn poll(&mut self, path: &Path) -> bool {
+        let modified = fs::metadata(path).and_then(|m| m.modified()).ok();
+        if self.modified.is_none() {
+            self.modified = modified;
+            return false;
+        }
+
+        if modified != self.modified {
+            self.modified = modified;
+            self.changed_at = Some(Instant::now());
+            return false;
+        }
+
+        match self.changed_at {
+            Some(time) if time.elapsed() >= Self::DEBOUNCE => {
+                self.changed_at = None;
+                true
+            }
+            _ => false,
+        }
+    }
+}
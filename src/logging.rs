@@ -0,0 +1,81 @@
+use std::{
+    fs::File,
+    io::{self, LineWriter, Write},
+    panic,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, OnceLock},
+};
+
+use tracing_subscriber::EnvFilter;
+
+/// The environment variable holding the log filter, in the syntax of `RUST_LOG`.
+const FILTER_ENV: &str = "TEDIT_LOG";
+/// The filter used if [`FILTER_ENV`] is not set.
+const DEFAULT_FILTER: &str = "info";
+
+/// The path and the shared writer of the log file, set once logging is initialized.
+static LOG: OnceLock<(PathBuf, LogWriter)> = OnceLock::new();
+
+/// A line-buffered writer of the log file that can be shared between the subscriber and the panic
+/// hook.
+#[derive(Debug, Clone)]
+struct LogWriter(Arc<Mutex<LineWriter<File>>>);
+
+impl Write for LogWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner()).write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner()).flush()
+    }
+}
+
+/// Flushes the log file when dropped.
+#[must_use]
+pub struct LogGuard;
+
+impl Drop for LogGuard {
+    fn drop(&mut self) {
+        flush();
+    }
+}
+
+/// Starts writing logs to the file at the given path, filtered by the `TEDIT_LOG` environment
+/// variable. Panics are logged and flush the file before the previous panic hook runs.
+pub fn init(path: &Path) -> io::Result<LogGuard> {
+    let writer = LogWriter(Arc::new(Mutex::new(LineWriter::new(File::create(path)?))));
+    let filter =
+        EnvFilter::try_from_env(FILTER_ENV).unwrap_or_else(|_| EnvFilter::new(DEFAULT_FILTER));
+
+    let make_writer = writer.clone();
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_ansi(false)
+        .with_writer(move || make_writer.clone())
+        .try_init()
+        .map_err(io::Error::other)?;
+    let _ = LOG.set((std::path::absolute(path)?, writer));
+
+    let previous_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        tracing::error!("{info}");
+        flush();
+        previous_hook(info);
+    }));
+
+    tracing::info!(version = env!("CARGO_PKG_VERSION"), "logging started");
+    Ok(LogGuard)
+}
+
+/// Returns the path of the log file, or `None` if logging is disabled.
+pub fn path() -> Option<&'static Path> {
+    LOG.get().map(|(path, _)| path.as_path())
+}
+
+/// Writes all buffered log entries to the log file.
+pub fn flush() {
+    if let Some((_, writer)) = LOG.get() {
+        let _ = writer.clone().flush();
+    }
+}
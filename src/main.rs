@@ -5,6 +5,7 @@ use clap::Parser;
 use crate::editor::Editor;
 
 mod editor;
+mod logging;
 
 #[derive(Debug, Parser)]
 #[clap(author, version, about, long_about = None)]
@@ -14,10 +15,15 @@ struct Args {
     /// Path to a custom configuration file.
     #[arg(short, long)]
     config: Option<PathBuf>,
+    /// Path to a file to write logs to. The level is filtered by the `TEDIT_LOG` environment
+    /// variable, e.g. `TEDIT_LOG=debug`.
+    #[arg(long)]
+    log: Option<PathBuf>,
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args = Args::parse();
+    let _log_guard = args.log.as_deref().map(logging::init).transpose()?;
     if let Ok(mut editor) = Editor::new(args.files, args.config) {
         editor.run()?;
     }